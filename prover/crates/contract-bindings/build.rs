@@ -0,0 +1,55 @@
+//! Fails the build if the committed ABI no longer matches the contract.
+//!
+//! The bindings are generated from `abi/ConsolidationIncentives.json`. When
+//! `forge build` has produced the Foundry artifact, its `abi` must list the
+//! same entries, so a contract change can't silently leave stale bindings.
+//! Without the artifact, `test_abi_matches_contract_source` still checks the
+//! signatures against the Solidity source.
+
+use serde_json::Value;
+
+const ABI: &str = "abi/ConsolidationIncentives.json";
+const ARTIFACT: &str =
+    "../../../contracts/out/ConsolidationIncentives.sol/ConsolidationIncentives.json";
+
+fn main() {
+    println!("cargo:rerun-if-changed={ABI}");
+    println!("cargo:rerun-if-changed={ARTIFACT}");
+
+    let committed = read(ABI);
+    let Ok(artifact) = std::fs::read_to_string(ARTIFACT) else {
+        // Contracts not built; the unit test checks the ABI against the source
+        return;
+    };
+    let artifact: Value = serde_json::from_str(&artifact)
+        .unwrap_or_else(|e| panic!("{ARTIFACT} is not valid JSON: {e}"));
+
+    let expected = entries(&artifact["abi"]);
+    let actual = entries(&committed);
+    if expected != actual {
+        let missing: Vec<&String> = expected.iter().filter(|e| !actual.contains(e)).collect();
+        let stale: Vec<&String> = actual.iter().filter(|e| !expected.contains(e)).collect();
+        panic!(
+            "{ABI} does not match {ARTIFACT}\n  missing: {missing:?}\n  stale: {stale:?}\n\
+             Regenerate it with `forge inspect ConsolidationIncentives abi --json` in contracts/"
+        );
+    }
+}
+
+fn read(path: &str) -> Value {
+    let json =
+        std::fs::read_to_string(path).unwrap_or_else(|e| panic!("Failed to read {path}: {e}"));
+    serde_json::from_str(&json).unwrap_or_else(|e| panic!("{path} is not valid JSON: {e}"))
+}
+
+/// ABI entries in a canonical order; object keys are already sorted
+fn entries(abi: &Value) -> Vec<String> {
+    let mut entries: Vec<String> = abi
+        .as_array()
+        .expect("ABI is a JSON array")
+        .iter()
+        .map(Value::to_string)
+        .collect();
+    entries.sort();
+    entries
+}
@@ -0,0 +1,274 @@
+//! Claim transaction audit
+//!
+//! Decodes a `claimReward` transaction back into its proof bundle, re-checks
+//! the proofs against a block root and reads the outcome from the receipt.
+//! Used to investigate claims submitted by other relayers.
+
+use crate::{decode_claim, decode_revert, BindingsError, ConsolidationIncentivesEvents};
+use alloy::{
+    primitives::{Address, Bytes, Log, U256},
+    rpc::types::TransactionReceipt,
+    sol_types::SolEventInterface,
+};
+use proof_gen::{ConsolidationProofBundle, ProofGenerator};
+use serde::Serialize;
+
+/// Receipt fields an audit reads
+#[derive(Debug, Clone, Default)]
+pub struct ClaimReceipt {
+    /// Whether the transaction succeeded
+    pub status: bool,
+    /// Contract the transaction called; logs from other addresses are ignored
+    pub contract: Option<Address>,
+    /// Logs emitted by the transaction
+    pub logs: Vec<Log>,
+    /// Revert data of a failed claim, e.g. from replaying it with `eth_call`
+    pub revert_data: Option<Bytes>,
+}
+
+impl From<&TransactionReceipt> for ClaimReceipt {
+    fn from(receipt: &TransactionReceipt) -> Self {
+        Self {
+            status: receipt.status(),
+            contract: receipt.to,
+            logs: receipt
+                .inner
+                .logs()
+                .iter()
+                .map(|log| log.inner.clone())
+                .collect(),
+            revert_data: None,
+        }
+    }
+}
+
+/// Result of re-checking a claim's proofs against a block root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "result", content = "error", rename_all = "snake_case")]
+pub enum ProofCheck {
+    Valid,
+    Invalid(String),
+}
+
+/// What a claim transaction did on chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ClaimOutcome {
+    /// Succeeded and paid the reward
+    Rewarded {
+        source_index: u64,
+        recipient: Address,
+        amount: U256,
+    },
+    /// Succeeded without a `RewardClaimed` event from the contract
+    NoReward,
+    /// Reverted; `reason` is set when the revert data could be decoded
+    Reverted { reason: Option<String> },
+}
+
+/// Decoded claim transaction
+#[derive(Debug, Clone, Serialize)]
+pub struct ClaimAudit {
+    /// Claim arguments as a proof bundle (without target proofs)
+    pub bundle: ConsolidationProofBundle,
+    /// Execution address the contract pays, from the source credentials
+    pub recipient: Option<Address>,
+    /// Proof check, when a block root was supplied
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proofs: Option<ProofCheck>,
+    /// Outcome, when a receipt was supplied
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outcome: Option<ClaimOutcome>,
+}
+
+/// Audit a claim from its calldata.
+///
+/// The proofs are re-verified when `block_root` is given (the root the
+/// EIP-4788 oracle returns for the claim's `beaconTimestamp`), and the
+/// outcome is read from `receipt` when given.
+pub fn audit_claim(
+    calldata: &[u8],
+    block_root: Option<[u8; 32]>,
+    receipt: Option<&ClaimReceipt>,
+) -> Result<ClaimAudit, BindingsError> {
+    let bundle = decode_claim(calldata)?;
+    let proofs = block_root.map(
+        |root| match ProofGenerator::verify_proof_bundle(&bundle, root) {
+            Ok(()) => ProofCheck::Valid,
+            Err(e) => ProofCheck::Invalid(e.to_string()),
+        },
+    );
+    Ok(ClaimAudit {
+        recipient: bundle.recipient_address().map(Address::from),
+        proofs,
+        outcome: receipt.map(claim_outcome),
+        bundle,
+    })
+}
+
+/// Read a claim's outcome from its receipt
+pub fn claim_outcome(receipt: &ClaimReceipt) -> ClaimOutcome {
+    if !receipt.status {
+        return ClaimOutcome::Reverted {
+            reason: receipt
+                .revert_data
+                .as_ref()
+                .and_then(|data| revert_reason(data)),
+        };
+    }
+    receipt
+        .logs
+        .iter()
+        .filter(|log| {
+            receipt
+                .contract
+                .is_none_or(|contract| log.address == contract)
+        })
+        .find_map(|log| {
+            match ConsolidationIncentivesEvents::decode_raw_log(log.topics(), &log.data.data) {
+                Ok(ConsolidationIncentivesEvents::RewardClaimed(event)) => {
+                    Some(ClaimOutcome::Rewarded {
+                        source_index: event.sourceIndex,
+                        recipient: event.recipient,
+                        amount: event.amount,
+                    })
+                }
+                _ => None,
+            }
+        })
+        .unwrap_or(ClaimOutcome::NoReward)
+}
+
+/// Readable revert reason: a contract error, `Error(string)` or a panic code
+pub fn revert_reason(data: &[u8]) -> Option<String> {
+    match decode_revert(data) {
+        Some(error) => Some(format!("{error:?}")),
+        None => alloy::sol_types::decode_revert_reason(data),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{claimRewardCall, ConsolidationIncentives, RewardClaimed};
+    use alloy::{
+        primitives::{FixedBytes, B256},
+        sol_types::{Revert, SolCall, SolError, SolEvent},
+    };
+    use std::str::FromStr;
+
+    const TEST_VECTORS: &str = include_str!("../../../../contracts/test-vectors/test_vectors.json");
+
+    fn vector_claim() -> (Vec<u8>, [u8; 32]) {
+        let vectors: serde_json::Value = serde_json::from_str(TEST_VECTORS).unwrap();
+        let claim = &vectors["claims"][0];
+        let word = |v: &serde_json::Value| FixedBytes::<32>::from_str(v.as_str().unwrap()).unwrap();
+        let branch = |v: &serde_json::Value| v.as_array().unwrap().iter().map(word).collect();
+        let call = claimRewardCall {
+            beaconTimestamp: vectors["beacon_timestamp"].as_u64().unwrap(),
+            consolidationIndex: claim["consolidation_index"].as_u64().unwrap(),
+            sourceIndex: claim["source_index"].as_u64().unwrap(),
+            activationEpoch: claim["activation_epoch"].as_u64().unwrap(),
+            sourceCredentials: word(&claim["source_credentials"]),
+            proofConsolidation: branch(&claim["proof_consolidation"]),
+            proofCredentials: branch(&claim["proof_credentials"]),
+            proofActivationEpoch: branch(&claim["proof_activation_epoch"]),
+        };
+        (call.abi_encode(), word(&vectors["block_root"]).0)
+    }
+
+    fn reward_log(contract: Address, source_index: u64, recipient: Address) -> Log {
+        let event = RewardClaimed {
+            sourceIndex: source_index,
+            recipient,
+            amount: U256::from(10u64.pow(18)),
+        };
+        Log {
+            address: contract,
+            data: event.encode_log_data(),
+        }
+    }
+
+    #[test]
+    fn test_audit_reverifies_proofs() {
+        let (calldata, block_root) = vector_claim();
+
+        let audit = audit_claim(&calldata, Some(block_root), None).unwrap();
+        assert_eq!(audit.proofs, Some(ProofCheck::Valid));
+        assert!(audit.outcome.is_none());
+
+        let audit = audit_claim(&calldata, Some([0x11; 32]), None).unwrap();
+        assert!(matches!(audit.proofs, Some(ProofCheck::Invalid(_))));
+
+        let audit = audit_claim(&calldata, None, None).unwrap();
+        assert!(audit.proofs.is_none());
+    }
+
+    #[test]
+    fn test_audit_reports_recipient() {
+        let (calldata, _) = vector_claim();
+        let vectors: serde_json::Value = serde_json::from_str(TEST_VECTORS).unwrap();
+        let expected =
+            Address::from_str(vectors["claims"][0]["expected_recipient"].as_str().unwrap())
+                .unwrap();
+
+        let audit = audit_claim(&calldata, None, None).unwrap();
+        assert_eq!(audit.recipient, Some(expected));
+    }
+
+    #[test]
+    fn test_outcome_reads_reward_event_from_contract() {
+        let contract = Address::repeat_byte(0xcc);
+        let recipient = Address::repeat_byte(0x42);
+        let mut receipt = ClaimReceipt {
+            status: true,
+            contract: Some(contract),
+            logs: vec![reward_log(Address::repeat_byte(0xdd), 1, recipient)],
+            revert_data: None,
+        };
+        assert_eq!(claim_outcome(&receipt), ClaimOutcome::NoReward);
+
+        receipt.logs.push(reward_log(contract, 7, recipient));
+        assert_eq!(
+            claim_outcome(&receipt),
+            ClaimOutcome::Rewarded {
+                source_index: 7,
+                recipient,
+                amount: U256::from(10u64.pow(18)),
+            }
+        );
+    }
+
+    #[test]
+    fn test_outcome_decodes_revert_reason() {
+        let receipt = |data: Vec<u8>| ClaimReceipt {
+            revert_data: Some(data.into()),
+            ..Default::default()
+        };
+
+        let claimed = ConsolidationIncentives::AlreadyClaimed { sourceIndex: 42 }.abi_encode();
+        let ClaimOutcome::Reverted { reason } = claim_outcome(&receipt(claimed)) else {
+            panic!("expected a revert");
+        };
+        assert!(reason.unwrap().contains("AlreadyClaimed"));
+
+        let message = Revert::from("not enough gas").abi_encode();
+        assert_eq!(
+            claim_outcome(&receipt(message)),
+            ClaimOutcome::Reverted {
+                reason: Some("revert: not enough gas".to_string())
+            }
+        );
+
+        assert_eq!(
+            claim_outcome(&ClaimReceipt::default()),
+            ClaimOutcome::Reverted { reason: None }
+        );
+    }
+
+    #[test]
+    fn test_audit_rejects_non_claim_input() {
+        let topic = B256::repeat_byte(1);
+        assert!(audit_claim(topic.as_slice(), None, None).is_err());
+    }
+}
@@ -0,0 +1,301 @@
+//! # Contract Bindings
+//!
+//! Rust bindings for the `ConsolidationIncentives` contract, generated by
+//! alloy's `sol!` from `abi/ConsolidationIncentives.json` (the `abi` field of
+//! the Foundry artifact). A test checks its signatures against the contract
+//! source, and the build script compares it with `contracts/out` after a
+//! `forge build`.
+//!
+//! On top of the generated calls, events and errors this crate converts
+//! between [`ConsolidationProofBundle`] and `claimReward` calldata, and
+//! [`audit`] decodes claim transactions for investigation.
+
+use alloy::{
+    primitives::{Bytes, FixedBytes},
+    sol,
+    sol_types::{SolCall, SolInterface},
+};
+use proof_gen::ConsolidationProofBundle;
+use thiserror::Error;
+
+pub mod audit;
+
+sol!(
+    #[allow(clippy::too_many_arguments)]
+    #[sol(rpc, all_derives)]
+    ConsolidationIncentives,
+    "abi/ConsolidationIncentives.json"
+);
+
+pub use ConsolidationIncentives::{
+    claimRewardCall, ConsolidationIncentivesCalls, ConsolidationIncentivesErrors,
+    ConsolidationIncentivesEvents, ConsolidationIncentivesInstance, RewardClaimed, Withdrawn,
+};
+
+/// Errors from decoding contract data
+#[derive(Debug, Error)]
+pub enum BindingsError {
+    #[error("Not claimReward calldata: {0}")]
+    NotClaimReward(#[from] alloy::sol_types::Error),
+}
+
+impl From<&ConsolidationProofBundle> for claimRewardCall {
+    fn from(bundle: &ConsolidationProofBundle) -> Self {
+        let branch = |proof: &[[u8; 32]]| proof.iter().copied().map(FixedBytes::from).collect();
+        Self {
+            beaconTimestamp: bundle.beacon_timestamp,
+            consolidationIndex: bundle.consolidation_index,
+            sourceIndex: bundle.source_index,
+            activationEpoch: bundle.activation_epoch,
+            sourceCredentials: bundle.source_credentials.into(),
+            proofConsolidation: branch(&bundle.proof_consolidation),
+            proofCredentials: branch(&bundle.proof_credentials),
+            proofActivationEpoch: branch(&bundle.proof_activation_epoch),
+        }
+    }
+}
+
+impl From<claimRewardCall> for ConsolidationProofBundle {
+    /// The contract takes no target-validator proofs, so `target` is `None`
+    fn from(call: claimRewardCall) -> Self {
+        let branch = |proof: Vec<FixedBytes<32>>| proof.into_iter().map(|p| p.0).collect();
+        Self {
+            beacon_timestamp: call.beaconTimestamp,
+            consolidation_index: call.consolidationIndex,
+            source_index: call.sourceIndex,
+            activation_epoch: call.activationEpoch,
+            source_credentials: call.sourceCredentials.0,
+            proof_consolidation: branch(call.proofConsolidation),
+            proof_credentials: branch(call.proofCredentials),
+            proof_activation_epoch: branch(call.proofActivationEpoch),
+            target: None,
+        }
+    }
+}
+
+/// ABI-encode a `claimReward` call for a proof bundle
+pub fn encode_claim(bundle: &ConsolidationProofBundle) -> Bytes {
+    claimRewardCall::from(bundle).abi_encode().into()
+}
+
+/// Decode `claimReward` calldata (selector included) back into a proof bundle
+pub fn decode_claim(calldata: &[u8]) -> Result<ConsolidationProofBundle, BindingsError> {
+    Ok(claimRewardCall::abi_decode(calldata)?.into())
+}
+
+/// Decode revert data into one of the contract's custom errors
+pub fn decode_revert(data: &[u8]) -> Option<ConsolidationIncentivesErrors> {
+    ConsolidationIncentivesErrors::abi_decode(data).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{
+        primitives::{Address, LogData, U256},
+        sol_types::{SolError, SolEvent, SolEventInterface},
+    };
+    use std::collections::BTreeSet;
+
+    const ABI: &str = include_str!("../abi/ConsolidationIncentives.json");
+    const SOURCE: &str = include_str!("../../../../contracts/src/ConsolidationIncentives.sol");
+
+    fn sample_bundle() -> ConsolidationProofBundle {
+        ConsolidationProofBundle {
+            beacon_timestamp: 1_700_000_000,
+            consolidation_index: 3,
+            source_index: 42,
+            activation_epoch: 1_000,
+            source_credentials: [0x01; 32],
+            proof_consolidation: vec![[0xaa; 32]; 29],
+            proof_credentials: vec![[0xbb; 32]; 53],
+            proof_activation_epoch: vec![[0xcc; 32]; 53],
+            target: None,
+        }
+    }
+
+    #[test]
+    fn test_signatures_match_contract() {
+        assert_eq!(
+            claimRewardCall::SIGNATURE,
+            "claimReward(uint64,uint64,uint64,uint64,bytes32,bytes32[],bytes32[],bytes32[])"
+        );
+        assert_eq!(
+            RewardClaimed::SIGNATURE,
+            "RewardClaimed(uint64,address,uint256)"
+        );
+        assert_eq!(Withdrawn::SIGNATURE, "Withdrawn(address,uint256)");
+        assert_eq!(
+            ConsolidationIncentives::InvalidProof::SIGNATURE,
+            "InvalidProof(string)"
+        );
+    }
+
+    /// ABI entries inherited from OpenZeppelin's `UUPSUpgradeable` and
+    /// `OwnableUpgradeable`
+    const INHERITED: &[&str] = &[
+        "error AddressEmptyCode(address)",
+        "error ERC1967InvalidImplementation(address)",
+        "error ERC1967NonPayable()",
+        "error FailedCall()",
+        "error InvalidInitialization()",
+        "error NotInitializing()",
+        "error OwnableInvalidOwner(address)",
+        "error OwnableUnauthorizedAccount(address)",
+        "error UUPSUnauthorizedCallContext()",
+        "error UUPSUnsupportedProxiableUUID(bytes32)",
+        "event Initialized(uint64)",
+        "event OwnershipTransferred(address,address)",
+        "event Upgraded(address)",
+        "function UPGRADE_INTERFACE_VERSION()",
+        "function owner()",
+        "function proxiableUUID()",
+        "function renounceOwnership()",
+        "function transferOwnership(address)",
+        "function upgradeToAndCall(address,bytes)",
+    ];
+
+    /// `kind name(types)` of each function, event and error in the ABI
+    fn abi_signatures(abi: &str) -> BTreeSet<String> {
+        let abi: serde_json::Value = serde_json::from_str(abi).unwrap();
+        abi.as_array()
+            .unwrap()
+            .iter()
+            .filter(|entry| matches!(entry["type"].as_str(), Some("function" | "event" | "error")))
+            .map(|entry| {
+                let types: Vec<&str> = entry["inputs"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|input| input["type"].as_str().unwrap())
+                    .collect();
+                format!(
+                    "{} {}({})",
+                    entry["type"].as_str().unwrap(),
+                    entry["name"].as_str().unwrap(),
+                    types.join(",")
+                )
+            })
+            .collect()
+    }
+
+    /// `kind name(types)` of the external functions, events and errors
+    /// declared in `source`, and of its public state variable getters
+    fn source_signatures(source: &str) -> BTreeSet<String> {
+        let code: Vec<&str> = source
+            .lines()
+            .map(|line| line.split("//").next().unwrap().trim())
+            .filter(|line| !line.starts_with('*') && !line.starts_with("/*"))
+            .collect();
+        let code = code.join(" ");
+
+        let mut signatures = BTreeSet::new();
+        for statement in code.split([';', '{', '}']).map(str::trim) {
+            let declaration = ["function", "event", "error"].iter().find_map(|kind| {
+                let rest = statement.strip_prefix(kind)?.strip_prefix(' ')?;
+                Some((*kind, rest))
+            });
+            if let Some((kind, rest)) = declaration {
+                let (name, params) = rest.split_once('(').unwrap();
+                let (params, modifiers) = params.split_once(')').unwrap();
+                let external = modifiers.contains("external") || modifiers.contains("public");
+                if name.starts_with('_') || (kind == "function" && !external) {
+                    continue;
+                }
+                let types: Vec<&str> = params
+                    .split(',')
+                    .filter_map(|param| param.split_whitespace().next())
+                    .collect();
+                signatures.insert(format!("{kind} {}({})", name.trim(), types.join(",")));
+            } else if statement.split_whitespace().any(|token| token == "public") {
+                let name = statement
+                    .split_whitespace()
+                    .take_while(|token| *token != "=")
+                    .last()
+                    .unwrap();
+                let key = statement
+                    .strip_prefix("mapping(")
+                    .and_then(|rest| rest.split_once("=>"))
+                    .map_or("", |(key, _)| key.trim());
+                signatures.insert(format!("function {name}({key})"));
+            }
+        }
+        signatures
+    }
+
+    #[test]
+    fn test_abi_matches_contract_source() {
+        let abi = abi_signatures(ABI);
+        let declared = source_signatures(SOURCE);
+        assert!(declared.contains(
+            "function claimReward(uint64,uint64,uint64,uint64,bytes32,bytes32[],bytes32[],bytes32[])"
+        ));
+        assert!(declared.contains("function rewarded(uint64)"));
+
+        let missing: Vec<&String> = declared.difference(&abi).collect();
+        let stale: Vec<&String> = abi
+            .iter()
+            .filter(|s| !declared.contains(*s) && !INHERITED.contains(&s.as_str()))
+            .collect();
+        assert!(
+            missing.is_empty() && stale.is_empty(),
+            "abi/ConsolidationIncentives.json does not match the contract source\n  \
+             missing: {missing:?}\n  stale: {stale:?}\n\
+             Regenerate it with `forge inspect ConsolidationIncentives abi --json` in contracts/"
+        );
+    }
+
+    #[test]
+    fn test_claim_calldata_roundtrip() {
+        let bundle = sample_bundle();
+        let calldata = encode_claim(&bundle);
+        assert_eq!(calldata[..4], claimRewardCall::SELECTOR);
+        let decoded = decode_claim(&calldata).unwrap();
+        assert_eq!(
+            serde_json::to_value(decoded).unwrap(),
+            serde_json::to_value(bundle).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_decode_claim_rejects_other_calls() {
+        let withdraw = ConsolidationIncentives::withdrawCall {
+            to: Address::repeat_byte(1),
+            amount: U256::from(1),
+        }
+        .abi_encode();
+        assert!(decode_claim(&withdraw).is_err());
+
+        let truncated = encode_claim(&sample_bundle());
+        assert!(decode_claim(&truncated[..100]).is_err());
+    }
+
+    #[test]
+    fn test_decode_revert() {
+        let data = ConsolidationIncentives::AlreadyClaimed { sourceIndex: 42 }.abi_encode();
+        assert!(matches!(
+            decode_revert(&data),
+            Some(ConsolidationIncentivesErrors::AlreadyClaimed(e)) if e.sourceIndex == 42
+        ));
+        assert!(decode_revert(&[0xde, 0xad, 0xbe, 0xef]).is_none());
+    }
+
+    #[test]
+    fn test_decode_reward_claimed_log() {
+        let recipient = Address::repeat_byte(0x42);
+        let event = RewardClaimed {
+            sourceIndex: 7,
+            recipient,
+            amount: U256::from(10u64.pow(18)),
+        };
+        let log: LogData = event.encode_log_data();
+        assert_eq!(log.topics()[0], RewardClaimed::SIGNATURE_HASH);
+
+        let decoded =
+            ConsolidationIncentivesEvents::decode_raw_log(log.topics(), &log.data).unwrap();
+        assert!(matches!(
+            decoded,
+            ConsolidationIncentivesEvents::RewardClaimed(e) if e.sourceIndex == 7 && e.recipient == recipient
+        ));
+    }
+}
@@ -0,0 +1,43 @@
+//! Compiles the contracts for the EVM simulation.
+//!
+//! `forge build` writes the artifacts to `OUT_DIR`, so the simulated
+//! `ConsolidationIncentives` is always the one in `contracts/src` and the
+//! contract tests run in a plain `cargo test`. Needs Foundry on `PATH` and the
+//! `contracts/lib` submodules checked out.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+const CONTRACTS: &str = "../../../contracts";
+
+fn main() {
+    for input in ["src", "foundry.toml", "foundry.lock"] {
+        println!("cargo:rerun-if-changed={CONTRACTS}/{input}");
+    }
+    println!("cargo:rerun-if-env-changed=FORGE");
+
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo"));
+    let forge_out = out_dir.join("forge-out");
+    let forge = std::env::var("FORGE").unwrap_or_else(|_| "forge".to_string());
+
+    let status = Command::new(&forge)
+        .current_dir(CONTRACTS)
+        .arg("build")
+        .arg("--out")
+        .arg(&forge_out)
+        .arg("--cache-path")
+        .arg(out_dir.join("forge-cache"))
+        .status()
+        .unwrap_or_else(|e| {
+            panic!(
+                "Failed to run `{forge} build` for the EVM simulation: {e}\n\
+                 Install Foundry (https://getfoundry.sh) or point FORGE at it"
+            )
+        });
+    assert!(
+        status.success(),
+        "`{forge} build` in contracts/ failed ({status}); are the contracts/lib submodules checked out?"
+    );
+
+    println!("cargo:rustc-env=FORGE_OUT={}", forge_out.display());
+}
@@ -0,0 +1,523 @@
+//! In-process EVM simulation of `ConsolidationIncentives`.
+//!
+//! Deploys the Foundry-compiled contract behind an `ERC1967Proxy` in revm,
+//! next to a mock EIP-4788 oracle whose roots are written straight into its
+//! storage, and runs `claimReward` against proof-gen bundles. The build script
+//! compiles the contracts with `forge build`, so everything then runs offline
+//! in a plain `cargo test -p integration-tests`.
+
+use alloy::{
+    primitives::{address, Address, Bytes, TxKind, U256},
+    sol_types::{SolCall, SolEvent, SolValue},
+};
+use contract_bindings::{
+    decode_revert, encode_claim, ConsolidationIncentives, ConsolidationIncentivesErrors,
+    RewardClaimed,
+};
+use proof_gen::ConsolidationProofBundle;
+use revm::{
+    context::{
+        result::{ExecutionResult, Output},
+        TxEnv,
+    },
+    database::{CacheDB, EmptyDB},
+    state::{AccountInfo, Bytecode},
+    Context, ExecuteCommitEvm, MainBuilder, MainContext,
+};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// EIP-4788 beacon roots contract, hardcoded in `ConsolidationIncentives`
+pub const EIP4788_ORACLE: Address = address!("000F3df6D732807Ef1319fB7B8bB8522d0Beac02");
+
+/// Mock oracle runtime: returns `sload(timestamp)`, reverting when unset
+/// like the real contract does for unknown timestamps.
+///
+/// `PUSH1 0 CALLDATALOAD SLOAD DUP1 PUSH1 0x0c JUMPI PUSH1 0 DUP1 REVERT
+///  JUMPDEST PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN`
+const MOCK_ORACLE_CODE: [u8; 21] = [
+    0x60, 0x00, 0x35, 0x54, 0x80, 0x60, 0x0c, 0x57, 0x60, 0x00, 0x80, 0xfd, 0x5b, 0x60, 0x00, 0x52,
+    0x60, 0x20, 0x60, 0x00, 0xf3,
+];
+
+const DEPLOYER: Address = address!("00000000000000000000000000000000000d3910");
+const RELAYER: Address = address!("0000000000000000000000000000000000000e1a");
+const GAS_LIMIT: u64 = 30_000_000;
+
+/// Errors from setting up or driving the simulation
+#[derive(Debug, Error)]
+pub enum SimError {
+    #[error("Failed to read artifact {path}: {source}")]
+    Artifact {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid artifact {path}: {reason}")]
+    InvalidArtifact { path: PathBuf, reason: String },
+
+    #[error("EVM error: {0}")]
+    Evm(String),
+
+    #[error("Deploying {contract} failed: {reason}")]
+    Deploy {
+        contract: &'static str,
+        reason: String,
+    },
+}
+
+/// Why a simulated claim failed
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClaimFailure {
+    /// Reverted with one of the contract's custom errors
+    Reverted(ConsolidationIncentivesErrors),
+    /// Reverted with data that isn't a contract error
+    RevertData(Bytes),
+    /// Halted (out of gas, invalid opcode, ...)
+    Halted(String),
+}
+
+/// Creation bytecode from a Foundry `out` directory
+#[derive(Debug, Clone)]
+pub struct Artifacts {
+    pub incentives: Vec<u8>,
+    pub proxy: Vec<u8>,
+}
+
+impl Artifacts {
+    /// Foundry `out` directory the build script compiled `contracts/` into
+    pub fn forge_out() -> PathBuf {
+        PathBuf::from(env!("FORGE_OUT"))
+    }
+
+    /// Load `ConsolidationIncentives` and `ERC1967Proxy` from `out`
+    pub fn load(out: &Path) -> Result<Self, SimError> {
+        Ok(Self {
+            incentives: creation_code(
+                &out.join("ConsolidationIncentives.sol/ConsolidationIncentives.json"),
+            )?,
+            proxy: creation_code(&out.join("ERC1967Proxy.sol/ERC1967Proxy.json"))?,
+        })
+    }
+}
+
+fn creation_code(path: &Path) -> Result<Vec<u8>, SimError> {
+    let invalid = |reason: String| SimError::InvalidArtifact {
+        path: path.to_path_buf(),
+        reason,
+    };
+    let json = std::fs::read_to_string(path).map_err(|source| SimError::Artifact {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let artifact: serde_json::Value =
+        serde_json::from_str(&json).map_err(|e| invalid(e.to_string()))?;
+    let object = artifact["bytecode"]["object"]
+        .as_str()
+        .ok_or_else(|| invalid("missing bytecode.object".to_string()))?;
+    hex::decode(object.trim_start_matches("0x")).map_err(|e| invalid(e.to_string()))
+}
+
+/// `initialize` arguments for the deployed proxy
+#[derive(Debug, Clone)]
+pub struct SimConfig {
+    pub owner: Address,
+    pub max_epoch: u64,
+    pub reward_amount: U256,
+    pub min_claim_delay: u64,
+    /// Contract balance after deployment
+    pub balance: U256,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        Self {
+            owner: address!("00000000000000000000000000000000000000a1"),
+            max_epoch: 100,
+            reward_amount: U256::from(10u64).pow(U256::from(18)),
+            min_claim_delay: 0,
+            balance: U256::from(100u64) * U256::from(10u64).pow(U256::from(18)),
+        }
+    }
+}
+
+/// Deployed contract in an in-memory EVM
+pub struct ClaimSimulator {
+    db: CacheDB<EmptyDB>,
+    contract: Address,
+    timestamp: u64,
+}
+
+impl ClaimSimulator {
+    /// Deploy the implementation, a proxy initialised with `config`, and the
+    /// mock oracle
+    pub fn deploy(artifacts: &Artifacts, config: &SimConfig) -> Result<Self, SimError> {
+        let mut sim = Self::with_oracle();
+        let implementation = sim.create("ConsolidationIncentives", artifacts.incentives.clone())?;
+        let init = ConsolidationIncentives::initializeCall {
+            _owner: config.owner,
+            _maxEpoch: config.max_epoch,
+            _rewardAmount: config.reward_amount,
+            _minClaimDelay: U256::from(config.min_claim_delay),
+        }
+        .abi_encode();
+        let mut proxy_code = artifacts.proxy.clone();
+        proxy_code.extend((implementation, Bytes::from(init)).abi_encode_params());
+        sim.contract = sim.create("ERC1967Proxy", proxy_code)?;
+        sim.set_balance(sim.contract, config.balance)?;
+        Ok(sim)
+    }
+
+    /// Empty chain holding only the mock oracle
+    fn with_oracle() -> Self {
+        let mut db = CacheDB::<EmptyDB>::default();
+        db.insert_account_info(
+            EIP4788_ORACLE,
+            AccountInfo::from_bytecode(Bytecode::new_raw(MOCK_ORACLE_CODE.to_vec().into())),
+        );
+        Self {
+            db,
+            contract: Address::ZERO,
+            timestamp: 1,
+        }
+    }
+
+    /// Address of the proxy
+    pub fn contract(&self) -> Address {
+        self.contract
+    }
+
+    /// Make the oracle return `root` for `timestamp`
+    pub fn set_beacon_root(&mut self, timestamp: u64, root: [u8; 32]) -> Result<(), SimError> {
+        self.db
+            .insert_account_storage(
+                EIP4788_ORACLE,
+                U256::from(timestamp),
+                U256::from_be_bytes(root),
+            )
+            .map_err(|e| SimError::Evm(format!("{e:?}")))
+    }
+
+    /// Set the execution block timestamp for later calls
+    pub fn warp(&mut self, timestamp: u64) {
+        self.timestamp = timestamp;
+    }
+
+    /// Set an account's balance
+    pub fn set_balance(&mut self, account: Address, balance: U256) -> Result<(), SimError> {
+        self.db
+            .load_account(account)
+            .map_err(|e| SimError::Evm(format!("{e:?}")))?
+            .info
+            .balance = balance;
+        Ok(())
+    }
+
+    /// Balance of an account
+    pub fn balance(&self, account: Address) -> U256 {
+        self.db
+            .cache
+            .accounts
+            .get(&account)
+            .map_or(U256::ZERO, |account| account.info.balance)
+    }
+
+    /// Read `rewarded(sourceIndex)`
+    pub fn rewarded(&mut self, source_index: u64) -> Result<bool, SimError> {
+        let call = ConsolidationIncentives::rewardedCall::new((source_index,));
+        let to = TxKind::Call(self.contract);
+        match self.transact(RELAYER, to, call.abi_encode())? {
+            ExecutionResult::Success {
+                output: Output::Call(output),
+                ..
+            } => ConsolidationIncentives::rewardedCall::abi_decode_returns(&output)
+                .map_err(|e| SimError::Evm(e.to_string())),
+            other => Err(SimError::Evm(format!("rewarded() failed: {other:?}"))),
+        }
+    }
+
+    /// Submit `claimReward` for a bundle from a relayer account
+    pub fn claim(
+        &mut self,
+        bundle: &ConsolidationProofBundle,
+    ) -> Result<RewardClaimed, ClaimFailure> {
+        let to = TxKind::Call(self.contract);
+        let result = self
+            .transact(RELAYER, to, encode_claim(bundle).to_vec())
+            .map_err(|e| ClaimFailure::Halted(e.to_string()))?;
+        match result {
+            ExecutionResult::Success { logs, .. } => logs
+                .iter()
+                .filter(|log| log.address == self.contract)
+                .find_map(|log| RewardClaimed::decode_log_data(&log.data).ok())
+                .ok_or_else(|| ClaimFailure::Halted("no RewardClaimed event".to_string())),
+            ExecutionResult::Revert { output, .. } => Err(match decode_revert(&output) {
+                Some(error) => ClaimFailure::Reverted(error),
+                None => ClaimFailure::RevertData(output),
+            }),
+            ExecutionResult::Halt { reason, .. } => {
+                Err(ClaimFailure::Halted(format!("{reason:?}")))
+            }
+        }
+    }
+
+    fn create(&mut self, contract: &'static str, code: Vec<u8>) -> Result<Address, SimError> {
+        match self.transact(DEPLOYER, TxKind::Create, code)? {
+            ExecutionResult::Success {
+                output: Output::Create(_, Some(address)),
+                ..
+            } => Ok(address),
+            other => Err(SimError::Deploy {
+                contract,
+                reason: format!("{other:?}"),
+            }),
+        }
+    }
+
+    /// Execute and commit a transaction at the current timestamp
+    fn transact(
+        &mut self,
+        caller: Address,
+        kind: TxKind,
+        data: Vec<u8>,
+    ) -> Result<ExecutionResult, SimError> {
+        let nonce = self
+            .db
+            .cache
+            .accounts
+            .get(&caller)
+            .map_or(0, |account| account.info.nonce);
+        let tx = TxEnv {
+            caller,
+            kind,
+            data: data.into(),
+            nonce,
+            gas_limit: GAS_LIMIT,
+            ..TxEnv::default()
+        };
+        let timestamp = U256::from(self.timestamp);
+        Context::mainnet()
+            .with_db(&mut self.db)
+            .modify_block_chained(|block| block.timestamp = timestamp)
+            .build_mainnet()
+            .transact_commit(tx)
+            .map_err(|e| SimError::Evm(format!("{e:?}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::FixedBytes;
+    use proof_gen::beacon_state::{
+        BeaconBlockHeader, GnosisBeaconState, PendingConsolidation, Validator,
+    };
+    use proof_gen::StateProver;
+    use ssz_rs::prelude::HashTreeRoot;
+    use ConsolidationIncentives as CI;
+
+    /// First Electra slot on Gnosis: epoch 1337856 * 16
+    const ELECTRA_SLOT: u64 = 21_405_696;
+    const BEACON_TIMESTAMP: u64 = 1_700_000_000;
+
+    /// Contracts compiled by the build script
+    fn artifacts() -> Artifacts {
+        Artifacts::load(&Artifacts::forge_out()).unwrap()
+    }
+
+    /// Consolidations 0..4 of a fresh state: eligible 0x01 and 0x02 sources,
+    /// a BLS (0x00) source and a source activated after `maxEpoch`
+    fn fresh_bundles() -> (Vec<ConsolidationProofBundle>, [u8; 32]) {
+        let mut state = GnosisBeaconState {
+            slot: ELECTRA_SLOT,
+            ..GnosisBeaconState::default()
+        };
+        for (prefix, activation_epoch) in
+            [(0x01, 10), (0x02, 20), (0x00, 30), (0x01, 500), (0x01, 1)]
+        {
+            let mut validator = Validator::default();
+            validator.withdrawal_credentials[0] = prefix;
+            validator.withdrawal_credentials[12..].fill(0x40 + state.validators.len() as u8);
+            validator.activation_epoch = activation_epoch;
+            validator.effective_balance = 32_000_000_000;
+            state.validators.push(validator);
+        }
+        for source_index in 0..4 {
+            state.pending_consolidations.push(PendingConsolidation {
+                source_index,
+                target_index: 4,
+            });
+        }
+        let header = BeaconBlockHeader {
+            slot: ELECTRA_SLOT,
+            proposer_index: 0,
+            parent_root: [0x01; 32],
+            state_root: state.hash_tree_root().unwrap().into(),
+            body_root: [0x02; 32],
+        };
+        let block_root: [u8; 32] = header.hash_tree_root().unwrap().into();
+        let prover = StateProver::from_gnosis_state(&state).unwrap();
+        let bundles = (0..4)
+            .map(|i| {
+                prover
+                    .generate_full_proof_bundle(&header, i, BEACON_TIMESTAMP)
+                    .unwrap()
+            })
+            .collect();
+        (bundles, block_root)
+    }
+
+    fn deployed(artifacts: &Artifacts, config: &SimConfig, block_root: [u8; 32]) -> ClaimSimulator {
+        let mut sim = ClaimSimulator::deploy(artifacts, config).unwrap();
+        sim.set_beacon_root(BEACON_TIMESTAMP, block_root).unwrap();
+        sim.warp(BEACON_TIMESTAMP + config.min_claim_delay);
+        sim
+    }
+
+    #[test]
+    fn test_mock_oracle_returns_seeded_roots() {
+        let mut sim = ClaimSimulator::with_oracle();
+        sim.set_beacon_root(BEACON_TIMESTAMP, [0xab; 32]).unwrap();
+
+        let oracle = TxKind::Call(EIP4788_ORACLE);
+        let query = |ts: u64| U256::from(ts).to_be_bytes::<32>().to_vec();
+        match sim
+            .transact(RELAYER, oracle, query(BEACON_TIMESTAMP))
+            .unwrap()
+        {
+            ExecutionResult::Success {
+                output: Output::Call(root),
+                ..
+            } => assert_eq!(root.as_ref(), [0xab; 32]),
+            other => panic!("oracle call failed: {other:?}"),
+        }
+        assert!(matches!(
+            sim.transact(RELAYER, oracle, query(BEACON_TIMESTAMP + 1))
+                .unwrap(),
+            ExecutionResult::Revert { .. }
+        ));
+    }
+
+    #[test]
+    fn test_claim_fresh_bundles() {
+        let artifacts = artifacts();
+        let (bundles, block_root) = fresh_bundles();
+        let config = SimConfig::default();
+        let mut sim = deployed(&artifacts, &config, block_root);
+
+        for bundle in &bundles[..2] {
+            let recipient = Address::from(bundle.recipient_address().unwrap());
+            let event = sim.claim(bundle).unwrap();
+            assert_eq!(event.sourceIndex, bundle.source_index);
+            assert_eq!(event.recipient, recipient);
+            assert_eq!(event.amount, config.reward_amount);
+            assert_eq!(sim.balance(recipient), config.reward_amount);
+            assert!(sim.rewarded(bundle.source_index).unwrap());
+        }
+
+        assert_eq!(
+            sim.claim(&bundles[0]),
+            Err(ClaimFailure::Reverted(
+                ConsolidationIncentivesErrors::AlreadyClaimed(CI::AlreadyClaimed {
+                    sourceIndex: 0
+                })
+            ))
+        );
+    }
+
+    #[test]
+    fn test_claim_custom_errors() {
+        let artifacts = artifacts();
+        let (bundles, block_root) = fresh_bundles();
+        let config = SimConfig {
+            min_claim_delay: 60,
+            ..SimConfig::default()
+        };
+        let mut sim = deployed(&artifacts, &config, block_root);
+        let reverted = |error| Err(ClaimFailure::Reverted(error));
+
+        let mut unknown = bundles[0].clone();
+        unknown.beacon_timestamp += 12;
+        sim.warp(unknown.beacon_timestamp + 60);
+        assert_eq!(
+            sim.claim(&unknown),
+            reverted(ConsolidationIncentivesErrors::BeaconRootNotFound(
+                CI::BeaconRootNotFound {
+                    beaconTimestamp: unknown.beacon_timestamp
+                }
+            ))
+        );
+
+        sim.warp(BEACON_TIMESTAMP + 59);
+        assert_eq!(
+            sim.claim(&bundles[0]),
+            reverted(ConsolidationIncentivesErrors::TimestampTooRecent(
+                CI::TimestampTooRecent {
+                    beaconTimestamp: BEACON_TIMESTAMP,
+                    currentTime: U256::from(BEACON_TIMESTAMP + 59),
+                    requiredDelay: U256::from(60),
+                }
+            ))
+        );
+        sim.warp(BEACON_TIMESTAMP + 60);
+
+        let mut short = bundles[0].clone();
+        short.proof_credentials.pop();
+        assert_eq!(
+            sim.claim(&short),
+            reverted(ConsolidationIncentivesErrors::InvalidProofLength(
+                CI::InvalidProofLength {
+                    provided: U256::from(bundles[0].proof_credentials.len() - 1),
+                    expected: U256::from(bundles[0].proof_credentials.len()),
+                }
+            ))
+        );
+
+        let tampered: [(&str, fn(&mut ConsolidationProofBundle)); 3] = [
+            ("consolidation", |b| b.proof_consolidation[0][0] ^= 1),
+            ("credentials", |b| b.proof_credentials[0][0] ^= 1),
+            ("activationEpoch", |b| b.proof_activation_epoch[0][0] ^= 1),
+        ];
+        for (proof_type, tamper) in tampered {
+            let mut bundle = bundles[0].clone();
+            tamper(&mut bundle);
+            assert_eq!(
+                sim.claim(&bundle),
+                reverted(ConsolidationIncentivesErrors::InvalidProof(
+                    CI::InvalidProof {
+                        proofType: proof_type.to_string()
+                    }
+                ))
+            );
+        }
+
+        assert_eq!(
+            sim.claim(&bundles[2]),
+            reverted(ConsolidationIncentivesErrors::InvalidCredentialsPrefix(
+                CI::InvalidCredentialsPrefix {
+                    prefix: FixedBytes([0x00])
+                }
+            ))
+        );
+        assert_eq!(
+            sim.claim(&bundles[3]),
+            reverted(ConsolidationIncentivesErrors::NotEligible(
+                CI::NotEligible {
+                    activationEpoch: 500,
+                    maxEpoch: config.max_epoch,
+                }
+            ))
+        );
+
+        sim.set_balance(sim.contract(), U256::from(1)).unwrap();
+        assert_eq!(
+            sim.claim(&bundles[0]),
+            reverted(ConsolidationIncentivesErrors::InsufficientBalance(
+                CI::InsufficientBalance {
+                    required: config.reward_amount,
+                    available: U256::from(1),
+                }
+            ))
+        );
+    }
+}
@@ -1,8 +1,13 @@
 //! Integration tests for the prover workspace.
 
+pub mod evm;
+
 #[cfg(test)]
 mod tests {
-    use proof_gen::{ConsolidationProofBundle, GindexCalculator};
+    use proof_gen::{
+        BundleContext, BundleFileError, ConsolidationProofBundle, ForkName, GindexCalculator,
+        ProofBundleV1,
+    };
     use serde::{Deserialize, Serialize};
     use std::fs;
     use std::path::PathBuf;
@@ -244,6 +249,7 @@ mod tests {
             proof_consolidation: hex_to_vec_bytes32(&claim.proof_consolidation),
             proof_credentials: hex_to_vec_bytes32(&claim.proof_credentials),
             proof_activation_epoch: hex_to_vec_bytes32(&claim.proof_activation_epoch),
+            target: None,
         };
 
         // Serialize to JSON
@@ -273,6 +279,49 @@ mod tests {
         );
     }
 
+    /// Plain bundles built from the committed vectors migrate to
+    /// `ProofBundleV1` and verify against the vectors' block root
+    #[test]
+    fn test_migrate_test_vector_bundles() {
+        let vectors = load_test_vectors();
+        let context = BundleContext {
+            chain_id: 31_337,
+            contract_address: None,
+            fork: ForkName::Electra,
+            block_root: hex_to_bytes32(&vectors.block_root),
+            slot: 1000,
+        };
+
+        for claim in &vectors.claims {
+            let plain = ConsolidationProofBundle {
+                beacon_timestamp: vectors.beacon_timestamp,
+                consolidation_index: claim.consolidation_index,
+                source_index: claim.source_index,
+                activation_epoch: claim.activation_epoch,
+                source_credentials: hex_to_bytes32(&claim.source_credentials),
+                proof_consolidation: hex_to_vec_bytes32(&claim.proof_consolidation),
+                proof_credentials: hex_to_vec_bytes32(&claim.proof_credentials),
+                proof_activation_epoch: hex_to_vec_bytes32(&claim.proof_activation_epoch),
+                target: None,
+            };
+            let json = serde_json::to_vec(&plain).unwrap();
+
+            let file = ProofBundleV1::load(&json, Some(&context)).expect("migrates");
+            assert_eq!(file.context(), context);
+            assert_eq!(file.bundle.source_index, claim.source_index);
+
+            let from_ssz = ProofBundleV1::load(&file.to_ssz().unwrap(), None).unwrap();
+            assert_eq!(from_ssz.to_json().unwrap(), file.to_json().unwrap());
+
+            let mut other_root = context;
+            other_root.block_root[0] ^= 1;
+            assert!(matches!(
+                ProofBundleV1::load(&json, Some(&other_root)),
+                Err(BundleFileError::Proof(_))
+            ));
+        }
+    }
+
     #[test]
     fn test_cross_validate_test_vectors_structure() {
         let vectors = load_test_vectors();
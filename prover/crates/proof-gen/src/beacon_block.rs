@@ -0,0 +1,408 @@
+//! Electra SignedBeaconBlock SSZ Types
+//!
+//! Full and blinded Electra block containers with Gnosis preset limits, as
+//! served by `/eth/v2/beacon/blocks` and `/eth/v1/beacon/blinded_blocks`.
+//! A blinded body merkleizes to the same root as the full body, so either
+//! yields the block's [`BeaconBlockHeader`].
+
+use crate::beacon_state::{AttestationData, Eth1Data};
+use crate::types::BeaconBlockHeader;
+use ssz_rs::prelude::*;
+
+/// Maximum proposer slashings per block
+pub const MAX_PROPOSER_SLASHINGS: usize = 16;
+/// Maximum attester slashings per block (Electra)
+pub const MAX_ATTESTER_SLASHINGS: usize = 1;
+/// Maximum attestations per block (Electra)
+pub const MAX_ATTESTATIONS: usize = 8;
+/// Maximum deposits per block
+pub const MAX_DEPOSITS: usize = 16;
+/// Maximum voluntary exits per block
+pub const MAX_VOLUNTARY_EXITS: usize = 16;
+/// Maximum BLS to execution changes per block
+pub const MAX_BLS_TO_EXECUTION_CHANGES: usize = 16;
+/// Maximum blob KZG commitments per block
+pub const MAX_BLOB_COMMITMENTS_PER_BLOCK: usize = 4096;
+/// `MAX_VALIDATORS_PER_COMMITTEE * MAX_COMMITTEES_PER_SLOT` (Electra aggregates)
+pub const MAX_ATTESTING_INDICES: usize = 2048 * 64;
+/// Maximum committees per slot
+pub const MAX_COMMITTEES_PER_SLOT: usize = 64;
+/// Sync committee size
+pub const SYNC_COMMITTEE_SIZE: usize = 512;
+/// Maximum bytes per execution transaction (2^30)
+pub const MAX_BYTES_PER_TRANSACTION: usize = 1_073_741_824;
+/// Maximum transactions per execution payload (2^20)
+pub const MAX_TRANSACTIONS_PER_PAYLOAD: usize = 1_048_576;
+/// Maximum withdrawals per execution payload on Gnosis
+pub const MAX_WITHDRAWALS_PER_PAYLOAD: usize = 8;
+/// Maximum deposit requests per execution payload
+pub const MAX_DEPOSIT_REQUESTS_PER_PAYLOAD: usize = 8192;
+/// Maximum withdrawal requests per execution payload
+pub const MAX_WITHDRAWAL_REQUESTS_PER_PAYLOAD: usize = 16;
+/// Maximum consolidation requests per execution payload
+pub const MAX_CONSOLIDATION_REQUESTS_PER_PAYLOAD: usize = 2;
+
+/// BLS signature (96 bytes)
+pub type BlsSignature = Vector<u8, 96>;
+/// BLS public key (48 bytes)
+pub type BlsPubkey = Vector<u8, 48>;
+/// Opaque execution transaction
+pub type Transaction = List<u8, MAX_BYTES_PER_TRANSACTION>;
+
+/// Block header with the proposer's signature
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct SignedBeaconBlockHeader {
+    pub message: BeaconBlockHeader,
+    pub signature: BlsSignature,
+}
+
+/// Two conflicting headers signed by the same proposer
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct ProposerSlashing {
+    pub signed_header_1: SignedBeaconBlockHeader,
+    pub signed_header_2: SignedBeaconBlockHeader,
+}
+
+/// Attestation with explicit validator indices (Electra)
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct IndexedAttestation {
+    pub attesting_indices: List<u64, MAX_ATTESTING_INDICES>,
+    pub data: AttestationData,
+    pub signature: BlsSignature,
+}
+
+/// Two conflicting attestations (Electra)
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct AttesterSlashing {
+    pub attestation_1: IndexedAttestation,
+    pub attestation_2: IndexedAttestation,
+}
+
+/// Aggregate attestation spanning several committees (Electra)
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct Attestation {
+    pub aggregation_bits: Bitlist<MAX_ATTESTING_INDICES>,
+    pub data: AttestationData,
+    pub signature: BlsSignature,
+    pub committee_bits: Bitvector<MAX_COMMITTEES_PER_SLOT>,
+}
+
+/// Deposit message and signature
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct DepositData {
+    pub pubkey: BlsPubkey,
+    pub withdrawal_credentials: [u8; 32],
+    pub amount: u64,
+    pub signature: BlsSignature,
+}
+
+/// Deposit with its deposit-tree branch
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct Deposit {
+    pub proof: Vector<[u8; 32], 33>,
+    pub data: DepositData,
+}
+
+/// Voluntary exit message
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct VoluntaryExit {
+    pub epoch: u64,
+    pub validator_index: u64,
+}
+
+/// Signed voluntary exit
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct SignedVoluntaryExit {
+    pub message: VoluntaryExit,
+    pub signature: BlsSignature,
+}
+
+/// Sync committee participation and signature
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct SyncAggregate {
+    pub sync_committee_bits: Bitvector<SYNC_COMMITTEE_SIZE>,
+    pub sync_committee_signature: BlsSignature,
+}
+
+/// Withdrawal processed by the execution payload
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct Withdrawal {
+    pub index: u64,
+    pub validator_index: u64,
+    pub address: [u8; 20],
+    pub amount: u64,
+}
+
+/// Deneb/Electra execution payload
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct ExecutionPayload {
+    pub parent_hash: [u8; 32],
+    pub fee_recipient: [u8; 20],
+    pub state_root: [u8; 32],
+    pub receipts_root: [u8; 32],
+    pub logs_bloom: Vector<u8, 256>,
+    pub prev_randao: [u8; 32],
+    pub block_number: u64,
+    pub gas_limit: u64,
+    pub gas_used: u64,
+    pub timestamp: u64,
+    pub extra_data: List<u8, 32>,
+    pub base_fee_per_gas: U256,
+    pub block_hash: [u8; 32],
+    pub transactions: List<Transaction, MAX_TRANSACTIONS_PER_PAYLOAD>,
+    pub withdrawals: List<Withdrawal, MAX_WITHDRAWALS_PER_PAYLOAD>,
+    pub blob_gas_used: u64,
+    pub excess_blob_gas: u64,
+}
+
+/// Deneb/Electra execution payload header, as carried by blinded blocks
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct ExecutionPayloadHeader {
+    pub parent_hash: [u8; 32],
+    pub fee_recipient: [u8; 20],
+    pub state_root: [u8; 32],
+    pub receipts_root: [u8; 32],
+    pub logs_bloom: Vector<u8, 256>,
+    pub prev_randao: [u8; 32],
+    pub block_number: u64,
+    pub gas_limit: u64,
+    pub gas_used: u64,
+    pub timestamp: u64,
+    pub extra_data: List<u8, 32>,
+    pub base_fee_per_gas: U256,
+    pub block_hash: [u8; 32],
+    pub transactions_root: [u8; 32],
+    pub withdrawals_root: [u8; 32],
+    pub blob_gas_used: u64,
+    pub excess_blob_gas: u64,
+}
+
+/// Request to change BLS withdrawal credentials to an execution address
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct BlsToExecutionChange {
+    pub validator_index: u64,
+    pub from_bls_pubkey: BlsPubkey,
+    pub to_execution_address: [u8; 20],
+}
+
+/// Signed BLS to execution change
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct SignedBlsToExecutionChange {
+    pub message: BlsToExecutionChange,
+    pub signature: BlsSignature,
+}
+
+/// EIP-6110 deposit request
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct DepositRequest {
+    pub pubkey: BlsPubkey,
+    pub withdrawal_credentials: [u8; 32],
+    pub amount: u64,
+    pub signature: BlsSignature,
+    pub index: u64,
+}
+
+/// EIP-7002 withdrawal request
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct WithdrawalRequest {
+    pub source_address: [u8; 20],
+    pub validator_pubkey: BlsPubkey,
+    pub amount: u64,
+}
+
+/// EIP-7251 consolidation request
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct ConsolidationRequest {
+    pub source_address: [u8; 20],
+    pub source_pubkey: BlsPubkey,
+    pub target_pubkey: BlsPubkey,
+}
+
+/// Execution layer requests (Electra)
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct ExecutionRequests {
+    pub deposits: List<DepositRequest, MAX_DEPOSIT_REQUESTS_PER_PAYLOAD>,
+    pub withdrawals: List<WithdrawalRequest, MAX_WITHDRAWAL_REQUESTS_PER_PAYLOAD>,
+    pub consolidations: List<ConsolidationRequest, MAX_CONSOLIDATION_REQUESTS_PER_PAYLOAD>,
+}
+
+/// Electra block body
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct BeaconBlockBody {
+    pub randao_reveal: BlsSignature,
+    pub eth1_data: Eth1Data,
+    pub graffiti: [u8; 32],
+    pub proposer_slashings: List<ProposerSlashing, MAX_PROPOSER_SLASHINGS>,
+    pub attester_slashings: List<AttesterSlashing, MAX_ATTESTER_SLASHINGS>,
+    pub attestations: List<Attestation, MAX_ATTESTATIONS>,
+    pub deposits: List<Deposit, MAX_DEPOSITS>,
+    pub voluntary_exits: List<SignedVoluntaryExit, MAX_VOLUNTARY_EXITS>,
+    pub sync_aggregate: SyncAggregate,
+    pub execution_payload: ExecutionPayload,
+    pub bls_to_execution_changes: List<SignedBlsToExecutionChange, MAX_BLS_TO_EXECUTION_CHANGES>,
+    pub blob_kzg_commitments: List<Vector<u8, 48>, MAX_BLOB_COMMITMENTS_PER_BLOCK>,
+    pub execution_requests: ExecutionRequests,
+}
+
+/// Electra block body with the execution payload replaced by its header
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct BlindedBeaconBlockBody {
+    pub randao_reveal: BlsSignature,
+    pub eth1_data: Eth1Data,
+    pub graffiti: [u8; 32],
+    pub proposer_slashings: List<ProposerSlashing, MAX_PROPOSER_SLASHINGS>,
+    pub attester_slashings: List<AttesterSlashing, MAX_ATTESTER_SLASHINGS>,
+    pub attestations: List<Attestation, MAX_ATTESTATIONS>,
+    pub deposits: List<Deposit, MAX_DEPOSITS>,
+    pub voluntary_exits: List<SignedVoluntaryExit, MAX_VOLUNTARY_EXITS>,
+    pub sync_aggregate: SyncAggregate,
+    pub execution_payload_header: ExecutionPayloadHeader,
+    pub bls_to_execution_changes: List<SignedBlsToExecutionChange, MAX_BLS_TO_EXECUTION_CHANGES>,
+    pub blob_kzg_commitments: List<Vector<u8, 48>, MAX_BLOB_COMMITMENTS_PER_BLOCK>,
+    pub execution_requests: ExecutionRequests,
+}
+
+/// Electra beacon block
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct BeaconBlock {
+    pub slot: u64,
+    pub proposer_index: u64,
+    pub parent_root: [u8; 32],
+    pub state_root: [u8; 32],
+    pub body: BeaconBlockBody,
+}
+
+/// Electra beacon block with the proposer's signature
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct SignedBeaconBlock {
+    pub message: BeaconBlock,
+    pub signature: BlsSignature,
+}
+
+/// Blinded Electra beacon block
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct BlindedBeaconBlock {
+    pub slot: u64,
+    pub proposer_index: u64,
+    pub parent_root: [u8; 32],
+    pub state_root: [u8; 32],
+    pub body: BlindedBeaconBlockBody,
+}
+
+/// Blinded Electra beacon block with the proposer's signature
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+pub struct SignedBlindedBeaconBlock {
+    pub message: BlindedBeaconBlock,
+    pub signature: BlsSignature,
+}
+
+impl BeaconBlock {
+    /// Header committing to this block (`body_root = hash_tree_root(body)`)
+    ///
+    /// # Errors
+    /// Returns error if the body cannot be merkleized
+    pub fn header(&self) -> Result<BeaconBlockHeader, MerkleizationError> {
+        Ok(BeaconBlockHeader {
+            slot: self.slot,
+            proposer_index: self.proposer_index,
+            parent_root: self.parent_root,
+            state_root: self.state_root,
+            body_root: self.body.hash_tree_root()?.into(),
+        })
+    }
+}
+
+impl BlindedBeaconBlock {
+    /// Header committing to this block (`body_root = hash_tree_root(body)`)
+    ///
+    /// # Errors
+    /// Returns error if the body cannot be merkleized
+    pub fn header(&self) -> Result<BeaconBlockHeader, MerkleizationError> {
+        Ok(BeaconBlockHeader {
+            slot: self.slot,
+            proposer_index: self.proposer_index,
+            parent_root: self.parent_root,
+            state_root: self.state_root,
+            body_root: self.body.hash_tree_root()?.into(),
+        })
+    }
+}
+
+impl SignedBlindedBeaconBlock {
+    /// Signed header for this block; the signature carries over unchanged
+    ///
+    /// # Errors
+    /// Returns error if the body cannot be merkleized
+    pub fn signed_header(&self) -> Result<SignedBeaconBlockHeader, MerkleizationError> {
+        Ok(SignedBeaconBlockHeader {
+            message: self.message.header()?,
+            signature: self.signature.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_root_matches_header_root() {
+        let mut block = BeaconBlock {
+            slot: 42,
+            proposer_index: 7,
+            parent_root: [1; 32],
+            state_root: [2; 32],
+            ..Default::default()
+        };
+        block.body.execution_payload.timestamp = 1_700_000_000;
+
+        let header = block.header().unwrap();
+        assert_eq!(header.slot, 42);
+        assert_eq!(
+            block.hash_tree_root().unwrap(),
+            header.hash_tree_root().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_blinded_body_root_matches_full_body_root() {
+        let body = BeaconBlockBody {
+            graffiti: [9; 32],
+            execution_payload: ExecutionPayload {
+                block_number: 100,
+                timestamp: 1_700_000_000,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let payload = &body.execution_payload;
+        let blinded = BlindedBeaconBlockBody {
+            graffiti: body.graffiti,
+            execution_payload_header: ExecutionPayloadHeader {
+                block_number: payload.block_number,
+                timestamp: payload.timestamp,
+                transactions_root: payload.transactions.hash_tree_root().unwrap().into(),
+                withdrawals_root: payload.withdrawals.hash_tree_root().unwrap().into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(
+            body.hash_tree_root().unwrap(),
+            blinded.hash_tree_root().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_signed_block_ssz_roundtrip() {
+        let mut block = SignedBeaconBlock::default();
+        block.message.slot = 5;
+        block.message.body.execution_payload.timestamp = 99;
+
+        let encoded = ssz_rs::serialize(&block).expect("serialize");
+        let decoded: SignedBeaconBlock = ssz_rs::deserialize(&encoded).expect("deserialize");
+        assert_eq!(decoded, block);
+    }
+}
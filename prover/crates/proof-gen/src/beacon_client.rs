@@ -1,14 +1,31 @@
 //! Beacon API HTTP Client
 //!
-//! Fetches beacon state data from a Gnosis beacon node.
+//! Fetches beacon state data from one or more Gnosis beacon nodes. With several
+//! endpoints configured the client either fails over between them or requires
+//! a quorum of identical answers, see [`ClientMode`].
 
+use crate::beacon_block::{SignedBeaconBlock, SignedBeaconBlockHeader, SignedBlindedBeaconBlock};
+use crate::events::{
+    BeaconEvent, EventTopic, SseParser, SubscriptionUpdate, EVENT_CHANNEL_CAPACITY,
+};
+use crate::state_download::{DownloadOptions, DownloadTarget};
 use crate::types::{
     BeaconBlockHeader, FinalityCheckpoints, PendingConsolidationJson, ValidatorInfo,
+    ValidatorRecord, ValidatorStatus,
 };
-use reqwest::Client;
-use serde::Deserialize;
+use futures::future::join_all;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, RETRY_AFTER};
+use reqwest::{Client, Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use ssz_rs::prelude::{HashTreeRoot, MerkleizationError};
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use thiserror::Error;
-use tracing::instrument;
+use tokio::sync::mpsc;
+use tokio::time::sleep;
+use tracing::{debug, instrument, warn};
 
 /// Errors from beacon API operations
 #[derive(Debug, Error)]
@@ -24,30 +41,818 @@ pub enum BeaconClientError {
 
     #[error("Header not found for slot {0}")]
     HeaderNotFound(u64),
+
+    /// The returned block does not hash to the requested block root
+    #[error(
+        "Block root mismatch: requested 0x{}, got 0x{}",
+        hex::encode(.requested),
+        hex::encode(.actual)
+    )]
+    BlockRootMismatch {
+        requested: [u8; 32],
+        actual: [u8; 32],
+    },
+
+    /// The node-reported root of a header is not its `hash_tree_root`
+    #[error(
+        "Header root mismatch: node reported 0x{}, header hashes to 0x{}",
+        hex::encode(.reported),
+        hex::encode(.computed)
+    )]
+    HeaderRootMismatch {
+        reported: [u8; 32],
+        computed: [u8; 32],
+    },
+
+    /// Timeout, connection failure, 429 or 5xx that outlasted the retry policy
+    #[error("Beacon request failed (retryable): {0}")]
+    Retryable(String),
+
+    /// The node rejected the request; retrying will not help
+    #[error("Beacon request rejected with status {status}: {message}")]
+    Permanent { status: u16, message: String },
+
+    #[error("State exceeds the {limit}-byte download limit")]
+    SizeLimitExceeded { limit: u64 },
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid client configuration: {0}")]
+    Configuration(String),
+
+    #[error(
+        "Beacon quorum not reached: {agreeing} of {total} endpoints agree, {required} required"
+    )]
+    QuorumNotReached {
+        agreeing: usize,
+        required: usize,
+        total: usize,
+    },
+}
+
+impl BeaconClientError {
+    /// Whether the node answered that the requested state or header does not exist
+    #[must_use]
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, Self::StateNotFound(_) | Self::HeaderNotFound(_))
+    }
+
+    /// Whether the same request may succeed later
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Retryable(_) | Self::QuorumNotReached { .. } => true,
+            Self::HttpError(e) => e.is_timeout() || e.is_connect() || e.is_body(),
+            _ => false,
+        }
+    }
+}
+
+/// How requests are spread across the configured endpoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClientMode {
+    /// Exactly one endpoint
+    #[default]
+    Single,
+    /// Try endpoints in turn, rotating away from failing or slow nodes
+    Failover,
+    /// Query every endpoint and require `threshold` identical answers for
+    /// headers, finality checkpoints and pending consolidations
+    ///
+    /// Other requests fall back to failover.
+    Quorum { threshold: usize },
+}
+
+/// Request statistics for a single beacon endpoint
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct EndpointHealth {
+    pub url: String,
+    pub successes: u64,
+    pub failures: u64,
+    pub consecutive_failures: u64,
+    pub last_latency_ms: Option<u64>,
+    pub last_error: Option<String>,
+    /// Whether failover currently tries this endpoint first
+    pub preferred: bool,
+}
+
+#[derive(Debug)]
+struct Endpoint {
+    url: String,
+    health: Mutex<EndpointHealth>,
+}
+
+impl Endpoint {
+    fn new(url: String) -> Self {
+        let url = url.trim_end_matches('/').to_string();
+        Self {
+            health: Mutex::new(EndpointHealth {
+                url: url.clone(),
+                ..EndpointHealth::default()
+            }),
+            url,
+        }
+    }
+
+    fn health(&self) -> std::sync::MutexGuard<'_, EndpointHealth> {
+        self.health
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn record_success(&self, latency: Duration) {
+        let mut health = self.health();
+        health.successes += 1;
+        health.consecutive_failures = 0;
+        health.last_latency_ms = Some(duration_ms(latency));
+    }
+
+    fn record_failure(&self, error: &BeaconClientError, latency: Duration) {
+        let mut health = self.health();
+        health.failures += 1;
+        health.consecutive_failures += 1;
+        health.last_latency_ms = Some(duration_ms(latency));
+        health.last_error = Some(error.to_string());
+    }
+}
+
+fn duration_ms(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// Default timeout for JSON requests
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default timeout for full SSZ state downloads
+pub const DEFAULT_STATE_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Default number of validator ids per `POST /validators` request
+pub const DEFAULT_VALIDATOR_BATCH_SIZE: usize = 1000;
+
+/// Retry schedule for retryable failures (timeouts, connection errors, 429, 5xx)
+///
+/// Retries happen against the same endpoint before failover moves on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt (default 3); 0 disables retrying
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each further one
+    pub initial_backoff: Duration,
+    /// Upper bound on any delay, including a server's `Retry-After`
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `retry` (0-based) when the server gave no `Retry-After`
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
+}
+
+#[derive(Clone)]
+enum Auth {
+    Basic {
+        username: String,
+        password: Option<String>,
+    },
+    Bearer(String),
+}
+
+impl std::fmt::Debug for Auth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Basic { username, .. } => write!(f, "Basic({username}, <redacted>)"),
+            Self::Bearer(_) => f.write_str("Bearer(<redacted>)"),
+        }
+    }
+}
+
+/// Builder for [`BeaconClient`]
+///
+/// Header values and credentials are redacted from `Debug` output.
+#[derive(Clone)]
+pub struct BeaconClientBuilder {
+    urls: Vec<String>,
+    mode: ClientMode,
+    request_timeout: Duration,
+    state_timeout: Duration,
+    retry: RetryPolicy,
+    headers: Vec<(String, String)>,
+    auth: Option<Auth>,
+    latency_threshold: Option<Duration>,
+    validator_batch_size: usize,
+}
+
+impl Default for BeaconClientBuilder {
+    fn default() -> Self {
+        Self {
+            urls: Vec::new(),
+            mode: ClientMode::Single,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+            state_timeout: DEFAULT_STATE_TIMEOUT,
+            retry: RetryPolicy::default(),
+            headers: Vec::new(),
+            auth: None,
+            latency_threshold: None,
+            validator_batch_size: DEFAULT_VALIDATOR_BATCH_SIZE,
+        }
+    }
+}
+
+impl std::fmt::Debug for BeaconClientBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let header_names: Vec<&str> = self.headers.iter().map(|(name, _)| name.as_str()).collect();
+        f.debug_struct("BeaconClientBuilder")
+            .field("urls", &self.urls)
+            .field("mode", &self.mode)
+            .field("request_timeout", &self.request_timeout)
+            .field("state_timeout", &self.state_timeout)
+            .field("retry", &self.retry)
+            .field("headers", &header_names)
+            .field("auth", &self.auth)
+            .field("latency_threshold", &self.latency_threshold)
+            .field("validator_batch_size", &self.validator_batch_size)
+            .finish()
+    }
+}
+
+impl BeaconClientBuilder {
+    /// Add a beacon node
+    #[must_use]
+    pub fn endpoint(mut self, url: impl Into<String>) -> Self {
+        self.urls.push(url.into());
+        self
+    }
+
+    /// Add several beacon nodes
+    #[must_use]
+    pub fn endpoints<I, S>(mut self, urls: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.urls.extend(urls.into_iter().map(Into::into));
+        self
+    }
+
+    /// How requests are spread across the endpoints
+    #[must_use]
+    pub fn mode(mut self, mode: ClientMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Timeout for each JSON request attempt
+    #[must_use]
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = timeout;
+        self
+    }
+
+    /// Timeout for each full SSZ state download attempt
+    #[must_use]
+    pub fn state_timeout(mut self, timeout: Duration) -> Self {
+        self.state_timeout = timeout;
+        self
+    }
+
+    /// Retry schedule for retryable failures
+    #[must_use]
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Send a header with every request (e.g. an API key)
+    #[must_use]
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Authenticate every request with HTTP basic auth
+    #[must_use]
+    pub fn basic_auth(mut self, username: impl Into<String>, password: Option<String>) -> Self {
+        self.auth = Some(Auth::Basic {
+            username: username.into(),
+            password,
+        });
+        self
+    }
+
+    /// Authenticate every request with a bearer token
+    #[must_use]
+    pub fn bearer_auth(mut self, token: impl Into<String>) -> Self {
+        self.auth = Some(Auth::Bearer(token.into()));
+        self
+    }
+
+    /// Rotate failover away from an endpoint whose successful response took
+    /// longer than `threshold`
+    #[must_use]
+    pub fn latency_threshold(mut self, threshold: Duration) -> Self {
+        self.latency_threshold = Some(threshold);
+        self
+    }
+
+    /// Maximum validator ids per request in [`BeaconClient::get_validators`]
+    #[must_use]
+    pub fn validator_batch_size(mut self, size: usize) -> Self {
+        self.validator_batch_size = size;
+        self
+    }
+
+    /// Build the client
+    ///
+    /// # Errors
+    /// Returns [`BeaconClientError::Configuration`] if no endpoints are given,
+    /// `Single` mode gets more than one, the quorum threshold is not in
+    /// `1..=endpoints`, the validator batch size is zero, or a header name or
+    /// value is invalid
+    pub fn build(self) -> Result<BeaconClient, BeaconClientError> {
+        let count = self.urls.len();
+        if count == 0 {
+            return Err(BeaconClientError::Configuration(
+                "at least one beacon endpoint is required".to_string(),
+            ));
+        }
+        if self.validator_batch_size == 0 {
+            return Err(BeaconClientError::Configuration(
+                "validator batch size must be positive".to_string(),
+            ));
+        }
+        match self.mode {
+            ClientMode::Single if count > 1 => {
+                return Err(BeaconClientError::Configuration(format!(
+                    "single mode takes one endpoint, got {count}"
+                )));
+            }
+            ClientMode::Quorum { threshold } if threshold == 0 || threshold > count => {
+                return Err(BeaconClientError::Configuration(format!(
+                    "quorum threshold {threshold} must be between 1 and {count}"
+                )));
+            }
+            _ => {}
+        }
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes()).map_err(|e| {
+                BeaconClientError::Configuration(format!("invalid header name `{name}`: {e}"))
+            })?;
+            let mut value = HeaderValue::from_str(value).map_err(|e| {
+                BeaconClientError::Configuration(format!("invalid value for header `{name}`: {e}"))
+            })?;
+            value.set_sensitive(true);
+            headers.append(name, value);
+        }
+        let client = Client::builder()
+            .default_headers(headers)
+            .build()
+            .map_err(|e| BeaconClientError::Configuration(e.to_string()))?;
+
+        Ok(BeaconClient {
+            client,
+            endpoints: self.urls.into_iter().map(Endpoint::new).collect(),
+            mode: self.mode,
+            preferred: Arc::new(AtomicUsize::new(0)),
+            latency_threshold: self.latency_threshold,
+            request_timeout: self.request_timeout,
+            state_timeout: self.state_timeout,
+            retry: self.retry,
+            auth: self.auth,
+            validator_batch_size: self.validator_batch_size,
+        })
+    }
 }
 
 /// Client for interacting with the Beacon API
+///
+/// Clones share endpoint health and the failover preference.
 #[derive(Debug, Clone)]
 pub struct BeaconClient {
     client: Client,
-    base_url: String,
+    endpoints: Arc<[Endpoint]>,
+    mode: ClientMode,
+    preferred: Arc<AtomicUsize>,
+    latency_threshold: Option<Duration>,
+    pub(crate) request_timeout: Duration,
+    pub(crate) state_timeout: Duration,
+    pub(crate) retry: RetryPolicy,
+    auth: Option<Auth>,
+    validator_batch_size: usize,
 }
 
 impl BeaconClient {
-    /// Create a new beacon client
+    /// Create a new beacon client with default timeouts and retry policy
     ///
     /// # Arguments
     /// * `base_url` - Base URL of the beacon node (e.g., `http://localhost:5052`)
     #[must_use]
     pub fn new(base_url: impl Into<String>) -> Self {
-        Self {
-            client: Client::new(),
-            base_url: base_url.into(),
+        Self::builder()
+            .endpoint(base_url)
+            .build()
+            .expect("a single endpoint without headers is always valid")
+    }
+
+    /// Start configuring a client
+    #[must_use]
+    pub fn builder() -> BeaconClientBuilder {
+        BeaconClientBuilder::default()
+    }
+
+    /// Create a client over several beacon nodes
+    ///
+    /// Shorthand for [`BeaconClient::builder`] with default timeouts and retry policy.
+    ///
+    /// # Errors
+    /// See [`BeaconClientBuilder::build`]
+    pub fn with_endpoints<I, S>(urls: I, mode: ClientMode) -> Result<Self, BeaconClientError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::builder().endpoints(urls).mode(mode).build()
+    }
+
+    /// GET request carrying the configured auth
+    fn get(&self, url: &str) -> reqwest::RequestBuilder {
+        self.request(Method::GET, url)
+    }
+
+    /// Request carrying the configured auth
+    fn request(&self, method: Method, url: &str) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, url);
+        match &self.auth {
+            Some(Auth::Basic { username, password }) => {
+                request.basic_auth(username, password.as_ref())
+            }
+            Some(Auth::Bearer(token)) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// Subscribe to `/eth/v1/events` for `topics`
+    ///
+    /// A background task holds the stream open and reconnects with the retry
+    /// policy's backoff, moving to the next endpoint when a connection attempt
+    /// fails. A stream that stays silent for longer than the request timeout
+    /// is treated as dropped. The task stops once the receiver is dropped.
+    #[must_use]
+    pub fn subscribe_events(&self, topics: &[EventTopic]) -> mpsc::Receiver<SubscriptionUpdate> {
+        let (tx, rx) = mpsc::channel(EVENT_CHANNEL_CAPACITY);
+        let topics = topics
+            .iter()
+            .map(|topic| topic.as_str())
+            .collect::<Vec<_>>()
+            .join(",");
+        tokio::spawn(self.clone().run_event_stream(topics, tx));
+        rx
+    }
+
+    async fn run_event_stream(self, topics: String, tx: mpsc::Sender<SubscriptionUpdate>) {
+        let mut index = self.preferred.load(Ordering::Relaxed);
+        let mut failures = 0;
+
+        loop {
+            let endpoint = &self.endpoints[index % self.endpoints.len()];
+            let url = format!("{}/eth/v1/events?topics={topics}", endpoint.url);
+
+            match self
+                .get(&url)
+                .header(ACCEPT, "text/event-stream")
+                .send()
+                .await
+            {
+                Ok(response) if response.status().is_success() => {
+                    failures = 0;
+                    let connected = SubscriptionUpdate::Connected {
+                        endpoint: endpoint.url.clone(),
+                    };
+                    if tx.send(connected).await.is_err() {
+                        return;
+                    }
+
+                    let error = match self.read_events(response, &tx).await {
+                        Ok(()) => "event stream closed".to_string(),
+                        Err(e) => e.to_string(),
+                    };
+                    if tx.is_closed() {
+                        return;
+                    }
+                    warn!(endpoint = %endpoint.url, %error, "Beacon event stream dropped");
+                    if tx
+                        .send(SubscriptionUpdate::Disconnected { error })
+                        .await
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+                Ok(response) => {
+                    warn!(endpoint = %endpoint.url, status = %response.status(), "Beacon event stream rejected");
+                    failures += 1;
+                    index += 1;
+                }
+                Err(error) => {
+                    warn!(endpoint = %endpoint.url, %error, "Beacon event stream connection failed");
+                    failures += 1;
+                    index += 1;
+                }
+            }
+
+            sleep(self.retry.backoff(failures)).await;
+            if tx.is_closed() {
+                return;
+            }
+        }
+    }
+
+    /// Forward events from one SSE response until it ends or goes silent
+    async fn read_events(
+        &self,
+        mut response: reqwest::Response,
+        tx: &mpsc::Sender<SubscriptionUpdate>,
+    ) -> Result<(), BeaconClientError> {
+        let mut parser = SseParser::default();
+        loop {
+            let chunk = tokio::time::timeout(self.request_timeout, response.chunk())
+                .await
+                .map_err(|_| {
+                    BeaconClientError::Retryable(format!(
+                        "no events for {}s",
+                        self.request_timeout.as_secs()
+                    ))
+                })??;
+            let Some(chunk) = chunk else {
+                return Ok(());
+            };
+
+            for message in parser.push(&chunk) {
+                match BeaconEvent::parse(&message.event, &message.data) {
+                    Ok(Some(event)) => {
+                        if tx.send(SubscriptionUpdate::Event(event)).await.is_err() {
+                            return Ok(());
+                        }
+                    }
+                    Ok(None) => debug!(event = %message.event, "Ignoring beacon event"),
+                    Err(error) => warn!(event = %message.event, %error, "Malformed beacon event"),
+                }
+            }
+        }
+    }
+
+    /// GET `url` with retries, returning the response if it succeeded or was a 404
+    ///
+    /// 429, 5xx, timeouts and connection failures are retried per the
+    /// [`RetryPolicy`], waiting for `Retry-After` when the server sends one.
+    /// Other statuses fail with [`BeaconClientError::Permanent`].
+    async fn send(
+        &self,
+        url: &str,
+        accept: Option<&str>,
+        timeout: Duration,
+    ) -> Result<reqwest::Response, BeaconClientError> {
+        self.send_with(url, timeout, |request| match accept {
+            Some(accept) => request.header(ACCEPT, accept),
+            None => request,
+        })
+        .await
+    }
+
+    /// [`Self::send`] with extra per-request configuration (headers, ranges)
+    pub(crate) async fn send_with(
+        &self,
+        url: &str,
+        timeout: Duration,
+        configure: impl Fn(reqwest::RequestBuilder) -> reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, BeaconClientError> {
+        self.send_method(Method::GET, url, timeout, configure).await
+    }
+
+    /// [`Self::send_with`] for an arbitrary HTTP method
+    async fn send_method(
+        &self,
+        method: Method,
+        url: &str,
+        timeout: Duration,
+        configure: impl Fn(reqwest::RequestBuilder) -> reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, BeaconClientError> {
+        let mut retry = 0;
+        loop {
+            let request = configure(self.request(method.clone(), url).timeout(timeout));
+
+            let (error, retry_after) = match request.send().await {
+                Ok(response)
+                    if response.status().is_success()
+                        || response.status() == StatusCode::NOT_FOUND =>
+                {
+                    return Ok(response);
+                }
+                Ok(response) => {
+                    let status = response.status();
+                    let retry_after = parse_retry_after(response.headers());
+                    let body = response.text().await.unwrap_or_default();
+                    let message = format!("{status} from {url}: {}", truncate(&body, 200));
+                    if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+                        (BeaconClientError::Retryable(message), retry_after)
+                    } else {
+                        return Err(BeaconClientError::Permanent {
+                            status: status.as_u16(),
+                            message,
+                        });
+                    }
+                }
+                Err(e) if is_transport_failure(&e) => {
+                    (BeaconClientError::Retryable(format!("{url}: {e}")), None)
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            if retry >= self.retry.max_retries {
+                return Err(error);
+            }
+            let delay = retry_after
+                .unwrap_or_else(|| self.retry.backoff(retry))
+                .min(self.retry.max_backoff);
+            debug!(%error, retry = retry + 1, delay_ms = duration_ms(delay), "Retrying beacon request");
+            sleep(delay).await;
+            retry += 1;
+        }
+    }
+
+    /// Dispatch mode
+    #[must_use]
+    pub fn mode(&self) -> ClientMode {
+        self.mode
+    }
+
+    /// Snapshot of per-endpoint request statistics, in configuration order
+    #[must_use]
+    pub fn endpoint_health(&self) -> Vec<EndpointHealth> {
+        let preferred = self.preferred.load(Ordering::Relaxed);
+        self.endpoints
+            .iter()
+            .enumerate()
+            .map(|(index, endpoint)| EndpointHealth {
+                preferred: index == preferred,
+                ..endpoint.health().clone()
+            })
+            .collect()
+    }
+
+    /// Run `op` against endpoints starting at the preferred one until one answers
+    ///
+    /// A not-found answer is passed on to the next endpoint (it may keep more
+    /// history) but does not count as a failure. If every endpoint fails, a
+    /// not-found error wins over transport errors.
+    pub(crate) async fn failover<'a, T, F, Fut>(&'a self, op: F) -> Result<T, BeaconClientError>
+    where
+        F: Fn(&'a str) -> Fut,
+        Fut: Future<Output = Result<T, BeaconClientError>>,
+    {
+        let count = self.endpoints.len();
+        let start = self.preferred.load(Ordering::Relaxed) % count;
+        let mut not_found = None;
+        let mut last_error = None;
+
+        for offset in 0..count {
+            let index = (start + offset) % count;
+            let endpoint = &self.endpoints[index];
+            let started = Instant::now();
+            let result = op(&endpoint.url).await;
+            let latency = started.elapsed();
+
+            match result {
+                Ok(value) => {
+                    endpoint.record_success(latency);
+                    let slow = count > 1 && self.latency_threshold.is_some_and(|t| latency > t);
+                    if slow {
+                        warn!(
+                            endpoint = %endpoint.url,
+                            latency_ms = duration_ms(latency),
+                            "Slow beacon endpoint, rotating"
+                        );
+                    }
+                    let next = if slow { (index + 1) % count } else { index };
+                    self.preferred.store(next, Ordering::Relaxed);
+                    return Ok(value);
+                }
+                Err(error) if error.is_not_found() => {
+                    endpoint.record_success(latency);
+                    not_found.get_or_insert(error);
+                }
+                Err(error) => {
+                    endpoint.record_failure(&error, latency);
+                    if count > 1 {
+                        warn!(endpoint = %endpoint.url, %error, "Beacon endpoint failed, trying next");
+                    }
+                    last_error = Some(error);
+                }
+            }
+        }
+
+        Err(not_found.or(last_error).unwrap_or_else(|| {
+            BeaconClientError::Configuration("no beacon endpoints configured".to_string())
+        }))
+    }
+
+    /// Run `op` against every endpoint concurrently and return the answer at
+    /// least `threshold` endpoints agree on
+    async fn quorum<'a, T, F, Fut>(
+        &'a self,
+        threshold: usize,
+        op: F,
+    ) -> Result<T, BeaconClientError>
+    where
+        T: PartialEq,
+        F: Fn(&'a str) -> Fut,
+        Fut: Future<Output = Result<T, BeaconClientError>>,
+    {
+        let results = join_all(self.endpoints.iter().map(|endpoint| {
+            let op = &op;
+            async move {
+                let started = Instant::now();
+                let result = op(&endpoint.url).await;
+                (endpoint, started.elapsed(), result)
+            }
+        }))
+        .await;
+
+        let mut answers: Vec<(T, usize)> = Vec::new();
+        let mut not_found = Vec::new();
+        for (endpoint, latency, result) in results {
+            match result {
+                Ok(value) => {
+                    endpoint.record_success(latency);
+                    match answers.iter_mut().find(|(answer, _)| *answer == value) {
+                        Some((_, votes)) => *votes += 1,
+                        None => answers.push((value, 1)),
+                    }
+                }
+                Err(error) if error.is_not_found() => {
+                    endpoint.record_success(latency);
+                    not_found.push(error);
+                }
+                Err(error) => {
+                    endpoint.record_failure(&error, latency);
+                    warn!(endpoint = %endpoint.url, %error, "Beacon endpoint failed");
+                }
+            }
+        }
+
+        if let Some(index) = answers.iter().position(|(_, votes)| *votes >= threshold) {
+            return Ok(answers.swap_remove(index).0);
+        }
+        if not_found.len() >= threshold {
+            return Err(not_found.swap_remove(0));
+        }
+        if answers.len() > 1 {
+            warn!(
+                distinct_answers = answers.len(),
+                "Beacon endpoints disagree"
+            );
+        }
+
+        Err(BeaconClientError::QuorumNotReached {
+            agreeing: answers.iter().map(|(_, votes)| *votes).max().unwrap_or(0),
+            required: threshold,
+            total: self.endpoints.len(),
+        })
+    }
+
+    /// Quorum in `Quorum` mode, failover otherwise
+    async fn agreed<'a, T, F, Fut>(&'a self, op: F) -> Result<T, BeaconClientError>
+    where
+        T: PartialEq,
+        F: Fn(&'a str) -> Fut,
+        Fut: Future<Output = Result<T, BeaconClientError>>,
+    {
+        match self.mode {
+            ClientMode::Quorum { threshold } => self.quorum(threshold, op).await,
+            ClientMode::Single | ClientMode::Failover => self.failover(op).await,
         }
     }
 
     /// Fetch beacon state as SSZ bytes
     ///
+    /// Buffers the whole state in memory; prefer [`Self::download_state`] for
+    /// full-size Gnosis states.
+    ///
     /// # Arguments
     /// * `state_id` - State identifier (slot number, "head", "finalized", etc.)
     ///
@@ -55,29 +860,11 @@ impl BeaconClient {
     /// Returns error if the request fails or state is not found
     #[instrument(skip(self))]
     pub async fn get_state_ssz(&self, state_id: &str) -> Result<Vec<u8>, BeaconClientError> {
-        let url = format!("{}/eth/v2/debug/beacon/states/{state_id}", self.base_url);
-
-        let response = self
-            .client
-            .get(&url)
-            .header("Accept", "application/octet-stream")
-            .send()
-            .await?;
-
-        if response.status() == reqwest::StatusCode::NOT_FOUND {
-            return Err(BeaconClientError::StateNotFound(
-                state_id.parse().unwrap_or(0),
-            ));
-        }
-
-        if !response.status().is_success() {
-            return Err(BeaconClientError::InvalidResponse(format!(
-                "Unexpected status: {}",
-                response.status()
-            )));
-        }
-
-        Ok(response.bytes().await?.to_vec())
+        let options = DownloadOptions {
+            target: DownloadTarget::Memory,
+            ..DownloadOptions::default()
+        };
+        Ok(self.download_state(state_id, &options).await?.into_vec())
     }
 
     /// Fetch beacon block header
@@ -89,11 +876,61 @@ impl BeaconClient {
     /// Returns error if the request fails or header is not found
     #[instrument(skip(self))]
     pub async fn get_header(&self, block_id: &str) -> Result<BeaconBlockHeader, BeaconClientError> {
-        let url = format!("{}/eth/v1/beacon/headers/{block_id}", self.base_url);
+        let (_, header) = self
+            .agreed(|base_url| self.fetch_header(base_url, block_id))
+            .await?;
+        Ok(header)
+    }
+
+    /// Fetch beacon block header together with its block root
+    ///
+    /// The root is `hash_tree_root(header)`; the node-reported `data.root`
+    /// must match it, and so must a requested block root.
+    ///
+    /// # Arguments
+    /// * `block_id` - Block identifier (slot, `0x`-prefixed block root, "head", "finalized", etc.)
+    ///
+    /// # Errors
+    /// Returns [`BeaconClientError::HeaderRootMismatch`] or
+    /// [`BeaconClientError::BlockRootMismatch`] on a root mismatch, or error if
+    /// the request fails or header is not found
+    #[instrument(skip(self))]
+    pub async fn get_header_with_root(
+        &self,
+        block_id: &str,
+    ) -> Result<([u8; 32], BeaconBlockHeader), BeaconClientError> {
+        let (root, header) = self
+            .agreed(|base_url| self.fetch_header(base_url, block_id))
+            .await?;
+        let reported = parse_hex32(&root.ok_or_else(|| {
+            BeaconClientError::InvalidResponse(format!("header {block_id} response has no root"))
+        })?)?;
+        let computed: [u8; 32] = header.hash_tree_root().map_err(merkleization_error)?.into();
+        if reported != computed {
+            return Err(BeaconClientError::HeaderRootMismatch { reported, computed });
+        }
+        if block_id.starts_with("0x") {
+            let requested = parse_hex32(block_id)?;
+            if requested != computed {
+                return Err(BeaconClientError::BlockRootMismatch {
+                    requested,
+                    actual: computed,
+                });
+            }
+        }
+        Ok((computed, header))
+    }
+
+    async fn fetch_header(
+        &self,
+        base_url: &str,
+        block_id: &str,
+    ) -> Result<(Option<String>, BeaconBlockHeader), BeaconClientError> {
+        let url = format!("{base_url}/eth/v1/beacon/headers/{block_id}");
 
-        let response = self.client.get(&url).send().await?;
+        let response = self.send(&url, None, self.request_timeout).await?;
 
-        if response.status() == reqwest::StatusCode::NOT_FOUND {
+        if response.status() == StatusCode::NOT_FOUND {
             return Err(BeaconClientError::HeaderNotFound(
                 block_id.parse().unwrap_or(0),
             ));
@@ -106,6 +943,7 @@ impl BeaconClient {
 
         #[derive(Deserialize)]
         struct HeaderData {
+            root: Option<String>,
             header: HeaderMessage,
         }
 
@@ -124,9 +962,10 @@ impl BeaconClient {
         }
 
         let header_resp: HeaderResponse = response.json().await?;
+        let root = header_resp.data.root;
         let msg = header_resp.data.header.message;
 
-        Ok(BeaconBlockHeader {
+        let header = BeaconBlockHeader {
             slot: msg
                 .slot
                 .parse()
@@ -137,7 +976,103 @@ impl BeaconClient {
             parent_root: parse_hex32(&msg.parent_root)?,
             state_root: parse_hex32(&msg.state_root)?,
             body_root: parse_hex32(&msg.body_root)?,
-        })
+        };
+
+        Ok((root, header))
+    }
+
+    /// Fetch a signed block header as SSZ
+    ///
+    /// The headers endpoint has no SSZ encoding, so this reads
+    /// `GET /eth/v1/beacon/blinded_blocks/{block_id}` and rebuilds the header
+    /// from the blinded block. A `0x` root `block_id` must match the header's
+    /// `hash_tree_root`; a slot must match its slot.
+    ///
+    /// # Errors
+    /// Returns [`BeaconClientError::BlockRootMismatch`] on a root mismatch, or
+    /// error if the request fails, the block is missing or not Electra
+    #[instrument(skip(self))]
+    pub async fn get_header_ssz(
+        &self,
+        block_id: &str,
+    ) -> Result<SignedBeaconBlockHeader, BeaconClientError> {
+        self.failover(|base_url| self.fetch_header_ssz(base_url, block_id))
+            .await
+    }
+
+    async fn fetch_header_ssz(
+        &self,
+        base_url: &str,
+        block_id: &str,
+    ) -> Result<SignedBeaconBlockHeader, BeaconClientError> {
+        let url = format!("{base_url}/eth/v1/beacon/blinded_blocks/{block_id}");
+        let bytes = self.fetch_block_bytes(&url, block_id).await?;
+
+        let block: SignedBlindedBeaconBlock = ssz_rs::deserialize(&bytes).map_err(|e| {
+            BeaconClientError::InvalidResponse(format!("Invalid SSZ blinded block: {e:?}"))
+        })?;
+        let header = block.signed_header().map_err(merkleization_error)?;
+        check_block_id(block_id, &header.message)?;
+        Ok(header)
+    }
+
+    /// Fetch a signed Electra block, including its execution payload, as SSZ
+    ///
+    /// `GET /eth/v2/beacon/blocks/{block_id}`. A `0x` root `block_id` must
+    /// match the block's `hash_tree_root`; a slot must match its slot.
+    ///
+    /// # Errors
+    /// Returns [`BeaconClientError::BlockRootMismatch`] on a root mismatch, or
+    /// error if the request fails, the block is missing or not Electra
+    #[instrument(skip(self))]
+    pub async fn get_block_ssz(
+        &self,
+        block_id: &str,
+    ) -> Result<SignedBeaconBlock, BeaconClientError> {
+        self.failover(|base_url| self.fetch_block_ssz(base_url, block_id))
+            .await
+    }
+
+    async fn fetch_block_ssz(
+        &self,
+        base_url: &str,
+        block_id: &str,
+    ) -> Result<SignedBeaconBlock, BeaconClientError> {
+        let url = format!("{base_url}/eth/v2/beacon/blocks/{block_id}");
+        let bytes = self.fetch_block_bytes(&url, block_id).await?;
+
+        let block: SignedBeaconBlock = ssz_rs::deserialize(&bytes)
+            .map_err(|e| BeaconClientError::InvalidResponse(format!("Invalid SSZ block: {e:?}")))?;
+        let header = block.message.header().map_err(merkleization_error)?;
+        check_block_id(block_id, &header)?;
+        Ok(block)
+    }
+
+    /// SSZ body of a block endpoint, rejecting forks other than Electra
+    async fn fetch_block_bytes(
+        &self,
+        url: &str,
+        block_id: &str,
+    ) -> Result<Vec<u8>, BeaconClientError> {
+        let response = self
+            .send(url, Some("application/octet-stream"), self.request_timeout)
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(BeaconClientError::HeaderNotFound(
+                block_id.parse().unwrap_or(0),
+            ));
+        }
+        if let Some(version) = response.headers().get("eth-consensus-version") {
+            let version = version.to_str().unwrap_or_default();
+            if !version.eq_ignore_ascii_case("electra") {
+                return Err(BeaconClientError::InvalidResponse(format!(
+                    "block {block_id} is from unsupported fork `{version}`"
+                )));
+            }
+        }
+
+        Ok(response.bytes().await?.to_vec())
     }
 
     /// Fetch finality checkpoints
@@ -146,12 +1081,17 @@ impl BeaconClient {
     /// Returns error if the request fails
     #[instrument(skip(self))]
     pub async fn get_finality_checkpoints(&self) -> Result<FinalityCheckpoints, BeaconClientError> {
-        let url = format!(
-            "{}/eth/v1/beacon/states/head/finality_checkpoints",
-            self.base_url
-        );
+        self.agreed(|base_url| self.fetch_finality_checkpoints(base_url))
+            .await
+    }
+
+    async fn fetch_finality_checkpoints(
+        &self,
+        base_url: &str,
+    ) -> Result<FinalityCheckpoints, BeaconClientError> {
+        let url = format!("{base_url}/eth/v1/beacon/states/head/finality_checkpoints");
 
-        let response = self.client.get(&url).send().await?;
+        let response = self.send(&url, None, self.request_timeout).await?;
 
         #[derive(Deserialize)]
         struct CheckpointsResponse {
@@ -198,10 +1138,14 @@ impl BeaconClient {
 
     /// Get current head slot
     ///
+    /// Always uses failover: nodes rarely agree on the head at the same instant.
+    ///
     /// # Errors
     /// Returns error if the request fails
     pub async fn get_head_slot(&self) -> Result<u64, BeaconClientError> {
-        let header = self.get_header("head").await?;
+        let (_, header) = self
+            .failover(|base_url| self.fetch_header(base_url, "head"))
+            .await?;
         Ok(header.slot)
     }
 
@@ -217,14 +1161,20 @@ impl BeaconClient {
         &self,
         state_id: &str,
     ) -> Result<Vec<PendingConsolidationJson>, BeaconClientError> {
-        let url = format!(
-            "{}/eth/v1/beacon/states/{state_id}/pending_consolidations",
-            self.base_url
-        );
+        self.agreed(|base_url| self.fetch_pending_consolidations(base_url, state_id))
+            .await
+    }
 
-        let response = self.client.get(&url).send().await?;
+    async fn fetch_pending_consolidations(
+        &self,
+        base_url: &str,
+        state_id: &str,
+    ) -> Result<Vec<PendingConsolidationJson>, BeaconClientError> {
+        let url = format!("{base_url}/eth/v1/beacon/states/{state_id}/pending_consolidations");
+
+        let response = self.send(&url, None, self.request_timeout).await?;
 
-        if response.status() == reqwest::StatusCode::NOT_FOUND {
+        if response.status() == StatusCode::NOT_FOUND {
             return match state_id.parse::<u64>() {
                 Ok(slot) => Err(BeaconClientError::StateNotFound(slot)),
                 Err(_) => Err(BeaconClientError::InvalidResponse(format!(
@@ -233,13 +1183,6 @@ impl BeaconClient {
             };
         }
 
-        if !response.status().is_success() {
-            return Err(BeaconClientError::InvalidResponse(format!(
-                "Unexpected status: {}",
-                response.status()
-            )));
-        }
-
         #[derive(Deserialize)]
         struct PendingConsolidationsResponse {
             data: Vec<PendingConsolidationEntry>,
@@ -280,14 +1223,21 @@ impl BeaconClient {
         state_id: &str,
         validator_id: u64,
     ) -> Result<ValidatorInfo, BeaconClientError> {
-        let url = format!(
-            "{}/eth/v1/beacon/states/{state_id}/validators/{validator_id}",
-            self.base_url
-        );
+        self.failover(|base_url| self.fetch_validator_info(base_url, state_id, validator_id))
+            .await
+    }
 
-        let response = self.client.get(&url).send().await?;
+    async fn fetch_validator_info(
+        &self,
+        base_url: &str,
+        state_id: &str,
+        validator_id: u64,
+    ) -> Result<ValidatorInfo, BeaconClientError> {
+        let url = format!("{base_url}/eth/v1/beacon/states/{state_id}/validators/{validator_id}");
+
+        let response = self.send(&url, None, self.request_timeout).await?;
 
-        if response.status() == reqwest::StatusCode::NOT_FOUND {
+        if response.status() == StatusCode::NOT_FOUND {
             return match state_id.parse::<u64>() {
                 Ok(slot) => Err(BeaconClientError::StateNotFound(slot)),
                 Err(_) => Err(BeaconClientError::InvalidResponse(format!(
@@ -296,13 +1246,6 @@ impl BeaconClient {
             };
         }
 
-        if !response.status().is_success() {
-            return Err(BeaconClientError::InvalidResponse(format!(
-                "Unexpected status: {}",
-                response.status()
-            )));
-        }
-
         #[derive(Deserialize)]
         struct ValidatorResponse {
             data: ValidatorData,
@@ -328,15 +1271,203 @@ impl BeaconClient {
             })?,
         })
     }
+
+    /// Fetch full validator records in bulk
+    ///
+    /// `POST /eth/v1/beacon/states/{state_id}/validators`, split into requests
+    /// of at most [`BeaconClientBuilder::validator_batch_size`] ids. Empty `ids`
+    /// fetches every validator; empty `statuses` applies no status filter.
+    /// Unknown indices are omitted by the node, so the result may be shorter
+    /// than `ids`. Records are returned sorted by index.
+    ///
+    /// # Errors
+    /// Returns error if any request fails or a response is invalid
+    #[instrument(skip(self, ids), fields(ids = ids.len()))]
+    pub async fn get_validators(
+        &self,
+        state_id: &str,
+        ids: &[u64],
+        statuses: &[ValidatorStatus],
+    ) -> Result<Vec<ValidatorRecord>, BeaconClientError> {
+        let mut ids = ids.to_vec();
+        ids.sort_unstable();
+        ids.dedup();
+
+        let mut records = Vec::with_capacity(ids.len());
+        if ids.is_empty() {
+            records = self
+                .failover(|base_url| self.fetch_validators(base_url, state_id, &[], statuses))
+                .await?;
+        } else {
+            for chunk in ids.chunks(self.validator_batch_size) {
+                let batch = self
+                    .failover(|base_url| self.fetch_validators(base_url, state_id, chunk, statuses))
+                    .await?;
+                records.extend(batch);
+            }
+        }
+        records.sort_unstable_by_key(|record| record.index);
+        Ok(records)
+    }
+
+    async fn fetch_validators(
+        &self,
+        base_url: &str,
+        state_id: &str,
+        ids: &[u64],
+        statuses: &[ValidatorStatus],
+    ) -> Result<Vec<ValidatorRecord>, BeaconClientError> {
+        #[derive(Serialize)]
+        struct ValidatorsRequest<'a> {
+            #[serde(skip_serializing_if = "Vec::is_empty")]
+            ids: Vec<String>,
+            #[serde(skip_serializing_if = "<[_]>::is_empty")]
+            statuses: &'a [ValidatorStatus],
+        }
+
+        #[derive(Deserialize)]
+        struct ValidatorsResponse {
+            data: Vec<ValidatorEntry>,
+        }
+
+        #[derive(Deserialize)]
+        struct ValidatorEntry {
+            index: String,
+            balance: String,
+            status: ValidatorStatus,
+            validator: ValidatorFields,
+        }
+
+        #[derive(Deserialize)]
+        struct ValidatorFields {
+            pubkey: String,
+            withdrawal_credentials: String,
+            effective_balance: String,
+            slashed: bool,
+            activation_eligibility_epoch: String,
+            activation_epoch: String,
+            exit_epoch: String,
+            withdrawable_epoch: String,
+        }
+
+        let url = format!("{base_url}/eth/v1/beacon/states/{state_id}/validators");
+        let body = ValidatorsRequest {
+            ids: ids.iter().map(u64::to_string).collect(),
+            statuses,
+        };
+
+        let response = self
+            .send_method(Method::POST, &url, self.request_timeout, |request| {
+                request.json(&body)
+            })
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return match state_id.parse::<u64>() {
+                Ok(slot) => Err(BeaconClientError::StateNotFound(slot)),
+                Err(_) => Err(BeaconClientError::InvalidResponse(format!(
+                    "validators not found for state_id={state_id}"
+                ))),
+            };
+        }
+
+        let resp: ValidatorsResponse = response.json().await?;
+
+        resp.data
+            .into_iter()
+            .map(|entry| {
+                let v = entry.validator;
+                Ok(ValidatorRecord {
+                    index: parse_u64("index", &entry.index)?,
+                    balance: parse_u64("balance", &entry.balance)?,
+                    status: entry.status,
+                    pubkey: parse_hex(&v.pubkey)?,
+                    withdrawal_credentials: parse_hex(&v.withdrawal_credentials)?,
+                    effective_balance: parse_u64("effective_balance", &v.effective_balance)?,
+                    slashed: v.slashed,
+                    activation_eligibility_epoch: parse_u64(
+                        "activation_eligibility_epoch",
+                        &v.activation_eligibility_epoch,
+                    )?,
+                    activation_epoch: parse_u64("activation_epoch", &v.activation_epoch)?,
+                    exit_epoch: parse_u64("exit_epoch", &v.exit_epoch)?,
+                    withdrawable_epoch: parse_u64("withdrawable_epoch", &v.withdrawable_epoch)?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Check a decoded header against the slot or `0x` root it was requested by
+fn check_block_id(block_id: &str, header: &BeaconBlockHeader) -> Result<(), BeaconClientError> {
+    if block_id.starts_with("0x") {
+        let requested = parse_hex32(block_id)?;
+        let actual: [u8; 32] = header.hash_tree_root().map_err(merkleization_error)?.into();
+        if actual != requested {
+            return Err(BeaconClientError::BlockRootMismatch { requested, actual });
+        }
+    } else if let Ok(slot) = block_id.parse::<u64>() {
+        if header.slot != slot {
+            return Err(BeaconClientError::InvalidResponse(format!(
+                "requested block at slot {slot}, got slot {}",
+                header.slot
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn merkleization_error(e: MerkleizationError) -> BeaconClientError {
+    BeaconClientError::InvalidResponse(format!("Failed to merkleize block: {e}"))
+}
+
+/// Whether a failed send may succeed when repeated: a timeout, a failed
+/// connection, or a request cut off by an I/O error. Errors building the
+/// request (bad URL or headers) would fail the same way again.
+fn is_transport_failure(e: &reqwest::Error) -> bool {
+    if e.is_builder() {
+        return false;
+    }
+    let io_failure = || {
+        std::iter::successors(std::error::Error::source(e), |cause| cause.source())
+            .any(|cause| cause.is::<std::io::Error>())
+    };
+    e.is_timeout() || e.is_connect() || (e.is_request() && io_failure())
+}
+
+/// `Retry-After` as delay-seconds; HTTP-date values are ignored
+fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
 }
 
-fn parse_hex32(s: &str) -> Result<[u8; 32], BeaconClientError> {
+fn truncate(s: &str, max_chars: usize) -> &str {
+    s.char_indices().nth(max_chars).map_or(s, |(i, _)| &s[..i])
+}
+
+pub(crate) fn parse_hex32(s: &str) -> Result<[u8; 32], BeaconClientError> {
+    parse_hex(s)
+}
+
+pub(crate) fn parse_hex<const N: usize>(s: &str) -> Result<[u8; N], BeaconClientError> {
     let s = s.strip_prefix("0x").unwrap_or(s);
     let bytes = hex::decode(s)
         .map_err(|e| BeaconClientError::InvalidResponse(format!("Invalid hex: {e}")))?;
     bytes
         .try_into()
-        .map_err(|_| BeaconClientError::InvalidResponse("Expected 32 bytes".to_string()))
+        .map_err(|_| BeaconClientError::InvalidResponse(format!("Expected {N} bytes")))
+}
+
+pub(crate) fn parse_u64(field: &str, value: &str) -> Result<u64, BeaconClientError> {
+    value
+        .parse()
+        .map_err(|e| BeaconClientError::InvalidResponse(format!("Invalid {field}: {e}")))
 }
 
 #[cfg(test)]
@@ -461,6 +1592,268 @@ mod tests {
         assert!(matches!(result, Err(BeaconClientError::HeaderNotFound(_))));
     }
 
+    /// `/eth/v1/beacon/headers/{id}` body for the header at `slot` 62, with
+    /// `root` as the node-reported root
+    fn header_json(root: &str) -> String {
+        format!(
+            r#"{{
+            "data": {{
+                "root": "{root}",
+                "header": {{
+                    "message": {{
+                        "slot": "62",
+                        "proposer_index": "7",
+                        "parent_root": "0x0101010101010101010101010101010101010101010101010101010101010101",
+                        "state_root": "0x0202020202020202020202020202020202020202020202020202020202020202",
+                        "body_root": "0x0303030303030303030303030303030303030303030303030303030303030303"
+                    }}
+                }}
+            }}
+        }}"#
+        )
+    }
+
+    fn header_json_root() -> [u8; 32] {
+        BeaconBlockHeader {
+            slot: 62,
+            proposer_index: 7,
+            parent_root: [1; 32],
+            state_root: [2; 32],
+            body_root: [3; 32],
+        }
+        .hash_tree_root()
+        .unwrap()
+        .into()
+    }
+
+    #[tokio::test]
+    async fn test_get_header_with_root() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        let root = format!("0x{}", hex::encode(header_json_root()));
+
+        Mock::given(method("GET"))
+            .and(path(format!("/eth/v1/beacon/headers/{root}")))
+            .respond_with(ResponseTemplate::new(200).set_body_string(header_json(&root)))
+            .mount(&mock_server)
+            .await;
+
+        let client = BeaconClient::new(mock_server.uri());
+        let (block_root, header) = client.get_header_with_root(&root).await.unwrap();
+
+        assert_eq!(block_root, header_json_root());
+        assert_eq!(header.slot, 62);
+        assert_eq!(header.state_root[0], 0x02);
+    }
+
+    #[tokio::test]
+    async fn test_get_header_with_root_rejects_wrong_roots() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        let root = format!("0x{}", hex::encode(header_json_root()));
+        let other_root = format!("0x{}", "cc".repeat(32));
+        // A root that the header does not hash to
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/headers/62"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(header_json(&other_root)))
+            .mount(&mock_server)
+            .await;
+        // A consistent header, but not the requested block
+        Mock::given(method("GET"))
+            .and(path(format!("/eth/v1/beacon/headers/{other_root}")))
+            .respond_with(ResponseTemplate::new(200).set_body_string(header_json(&root)))
+            .mount(&mock_server)
+            .await;
+
+        let client = BeaconClient::new(mock_server.uri());
+
+        assert!(matches!(
+            client.get_header_with_root("62").await,
+            Err(BeaconClientError::HeaderRootMismatch { reported, computed })
+                if reported == [0xcc; 32] && computed == header_json_root()
+        ));
+        assert!(matches!(
+            client.get_header_with_root(&other_root).await,
+            Err(BeaconClientError::BlockRootMismatch { requested, actual })
+                if requested == [0xcc; 32] && actual == header_json_root()
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_header_with_root_requires_root() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        let response_json = r#"{
+            "data": {
+                "header": {
+                    "message": {
+                        "slot": "1",
+                        "proposer_index": "1",
+                        "parent_root": "0x0101010101010101010101010101010101010101010101010101010101010101",
+                        "state_root": "0x0202020202020202020202020202020202020202020202020202020202020202",
+                        "body_root": "0x0303030303030303030303030303030303030303030303030303030303030303"
+                    }
+                }
+            }
+        }"#;
+
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/headers/finalized"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(response_json))
+            .mount(&mock_server)
+            .await;
+
+        let client = BeaconClient::new(mock_server.uri());
+        let result = client.get_header_with_root("finalized").await;
+
+        assert!(matches!(result, Err(BeaconClientError::InvalidResponse(_))));
+    }
+
+    fn electra_block(slot: u64) -> SignedBeaconBlock {
+        let mut block = SignedBeaconBlock::default();
+        block.message.slot = slot;
+        block.message.proposer_index = 7;
+        block.message.parent_root = [1; 32];
+        block.message.body.execution_payload.timestamp = 1_700_000_000;
+        block
+    }
+
+    async fn block_server(route: &str, version: &str, body: Vec<u8>) -> wiremock::MockServer {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(route))
+            .and(header("Accept", "application/octet-stream"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("eth-consensus-version", version)
+                    .set_body_bytes(body),
+            )
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn test_get_block_ssz_checks_requested_root() {
+        let block = electra_block(42);
+        let root: [u8; 32] = block
+            .message
+            .header()
+            .unwrap()
+            .hash_tree_root()
+            .unwrap()
+            .into();
+        let block_id = format!("0x{}", hex::encode(root));
+        let server = block_server(
+            &format!("/eth/v2/beacon/blocks/{block_id}"),
+            "electra",
+            ssz_rs::serialize(&block).unwrap(),
+        )
+        .await;
+
+        let client = BeaconClient::new(server.uri());
+        let fetched = client.get_block_ssz(&block_id).await.unwrap();
+        assert_eq!(fetched, block);
+        assert_eq!(
+            fetched.message.body.execution_payload.timestamp,
+            1_700_000_000
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_block_ssz_rejects_root_mismatch() {
+        let block_id = format!("0x{}", hex::encode([9u8; 32]));
+        let server = block_server(
+            &format!("/eth/v2/beacon/blocks/{block_id}"),
+            "electra",
+            ssz_rs::serialize(&electra_block(42)).unwrap(),
+        )
+        .await;
+
+        let client = BeaconClient::new(server.uri());
+        let result = client.get_block_ssz(&block_id).await;
+        assert!(matches!(
+            result,
+            Err(BeaconClientError::BlockRootMismatch { requested, .. }) if requested == [9; 32]
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_block_ssz_rejects_other_forks() {
+        let server = block_server(
+            "/eth/v2/beacon/blocks/42",
+            "deneb",
+            ssz_rs::serialize(&electra_block(42)).unwrap(),
+        )
+        .await;
+
+        let client = BeaconClient::new(server.uri());
+        let result = client.get_block_ssz("42").await;
+        assert!(matches!(result, Err(BeaconClientError::InvalidResponse(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_header_ssz_from_blinded_block() {
+        let mut blinded = SignedBlindedBeaconBlock::default();
+        blinded.message.slot = 42;
+        blinded.message.proposer_index = 7;
+        blinded.message.state_root = [3; 32];
+        let expected = blinded.message.header().unwrap();
+        let server = block_server(
+            "/eth/v1/beacon/blinded_blocks/42",
+            "electra",
+            ssz_rs::serialize(&blinded).unwrap(),
+        )
+        .await;
+
+        let client = BeaconClient::new(server.uri());
+        let header = client.get_header_ssz("42").await.unwrap();
+        assert_eq!(header.message, expected);
+        assert_eq!(header.signature, blinded.signature);
+    }
+
+    #[tokio::test]
+    async fn test_get_header_ssz_slot_mismatch() {
+        let mut blinded = SignedBlindedBeaconBlock::default();
+        blinded.message.slot = 41;
+        let server = block_server(
+            "/eth/v1/beacon/blinded_blocks/42",
+            "electra",
+            ssz_rs::serialize(&blinded).unwrap(),
+        )
+        .await;
+
+        let client = BeaconClient::new(server.uri());
+        let result = client.get_header_ssz("42").await;
+        assert!(matches!(result, Err(BeaconClientError::InvalidResponse(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_block_ssz_not_found() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/eth/v2/beacon/blocks/42"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let client = BeaconClient::new(server.uri());
+        let result = client.get_block_ssz("42").await;
+        assert!(matches!(result, Err(BeaconClientError::HeaderNotFound(42))));
+    }
+
     #[tokio::test]
     async fn test_get_finality_checkpoints() {
         use wiremock::matchers::{method, path};
@@ -663,4 +2056,570 @@ mod tests {
         assert_eq!(info.activation_epoch, 123);
         assert_eq!(info.withdrawal_credentials[0], 0x01);
     }
+
+    fn validator_entry(index: u64, status: &str, slashed: bool) -> String {
+        format!(
+            r#"{{
+                "index": "{index}",
+                "balance": "{balance}",
+                "status": "{status}",
+                "validator": {{
+                    "pubkey": "0x{pubkey}",
+                    "withdrawal_credentials": "0x01{creds}",
+                    "effective_balance": "32000000000",
+                    "slashed": {slashed},
+                    "activation_eligibility_epoch": "1",
+                    "activation_epoch": "2",
+                    "exit_epoch": "18446744073709551615",
+                    "withdrawable_epoch": "18446744073709551615"
+                }}
+            }}"#,
+            balance = 32_000_000_000 + index,
+            pubkey = hex::encode([u8::try_from(index).unwrap(); 48]),
+            creds = "00".repeat(31),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_get_validators_batches_ids() {
+        use wiremock::matchers::{body_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let url_path = "/eth/v1/beacon/states/finalized/validators";
+        Mock::given(method("POST"))
+            .and(path(url_path))
+            .and(body_json(serde_json::json!({
+                "ids": ["3", "5"],
+                "statuses": ["active_ongoing", "active_slashed"]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                r#"{{"data": [{}, {}]}}"#,
+                validator_entry(5, "active_slashed", true),
+                validator_entry(3, "active_ongoing", false)
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+        // Index 9 is unknown to the node and simply omitted
+        Mock::given(method("POST"))
+            .and(path(url_path))
+            .and(body_json(serde_json::json!({
+                "ids": ["7", "9"],
+                "statuses": ["active_ongoing", "active_slashed"]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                r#"{{"data": [{}]}}"#,
+                validator_entry(7, "active_ongoing", false)
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = BeaconClient::builder()
+            .endpoint(server.uri())
+            .validator_batch_size(2)
+            .build()
+            .unwrap();
+        let records = client
+            .get_validators(
+                "finalized",
+                &[7, 3, 9, 5, 3],
+                &[
+                    ValidatorStatus::ActiveOngoing,
+                    ValidatorStatus::ActiveSlashed,
+                ],
+            )
+            .await
+            .unwrap();
+
+        let indices: Vec<u64> = records.iter().map(|r| r.index).collect();
+        assert_eq!(indices, vec![3, 5, 7]);
+        let slashed = &records[1];
+        assert!(slashed.slashed);
+        assert_eq!(slashed.status, ValidatorStatus::ActiveSlashed);
+        assert_eq!(slashed.balance, 32_000_000_005);
+        assert_eq!(slashed.pubkey, [5; 48]);
+        assert_eq!(slashed.withdrawal_credentials[0], 0x01);
+        assert_eq!(slashed.activation_epoch, 2);
+        assert_eq!(slashed.exit_epoch, u64::MAX);
+        assert_eq!(
+            ValidatorInfo::from(slashed),
+            ValidatorInfo {
+                withdrawal_credentials: slashed.withdrawal_credentials,
+                activation_epoch: 2,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_validators_without_ids_sends_empty_filter() {
+        use wiremock::matchers::{body_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/eth/v1/beacon/states/head/validators"))
+            .and(body_json(serde_json::json!({})))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                r#"{{"data": [{}]}}"#,
+                validator_entry(0, "exited_unslashed", false)
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = BeaconClient::new(server.uri());
+        let records = client.get_validators("head", &[], &[]).await.unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].status, ValidatorStatus::ExitedUnslashed);
+    }
+
+    #[tokio::test]
+    async fn test_get_validators_not_found_returns_state_not_found() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/eth/v1/beacon/states/12345/validators"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let client = BeaconClient::new(server.uri());
+        let result = client.get_validators("12345", &[1], &[]).await;
+        assert!(matches!(
+            result,
+            Err(BeaconClientError::StateNotFound(12345))
+        ));
+    }
+
+    #[test]
+    fn test_builder_rejects_zero_validator_batch_size() {
+        let result = BeaconClient::builder()
+            .endpoint("http://a")
+            .validator_batch_size(0)
+            .build();
+        assert!(matches!(result, Err(BeaconClientError::Configuration(_))));
+    }
+
+    async fn pending_consolidations_server(status: u16, body: &str) -> wiremock::MockServer {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(
+                "/eth/v1/beacon/states/finalized/pending_consolidations",
+            ))
+            .respond_with(ResponseTemplate::new(status).set_body_string(body))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    const ONE_CONSOLIDATION: &str = r#"{"data": [{"source_index": "42", "target_index": "100"}]}"#;
+    const OTHER_CONSOLIDATION: &str =
+        r#"{"data": [{"source_index": "43", "target_index": "100"}]}"#;
+
+    #[test]
+    fn test_with_endpoints_validates_configuration() {
+        let none: [&str; 0] = [];
+        assert!(matches!(
+            BeaconClient::with_endpoints(none, ClientMode::Failover),
+            Err(BeaconClientError::Configuration(_))
+        ));
+        assert!(matches!(
+            BeaconClient::with_endpoints(["http://a", "http://b"], ClientMode::Single),
+            Err(BeaconClientError::Configuration(_))
+        ));
+        for threshold in [0, 3] {
+            assert!(matches!(
+                BeaconClient::with_endpoints(
+                    ["http://a", "http://b"],
+                    ClientMode::Quorum { threshold }
+                ),
+                Err(BeaconClientError::Configuration(_))
+            ));
+        }
+        assert!(BeaconClient::with_endpoints(
+            ["http://a", "http://b"],
+            ClientMode::Quorum { threshold: 2 }
+        )
+        .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_failover_skips_failing_endpoint_and_tracks_health() {
+        let broken = pending_consolidations_server(500, "").await;
+        let healthy = pending_consolidations_server(200, ONE_CONSOLIDATION).await;
+
+        let client = BeaconClient::builder()
+            .endpoints([broken.uri(), healthy.uri()])
+            .mode(ClientMode::Failover)
+            .retry(fast_retries(3))
+            .build()
+            .unwrap();
+        let pending = client
+            .get_pending_consolidations("finalized")
+            .await
+            .unwrap();
+        assert_eq!(pending[0].source_index, 42);
+
+        let health = client.endpoint_health();
+        assert_eq!(health[0].failures, 1);
+        assert_eq!(health[0].consecutive_failures, 1);
+        assert!(health[0].last_error.is_some());
+        assert!(!health[0].preferred);
+        assert_eq!(health[1].successes, 1);
+        assert!(health[1].preferred);
+
+        // The healthy endpoint is now tried first
+        client
+            .get_pending_consolidations("finalized")
+            .await
+            .unwrap();
+        let health = client.endpoint_health();
+        assert_eq!(health[0].failures, 1);
+        assert_eq!(health[1].successes, 2);
+    }
+
+    #[tokio::test]
+    async fn test_failover_returns_not_found_over_transport_errors() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let pruned = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/states/120/pending_consolidations"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&pruned)
+            .await;
+
+        let client = BeaconClient::builder()
+            .endpoints([pruned.uri(), "http://127.0.0.1:1".to_string()])
+            .mode(ClientMode::Failover)
+            .retry(fast_retries(3))
+            .build()
+            .unwrap();
+        let error = client.get_pending_consolidations("120").await.unwrap_err();
+        assert!(matches!(error, BeaconClientError::StateNotFound(120)));
+
+        let health = client.endpoint_health();
+        assert_eq!(health[0].failures, 0);
+        assert_eq!(health[1].failures, 1);
+    }
+
+    #[tokio::test]
+    async fn test_failover_rotates_away_from_slow_endpoint() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let slow = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(
+                "/eth/v1/beacon/states/finalized/pending_consolidations",
+            ))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(ONE_CONSOLIDATION)
+                    .set_delay(Duration::from_millis(200)),
+            )
+            .mount(&slow)
+            .await;
+        let fast = pending_consolidations_server(200, ONE_CONSOLIDATION).await;
+
+        let client = BeaconClient::builder()
+            .endpoints([slow.uri(), fast.uri()])
+            .mode(ClientMode::Failover)
+            .latency_threshold(Duration::from_millis(50))
+            .build()
+            .unwrap();
+        client
+            .get_pending_consolidations("finalized")
+            .await
+            .unwrap();
+
+        let health = client.endpoint_health();
+        assert_eq!(health[0].successes, 1);
+        assert!(health[1].preferred);
+    }
+
+    #[tokio::test]
+    async fn test_quorum_returns_agreed_answer() {
+        let a = pending_consolidations_server(200, ONE_CONSOLIDATION).await;
+        let b = pending_consolidations_server(200, OTHER_CONSOLIDATION).await;
+        let c = pending_consolidations_server(200, ONE_CONSOLIDATION).await;
+
+        let client = BeaconClient::with_endpoints(
+            [a.uri(), b.uri(), c.uri()],
+            ClientMode::Quorum { threshold: 2 },
+        )
+        .unwrap();
+        let pending = client
+            .get_pending_consolidations("finalized")
+            .await
+            .unwrap();
+
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].source_index, 42);
+        assert!(client.endpoint_health().iter().all(|h| h.successes == 1));
+    }
+
+    #[tokio::test]
+    async fn test_quorum_fails_on_disagreement() {
+        let a = pending_consolidations_server(200, ONE_CONSOLIDATION).await;
+        let b = pending_consolidations_server(200, OTHER_CONSOLIDATION).await;
+        let c = pending_consolidations_server(500, "").await;
+
+        let client = BeaconClient::builder()
+            .endpoints([a.uri(), b.uri(), c.uri()])
+            .mode(ClientMode::Quorum { threshold: 2 })
+            .retry(fast_retries(3))
+            .build()
+            .unwrap();
+        let error = client
+            .get_pending_consolidations("finalized")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            BeaconClientError::QuorumNotReached {
+                agreeing: 1,
+                required: 2,
+                total: 3
+            }
+        ));
+        assert_eq!(client.endpoint_health()[2].failures, 1);
+    }
+
+    fn fast_retries(max_retries: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_retries,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(10),
+        }
+    }
+
+    #[test]
+    fn test_retry_backoff_doubles_up_to_cap() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(350),
+        };
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(2), Duration::from_millis(350));
+        assert_eq!(policy.backoff(40), Duration::from_millis(350));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(parse_retry_after(&headers), None);
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+        assert_eq!(parse_retry_after(&headers), Some(Duration::from_secs(7)));
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(parse_retry_after(&headers), None);
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_header() {
+        let error = BeaconClient::builder()
+            .endpoint("http://a")
+            .header("bad header", "x")
+            .build()
+            .unwrap_err();
+        assert!(matches!(error, BeaconClientError::Configuration(_)));
+    }
+
+    #[test]
+    fn test_builder_debug_redacts_secrets() {
+        let builder = BeaconClient::builder()
+            .endpoint("http://a")
+            .header("x-api-key", "secret-key")
+            .basic_auth("user", Some("secret-password".to_string()));
+        let debug = format!("{builder:?}");
+        assert!(debug.contains("x-api-key"));
+        assert!(!debug.contains("secret"));
+    }
+
+    #[tokio::test]
+    async fn test_retries_rate_limit_honouring_retry_after() {
+        let server = pending_consolidations_server(200, ONE_CONSOLIDATION).await;
+        {
+            use wiremock::matchers::{method, path};
+            use wiremock::{Mock, ResponseTemplate};
+
+            Mock::given(method("GET"))
+                .and(path(
+                    "/eth/v1/beacon/states/finalized/pending_consolidations",
+                ))
+                .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+                .up_to_n_times(1)
+                .with_priority(1)
+                .mount(&server)
+                .await;
+        }
+
+        let client = BeaconClient::builder()
+            .endpoint(server.uri())
+            .retry(RetryPolicy {
+                // Retry-After: 0 must win over this backoff
+                initial_backoff: Duration::from_secs(60),
+                max_backoff: Duration::from_secs(60),
+                ..fast_retries(1)
+            })
+            .build()
+            .unwrap();
+        let pending = tokio::time::timeout(
+            Duration::from_secs(5),
+            client.get_pending_consolidations("finalized"),
+        )
+        .await
+        .expect("Retry-After was not honoured")
+        .unwrap();
+
+        assert_eq!(pending[0].source_index, 42);
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_exhaust_retries_as_retryable() {
+        let server = pending_consolidations_server(503, "overloaded").await;
+
+        let client = BeaconClient::builder()
+            .endpoint(server.uri())
+            .retry(fast_retries(2))
+            .build()
+            .unwrap();
+        let error = client
+            .get_pending_consolidations("finalized")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, BeaconClientError::Retryable(_)));
+        assert!(error.is_retryable());
+        assert!(error.to_string().contains("overloaded"));
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_permanent_and_not_retried() {
+        let server = pending_consolidations_server(400, "bad state id").await;
+
+        let client = BeaconClient::builder()
+            .endpoint(server.uri())
+            .retry(fast_retries(3))
+            .build()
+            .unwrap();
+        let error = client
+            .get_pending_consolidations("finalized")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            BeaconClientError::Permanent { status: 400, .. }
+        ));
+        assert!(!error.is_retryable());
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout_is_retryable() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/states/head/finality_checkpoints"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(500)))
+            .mount(&server)
+            .await;
+
+        let client = BeaconClient::builder()
+            .endpoint(server.uri())
+            .request_timeout(Duration::from_millis(50))
+            .retry(fast_retries(1))
+            .build()
+            .unwrap();
+        let error = client.get_finality_checkpoints().await.unwrap_err();
+
+        assert!(matches!(error, BeaconClientError::Retryable(_)));
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_default_policy_retries_connection_failures() {
+        let client = BeaconClient::builder()
+            .endpoint("http://127.0.0.1:1")
+            .retry(RetryPolicy {
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(10),
+                ..RetryPolicy::default()
+            })
+            .build()
+            .unwrap();
+        assert_eq!(RetryPolicy::default().max_retries, 3);
+
+        let error = client.get_finality_checkpoints().await.unwrap_err();
+        assert!(matches!(error, BeaconClientError::Retryable(_)));
+    }
+
+    #[tokio::test]
+    async fn test_request_building_errors_are_not_retried() {
+        let client = BeaconClient::builder()
+            .endpoint("ftp://127.0.0.1:1")
+            .retry(RetryPolicy {
+                // A retry would outlast the timeout below
+                initial_backoff: Duration::from_secs(60),
+                max_backoff: Duration::from_secs(60),
+                ..RetryPolicy::default()
+            })
+            .build()
+            .unwrap();
+
+        let error = tokio::time::timeout(Duration::from_secs(5), client.get_finality_checkpoints())
+            .await
+            .expect("request building error was retried")
+            .unwrap_err();
+        assert!(!error.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_custom_headers_and_basic_auth_are_sent() {
+        use wiremock::matchers::{basic_auth, header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(
+                "/eth/v1/beacon/states/finalized/pending_consolidations",
+            ))
+            .and(header("x-api-key", "k3y"))
+            .and(basic_auth("user", "pass"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(ONE_CONSOLIDATION))
+            .mount(&server)
+            .await;
+
+        let client = BeaconClient::builder()
+            .endpoint(server.uri())
+            .header("x-api-key", "k3y")
+            .basic_auth("user", Some("pass".to_string()))
+            .build()
+            .unwrap();
+        let pending = client
+            .get_pending_consolidations("finalized")
+            .await
+            .unwrap();
+
+        assert_eq!(pending[0].source_index, 42);
+    }
 }
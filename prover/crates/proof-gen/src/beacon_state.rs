@@ -12,6 +12,8 @@
 //! These produce proofs with different lengths than gnosis mainnet (which uses 2^40 validators
 //! and 2^18 pending consolidations). The Solidity test vectors generator will account for this.
 
+use crate::beacon_block::ExecutionPayloadHeader;
+use crate::fork::{ForkName, StateLayout};
 use ssz_rs::prelude::*;
 
 /// Checkpoint for fork choice
@@ -99,7 +101,7 @@ pub struct PendingDeposit {
 /// Pending partial withdrawal (Electra)
 #[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
 pub struct PendingPartialWithdrawal {
-    pub index: u64,
+    pub validator_index: u64,
     pub amount: u64,
     pub withdrawable_epoch: u64,
 }
@@ -272,6 +274,245 @@ impl MinimalBeaconState {
     pub const CONSOLIDATION_PROOF_DEPTH_FROM_STATE: u32 = 6 + 1 + 6 + 1;
 }
 
+// ============================================================================
+// Gnosis BeaconState - Production limits for parsing real states
+// ============================================================================
+
+/// Gnosis preset limits for the Electra and Fulu BeaconStates
+pub mod gnosis_limits {
+    /// Block/state roots vector length
+    pub const SLOTS_PER_HISTORICAL_ROOT: usize = 8192;
+    /// Historical roots/summaries limit (2^24)
+    pub const HISTORICAL_ROOTS_LIMIT: usize = 16_777_216;
+    /// `EPOCHS_PER_ETH1_VOTING_PERIOD * SLOTS_PER_EPOCH` = 64 * 16
+    pub const ETH1_DATA_VOTES_LIMIT: usize = 1024;
+    /// Validator registry limit (2^40)
+    pub const VALIDATOR_REGISTRY_LIMIT: usize = 1_099_511_627_776;
+    /// RANDAO mixes vector length
+    pub const EPOCHS_PER_HISTORICAL_VECTOR: usize = 65_536;
+    /// Slashings vector length
+    pub const EPOCHS_PER_SLASHINGS_VECTOR: usize = 8192;
+    /// Pending deposits limit (2^27)
+    pub const PENDING_DEPOSITS_LIMIT: usize = 134_217_728;
+    /// Pending partial withdrawals limit (2^27)
+    pub const PENDING_PARTIAL_WITHDRAWALS_LIMIT: usize = 134_217_728;
+    /// Pending consolidations limit (2^18)
+    pub const PENDING_CONSOLIDATIONS_LIMIT: usize = 262_144;
+    /// `(MIN_SEED_LOOKAHEAD + 1) * SLOTS_PER_EPOCH` = 2 * 16 (Fulu)
+    pub const PROPOSER_LOOKAHEAD_LENGTH: usize = 32;
+}
+
+use gnosis_limits::*;
+
+/// Defines a Gnosis BeaconState container: the Electra fields, then the
+/// fields later forks append, with a `field_roots` accessor over all of them
+macro_rules! gnosis_beacon_state {
+    (@define $(#[$meta:meta])* $name:ident { $($field:ident: $ty:ty),* $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+        pub struct $name {
+            $(pub $field: $ty,)*
+        }
+
+        impl $name {
+            /// Hash tree roots of all fields, in field order
+            ///
+            /// # Errors
+            /// Returns error if a field cannot be merkleized
+            pub fn field_roots(&self) -> Result<Vec<[u8; 32]>, MerkleizationError> {
+                Ok(vec![$(self.$field.hash_tree_root()?.into()),*])
+            }
+        }
+    };
+    ($(#[$meta:meta])* $name:ident { $($extra:ident: $extra_ty:ty),* $(,)? }) => {
+        gnosis_beacon_state!(@define $(#[$meta])* $name {
+            genesis_time: u64,
+            genesis_validators_root: [u8; 32],
+            slot: u64,
+            fork: Fork,
+            latest_block_header: BeaconBlockHeader,
+            block_roots: Vector<[u8; 32], SLOTS_PER_HISTORICAL_ROOT>,
+            state_roots: Vector<[u8; 32], SLOTS_PER_HISTORICAL_ROOT>,
+            historical_roots: List<[u8; 32], HISTORICAL_ROOTS_LIMIT>,
+            eth1_data: Eth1Data,
+            eth1_data_votes: List<Eth1Data, ETH1_DATA_VOTES_LIMIT>,
+            eth1_deposit_index: u64,
+            validators: List<Validator, VALIDATOR_REGISTRY_LIMIT>,
+            balances: List<u64, VALIDATOR_REGISTRY_LIMIT>,
+            randao_mixes: Vector<[u8; 32], EPOCHS_PER_HISTORICAL_VECTOR>,
+            slashings: Vector<u64, EPOCHS_PER_SLASHINGS_VECTOR>,
+            previous_epoch_participation: List<u8, VALIDATOR_REGISTRY_LIMIT>,
+            current_epoch_participation: List<u8, VALIDATOR_REGISTRY_LIMIT>,
+            justification_bits: Bitvector<4>,
+            previous_justified_checkpoint: Checkpoint,
+            current_justified_checkpoint: Checkpoint,
+            finalized_checkpoint: Checkpoint,
+            inactivity_scores: List<u64, VALIDATOR_REGISTRY_LIMIT>,
+            current_sync_committee: SyncCommittee,
+            next_sync_committee: SyncCommittee,
+            latest_execution_payload_header: ExecutionPayloadHeader,
+            next_withdrawal_index: u64,
+            next_withdrawal_validator_index: u64,
+            historical_summaries: List<HistoricalSummary, HISTORICAL_ROOTS_LIMIT>,
+            deposit_requests_start_index: u64,
+            deposit_balance_to_consume: u64,
+            exit_balance_to_consume: u64,
+            earliest_exit_epoch: u64,
+            consolidation_balance_to_consume: u64,
+            earliest_consolidation_epoch: u64,
+            pending_deposits: List<PendingDeposit, PENDING_DEPOSITS_LIMIT>,
+            pending_partial_withdrawals: List<PendingPartialWithdrawal, PENDING_PARTIAL_WITHDRAWALS_LIMIT>,
+            pending_consolidations: List<PendingConsolidation, PENDING_CONSOLIDATIONS_LIMIT>,
+            $($extra: $extra_ty,)*
+        });
+    };
+}
+
+gnosis_beacon_state! {
+    /// Electra BeaconState with Gnosis preset limits
+    ///
+    /// The layout served by `/eth/v2/debug/beacon/states` and stored in state
+    /// dumps and era files. Hashing is fine at these limits; use [`StateProver`]
+    /// (via [`GnosisBeaconState::field_roots`]) rather than `ssz_rs::Prove` for
+    /// proofs.
+    ///
+    /// [`StateProver`]: crate::StateProver
+    GnosisBeaconState {}
+}
+
+gnosis_beacon_state! {
+    /// Fulu BeaconState with Gnosis preset limits: Electra plus
+    /// `proposer_lookahead`
+    GnosisFuluBeaconState {
+        proposer_lookahead: Vector<u64, PROPOSER_LOOKAHEAD_LENGTH>,
+    }
+}
+
+impl GnosisBeaconState {
+    /// Tree depth for validators list: log2(2^40) = 40
+    pub const VALIDATORS_TREE_DEPTH: u32 = 40;
+
+    /// Tree depth for pending consolidations list: log2(2^18) = 18
+    pub const PENDING_CONSOLIDATIONS_TREE_DEPTH: u32 = 18;
+
+    /// Byte offset of `slot` in the SSZ encoding (after `genesis_time` and
+    /// `genesis_validators_root`), readable without decoding the state
+    pub const SLOT_OFFSET: usize = 8 + 32;
+
+    /// Byte offset of `fork.current_version` in the SSZ encoding (after `slot`
+    /// and `fork.previous_version`); the same in every fork
+    pub const FORK_VERSION_OFFSET: usize = Self::SLOT_OFFSET + 8 + 4;
+}
+
+/// A Gnosis state decoded with the container layout of its fork
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionedGnosisState {
+    Electra(Box<GnosisBeaconState>),
+    Fulu(Box<GnosisFuluBeaconState>),
+}
+
+impl VersionedGnosisState {
+    /// Fork whose layout the state was decoded with
+    pub fn fork(&self) -> ForkName {
+        match self {
+            Self::Electra(_) => ForkName::Electra,
+            Self::Fulu(_) => ForkName::Fulu,
+        }
+    }
+
+    /// Container layout of the state
+    pub fn layout(&self) -> StateLayout {
+        match self {
+            Self::Electra(_) => StateLayout::ELECTRA,
+            Self::Fulu(_) => StateLayout::FULU,
+        }
+    }
+
+    /// Genesis time in seconds since the Unix epoch
+    pub fn genesis_time(&self) -> u64 {
+        match self {
+            Self::Electra(state) => state.genesis_time,
+            Self::Fulu(state) => state.genesis_time,
+        }
+    }
+
+    /// State slot
+    pub fn slot(&self) -> u64 {
+        match self {
+            Self::Electra(state) => state.slot,
+            Self::Fulu(state) => state.slot,
+        }
+    }
+
+    /// Block roots of the last `SLOTS_PER_HISTORICAL_ROOT` slots, indexed by
+    /// `slot % SLOTS_PER_HISTORICAL_ROOT`
+    pub fn block_roots(&self) -> &[[u8; 32]] {
+        match self {
+            Self::Electra(state) => &state.block_roots,
+            Self::Fulu(state) => &state.block_roots,
+        }
+    }
+
+    /// Validator registry
+    pub fn validators(&self) -> &[Validator] {
+        match self {
+            Self::Electra(state) => &state.validators,
+            Self::Fulu(state) => &state.validators,
+        }
+    }
+
+    /// Pending consolidations queue
+    pub fn pending_consolidations(&self) -> &[PendingConsolidation] {
+        match self {
+            Self::Electra(state) => &state.pending_consolidations,
+            Self::Fulu(state) => &state.pending_consolidations,
+        }
+    }
+
+    /// Validator balances in gwei
+    pub fn balances(&self) -> &[u64] {
+        match self {
+            Self::Electra(state) => &state.balances,
+            Self::Fulu(state) => &state.balances,
+        }
+    }
+
+    /// Pending deposits queue
+    pub fn pending_deposits(&self) -> &[PendingDeposit] {
+        match self {
+            Self::Electra(state) => &state.pending_deposits,
+            Self::Fulu(state) => &state.pending_deposits,
+        }
+    }
+
+    /// Pending partial withdrawals queue
+    pub fn pending_partial_withdrawals(&self) -> &[PendingPartialWithdrawal] {
+        match self {
+            Self::Electra(state) => &state.pending_partial_withdrawals,
+            Self::Fulu(state) => &state.pending_partial_withdrawals,
+        }
+    }
+
+    /// Historical summaries since Capella
+    pub fn historical_summaries(&self) -> &[HistoricalSummary] {
+        match self {
+            Self::Electra(state) => &state.historical_summaries,
+            Self::Fulu(state) => &state.historical_summaries,
+        }
+    }
+
+    /// Hash tree roots of all fields, in field order
+    ///
+    /// # Errors
+    /// Returns error if a field cannot be merkleized
+    pub fn field_roots(&self) -> Result<Vec<[u8; 32]>, MerkleizationError> {
+        match self {
+            Self::Electra(state) => state.field_roots(),
+            Self::Fulu(state) => state.field_roots(),
+        }
+    }
+}
+
 // ============================================================================
 // Test-only BeaconState with tiny limits (for unit tests)
 // ============================================================================
@@ -395,6 +636,36 @@ mod tests {
         assert_ne!(root_bytes, [0u8; 32]);
     }
 
+    #[test]
+    fn test_gnosis_state_slot_offset_and_field_roots() {
+        let state = GnosisBeaconState {
+            slot: 0x0102_0304,
+            ..Default::default()
+        };
+        let encoded = ssz_rs::serialize(&state).expect("serialize");
+        let offset = GnosisBeaconState::SLOT_OFFSET;
+        let slot = u64::from_le_bytes(encoded[offset..offset + 8].try_into().unwrap());
+        assert_eq!(slot, state.slot);
+        assert_eq!(state.field_roots().expect("field roots").len(), 37);
+    }
+
+    #[test]
+    fn test_gnosis_state_fork_version_offset() {
+        let mut state = GnosisFuluBeaconState::default();
+        state.fork.previous_version = [0x05, 0x00, 0x00, 0x64];
+        state.fork.current_version = [0x06, 0x00, 0x00, 0x64];
+        let encoded = ssz_rs::serialize(&state).expect("serialize");
+        let offset = GnosisBeaconState::FORK_VERSION_OFFSET;
+        assert_eq!(encoded[offset..offset + 4], state.fork.current_version);
+
+        let fields = state.field_roots().expect("field roots");
+        assert_eq!(fields.len(), StateLayout::FULU.field_count);
+        assert_eq!(
+            fields[37],
+            <[u8; 32]>::from(state.proposer_lookahead.hash_tree_root().unwrap())
+        );
+    }
+
     #[test]
     fn test_validator_proof() {
         let mut validator = Validator::default();
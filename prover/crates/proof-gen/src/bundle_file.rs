@@ -0,0 +1,790 @@
+//! Proof Bundle Files
+//!
+//! A plain [`ConsolidationProofBundle`] doesn't say which chain, contract or
+//! block it was proven against, so a bundle handed to someone else can't be
+//! checked before it is submitted. [`ProofBundleV1`] wraps the bundle with
+//! that context and a format version.
+//!
+//! ## Encodings
+//!
+//! - **JSON**: the bundle is nested unchanged under `bundle`; the layout is
+//!   described by [`PROOF_BUNDLE_V1_SCHEMA`]
+//! - **SSZ**: a fixed container with the fork as its ordinal in
+//!   [`ForkName::ALL`], a zero address for an unbound contract and the target
+//!   proof as a list of at most one element
+//!
+//! [`ProofBundleV1::load`] accepts either encoding, migrates plain bundle JSON
+//! written before this format given a [`BundleContext`], and validates the
+//! result.
+
+use crate::fork::{ForkName, ForkRegistry, StateLayout};
+use crate::gindex::GindexCalculator;
+use crate::proof::{ConsolidationProofBundle, ProofError, ProofGenerator, TargetProof};
+use crate::types::preset;
+use serde::{Deserialize, Serialize};
+use ssz_rs::prelude::*;
+use std::path::Path;
+use thiserror::Error;
+
+/// Current bundle file format version
+pub const PROOF_BUNDLE_VERSION: u32 = 1;
+
+/// EIP-155 chain ID of Gnosis Chain
+pub const GNOSIS_CHAIN_ID: u64 = 100;
+
+/// Longest proof the SSZ encoding can carry
+pub const MAX_PROOF_LENGTH: usize = 64;
+
+/// JSON Schema of the [`ProofBundleV1`] JSON encoding
+pub const PROOF_BUNDLE_V1_SCHEMA: &str = include_str!("../schemas/proof_bundle_v1.schema.json");
+
+/// Errors reading, writing or validating bundle files
+#[derive(Error, Debug)]
+pub enum BundleFileError {
+    #[error("Failed to access bundle file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid bundle JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Invalid bundle SSZ: {0}")]
+    Ssz(String),
+
+    #[error("Unsupported bundle format version {0}")]
+    UnsupportedVersion(u64),
+
+    #[error("Plain bundle carries no chain context; one is needed to migrate it")]
+    MissingContext,
+
+    #[error("Unknown fork ordinal {0}")]
+    UnknownFork(u8),
+
+    #[error("Fork {0} has no pending consolidations")]
+    UnsupportedFork(ForkName),
+
+    #[error("Slot {slot} is in {expected} on Gnosis, bundle says {actual}")]
+    ForkMismatch {
+        slot: u64,
+        expected: ForkName,
+        actual: ForkName,
+    },
+
+    #[error("{proof} proof has {actual} nodes, expected {expected}")]
+    ProofLength {
+        proof: &'static str,
+        actual: usize,
+        expected: usize,
+    },
+
+    #[error("Bundle is for chain {actual}, expected {expected}")]
+    ChainMismatch { expected: u64, actual: u64 },
+
+    #[error(
+        "Bundle is for contract 0x{}, expected 0x{}",
+        hex::encode(.actual),
+        hex::encode(.expected)
+    )]
+    ContractMismatch {
+        expected: [u8; 20],
+        actual: [u8; 20],
+    },
+
+    #[error("Invalid proof: {0}")]
+    Proof(#[from] ProofError),
+}
+
+/// What [`ProofBundleV1`] adds to a plain bundle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BundleContext {
+    /// EIP-155 chain ID the claim is submitted to
+    pub chain_id: u64,
+    /// Contract the bundle is meant for, `None` if not bound to one
+    pub contract_address: Option<[u8; 20]>,
+    /// Fork of the proven state
+    pub fork: ForkName,
+    /// Block root the proofs verify against
+    pub block_root: [u8; 32],
+    /// Slot of the proven block
+    pub slot: u64,
+}
+
+impl BundleContext {
+    /// Gnosis Chain context for a block, with the fork taken from the schedule
+    #[must_use]
+    pub fn gnosis(block_root: [u8; 32], slot: u64) -> Self {
+        let fork = ForkRegistry::gnosis()
+            .at_epoch(slot / preset::SLOTS_PER_EPOCH)
+            .map_or(ForkName::Phase0, |fork| fork.name);
+        Self {
+            chain_id: GNOSIS_CHAIN_ID,
+            contract_address: None,
+            fork,
+            block_root,
+            slot,
+        }
+    }
+
+    /// Bind the context to a contract
+    #[must_use]
+    pub fn with_contract(mut self, contract_address: [u8; 20]) -> Self {
+        self.contract_address = Some(contract_address);
+        self
+    }
+}
+
+/// Versioned, self-describing consolidation proof bundle
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProofBundleV1 {
+    /// Format version, [`PROOF_BUNDLE_VERSION`]
+    pub version: u32,
+
+    /// EIP-155 chain ID the claim is submitted to
+    pub chain_id: u64,
+
+    /// Contract the bundle is meant for, `None` if not bound to one
+    #[serde(with = "option_hex_serde")]
+    pub contract_address: Option<[u8; 20]>,
+
+    /// Fork of the proven state
+    pub fork: ForkName,
+
+    /// Block root the proofs verify against
+    #[serde(with = "hex_serde")]
+    pub block_root: [u8; 32],
+
+    /// Slot of the proven block
+    pub slot: u64,
+
+    /// The proofs themselves
+    pub bundle: ConsolidationProofBundle,
+}
+
+impl ProofBundleV1 {
+    /// Wrap a bundle proven in `context`
+    #[must_use]
+    pub fn new(context: BundleContext, bundle: ConsolidationProofBundle) -> Self {
+        Self {
+            version: PROOF_BUNDLE_VERSION,
+            chain_id: context.chain_id,
+            contract_address: context.contract_address,
+            fork: context.fork,
+            block_root: context.block_root,
+            slot: context.slot,
+            bundle,
+        }
+    }
+
+    /// Context the bundle was proven in
+    #[must_use]
+    pub fn context(&self) -> BundleContext {
+        BundleContext {
+            chain_id: self.chain_id,
+            contract_address: self.contract_address,
+            fork: self.fork,
+            block_root: self.block_root,
+            slot: self.slot,
+        }
+    }
+
+    /// Decode and validate a bundle file in either encoding
+    ///
+    /// Plain bundle JSON is migrated using `legacy`.
+    ///
+    /// # Errors
+    /// Returns an error if the file can't be decoded, is a plain bundle and
+    /// `legacy` is `None`, or fails [`Self::validate`]
+    pub fn load(bytes: &[u8], legacy: Option<&BundleContext>) -> Result<Self, BundleFileError> {
+        let first = bytes.iter().find(|b| !b.is_ascii_whitespace());
+        let bundle = if first == Some(&b'{') {
+            let value: serde_json::Value = serde_json::from_slice(bytes)?;
+            if value.get("version").is_some() {
+                Self::from_json_value(value)?
+            } else {
+                let context = legacy.ok_or(BundleFileError::MissingContext)?;
+                Self::new(*context, serde_json::from_value(value)?)
+            }
+        } else {
+            Self::from_ssz(bytes)?
+        };
+        bundle.validate()?;
+        Ok(bundle)
+    }
+
+    /// Read a bundle file and [`Self::load`] it
+    ///
+    /// # Errors
+    /// Returns an error if the file can't be read or loaded
+    pub fn load_file(path: &Path, legacy: Option<&BundleContext>) -> Result<Self, BundleFileError> {
+        Self::load(&std::fs::read(path)?, legacy)
+    }
+
+    /// Write the bundle as SSZ if `path` ends in `.ssz`, JSON otherwise
+    ///
+    /// # Errors
+    /// Returns an error if encoding or writing fails
+    pub fn write_file(&self, path: &Path) -> Result<(), BundleFileError> {
+        let bytes = if path.extension().is_some_and(|ext| ext == "ssz") {
+            self.to_ssz()?
+        } else {
+            self.to_json()?.into_bytes()
+        };
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    /// Encode as pretty-printed JSON
+    ///
+    /// # Errors
+    /// Returns an error if serialization fails
+    pub fn to_json(&self) -> Result<String, BundleFileError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Decode JSON without validating the proofs
+    ///
+    /// # Errors
+    /// Returns an error if the JSON is malformed or of another version
+    pub fn from_json(json: &str) -> Result<Self, BundleFileError> {
+        Self::from_json_value(serde_json::from_str(json)?)
+    }
+
+    fn from_json_value(value: serde_json::Value) -> Result<Self, BundleFileError> {
+        if let Some(version) = value.get("version").and_then(serde_json::Value::as_u64) {
+            if version != u64::from(PROOF_BUNDLE_VERSION) {
+                return Err(BundleFileError::UnsupportedVersion(version));
+            }
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Encode as SSZ
+    ///
+    /// # Errors
+    /// Returns an error if a proof is longer than [`MAX_PROOF_LENGTH`]
+    pub fn to_ssz(&self) -> Result<Vec<u8>, BundleFileError> {
+        let bundle = &self.bundle;
+        let target = match &bundle.target {
+            Some(target) => vec![TargetProofSsz {
+                target_index: target.target_index,
+                target_credentials: target.target_credentials,
+                target_effective_balance: target.target_effective_balance,
+                proof_target_index: to_branch(&target.proof_target_index)?,
+                proof_target_credentials: to_branch(&target.proof_target_credentials)?,
+                proof_target_effective_balance: to_branch(&target.proof_target_effective_balance)?,
+            }],
+            None => vec![],
+        };
+        let fork = ForkName::ALL
+            .iter()
+            .position(|fork| *fork == self.fork)
+            .expect("every fork is in ForkName::ALL");
+        let container = ProofBundleV1Ssz {
+            version: self.version,
+            chain_id: self.chain_id,
+            contract_address: self.contract_address.unwrap_or_default(),
+            fork: fork as u8,
+            block_root: self.block_root,
+            slot: self.slot,
+            beacon_timestamp: bundle.beacon_timestamp,
+            consolidation_index: bundle.consolidation_index,
+            source_index: bundle.source_index,
+            activation_epoch: bundle.activation_epoch,
+            source_credentials: bundle.source_credentials,
+            proof_consolidation: to_branch(&bundle.proof_consolidation)?,
+            proof_credentials: to_branch(&bundle.proof_credentials)?,
+            proof_activation_epoch: to_branch(&bundle.proof_activation_epoch)?,
+            target: List::try_from(target)
+                .map_err(|_| BundleFileError::Ssz("More than one target proof".to_string()))?,
+        };
+        ssz_rs::serialize(&container).map_err(|e| BundleFileError::Ssz(format!("{e:?}")))
+    }
+
+    /// Decode SSZ without validating the proofs
+    ///
+    /// # Errors
+    /// Returns an error if the encoding is malformed or of another version
+    pub fn from_ssz(bytes: &[u8]) -> Result<Self, BundleFileError> {
+        // Every version starts with its version number
+        let version = bytes
+            .get(..4)
+            .map(|prefix| u32::from_le_bytes(prefix.try_into().expect("4 bytes")))
+            .ok_or_else(|| BundleFileError::Ssz("Missing version".to_string()))?;
+        if version != PROOF_BUNDLE_VERSION {
+            return Err(BundleFileError::UnsupportedVersion(u64::from(version)));
+        }
+        let container: ProofBundleV1Ssz =
+            ssz_rs::deserialize(bytes).map_err(|e| BundleFileError::Ssz(format!("{e:?}")))?;
+        let fork = *ForkName::ALL
+            .get(usize::from(container.fork))
+            .ok_or(BundleFileError::UnknownFork(container.fork))?;
+        let target = container.target.iter().next().map(|target| TargetProof {
+            target_index: target.target_index,
+            target_credentials: target.target_credentials,
+            target_effective_balance: target.target_effective_balance,
+            proof_target_index: target.proof_target_index.to_vec(),
+            proof_target_credentials: target.proof_target_credentials.to_vec(),
+            proof_target_effective_balance: target.proof_target_effective_balance.to_vec(),
+        });
+        Ok(Self {
+            version: container.version,
+            chain_id: container.chain_id,
+            contract_address: Some(container.contract_address)
+                .filter(|address| *address != [0u8; 20]),
+            fork,
+            block_root: container.block_root,
+            slot: container.slot,
+            bundle: ConsolidationProofBundle {
+                beacon_timestamp: container.beacon_timestamp,
+                consolidation_index: container.consolidation_index,
+                source_index: container.source_index,
+                activation_epoch: container.activation_epoch,
+                source_credentials: container.source_credentials,
+                proof_consolidation: container.proof_consolidation.to_vec(),
+                proof_credentials: container.proof_credentials.to_vec(),
+                proof_activation_epoch: container.proof_activation_epoch.to_vec(),
+                target,
+            },
+        })
+    }
+
+    /// Check the version, the fork against the Gnosis schedule, the proof
+    /// lengths and the proofs against `block_root`
+    ///
+    /// # Errors
+    /// Returns the first check that fails
+    pub fn validate(&self) -> Result<(), BundleFileError> {
+        if self.version != PROOF_BUNDLE_VERSION {
+            return Err(BundleFileError::UnsupportedVersion(u64::from(self.version)));
+        }
+        if StateLayout::for_fork(self.fork).is_none() {
+            return Err(BundleFileError::UnsupportedFork(self.fork));
+        }
+        if self.chain_id == GNOSIS_CHAIN_ID {
+            let expected = BundleContext::gnosis(self.block_root, self.slot).fork;
+            if expected != self.fork {
+                return Err(BundleFileError::ForkMismatch {
+                    slot: self.slot,
+                    expected,
+                    actual: self.fork,
+                });
+            }
+        }
+
+        let lengths =
+            GindexCalculator::expected_proof_lengths(self.fork).map_err(ProofError::from)?;
+        let consolidation = lengths.consolidation as usize;
+        let validator = lengths.validator as usize;
+        let bundle = &self.bundle;
+        let mut proofs = vec![
+            ("consolidation", &bundle.proof_consolidation, consolidation),
+            ("credentials", &bundle.proof_credentials, validator),
+            (
+                "activation epoch",
+                &bundle.proof_activation_epoch,
+                validator,
+            ),
+        ];
+        if let Some(target) = &bundle.target {
+            proofs.extend([
+                ("target index", &target.proof_target_index, consolidation),
+                (
+                    "target credentials",
+                    &target.proof_target_credentials,
+                    validator,
+                ),
+                (
+                    "target effective balance",
+                    &target.proof_target_effective_balance,
+                    validator,
+                ),
+            ]);
+        }
+        for (proof, branch, expected) in proofs {
+            if branch.len() != expected {
+                return Err(BundleFileError::ProofLength {
+                    proof,
+                    actual: branch.len(),
+                    expected,
+                });
+            }
+        }
+
+        ProofGenerator::verify_proof_bundle(bundle, self.block_root)?;
+        Ok(())
+    }
+
+    /// Check the bundle is meant for `chain_id` and `contract_address`
+    ///
+    /// A bundle not bound to a contract matches any contract.
+    ///
+    /// # Errors
+    /// Returns [`BundleFileError::ChainMismatch`] or
+    /// [`BundleFileError::ContractMismatch`]
+    pub fn check_deployment(
+        &self,
+        chain_id: u64,
+        contract_address: [u8; 20],
+    ) -> Result<(), BundleFileError> {
+        if self.chain_id != chain_id {
+            return Err(BundleFileError::ChainMismatch {
+                expected: chain_id,
+                actual: self.chain_id,
+            });
+        }
+        match self.contract_address {
+            Some(actual) if actual != contract_address => Err(BundleFileError::ContractMismatch {
+                expected: contract_address,
+                actual,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Proof branch in the SSZ encoding
+type Branch = List<[u8; 32], MAX_PROOF_LENGTH>;
+
+fn to_branch(proof: &[[u8; 32]]) -> Result<Branch, BundleFileError> {
+    Branch::try_from(proof.to_vec()).map_err(|_| {
+        BundleFileError::Ssz(format!(
+            "Proof of {} nodes exceeds {MAX_PROOF_LENGTH}",
+            proof.len()
+        ))
+    })
+}
+
+/// SSZ encoding of [`ProofBundleV1`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+struct ProofBundleV1Ssz {
+    version: u32,
+    chain_id: u64,
+    contract_address: [u8; 20],
+    fork: u8,
+    block_root: [u8; 32],
+    slot: u64,
+    beacon_timestamp: u64,
+    consolidation_index: u64,
+    source_index: u64,
+    activation_epoch: u64,
+    source_credentials: [u8; 32],
+    proof_consolidation: Branch,
+    proof_credentials: Branch,
+    proof_activation_epoch: Branch,
+    target: List<TargetProofSsz, 1>,
+}
+
+/// SSZ encoding of [`TargetProof`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
+struct TargetProofSsz {
+    target_index: u64,
+    target_credentials: [u8; 32],
+    target_effective_balance: u64,
+    proof_target_index: Branch,
+    proof_target_credentials: Branch,
+    proof_target_effective_balance: Branch,
+}
+
+fn parse_hex_array<const N: usize>(s: &str) -> Result<[u8; N], String> {
+    let bytes = hex::decode(s.strip_prefix("0x").unwrap_or(s)).map_err(|e| e.to_string())?;
+    bytes.try_into().map_err(|_| format!("expected {N} bytes"))
+}
+
+/// 0x-prefixed hex for fixed-size byte arrays
+mod hex_serde {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<const N: usize, S>(data: &[u8; N], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&format!("0x{}", hex::encode(data)))
+    }
+
+    pub fn deserialize<'de, const N: usize, D>(deserializer: D) -> Result<[u8; N], D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        super::parse_hex_array(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Like [`hex_serde`], with `null` for `None`
+mod option_hex_serde {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<const N: usize, S>(
+        data: &Option<[u8; N]>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match data {
+            Some(data) => super::hex_serde::serialize(data, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, const N: usize, D>(deserializer: D) -> Result<Option<[u8; N]>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<String>::deserialize(deserializer)?
+            .map(|s| super::parse_hex_array(&s).map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::beacon_state::{
+        BeaconBlockHeader, GnosisBeaconState, PendingConsolidation, Validator,
+    };
+    use crate::state_prover::StateProver;
+
+    /// First Electra slot on Gnosis: epoch 1337856 * 16
+    const ELECTRA_SLOT: u64 = 21_405_696;
+
+    const CONTRACT: [u8; 20] = [0xcc; 20];
+
+    /// Bundle for consolidation 0 of a small state at `slot`, and its block root
+    fn proven_bundle(slot: u64) -> (ConsolidationProofBundle, [u8; 32]) {
+        let mut state = GnosisBeaconState {
+            slot,
+            ..GnosisBeaconState::default()
+        };
+        for i in 0..3u8 {
+            let mut validator = Validator::default();
+            validator.withdrawal_credentials[0] = 0x01;
+            validator.withdrawal_credentials[12..].copy_from_slice(&[i; 20]);
+            validator.activation_epoch = 10 + u64::from(i);
+            validator.effective_balance = 32_000_000_000;
+            state.validators.push(validator);
+        }
+        state.pending_consolidations.push(PendingConsolidation {
+            source_index: 1,
+            target_index: 2,
+        });
+        let header = BeaconBlockHeader {
+            slot,
+            proposer_index: 0,
+            parent_root: [0x01; 32],
+            state_root: state.hash_tree_root().unwrap().into(),
+            body_root: [0x02; 32],
+        };
+        let block_root: [u8; 32] = header.hash_tree_root().unwrap().into();
+        let prover = StateProver::from_gnosis_state(&state).unwrap();
+        let bundle = prover
+            .generate_full_proof_bundle(&header, 0, 1_700_000_000)
+            .unwrap();
+        (bundle, block_root)
+    }
+
+    fn proven_file() -> ProofBundleV1 {
+        let (bundle, block_root) = proven_bundle(ELECTRA_SLOT);
+        let context = BundleContext::gnosis(block_root, ELECTRA_SLOT).with_contract(CONTRACT);
+        ProofBundleV1::new(context, bundle)
+    }
+
+    #[test]
+    #[cfg(all(feature = "gnosis", not(feature = "minimal")))]
+    fn test_gnosis_context_uses_fork_schedule() {
+        assert_eq!(
+            BundleContext::gnosis([0; 32], ELECTRA_SLOT).fork,
+            ForkName::Electra
+        );
+        assert_eq!(
+            BundleContext::gnosis([0; 32], ELECTRA_SLOT - 1).fork,
+            ForkName::Deneb
+        );
+    }
+
+    #[test]
+    fn test_json_roundtrip_and_layout() {
+        let file = proven_file();
+        let json = file.to_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["version"], 1);
+        assert_eq!(value["chain_id"], 100);
+        assert_eq!(value["fork"], "electra");
+        assert_eq!(
+            value["contract_address"],
+            format!("0x{}", hex::encode(CONTRACT))
+        );
+        assert_eq!(
+            value["block_root"],
+            format!("0x{}", hex::encode(file.block_root))
+        );
+        assert_eq!(value["bundle"]["source_index"], 1);
+
+        let decoded = ProofBundleV1::from_json(&json).unwrap();
+        assert_eq!(decoded.to_json().unwrap(), json);
+        assert_eq!(decoded.context(), file.context());
+    }
+
+    #[test]
+    fn test_schema_describes_json_encoding() {
+        let schema: serde_json::Value = serde_json::from_str(PROOF_BUNDLE_V1_SCHEMA).unwrap();
+        let value = serde_json::to_value(proven_file()).unwrap();
+        let keys = |v: &serde_json::Value| {
+            let mut keys: Vec<String> = v.as_object().unwrap().keys().cloned().collect();
+            keys.sort();
+            keys
+        };
+
+        let mut required: Vec<String> = serde_json::from_value(schema["required"].clone()).unwrap();
+        required.sort();
+        assert_eq!(keys(&value), required);
+        assert_eq!(keys(&value), keys(&schema["properties"]));
+
+        let bundle_schema = &schema["$defs"]["consolidationProofBundle"];
+        let mut bundle_required: Vec<String> =
+            serde_json::from_value(bundle_schema["required"].clone()).unwrap();
+        bundle_required.sort();
+        assert_eq!(keys(&value["bundle"]), bundle_required);
+
+        let forks: Vec<ForkName> =
+            serde_json::from_value(schema["properties"]["fork"]["enum"].clone()).unwrap();
+        assert_eq!(forks, ForkName::ALL);
+    }
+
+    #[test]
+    fn test_ssz_roundtrip() {
+        let mut file = proven_file();
+        let ssz = file.to_ssz().unwrap();
+        let decoded = ProofBundleV1::from_ssz(&ssz).unwrap();
+        assert_eq!(decoded.to_json().unwrap(), file.to_json().unwrap());
+
+        // An unbound contract is encoded as the zero address
+        file.contract_address = None;
+        let decoded = ProofBundleV1::from_ssz(&file.to_ssz().unwrap()).unwrap();
+        assert_eq!(decoded.contract_address, None);
+
+        let mut too_long = file.clone();
+        too_long.bundle.proof_consolidation = vec![[0; 32]; MAX_PROOF_LENGTH + 1];
+        assert!(matches!(too_long.to_ssz(), Err(BundleFileError::Ssz(_))));
+    }
+
+    #[test]
+    #[cfg(all(feature = "gnosis", not(feature = "minimal")))]
+    fn test_load_detects_encoding_and_validates() {
+        let file = proven_file();
+        let from_json = ProofBundleV1::load(file.to_json().unwrap().as_bytes(), None).unwrap();
+        let from_ssz = ProofBundleV1::load(&file.to_ssz().unwrap(), None).unwrap();
+        assert_eq!(from_json.context(), file.context());
+        assert_eq!(from_ssz.context(), file.context());
+
+        let mut wrong_root = file.clone();
+        wrong_root.block_root = [0xaa; 32];
+        assert!(matches!(
+            ProofBundleV1::load(&wrong_root.to_ssz().unwrap(), None),
+            Err(BundleFileError::Proof(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(all(feature = "gnosis", not(feature = "minimal")))]
+    fn test_load_migrates_plain_bundles() {
+        let file = proven_file();
+        let plain = serde_json::to_vec(&file.bundle).unwrap();
+
+        assert!(matches!(
+            ProofBundleV1::load(&plain, None),
+            Err(BundleFileError::MissingContext)
+        ));
+
+        let context = file.context();
+        let migrated = ProofBundleV1::load(&plain, Some(&context)).unwrap();
+        assert_eq!(migrated.version, PROOF_BUNDLE_VERSION);
+        assert_eq!(migrated.context(), context);
+        assert_eq!(migrated.to_json().unwrap(), file.to_json().unwrap());
+    }
+
+    #[test]
+    #[cfg(all(feature = "gnosis", not(feature = "minimal")))]
+    fn test_validate_rejects_inconsistent_context() {
+        let file = proven_file();
+        file.validate().unwrap();
+
+        let mut future = serde_json::to_value(&file).unwrap();
+        future["version"] = 2.into();
+        assert!(matches!(
+            ProofBundleV1::from_json(&future.to_string()),
+            Err(BundleFileError::UnsupportedVersion(2))
+        ));
+        let mut ssz = file.to_ssz().unwrap();
+        ssz[0] = 2;
+        assert!(matches!(
+            ProofBundleV1::from_ssz(&ssz),
+            Err(BundleFileError::UnsupportedVersion(2))
+        ));
+
+        let mut deneb = file.clone();
+        deneb.fork = ForkName::Deneb;
+        assert!(matches!(
+            deneb.validate(),
+            Err(BundleFileError::UnsupportedFork(ForkName::Deneb))
+        ));
+
+        let mut wrong_fork = file.clone();
+        wrong_fork.fork = ForkName::Fulu;
+        assert!(matches!(
+            wrong_fork.validate(),
+            Err(BundleFileError::ForkMismatch {
+                expected: ForkName::Electra,
+                ..
+            })
+        ));
+
+        // Other chains carry their own schedule
+        wrong_fork.chain_id = 10_200;
+        assert!(!matches!(
+            wrong_fork.validate(),
+            Err(BundleFileError::ForkMismatch { .. })
+        ));
+
+        let mut truncated = file.clone();
+        truncated.bundle.proof_credentials.pop();
+        assert!(matches!(
+            truncated.validate(),
+            Err(BundleFileError::ProofLength {
+                proof: "credentials",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_check_deployment() {
+        let mut file = proven_file();
+        file.check_deployment(GNOSIS_CHAIN_ID, CONTRACT).unwrap();
+        assert!(matches!(
+            file.check_deployment(10_200, CONTRACT),
+            Err(BundleFileError::ChainMismatch { .. })
+        ));
+        assert!(matches!(
+            file.check_deployment(GNOSIS_CHAIN_ID, [0xdd; 20]),
+            Err(BundleFileError::ContractMismatch { .. })
+        ));
+
+        file.contract_address = None;
+        file.check_deployment(GNOSIS_CHAIN_ID, [0xdd; 20]).unwrap();
+    }
+
+    #[test]
+    #[cfg(all(feature = "gnosis", not(feature = "minimal")))]
+    fn test_write_and_load_file() {
+        let file = proven_file();
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["bundle.json", "bundle.ssz"] {
+            let path = dir.path().join(name);
+            file.write_file(&path).unwrap();
+            let loaded = ProofBundleV1::load_file(&path, None).unwrap();
+            assert_eq!(loaded.to_json().unwrap(), file.to_json().unwrap());
+        }
+        assert_eq!(std::fs::read(dir.path().join("bundle.ssz")).unwrap()[0], 1);
+    }
+}
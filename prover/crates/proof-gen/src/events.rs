@@ -0,0 +1,384 @@
+//! Beacon Node Events
+//!
+//! Typed events from the `/eth/v1/events` server-sent event stream, see
+//! [`BeaconClient::subscribe_events`](crate::BeaconClient::subscribe_events).
+
+use crate::beacon_client::{parse_hex32, parse_u64, BeaconClientError};
+use serde::Deserialize;
+
+/// Buffer between the stream task and its subscriber
+pub(crate) const EVENT_CHANNEL_CAPACITY: usize = 64;
+
+/// Event stream topics the client understands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventTopic {
+    Head,
+    FinalizedCheckpoint,
+    ChainReorg,
+}
+
+impl EventTopic {
+    /// Topic name as used in the `topics` query parameter
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Head => "head",
+            Self::FinalizedCheckpoint => "finalized_checkpoint",
+            Self::ChainReorg => "chain_reorg",
+        }
+    }
+}
+
+/// New head block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeadEvent {
+    pub slot: u64,
+    pub block: [u8; 32],
+    pub state: [u8; 32],
+    pub epoch_transition: bool,
+}
+
+/// New finalized checkpoint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FinalizedCheckpointEvent {
+    pub epoch: u64,
+    pub block: [u8; 32],
+    pub state: [u8; 32],
+}
+
+/// Head moved to a different fork
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainReorgEvent {
+    pub slot: u64,
+    pub depth: u64,
+    pub epoch: u64,
+    pub old_head_block: [u8; 32],
+    pub new_head_block: [u8; 32],
+}
+
+/// Event from the beacon node's event stream
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BeaconEvent {
+    Head(HeadEvent),
+    FinalizedCheckpoint(FinalizedCheckpointEvent),
+    ChainReorg(ChainReorgEvent),
+}
+
+/// Item delivered to an event subscriber
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubscriptionUpdate {
+    /// A stream was (re)opened
+    Connected { endpoint: String },
+    /// An event arrived
+    Event(BeaconEvent),
+    /// An open stream dropped; the client is reconnecting
+    Disconnected { error: String },
+}
+
+impl BeaconEvent {
+    /// Parse an SSE message; `Ok(None)` for topics this client doesn't model
+    ///
+    /// # Errors
+    /// Returns error if the payload of a known topic is malformed
+    pub fn parse(event: &str, data: &str) -> Result<Option<Self>, BeaconClientError> {
+        #[derive(Deserialize)]
+        struct Head {
+            slot: String,
+            block: String,
+            state: String,
+            #[serde(default)]
+            epoch_transition: bool,
+        }
+
+        #[derive(Deserialize)]
+        struct FinalizedCheckpoint {
+            epoch: String,
+            block: String,
+            state: String,
+        }
+
+        #[derive(Deserialize)]
+        struct ChainReorg {
+            slot: String,
+            depth: String,
+            epoch: String,
+            old_head_block: String,
+            new_head_block: String,
+        }
+
+        let invalid = |e: serde_json::Error| {
+            BeaconClientError::InvalidResponse(format!("Invalid {event} event: {e}"))
+        };
+
+        let parsed = match event {
+            "head" => {
+                let head: Head = serde_json::from_str(data).map_err(invalid)?;
+                Self::Head(HeadEvent {
+                    slot: parse_u64("slot", &head.slot)?,
+                    block: parse_hex32(&head.block)?,
+                    state: parse_hex32(&head.state)?,
+                    epoch_transition: head.epoch_transition,
+                })
+            }
+            "finalized_checkpoint" => {
+                let checkpoint: FinalizedCheckpoint =
+                    serde_json::from_str(data).map_err(invalid)?;
+                Self::FinalizedCheckpoint(FinalizedCheckpointEvent {
+                    epoch: parse_u64("epoch", &checkpoint.epoch)?,
+                    block: parse_hex32(&checkpoint.block)?,
+                    state: parse_hex32(&checkpoint.state)?,
+                })
+            }
+            "chain_reorg" => {
+                let reorg: ChainReorg = serde_json::from_str(data).map_err(invalid)?;
+                Self::ChainReorg(ChainReorgEvent {
+                    slot: parse_u64("slot", &reorg.slot)?,
+                    depth: parse_u64("depth", &reorg.depth)?,
+                    epoch: parse_u64("epoch", &reorg.epoch)?,
+                    old_head_block: parse_hex32(&reorg.old_head_block)?,
+                    new_head_block: parse_hex32(&reorg.new_head_block)?,
+                })
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(parsed))
+    }
+}
+
+/// One dispatched server-sent event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SseMessage {
+    pub event: String,
+    pub data: String,
+}
+
+/// Incremental `text/event-stream` parser
+///
+/// Chunks may split lines (or UTF-8 sequences) anywhere. `id` and `retry`
+/// fields are ignored.
+#[derive(Debug, Default)]
+pub(crate) struct SseParser {
+    buffer: Vec<u8>,
+    event: String,
+    data: Vec<String>,
+}
+
+impl SseParser {
+    /// Feed a chunk, returning every message it completes
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<SseMessage> {
+        self.buffer.extend_from_slice(chunk);
+        let mut messages = Vec::new();
+
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let raw: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&raw);
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            if line.is_empty() {
+                let event = std::mem::take(&mut self.event);
+                if !self.data.is_empty() {
+                    messages.push(SseMessage {
+                        event: if event.is_empty() {
+                            "message".to_string()
+                        } else {
+                            event
+                        },
+                        data: self.data.join("\n"),
+                    });
+                    self.data.clear();
+                }
+                continue;
+            }
+            if line.starts_with(':') {
+                continue;
+            }
+
+            let (field, value) = line.split_once(':').map_or((line, ""), |(field, value)| {
+                (field, value.strip_prefix(' ').unwrap_or(value))
+            });
+            match field {
+                "event" => self.event = value.to_string(),
+                "data" => self.data.push(value.to_string()),
+                _ => {}
+            }
+        }
+
+        messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BeaconClient;
+    use std::time::Duration;
+
+    const ROOT_A: &str = "0x0101010101010101010101010101010101010101010101010101010101010101";
+    const ROOT_B: &str = "0x0202020202020202020202020202020202020202020202020202020202020202";
+
+    fn finalized_data(epoch: u64) -> String {
+        format!(
+            r#"{{"block":"{ROOT_A}","state":"{ROOT_B}","epoch":"{epoch}","execution_optimistic":false}}"#
+        )
+    }
+
+    #[test]
+    fn test_sse_parser_handles_split_chunks_and_comments() {
+        let mut parser = SseParser::default();
+        assert!(parser.push(b": keepalive\n\nevent: fin").is_empty());
+        assert!(parser
+            .push(b"alized_checkpoint\r\ndata: {\"a\":")
+            .is_empty());
+        let messages = parser.push(b"1}\r\n\r\nevent: head\ndata: x\ndata: y\n\n");
+
+        assert_eq!(
+            messages,
+            vec![
+                SseMessage {
+                    event: "finalized_checkpoint".to_string(),
+                    data: "{\"a\":1}".to_string(),
+                },
+                SseMessage {
+                    event: "head".to_string(),
+                    data: "x\ny".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sse_parser_defaults_event_name() {
+        let mut parser = SseParser::default();
+        let messages = parser.push(b"data:plain\n\n");
+        assert_eq!(messages[0].event, "message");
+        assert_eq!(messages[0].data, "plain");
+    }
+
+    #[test]
+    fn test_parse_head_event() {
+        let data = format!(
+            r#"{{"slot":"10","block":"{ROOT_A}","state":"{ROOT_B}","epoch_transition":true,"execution_optimistic":false}}"#
+        );
+        let event = BeaconEvent::parse("head", &data).unwrap().unwrap();
+        assert_eq!(
+            event,
+            BeaconEvent::Head(HeadEvent {
+                slot: 10,
+                block: [1; 32],
+                state: [2; 32],
+                epoch_transition: true,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_finalized_checkpoint_event() {
+        let event = BeaconEvent::parse("finalized_checkpoint", &finalized_data(7))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            event,
+            BeaconEvent::FinalizedCheckpoint(FinalizedCheckpointEvent {
+                epoch: 7,
+                block: [1; 32],
+                state: [2; 32],
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_chain_reorg_event() {
+        let data = format!(
+            r#"{{"slot":"200","depth":"2","old_head_block":"{ROOT_A}","new_head_block":"{ROOT_B}","old_head_state":"{ROOT_A}","new_head_state":"{ROOT_B}","epoch":"12","execution_optimistic":false}}"#
+        );
+        let event = BeaconEvent::parse("chain_reorg", &data).unwrap().unwrap();
+        assert_eq!(
+            event,
+            BeaconEvent::ChainReorg(ChainReorgEvent {
+                slot: 200,
+                depth: 2,
+                epoch: 12,
+                old_head_block: [1; 32],
+                new_head_block: [2; 32],
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_unknown_and_malformed_events() {
+        assert_eq!(BeaconEvent::parse("block", "{}").unwrap(), None);
+        assert!(BeaconEvent::parse("head", "{}").is_err());
+        assert!(BeaconEvent::parse("finalized_checkpoint", "not json").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_events_streams_and_reconnects() {
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let body = format!(
+            ": ok\n\nevent: finalized_checkpoint\ndata: {}\n\nevent: block\ndata: {{}}\n\n",
+            finalized_data(3)
+        );
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/events"))
+            .and(query_param(
+                "topics",
+                "head,finalized_checkpoint,chain_reorg",
+            ))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "text/event-stream")
+                    .set_body_string(body),
+            )
+            .mount(&server)
+            .await;
+
+        let client = BeaconClient::builder()
+            .endpoint(server.uri())
+            .retry(crate::beacon_client::RetryPolicy {
+                initial_backoff: Duration::from_millis(1),
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        let mut updates = client.subscribe_events(&[
+            EventTopic::Head,
+            EventTopic::FinalizedCheckpoint,
+            EventTopic::ChainReorg,
+        ]);
+
+        async fn next(
+            updates: &mut tokio::sync::mpsc::Receiver<SubscriptionUpdate>,
+        ) -> SubscriptionUpdate {
+            tokio::time::timeout(Duration::from_secs(5), updates.recv())
+                .await
+                .unwrap()
+                .unwrap()
+        }
+
+        assert_eq!(
+            next(&mut updates).await,
+            SubscriptionUpdate::Connected {
+                endpoint: server.uri()
+            }
+        );
+        assert!(matches!(
+            next(&mut updates).await,
+            SubscriptionUpdate::Event(BeaconEvent::FinalizedCheckpoint(FinalizedCheckpointEvent {
+                epoch: 3,
+                ..
+            }))
+        ));
+        // The stub closes the stream after its body; the client reconnects
+        assert!(matches!(
+            next(&mut updates).await,
+            SubscriptionUpdate::Disconnected { .. }
+        ));
+        assert!(matches!(
+            next(&mut updates).await,
+            SubscriptionUpdate::Connected { .. }
+        ));
+    }
+}
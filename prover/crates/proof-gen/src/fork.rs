@@ -0,0 +1,449 @@
+//! Fork Registry
+//!
+//! Maps a state's `fork.current_version` to the BeaconState layout it was
+//! encoded with. Forks only ever append BeaconState fields, but once the field
+//! count passes a power of two the state tree gets deeper and every gindex
+//! below the state root moves, so proof generation and the contract's
+//! hardcoded gindices must both be checked against the fork in use.
+//!
+//! ## Gnosis schedule
+//!
+//! | Fork      | Version      | Epoch     | State fields |
+//! |-----------|--------------|-----------|--------------|
+//! | Phase0    | `0x00000064` | 0         | -            |
+//! | Altair    | `0x01000064` | 512       | -            |
+//! | Bellatrix | `0x02000064` | 385536    | -            |
+//! | Capella   | `0x03000064` | 648704    | -            |
+//! | Deneb     | `0x04000064` | 889856    | -            |
+//! | Electra   | `0x05000064` | 1337856   | 37           |
+//! | Fulu      | `0x06000064` | unscheduled | 38         |
+//!
+//! Forks before Electra have no `pending_consolidations` and cannot be proven.
+
+use crate::gindex::GindexCalculator;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tracing::warn;
+
+/// Epoch used for forks that are not scheduled yet
+pub const FAR_FUTURE_EPOCH: u64 = u64::MAX;
+
+/// Errors from fork lookups
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ForkError {
+    #[error("Unknown fork version 0x{}", hex::encode(.0))]
+    UnknownVersion([u8; 4]),
+
+    #[error("Fork {0} has no pending consolidations")]
+    Unsupported(ForkName),
+}
+
+/// Consensus forks, in activation order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForkName {
+    Phase0,
+    Altair,
+    Bellatrix,
+    Capella,
+    Deneb,
+    Electra,
+    Fulu,
+}
+
+impl ForkName {
+    /// All forks, in activation order
+    pub const ALL: [Self; 7] = [
+        Self::Phase0,
+        Self::Altair,
+        Self::Bellatrix,
+        Self::Capella,
+        Self::Deneb,
+        Self::Electra,
+        Self::Fulu,
+    ];
+}
+
+impl std::fmt::Display for ForkName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Phase0 => "phase0",
+            Self::Altair => "altair",
+            Self::Bellatrix => "bellatrix",
+            Self::Capella => "capella",
+            Self::Deneb => "deneb",
+            Self::Electra => "electra",
+            Self::Fulu => "fulu",
+        };
+        f.write_str(name)
+    }
+}
+
+/// BeaconState container layout fixed by a fork
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateLayout {
+    /// Number of top-level BeaconState fields
+    pub field_count: usize,
+    /// Index of `validators`
+    pub validators_field_index: usize,
+    /// Index of `pending_consolidations`
+    pub pending_consolidations_field_index: usize,
+}
+
+impl StateLayout {
+    /// Electra: 37 fields, `pending_consolidations` appended last
+    pub const ELECTRA: Self = Self {
+        field_count: 37,
+        validators_field_index: 11,
+        pending_consolidations_field_index: 36,
+    };
+
+    /// Fulu: Electra plus `proposer_lookahead`
+    pub const FULU: Self = Self {
+        field_count: 38,
+        ..Self::ELECTRA
+    };
+
+    /// Layout for `fork`, or `None` before Electra
+    #[must_use]
+    pub const fn for_fork(fork: ForkName) -> Option<Self> {
+        match fork {
+            ForkName::Electra => Some(Self::ELECTRA),
+            ForkName::Fulu => Some(Self::FULU),
+            _ => None,
+        }
+    }
+
+    /// Depth of the state container tree: `ceil(log2(field_count))`
+    #[must_use]
+    pub const fn tree_depth(&self) -> u32 {
+        self.field_count.next_power_of_two().trailing_zeros()
+    }
+
+    /// Gindex of `validators` relative to the state root
+    #[must_use]
+    pub const fn validators_gindex(&self) -> u64 {
+        (1 << self.tree_depth()) + self.validators_field_index as u64
+    }
+
+    /// Gindex of `pending_consolidations` relative to the state root
+    #[must_use]
+    pub const fn pending_consolidations_gindex(&self) -> u64 {
+        (1 << self.tree_depth()) + self.pending_consolidations_field_index as u64
+    }
+
+    /// Where this layout disagrees with the gindices the contract hardcodes
+    #[must_use]
+    pub fn contract_mismatches(&self) -> Vec<GindexMismatch> {
+        let contract = ContractGindices::DEPLOYED;
+        [
+            (
+                "state depth",
+                u64::from(contract.state_depth),
+                u64::from(self.tree_depth()),
+            ),
+            (
+                "validators gindex",
+                contract.validators_gindex,
+                self.validators_gindex(),
+            ),
+            (
+                "pending_consolidations gindex",
+                contract.pending_consolidations_gindex,
+                self.pending_consolidations_gindex(),
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, contract, fork)| contract != fork)
+        .map(|(field, contract, fork)| GindexMismatch {
+            field,
+            contract,
+            fork,
+        })
+        .collect()
+    }
+}
+
+/// Expected proof lengths (in siblings) from the block root
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofLengths {
+    /// `pending_consolidations[i].source_index`
+    pub consolidation: u32,
+    /// `validators[i].withdrawal_credentials` and `activation_epoch`
+    pub validator: u32,
+}
+
+/// State gindices hardcoded in `SSZMerkleVerifier.sol`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContractGindices {
+    pub state_depth: u32,
+    pub validators_gindex: u64,
+    pub pending_consolidations_gindex: u64,
+}
+
+impl ContractGindices {
+    /// Constants of the deployed contract (Electra layout)
+    pub const DEPLOYED: Self = Self {
+        state_depth: 6,
+        validators_gindex: 75,
+        pending_consolidations_gindex: 100,
+    };
+}
+
+/// A state gindex that differs between the contract and a fork
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GindexMismatch {
+    pub field: &'static str,
+    pub contract: u64,
+    pub fork: u64,
+}
+
+impl std::fmt::Display for GindexMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: contract {}, fork {}",
+            self.field, self.contract, self.fork
+        )
+    }
+}
+
+/// One entry of a fork schedule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ForkSpec {
+    pub name: ForkName,
+    /// `fork.current_version` of states in this fork
+    pub version: [u8; 4],
+    /// Activation epoch, [`FAR_FUTURE_EPOCH`] if unscheduled
+    pub epoch: u64,
+}
+
+impl ForkSpec {
+    /// State layout, or `None` before Electra
+    #[must_use]
+    pub const fn layout(&self) -> Option<StateLayout> {
+        StateLayout::for_fork(self.name)
+    }
+}
+
+/// Fork schedule of a chain, in activation order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForkRegistry {
+    forks: Vec<ForkSpec>,
+}
+
+impl ForkRegistry {
+    /// Build a registry from a schedule sorted by activation epoch
+    #[must_use]
+    pub fn new(forks: Vec<ForkSpec>) -> Self {
+        Self { forks }
+    }
+
+    /// Gnosis Chain mainnet schedule
+    #[must_use]
+    pub fn gnosis() -> Self {
+        let fork = |name, version: u8, epoch| ForkSpec {
+            name,
+            version: [version, 0x00, 0x00, 0x64],
+            epoch,
+        };
+        Self::new(vec![
+            fork(ForkName::Phase0, 0x00, 0),
+            fork(ForkName::Altair, 0x01, 512),
+            fork(ForkName::Bellatrix, 0x02, 385_536),
+            fork(ForkName::Capella, 0x03, 648_704),
+            fork(ForkName::Deneb, 0x04, 889_856),
+            fork(ForkName::Electra, 0x05, 1_337_856),
+            fork(ForkName::Fulu, 0x06, FAR_FUTURE_EPOCH),
+        ])
+    }
+
+    /// All forks, in activation order
+    #[must_use]
+    pub fn forks(&self) -> &[ForkSpec] {
+        &self.forks
+    }
+
+    /// Fork named `name`, if scheduled in this registry
+    #[must_use]
+    pub fn by_name(&self, name: ForkName) -> Option<&ForkSpec> {
+        self.forks.iter().find(|fork| fork.name == name)
+    }
+
+    /// Fork whose states carry `version` as `fork.current_version`
+    ///
+    /// # Errors
+    /// Returns [`ForkError::UnknownVersion`] if no fork uses `version`
+    pub fn by_version(&self, version: [u8; 4]) -> Result<&ForkSpec, ForkError> {
+        self.forks
+            .iter()
+            .find(|fork| fork.version == version)
+            .ok_or(ForkError::UnknownVersion(version))
+    }
+
+    /// Fork active at `epoch`
+    #[must_use]
+    pub fn at_epoch(&self, epoch: u64) -> Option<&ForkSpec> {
+        self.forks
+            .iter()
+            .rev()
+            .find(|fork| fork.epoch != FAR_FUTURE_EPOCH && fork.epoch <= epoch)
+    }
+
+    /// Fork and state layout for a state with `version` as `fork.current_version`
+    ///
+    /// # Errors
+    /// Returns error if the version is unknown or predates Electra
+    pub fn layout_for_version(
+        &self,
+        version: [u8; 4],
+    ) -> Result<(ForkName, StateLayout), ForkError> {
+        let fork = self.by_version(version)?;
+        let layout = fork.layout().ok_or(ForkError::Unsupported(fork.name))?;
+        Ok((fork.name, layout))
+    }
+
+    /// Expected proof lengths for every fork that can be proven
+    #[must_use]
+    pub fn expected_proof_lengths(&self) -> Vec<(ForkName, ProofLengths)> {
+        self.forks
+            .iter()
+            .filter_map(|fork| {
+                let lengths = GindexCalculator::expected_proof_lengths(fork.name).ok()?;
+                Some((fork.name, lengths))
+            })
+            .collect()
+    }
+
+    /// Check every provable fork against the contract's gindices
+    ///
+    /// Logs a warning for each fork whose layout the deployed contract would
+    /// reject, and returns the mismatches per fork.
+    pub fn check_contract_compatibility(&self) -> Vec<(ForkName, Vec<GindexMismatch>)> {
+        let incompatible: Vec<_> = self
+            .forks
+            .iter()
+            .filter_map(|fork| {
+                let mismatches = fork.layout()?.contract_mismatches();
+                (!mismatches.is_empty()).then_some((fork.name, mismatches))
+            })
+            .collect();
+
+        for (fork, mismatches) in &incompatible {
+            let details: Vec<String> = mismatches.iter().map(ToString::to_string).collect();
+            warn!(
+                %fork,
+                mismatches = %details.join("; "),
+                "Fork changes gindices hardcoded in the contract"
+            );
+        }
+        incompatible
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gnosis_layout_by_version() {
+        let registry = ForkRegistry::gnosis();
+        assert_eq!(
+            registry.layout_for_version([0x05, 0x00, 0x00, 0x64]),
+            Ok((ForkName::Electra, StateLayout::ELECTRA))
+        );
+        assert_eq!(
+            registry.layout_for_version([0x06, 0x00, 0x00, 0x64]),
+            Ok((ForkName::Fulu, StateLayout::FULU))
+        );
+        assert_eq!(
+            registry.layout_for_version([0x04, 0x00, 0x00, 0x64]),
+            Err(ForkError::Unsupported(ForkName::Deneb))
+        );
+        assert_eq!(
+            registry.layout_for_version([0x05, 0x00, 0x00, 0x00]),
+            Err(ForkError::UnknownVersion([0x05, 0x00, 0x00, 0x00]))
+        );
+    }
+
+    #[test]
+    fn test_at_epoch_skips_unscheduled_forks() {
+        let registry = ForkRegistry::gnosis();
+        assert_eq!(registry.at_epoch(0).unwrap().name, ForkName::Phase0);
+        assert_eq!(registry.at_epoch(1_337_855).unwrap().name, ForkName::Deneb);
+        assert_eq!(
+            registry.at_epoch(1_337_856).unwrap().name,
+            ForkName::Electra
+        );
+        assert_eq!(registry.at_epoch(u64::MAX).unwrap().name, ForkName::Electra);
+    }
+
+    #[test]
+    fn test_fork_name_serde_matches_display() {
+        for fork in ForkName::ALL {
+            let json = serde_json::to_string(&fork).unwrap();
+            assert_eq!(json, format!("\"{fork}\""));
+            assert_eq!(serde_json::from_str::<ForkName>(&json).unwrap(), fork);
+        }
+    }
+
+    #[test]
+    fn test_layout_gindices() {
+        let electra = StateLayout::ELECTRA;
+        assert_eq!(electra.tree_depth(), 6);
+        assert_eq!(electra.validators_gindex(), 75);
+        assert_eq!(electra.pending_consolidations_gindex(), 100);
+        assert_eq!(StateLayout::FULU.tree_depth(), 6);
+    }
+
+    #[test]
+    #[cfg(all(feature = "gnosis", not(feature = "minimal")))]
+    fn test_gnosis_forks_match_contract() {
+        let registry = ForkRegistry::gnosis();
+        let lengths = registry.expected_proof_lengths();
+        assert_eq!(
+            lengths,
+            vec![
+                (
+                    ForkName::Electra,
+                    ProofLengths {
+                        consolidation: 29,
+                        validator: 53
+                    }
+                ),
+                (
+                    ForkName::Fulu,
+                    ProofLengths {
+                        consolidation: 29,
+                        validator: 53
+                    }
+                ),
+            ]
+        );
+        assert!(registry.check_contract_compatibility().is_empty());
+    }
+
+    #[test]
+    fn test_deeper_state_tree_is_flagged() {
+        // A fork appending fields past 64 deepens the state tree to 7
+        let layout = StateLayout {
+            field_count: 65,
+            ..StateLayout::ELECTRA
+        };
+        assert_eq!(layout.tree_depth(), 7);
+        let fields: Vec<_> = layout
+            .contract_mismatches()
+            .into_iter()
+            .map(|m| m.field)
+            .collect();
+        assert!(fields.contains(&"state depth"));
+        assert!(fields.contains(&"validators gindex"));
+        assert!(fields.contains(&"pending_consolidations gindex"));
+
+        let mismatch = &layout.contract_mismatches()[1];
+        assert_eq!(
+            mismatch.to_string(),
+            "validators gindex: contract 75, fork 139"
+        );
+    }
+}
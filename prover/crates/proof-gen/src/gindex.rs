@@ -10,7 +10,13 @@
 //!
 //! The calculator methods use the configured preset. For test vector generation,
 //! use `GindexCalculator::for_test_state()` methods.
+//!
+//! Every gindex is derived from the declarative schemas in [`crate::schema`]
+//! by walking a path such as `["state_root", "validators", i, "exit_epoch"]`.
+//! The `*_for` methods take the fork explicitly, the others assume Electra.
 
+use crate::fork::{ForkName, ProofLengths};
+use crate::schema::{self, PathStep, SchemaError, StateLimits};
 use crate::types::preset;
 
 /// Calculator for generalized indices in the beacon state tree
@@ -18,38 +24,8 @@ use crate::types::preset;
 pub struct GindexCalculator;
 
 impl GindexCalculator {
-    // BeaconState structure constants
-    // BeaconState has 37 fields in Electra, giving a tree depth of 6 (2^6 = 64 >= 37)
-    const BEACON_STATE_TREE_DEPTH: u32 = 6;
-    const BEACON_STATE_BASE_GINDEX: u64 = 64; // 2^6
-
-    // Field indices in BeaconState (0-indexed)
-    const VALIDATORS_FIELD_INDEX: u64 = 11;
-    const PENDING_CONSOLIDATIONS_FIELD_INDEX: u64 = 36;
-
-    // BeaconBlockHeader structure constants
-    // Header has 5 fields, tree depth 3 (2^3 = 8 >= 5)
-    const HEADER_TREE_DEPTH: u32 = 3;
-    const HEADER_BASE_GINDEX: u64 = 8; // 2^3
-
-    // state_root is field index 3 in header
-    const STATE_ROOT_FIELD_INDEX: u64 = 3;
-
-    // Validator structure constants
-    // Validator has 8 fields, tree depth 3 (2^3 = 8)
-    const VALIDATOR_TREE_DEPTH: u32 = 3;
-    const VALIDATOR_BASE_GINDEX: u64 = 8; // 2^3
-
-    // Field indices in Validator
-    const WITHDRAWAL_CREDENTIALS_FIELD_INDEX: u64 = 1;
-    const ACTIVATION_EPOCH_FIELD_INDEX: u64 = 5;
-
-    // PendingConsolidation has 2 fields, tree depth 1 (2^1 = 2)
-    const CONSOLIDATION_TREE_DEPTH: u32 = 1;
-    const CONSOLIDATION_BASE_GINDEX: u64 = 2; // 2^1
-
-    // source_index is field index 0
-    const SOURCE_INDEX_FIELD_INDEX: u64 = 0;
+    /// Fork assumed by the methods without a fork argument
+    const DEFAULT_FORK: ForkName = ForkName::Electra;
 
     // Test state limits (MinimalBeaconState)
     /// Validators tree depth for test state: log2(1024) = 10
@@ -57,89 +33,242 @@ impl GindexCalculator {
     /// Pending consolidations tree depth for test state: log2(64) = 6
     pub const TEST_CONSOLIDATIONS_TREE_DEPTH: u32 = 6;
 
+    /// Compute the gindex of `path` from the block root
+    ///
+    /// The path starts at the header, e.g.
+    /// `["state_root", "validators", i, "exit_epoch"]`, and descends into the
+    /// `fork` BeaconState with the given list limits.
+    ///
+    /// # Errors
+    /// Returns error if the fork predates Electra or the path is invalid
+    pub fn block_root_gindex(
+        fork: ForkName,
+        limits: &StateLimits,
+        path: &[PathStep],
+    ) -> Result<u64, SchemaError> {
+        let header = schema::beacon_block_header(schema::beacon_state(fork, limits)?);
+        header.gindex(path)
+    }
+
+    fn consolidation_source_path(consolidation_index: u64) -> [PathStep; 4] {
+        [
+            "state_root".into(),
+            "pending_consolidations".into(),
+            consolidation_index.into(),
+            "source_index".into(),
+        ]
+    }
+
+    fn validator_field_path(validator_index: u64, field: &'static str) -> [PathStep; 4] {
+        [
+            "state_root".into(),
+            "validators".into(),
+            validator_index.into(),
+            field.into(),
+        ]
+    }
+
+    fn historical_summary_path(summary_index: u64) -> [PathStep; 4] {
+        [
+            "state_root".into(),
+            "historical_summaries".into(),
+            summary_index.into(),
+            "block_summary_root".into(),
+        ]
+    }
+
+    /// Gindex of `path(index)` under the default fork
+    ///
+    /// An index past the list limit is asserted against in debug builds only.
+    /// Release builds keep offsetting from element 0 as plain gindex
+    /// arithmetic does, which lands outside the list, so proofs against it
+    /// fail verification rather than the caller panicking.
+    fn default_gindex(
+        limits: &StateLimits,
+        index: u64,
+        path: impl Fn(u64) -> [PathStep; 4],
+    ) -> u64 {
+        let gindex = |index| Self::block_root_gindex(Self::DEFAULT_FORK, limits, &path(index));
+        let result = gindex(index);
+        debug_assert!(result.is_ok(), "invalid gindex path: {result:?}");
+        result.unwrap_or_else(|_| {
+            // Sibling elements are one element subtree apart
+            let first = gindex(0).unwrap_or_default();
+            let stride = gindex(1).unwrap_or_default().wrapping_sub(first);
+            first.wrapping_add(index.wrapping_mul(stride))
+        })
+    }
+
     /// Compute gindex for `pending_consolidations[i].source_index` from block root
     ///
     /// Path: header → state_root → pending_consolidations → [i] → source_index
+    ///
+    /// An index past the pending consolidations limit yields a gindex outside the list
+    /// (debug builds panic).
     #[must_use]
     pub fn consolidation_source_gindex(consolidation_index: u64) -> u64 {
-        // Start from header root
-        // gindex(state_root in header) = 8 + 3 = 11
-        let state_root_in_header = Self::HEADER_BASE_GINDEX + Self::STATE_ROOT_FIELD_INDEX;
-
-        // gindex(pending_consolidations in state) = 64 + 36 = 100
-        let pending_consolidations_in_state =
-            Self::BEACON_STATE_BASE_GINDEX + Self::PENDING_CONSOLIDATIONS_FIELD_INDEX;
-
-        // List data root is at gindex 2 * parent (left child for length, right child skipped, data at 2)
-        // Actually for List, the tree is: [length_mix_in | data_root]
-        // data_root is at gindex 2 relative to the list root (index 1 in 0-indexed, but gindex 2)
-        // Wait, let me reconsider...
-        // For a List<T, N>, the root is hash(data_root, length_mix_in)
-        // - gindex 2: data_root (left child)
-        // - gindex 3: length_mix_in (right child)
-
-        // Depth of pending_consolidations list data tree
-        let consolidations_data_depth = Self::pending_consolidations_tree_depth();
-
-        // Element [i] in the data tree
-        let element_gindex_in_data = (1_u64 << consolidations_data_depth) + consolidation_index;
-
-        // source_index field in PendingConsolidation
-        let source_in_consolidation =
-            Self::CONSOLIDATION_BASE_GINDEX + Self::SOURCE_INDEX_FIELD_INDEX;
-
-        // Combine paths:
-        // block_root -> state_root: depth 3, gindex 11
-        // state_root -> pending_consolidations: depth 6, gindex 100
-        // pending_consolidations -> data_root: depth 1, gindex 2
-        // data_root -> element[i]: depth varies, gindex = 2^depth + i
-        // element[i] -> source_index: depth 1, gindex 2
-
-        Self::concat_gindices(&[
-            state_root_in_header,
-            pending_consolidations_in_state,
-            2, // data_root of list
-            element_gindex_in_data,
-            source_in_consolidation,
-        ])
+        Self::default_gindex(
+            &StateLimits::PRESET,
+            consolidation_index,
+            Self::consolidation_source_path,
+        )
+    }
+
+    /// [`Self::consolidation_source_gindex`] under a given fork's state layout
+    ///
+    /// # Errors
+    /// Returns error if the fork predates Electra or the index is out of bounds
+    pub fn consolidation_source_gindex_for(
+        fork: ForkName,
+        consolidation_index: u64,
+    ) -> Result<u64, SchemaError> {
+        Self::block_root_gindex(
+            fork,
+            &StateLimits::PRESET,
+            &Self::consolidation_source_path(consolidation_index),
+        )
     }
 
     /// Compute gindex for `validators[i].withdrawal_credentials` from block root
+    ///
+    /// An index past the validator registry limit yields a gindex outside the list
+    /// (debug builds panic).
     #[must_use]
     pub fn validator_credentials_gindex(validator_index: u64) -> u64 {
-        let state_root_in_header = Self::HEADER_BASE_GINDEX + Self::STATE_ROOT_FIELD_INDEX;
-        let validators_in_state = Self::BEACON_STATE_BASE_GINDEX + Self::VALIDATORS_FIELD_INDEX;
-        let validators_data_depth = Self::validators_tree_depth();
-        let element_gindex_in_data = (1_u64 << validators_data_depth) + validator_index;
-        let credentials_in_validator =
-            Self::VALIDATOR_BASE_GINDEX + Self::WITHDRAWAL_CREDENTIALS_FIELD_INDEX;
-
-        Self::concat_gindices(&[
-            state_root_in_header,
-            validators_in_state,
-            2, // data_root of list
-            element_gindex_in_data,
-            credentials_in_validator,
-        ])
+        Self::default_gindex(&StateLimits::PRESET, validator_index, |i| {
+            Self::validator_field_path(i, "withdrawal_credentials")
+        })
+    }
+
+    /// [`Self::validator_credentials_gindex`] under a given fork's state layout
+    ///
+    /// # Errors
+    /// Returns error if the fork predates Electra or the index is out of bounds
+    pub fn validator_credentials_gindex_for(
+        fork: ForkName,
+        validator_index: u64,
+    ) -> Result<u64, SchemaError> {
+        Self::block_root_gindex(
+            fork,
+            &StateLimits::PRESET,
+            &Self::validator_field_path(validator_index, "withdrawal_credentials"),
+        )
     }
 
     /// Compute gindex for `validators[i].activation_epoch` from block root
+    ///
+    /// An index past the validator registry limit yields a gindex outside the list
+    /// (debug builds panic).
     #[must_use]
     pub fn validator_activation_epoch_gindex(validator_index: u64) -> u64 {
-        let state_root_in_header = Self::HEADER_BASE_GINDEX + Self::STATE_ROOT_FIELD_INDEX;
-        let validators_in_state = Self::BEACON_STATE_BASE_GINDEX + Self::VALIDATORS_FIELD_INDEX;
-        let validators_data_depth = Self::validators_tree_depth();
-        let element_gindex_in_data = (1_u64 << validators_data_depth) + validator_index;
-        let activation_in_validator =
-            Self::VALIDATOR_BASE_GINDEX + Self::ACTIVATION_EPOCH_FIELD_INDEX;
-
-        Self::concat_gindices(&[
-            state_root_in_header,
-            validators_in_state,
-            2, // data_root of list
-            element_gindex_in_data,
-            activation_in_validator,
-        ])
+        Self::default_gindex(&StateLimits::PRESET, validator_index, |i| {
+            Self::validator_field_path(i, "activation_epoch")
+        })
+    }
+
+    /// [`Self::validator_activation_epoch_gindex`] under a given fork's state layout
+    ///
+    /// # Errors
+    /// Returns error if the fork predates Electra or the index is out of bounds
+    pub fn validator_activation_epoch_gindex_for(
+        fork: ForkName,
+        validator_index: u64,
+    ) -> Result<u64, SchemaError> {
+        Self::block_root_gindex(
+            fork,
+            &StateLimits::PRESET,
+            &Self::validator_field_path(validator_index, "activation_epoch"),
+        )
+    }
+
+    /// Compute gindex for `validators[i].exit_epoch` from block root
+    ///
+    /// An index past the validator registry limit yields a gindex outside the list
+    /// (debug builds panic).
+    #[must_use]
+    pub fn validator_exit_epoch_gindex(validator_index: u64) -> u64 {
+        Self::default_gindex(&StateLimits::PRESET, validator_index, |i| {
+            Self::validator_field_path(i, "exit_epoch")
+        })
+    }
+
+    /// Compute gindex for `validators[i].withdrawable_epoch` from block root
+    ///
+    /// An index past the validator registry limit yields a gindex outside the list
+    /// (debug builds panic).
+    #[must_use]
+    pub fn validator_withdrawable_epoch_gindex(validator_index: u64) -> u64 {
+        Self::default_gindex(&StateLimits::PRESET, validator_index, |i| {
+            Self::validator_field_path(i, "withdrawable_epoch")
+        })
+    }
+
+    /// Compute gindex for `validators[i].effective_balance` from block root
+    ///
+    /// An index past the validator registry limit yields a gindex outside the list
+    /// (debug builds panic).
+    #[must_use]
+    pub fn validator_effective_balance_gindex(validator_index: u64) -> u64 {
+        Self::default_gindex(&StateLimits::PRESET, validator_index, |i| {
+            Self::validator_field_path(i, "effective_balance")
+        })
+    }
+
+    /// Gindex of any `validators[i]` field from block root under a given
+    /// fork's state layout and limits
+    ///
+    /// # Errors
+    /// Returns error if the fork predates Electra, the field is unknown or
+    /// the index is out of bounds
+    pub fn validator_field_gindex_for(
+        fork: ForkName,
+        limits: &StateLimits,
+        validator_index: u64,
+        field: &'static str,
+    ) -> Result<u64, SchemaError> {
+        Self::block_root_gindex(
+            fork,
+            limits,
+            &Self::validator_field_path(validator_index, field),
+        )
+    }
+
+    /// Compute gindex for `historical_summaries[k].block_summary_root` from block root
+    ///
+    /// An index past the historical roots limit yields a gindex outside the list
+    /// (debug builds panic).
+    #[must_use]
+    pub fn historical_summary_gindex(summary_index: u64) -> u64 {
+        Self::default_gindex(
+            &StateLimits::PRESET,
+            summary_index,
+            Self::historical_summary_path,
+        )
+    }
+
+    /// [`Self::historical_summary_gindex`] under a given fork's state layout
+    ///
+    /// # Errors
+    /// Returns error if the fork predates Electra or the index is out of bounds
+    pub fn historical_summary_gindex_for(
+        fork: ForkName,
+        summary_index: u64,
+    ) -> Result<u64, SchemaError> {
+        Self::block_root_gindex(
+            fork,
+            &StateLimits::PRESET,
+            &Self::historical_summary_path(summary_index),
+        )
+    }
+
+    /// Compute gindex of the block root for `slot` from a `block_summary_root`
+    ///
+    /// The summary root is the root of a period's `block_roots` vector, which
+    /// holds the root for `slot` at `slot % SLOTS_PER_HISTORICAL_ROOT`.
+    #[must_use]
+    pub fn historical_block_root_gindex(slot: u64) -> u64 {
+        let slots_per_historical_root = StateLimits::PRESET.slots_per_historical_root;
+        slots_per_historical_root + slot % slots_per_historical_root
     }
 
     /// Get the depth of the validators list data tree
@@ -184,6 +313,24 @@ impl GindexCalculator {
         Self::gindex_depth(gindex)
     }
 
+    /// Expected proof length for validator fields
+    #[must_use]
+    pub fn validator_proof_length() -> u32 {
+        let gindex = Self::validator_credentials_gindex(0);
+        Self::gindex_depth(gindex)
+    }
+
+    /// Expected proof lengths from the block root under a given fork
+    ///
+    /// # Errors
+    /// Returns error if the fork predates Electra
+    pub fn expected_proof_lengths(fork: ForkName) -> Result<ProofLengths, SchemaError> {
+        Ok(ProofLengths {
+            consolidation: Self::gindex_depth(Self::consolidation_source_gindex_for(fork, 0)?),
+            validator: Self::gindex_depth(Self::validator_credentials_gindex_for(fork, 0)?),
+        })
+    }
+
     // =========================================================================
     // Test State Methods (for MinimalBeaconState with small limits)
     // =========================================================================
@@ -191,88 +338,52 @@ impl GindexCalculator {
     /// Compute gindex for consolidation source_index using test state limits
     #[must_use]
     pub fn test_consolidation_source_gindex(consolidation_index: u64) -> u64 {
-        let state_root_in_header = Self::HEADER_BASE_GINDEX + Self::STATE_ROOT_FIELD_INDEX;
-        let pending_consolidations_in_state =
-            Self::BEACON_STATE_BASE_GINDEX + Self::PENDING_CONSOLIDATIONS_FIELD_INDEX;
-        let consolidations_data_depth = Self::TEST_CONSOLIDATIONS_TREE_DEPTH;
-        let element_gindex_in_data = (1_u64 << consolidations_data_depth) + consolidation_index;
-        let source_in_consolidation =
-            Self::CONSOLIDATION_BASE_GINDEX + Self::SOURCE_INDEX_FIELD_INDEX;
-
-        Self::concat_gindices(&[
-            state_root_in_header,
-            pending_consolidations_in_state,
-            2,
-            element_gindex_in_data,
-            source_in_consolidation,
-        ])
+        Self::default_gindex(
+            &StateLimits::TEST,
+            consolidation_index,
+            Self::consolidation_source_path,
+        )
     }
 
     /// Compute gindex for validator credentials using test state limits
     #[must_use]
     pub fn test_validator_credentials_gindex(validator_index: u64) -> u64 {
-        let state_root_in_header = Self::HEADER_BASE_GINDEX + Self::STATE_ROOT_FIELD_INDEX;
-        let validators_in_state = Self::BEACON_STATE_BASE_GINDEX + Self::VALIDATORS_FIELD_INDEX;
-        let validators_data_depth = Self::TEST_VALIDATORS_TREE_DEPTH;
-        let element_gindex_in_data = (1_u64 << validators_data_depth) + validator_index;
-        let credentials_in_validator =
-            Self::VALIDATOR_BASE_GINDEX + Self::WITHDRAWAL_CREDENTIALS_FIELD_INDEX;
-
-        Self::concat_gindices(&[
-            state_root_in_header,
-            validators_in_state,
-            2,
-            element_gindex_in_data,
-            credentials_in_validator,
-        ])
+        Self::default_gindex(&StateLimits::TEST, validator_index, |i| {
+            Self::validator_field_path(i, "withdrawal_credentials")
+        })
     }
 
     /// Compute gindex for validator activation_epoch using test state limits
     #[must_use]
     pub fn test_validator_activation_epoch_gindex(validator_index: u64) -> u64 {
-        let state_root_in_header = Self::HEADER_BASE_GINDEX + Self::STATE_ROOT_FIELD_INDEX;
-        let validators_in_state = Self::BEACON_STATE_BASE_GINDEX + Self::VALIDATORS_FIELD_INDEX;
-        let validators_data_depth = Self::TEST_VALIDATORS_TREE_DEPTH;
-        let element_gindex_in_data = (1_u64 << validators_data_depth) + validator_index;
-        let activation_in_validator =
-            Self::VALIDATOR_BASE_GINDEX + Self::ACTIVATION_EPOCH_FIELD_INDEX;
-
-        Self::concat_gindices(&[
-            state_root_in_header,
-            validators_in_state,
-            2,
-            element_gindex_in_data,
-            activation_in_validator,
-        ])
+        Self::default_gindex(&StateLimits::TEST, validator_index, |i| {
+            Self::validator_field_path(i, "activation_epoch")
+        })
+    }
+
+    /// Compute gindex for any validator field using test state limits
+    ///
+    /// # Panics
+    /// Debug builds panic if the field is not a `Validator` field
+    #[must_use]
+    pub fn test_validator_field_gindex(validator_index: u64, field: &'static str) -> u64 {
+        Self::default_gindex(&StateLimits::TEST, validator_index, |i| {
+            Self::validator_field_path(i, field)
+        })
     }
 
     /// Expected proof length for consolidation in test state
     #[must_use]
     pub fn test_consolidation_proof_length() -> u32 {
         // header (3) + state (6) + list (1) + data (6) + field (1) = 17
-        Self::HEADER_TREE_DEPTH
-            + Self::BEACON_STATE_TREE_DEPTH
-            + 1
-            + Self::TEST_CONSOLIDATIONS_TREE_DEPTH
-            + Self::CONSOLIDATION_TREE_DEPTH
+        Self::gindex_depth(Self::test_consolidation_source_gindex(0))
     }
 
     /// Expected proof length for validator fields in test state
     #[must_use]
     pub fn test_validator_proof_length() -> u32 {
         // header (3) + state (6) + list (1) + data (10) + field (3) = 23
-        Self::HEADER_TREE_DEPTH
-            + Self::BEACON_STATE_TREE_DEPTH
-            + 1
-            + Self::TEST_VALIDATORS_TREE_DEPTH
-            + Self::VALIDATOR_TREE_DEPTH
-    }
-
-    /// Expected proof length for validator fields
-    #[must_use]
-    pub fn validator_proof_length() -> u32 {
-        let gindex = Self::validator_credentials_gindex(0);
-        Self::gindex_depth(gindex)
+        Self::gindex_depth(Self::test_validator_credentials_gindex(0))
     }
 }
 
@@ -315,6 +426,115 @@ mod tests {
         assert_eq!(GindexCalculator::gindex_depth(8), 3);
     }
 
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "invalid gindex path"))]
+    fn test_out_of_range_index_asserts_in_debug_and_offsets_in_release() {
+        let limit = 1 << GindexCalculator::TEST_CONSOLIDATIONS_TREE_DEPTH;
+        // PendingConsolidation spans two leaves, so elements are 2 apart
+        assert_eq!(
+            GindexCalculator::test_consolidation_source_gindex(limit),
+            GindexCalculator::test_consolidation_source_gindex(0) + 2 * limit
+        );
+    }
+
+    #[test]
+    fn test_schema_gindices_match_hand_derived_paths() {
+        // header.state_root (11) -> state field -> list data (2) -> element -> field
+        let consolidations_leaves = 1 << GindexCalculator::pending_consolidations_tree_depth();
+        assert_eq!(
+            GindexCalculator::consolidation_source_gindex(5),
+            GindexCalculator::concat_gindices(&[11, 100, 2, consolidations_leaves + 5, 2])
+        );
+        assert_eq!(
+            GindexCalculator::validator_credentials_gindex(7),
+            GindexCalculator::concat_gindices(&[11, 75, 2, (1 << 40) + 7, 9])
+        );
+        assert_eq!(
+            GindexCalculator::test_validator_activation_epoch_gindex(7),
+            GindexCalculator::concat_gindices(&[11, 75, 2, (1 << 10) + 7, 13])
+        );
+        assert_eq!(
+            GindexCalculator::test_consolidation_source_gindex(3),
+            GindexCalculator::concat_gindices(&[11, 100, 2, (1 << 6) + 3, 2])
+        );
+    }
+
+    #[test]
+    fn test_block_root_gindex_for_new_fields() {
+        let path: [PathStep; 4] = [
+            "state_root".into(),
+            "validators".into(),
+            7.into(),
+            "exit_epoch".into(),
+        ];
+        let gindex =
+            GindexCalculator::block_root_gindex(ForkName::Fulu, &StateLimits::PRESET, &path)
+                .unwrap();
+        assert_eq!(
+            gindex,
+            GindexCalculator::concat_gindices(&[11, 75, 2, (1 << 40) + 7, 14])
+        );
+        assert!(GindexCalculator::consolidation_source_gindex_for(ForkName::Deneb, 0).is_err());
+    }
+
+    #[test]
+    fn test_exit_field_gindices() {
+        // Validator fields 2, 6 and 7 in an 8-leaf container
+        let base = [11, 75, 2, (1 << 40) + 3];
+        let with_field = |field| {
+            let mut path = base.to_vec();
+            path.push(field);
+            GindexCalculator::concat_gindices(&path)
+        };
+        assert_eq!(
+            GindexCalculator::validator_effective_balance_gindex(3),
+            with_field(10)
+        );
+        assert_eq!(
+            GindexCalculator::validator_exit_epoch_gindex(3),
+            with_field(14)
+        );
+        assert_eq!(
+            GindexCalculator::validator_withdrawable_epoch_gindex(3),
+            with_field(15)
+        );
+        assert_eq!(
+            GindexCalculator::validator_field_gindex_for(
+                ForkName::Electra,
+                &StateLimits::PRESET,
+                3,
+                "exit_epoch"
+            ),
+            Ok(with_field(14))
+        );
+        assert_eq!(
+            GindexCalculator::test_validator_field_gindex(3, "withdrawal_credentials"),
+            GindexCalculator::test_validator_credentials_gindex(3)
+        );
+    }
+
+    #[test]
+    fn test_historical_gindices() {
+        // header.state_root (11) → historical_summaries (64 + 27) → data (2)
+        // → [k] (2^24 + k) → block_summary_root (2)
+        assert_eq!(
+            GindexCalculator::historical_summary_gindex(5),
+            GindexCalculator::concat_gindices(&[11, 64 + 27, 2, (1 << 24) + 5, 2])
+        );
+        assert_eq!(
+            GindexCalculator::gindex_depth(GindexCalculator::historical_summary_gindex(0)),
+            35
+        );
+        assert_eq!(
+            GindexCalculator::historical_summary_gindex_for(ForkName::Fulu, 5),
+            Ok(GindexCalculator::historical_summary_gindex(5))
+        );
+        assert_eq!(
+            GindexCalculator::historical_block_root_gindex(8192 * 3 + 7),
+            8192 + 7
+        );
+    }
+
     #[test]
     #[cfg(all(feature = "gnosis", not(feature = "minimal")))]
     fn test_consolidation_proof_length_gnosis() {
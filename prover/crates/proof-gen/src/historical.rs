@@ -0,0 +1,343 @@
+//! Historical Proofs
+//!
+//! EIP-4788 only exposes the last 8191 block roots, so a consolidation that
+//! was pending only in an older state cannot be proven against it directly.
+//! Every state since Capella carries `historical_summaries`, whose
+//! `block_summary_root` commits to the block roots of one
+//! `SLOTS_PER_HISTORICAL_ROOT` period. A historical proof chains:
+//!
+//! 1. recent block root → `historical_summaries[k].block_summary_root`
+//! 2. `block_summary_root` → `block_roots[slot % SLOTS_PER_HISTORICAL_ROOT]`,
+//!    the old block root
+//! 3. old block root → the old state's fields, as a regular
+//!    [`ConsolidationProofBundle`]
+//!
+//! The concatenated gindex would exceed 64 bits, so each step is proven and
+//! verified on its own, the leaf of one step being the root of the next.
+
+use crate::beacon_state::BeaconBlockHeader;
+use crate::fork::{ForkName, ForkRegistry};
+use crate::gindex::GindexCalculator;
+use crate::proof::{ConsolidationProofBundle, ProofError, ProofGenerator};
+use crate::schema::{PathStep, StateLimits};
+use crate::sparse_proof::prove_against_leaf_chunks;
+use crate::state_prover::{PathProof, StateProver};
+use crate::types::preset;
+use ssz_rs::prelude::*;
+
+/// Index into `historical_summaries` of the period containing `slot`, or
+/// `None` if `slot` predates Capella in `registry`
+#[must_use]
+pub fn summary_index(registry: &ForkRegistry, slot: u64) -> Option<u64> {
+    let slots_per_historical_root = StateLimits::PRESET.slots_per_historical_root;
+    let capella_slot = registry
+        .by_name(ForkName::Capella)?
+        .epoch
+        .checked_mul(preset::SLOTS_PER_EPOCH)?;
+    (slot >= capella_slot)
+        .then(|| slot / slots_per_historical_root - capella_slot / slots_per_historical_root)
+}
+
+/// First slot whose state's `block_roots` equal the `block_summary_root`
+/// covering `slot`, i.e. the first slot of the following period
+#[must_use]
+pub fn summary_state_slot(slot: u64) -> u64 {
+    let slots_per_historical_root = StateLimits::PRESET.slots_per_historical_root;
+    (slot / slots_per_historical_root + 1) * slots_per_historical_root
+}
+
+/// Proof of an old block root against a recent block root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoricalBlockProof {
+    /// Slot of the old block
+    pub slot: u64,
+    /// Index into the recent state's `historical_summaries`
+    pub summary_index: u64,
+    /// Recent block root → `historical_summaries[k].block_summary_root`
+    pub summary: PathProof,
+    /// `block_summary_root` → old block root
+    pub block_root: PathProof,
+}
+
+impl HistoricalBlockProof {
+    /// Prove the block root at `slot` from `recent_header`.
+    ///
+    /// `block_roots` is the `block_roots` vector of the state at
+    /// [`summary_state_slot`]`(slot)`; its root must match the recent state's
+    /// summary for that period.
+    pub fn generate(
+        recent: &StateProver,
+        recent_header: &BeaconBlockHeader,
+        block_roots: &[[u8; 32]],
+        slot: u64,
+    ) -> Result<Self, ProofError> {
+        let slots_per_historical_root = StateLimits::PRESET.slots_per_historical_root;
+        if block_roots.len() as u64 != slots_per_historical_root {
+            return Err(ProofError::ProofGenerationFailed(format!(
+                "Expected {slots_per_historical_root} block roots, got {}",
+                block_roots.len()
+            )));
+        }
+        let summary_index = summary_index(&ForkRegistry::gnosis(), slot).ok_or_else(|| {
+            ProofError::ProofGenerationFailed(format!("Slot {slot} predates historical summaries"))
+        })?;
+
+        let summary = recent.prove_path(
+            recent_header,
+            &[
+                "state_root".into(),
+                "historical_summaries".into(),
+                PathStep::Index(summary_index),
+                "block_summary_root".into(),
+            ],
+        )?;
+
+        let position = (slot % slots_per_historical_root) as usize;
+        let depth = slots_per_historical_root.trailing_zeros();
+        let (branch, summary_root) = prove_against_leaf_chunks(block_roots, position, depth);
+        if summary_root != summary.leaf {
+            return Err(ProofError::ProofGenerationFailed(format!(
+                "Block roots do not match historical summary {summary_index}"
+            )));
+        }
+
+        Ok(Self {
+            slot,
+            summary_index,
+            summary,
+            block_root: PathProof {
+                leaf: block_roots[position],
+                branch,
+                gindex: GindexCalculator::historical_block_root_gindex(slot),
+            },
+        })
+    }
+
+    /// The proven old block root
+    pub fn old_block_root(&self) -> [u8; 32] {
+        self.block_root.leaf
+    }
+
+    /// Verify against a recent block root, returning the old block root.
+    pub fn verify(&self, recent_block_root: [u8; 32]) -> Result<[u8; 32], ProofError> {
+        let expected_index = summary_index(&ForkRegistry::gnosis(), self.slot);
+        if expected_index != Some(self.summary_index) {
+            return Err(ProofError::ProofGenerationFailed(format!(
+                "Summary index {} does not cover slot {}",
+                self.summary_index, self.slot
+            )));
+        }
+        if self.summary.gindex
+            != GindexCalculator::historical_summary_gindex_for(
+                ForkName::Electra,
+                self.summary_index,
+            )?
+            || self.block_root.gindex != GindexCalculator::historical_block_root_gindex(self.slot)
+        {
+            return Err(ProofError::ProofGenerationFailed(
+                "Historical proof gindices do not match its slot".to_string(),
+            ));
+        }
+
+        self.summary.verify(recent_block_root)?;
+        self.block_root.verify(self.summary.leaf)?;
+        Ok(self.old_block_root())
+    }
+}
+
+/// Consolidation proof against an old block, chained to a recent block root.
+#[derive(Debug, Clone)]
+pub struct HistoricalConsolidationProof {
+    /// Recent block root → old block root
+    pub history: HistoricalBlockProof,
+    /// Old block root → consolidation and validator fields.
+    /// `beacon_timestamp` refers to the recent block.
+    pub bundle: ConsolidationProofBundle,
+}
+
+impl HistoricalConsolidationProof {
+    /// Prove `consolidation_index` of the old state behind `old_header`
+    /// against `recent_header`.
+    ///
+    /// `beacon_timestamp` is the EIP-4788 timestamp of the recent block.
+    pub fn generate(
+        recent: &StateProver,
+        recent_header: &BeaconBlockHeader,
+        block_roots: &[[u8; 32]],
+        old: &StateProver,
+        old_header: &BeaconBlockHeader,
+        consolidation_index: usize,
+        beacon_timestamp: u64,
+    ) -> Result<Self, ProofError> {
+        let history =
+            HistoricalBlockProof::generate(recent, recent_header, block_roots, old_header.slot)?;
+        let old_block_root: [u8; 32] = old_header.hash_tree_root()?.into();
+        if old_block_root != history.old_block_root() {
+            return Err(ProofError::ProofGenerationFailed(format!(
+                "Old header is not the canonical block at slot {}",
+                old_header.slot
+            )));
+        }
+        let bundle =
+            old.generate_full_proof_bundle(old_header, consolidation_index, beacon_timestamp)?;
+        Ok(Self { history, bundle })
+    }
+
+    /// Verify the chain from a recent block root down to the bundle's leaves.
+    pub fn verify(&self, recent_block_root: [u8; 32]) -> Result<(), ProofError> {
+        let old_block_root = self.history.verify(recent_block_root)?;
+        ProofGenerator::verify_proof_bundle(&self.bundle, old_block_root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::beacon_state::{
+        GnosisBeaconState, HistoricalSummary, PendingConsolidation, Validator,
+    };
+
+    /// First slot of the Capella period on Gnosis: epoch 648704 * 16
+    const CAPELLA_SLOT: u64 = 10_379_264;
+
+    struct Fixture {
+        recent: StateProver,
+        recent_header: BeaconBlockHeader,
+        block_roots: Vec<[u8; 32]>,
+        old: StateProver,
+        old_header: BeaconBlockHeader,
+    }
+
+    fn fixture() -> Fixture {
+        let old_slot = CAPELLA_SLOT + 2 * 8192 + 100;
+
+        let mut old_state = GnosisBeaconState {
+            slot: old_slot,
+            ..GnosisBeaconState::default()
+        };
+        for i in 0..3u8 {
+            let mut validator = Validator::default();
+            validator.withdrawal_credentials[0] = 0x01;
+            validator.withdrawal_credentials[12..].copy_from_slice(&[i; 20]);
+            validator.activation_epoch = 10 + i as u64;
+            old_state.validators.push(validator);
+        }
+        old_state.pending_consolidations.push(PendingConsolidation {
+            source_index: 2,
+            target_index: 0,
+        });
+        let old_header = BeaconBlockHeader {
+            slot: old_slot,
+            proposer_index: 1,
+            parent_root: [0x01; 32],
+            state_root: old_state.hash_tree_root().unwrap().into(),
+            body_root: [0x02; 32],
+        };
+        let old_block_root: [u8; 32] = old_header.hash_tree_root().unwrap().into();
+
+        let mut block_roots = vec![[0x33; 32]; 8192];
+        block_roots[100] = old_block_root;
+        let (_, block_summary_root) = prove_against_leaf_chunks(&block_roots, 0, 13);
+
+        let mut recent_state = GnosisBeaconState {
+            slot: old_slot + 50_000,
+            ..GnosisBeaconState::default()
+        };
+        for k in 0..4u8 {
+            recent_state.historical_summaries.push(HistoricalSummary {
+                block_summary_root: if k == 2 { block_summary_root } else { [k; 32] },
+                state_summary_root: [0x44; 32],
+            });
+        }
+        let recent_header = BeaconBlockHeader {
+            slot: recent_state.slot,
+            proposer_index: 2,
+            parent_root: [0x05; 32],
+            state_root: recent_state.hash_tree_root().unwrap().into(),
+            body_root: [0x06; 32],
+        };
+
+        Fixture {
+            recent: StateProver::from_gnosis_state(&recent_state).unwrap(),
+            recent_header,
+            block_roots,
+            old: StateProver::from_gnosis_state(&old_state).unwrap(),
+            old_header,
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "gnosis", not(feature = "minimal")))]
+    fn test_summary_index() {
+        let gnosis = ForkRegistry::gnosis();
+        assert_eq!(summary_index(&gnosis, CAPELLA_SLOT - 1), None);
+        assert_eq!(summary_index(&gnosis, CAPELLA_SLOT), Some(0));
+        assert_eq!(summary_index(&gnosis, CAPELLA_SLOT + 8191), Some(0));
+        assert_eq!(summary_index(&gnosis, CAPELLA_SLOT + 8192), Some(1));
+        assert_eq!(summary_state_slot(CAPELLA_SLOT + 5), CAPELLA_SLOT + 8192);
+    }
+
+    #[test]
+    #[cfg(all(feature = "gnosis", not(feature = "minimal")))]
+    fn test_historical_consolidation_proof_verifies() {
+        let f = fixture();
+        let recent_block_root: [u8; 32] = f.recent_header.hash_tree_root().unwrap().into();
+
+        let proof = HistoricalConsolidationProof::generate(
+            &f.recent,
+            &f.recent_header,
+            &f.block_roots,
+            &f.old,
+            &f.old_header,
+            0,
+            1_700_000_000,
+        )
+        .expect("should generate historical proof");
+        assert_eq!(proof.history.summary_index, 2);
+        assert_eq!(proof.bundle.source_index, 2);
+        proof.verify(recent_block_root).expect("should verify");
+
+        assert!(proof.verify([0xaa; 32]).is_err());
+
+        // Claiming a different slot breaks the gindex binding
+        let mut moved = proof.clone();
+        moved.history.slot += 1;
+        assert!(moved.verify(recent_block_root).is_err());
+    }
+
+    #[test]
+    fn test_generate_rejects_mismatched_inputs() {
+        let f = fixture();
+
+        let mut wrong_roots = f.block_roots.clone();
+        wrong_roots[0] = [0xff; 32];
+        assert!(HistoricalBlockProof::generate(
+            &f.recent,
+            &f.recent_header,
+            &wrong_roots,
+            f.old_header.slot
+        )
+        .is_err());
+
+        let mut other_header = f.old_header.clone();
+        other_header.proposer_index = 9;
+        assert!(HistoricalConsolidationProof::generate(
+            &f.recent,
+            &f.recent_header,
+            &f.block_roots,
+            &f.old,
+            &other_header,
+            0,
+            0
+        )
+        .is_err());
+
+        assert!(HistoricalBlockProof::generate(
+            &f.recent,
+            &f.recent_header,
+            &f.block_roots[..10],
+            f.old_header.slot
+        )
+        .is_err());
+    }
+}
@@ -18,17 +18,43 @@
 //!    generation that works with any list limits (including gnosis's 2^40 validators)
 //!    without allocating full Merkle trees. Required for production use.
 
+pub mod beacon_block;
 pub mod beacon_client;
 pub mod beacon_state;
+pub mod bundle_file;
+pub mod events;
+pub mod fork;
 pub mod gindex;
+pub mod historical;
+pub mod light_client;
+pub mod multiproof;
 pub mod proof;
+pub mod scan;
+pub mod schema;
 pub mod sparse_proof;
+pub mod state_download;
 pub mod state_prover;
+pub mod state_source;
+pub mod synthetic;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod synthetic_light_client;
 pub mod types;
 
 pub use beacon_client::BeaconClient;
-pub use beacon_state::{BeaconBlockHeader as FullBeaconBlockHeader, MinimalBeaconState};
+pub use beacon_state::{
+    BeaconBlockHeader as FullBeaconBlockHeader, GnosisBeaconState, MinimalBeaconState,
+    VersionedGnosisState,
+};
+pub use bundle_file::{BundleContext, BundleFileError, ProofBundleV1};
+pub use fork::{ForkName, ForkRegistry, StateLayout};
 pub use gindex::GindexCalculator;
-pub use proof::{ConsolidationProofBundle, ProofError, ProofGenerator};
-pub use state_prover::StateProver;
+pub use light_client::{LightClient, LightClientConfig};
+pub use multiproof::MultiProof;
+pub use proof::{
+    ConsolidationProofBundle, ExitedValidatorProofBundle, ProofError, ProofGenerator, TargetProof,
+};
+pub use schema::PathStep;
+pub use state_prover::{PathProof, StateProver};
+pub use state_source::StateSource;
+pub use synthetic::{SyntheticState, SyntheticStateConfig};
 pub use types::*;
@@ -0,0 +1,427 @@
+//! SSZ Compact Multiproofs
+//!
+//! A multiproof proves several leaves of the same tree with one shared set of
+//! helper nodes, following the consensus specs' `get_helper_indices` and
+//! `calculate_multi_merkle_root`. Siblings that separate branches have in
+//! common (the header levels, the upper state levels, the levels above a
+//! validator) are sent once instead of once per branch.
+//!
+//! Helper nodes are ordered by descending generalized index, as in the specs.
+
+use crate::sparse_proof::hash_pair;
+use std::collections::{BTreeMap, BTreeSet};
+use thiserror::Error;
+
+/// Errors from building or verifying a multiproof
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum MultiProofError {
+    #[error("Generalized index 0 is not a tree node")]
+    ZeroGindex,
+
+    #[error("Expected {expected} leaves, got {got}")]
+    LeafCountMismatch { expected: usize, got: usize },
+
+    #[error("Expected {expected} helper nodes, got {got}")]
+    HelperCountMismatch { expected: usize, got: usize },
+
+    #[error("Branch of length {len} does not match depth {depth} of gindex {gindex}")]
+    BranchDepthMismatch { gindex: u64, len: usize, depth: u32 },
+
+    #[error("Branches disagree on the value of node {0}")]
+    ConflictingNode(u64),
+
+    #[error("No value for node {0}")]
+    MissingNode(u64),
+
+    #[error("Generalized index {descendant} is {ancestor} or below it")]
+    OverlappingGindices { ancestor: u64, descendant: u64 },
+
+    #[error("Proof is for generalized indices {got:?}, expected {expected:?}")]
+    GindexMismatch { expected: Vec<u64>, got: Vec<u64> },
+
+    #[error("Computed root 0x{computed} does not match expected 0x{expected}")]
+    RootMismatch { computed: String, expected: String },
+}
+
+/// Sibling indices along the path from `gindex` to the root, deepest first.
+fn branch_indices(gindex: u64) -> impl Iterator<Item = u64> {
+    std::iter::successors(Some(gindex), |g| (*g > 1).then_some(g / 2))
+        .take_while(|g| *g > 1)
+        .map(|g| g ^ 1)
+}
+
+/// `gindex` and its ancestors, excluding the root.
+fn path_indices(gindex: u64) -> impl Iterator<Item = u64> {
+    std::iter::successors(Some(gindex), |g| (*g > 1).then_some(g / 2)).take_while(|g| *g > 1)
+}
+
+/// Reject index sets a verifier cannot hash up unambiguously: gindex 0, and
+/// an index that repeats another or lies below it, whose value would never
+/// reach the root.
+fn check_indices(indices: &[u64]) -> Result<(), MultiProofError> {
+    let mut seen = BTreeSet::new();
+    for &gindex in indices {
+        if gindex == 0 {
+            return Err(MultiProofError::ZeroGindex);
+        }
+        if !seen.insert(gindex) {
+            return Err(MultiProofError::OverlappingGindices {
+                ancestor: gindex,
+                descendant: gindex,
+            });
+        }
+    }
+    for &gindex in indices {
+        if let Some(ancestor) = path_indices(gindex).skip(1).find(|g| seen.contains(g)) {
+            return Err(MultiProofError::OverlappingGindices {
+                ancestor,
+                descendant: gindex,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Helper node indices needed to prove `indices`, in descending order.
+///
+/// These are the siblings of every path that are not themselves on a path,
+/// i.e. the nodes a verifier cannot compute from the leaves.
+pub fn helper_indices(indices: &[u64]) -> Vec<u64> {
+    let on_path: BTreeSet<u64> = indices.iter().flat_map(|g| path_indices(*g)).collect();
+    let helpers: BTreeSet<u64> = indices
+        .iter()
+        .flat_map(|g| branch_indices(*g))
+        .filter(|g| !on_path.contains(g))
+        .collect();
+    helpers.into_iter().rev().collect()
+}
+
+/// Proof of several leaves of one tree against its root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiProof {
+    /// Generalized indices of the proven leaves
+    pub indices: Vec<u64>,
+    /// Leaf values, in the same order as `indices`
+    pub leaves: Vec<[u8; 32]>,
+    /// Helper nodes, ordered as [`helper_indices`] returns them
+    pub proof: Vec<[u8; 32]>,
+}
+
+impl MultiProof {
+    /// Build a multiproof from single-leaf branches `(gindex, leaf, branch)`
+    /// of the same tree, with each branch ordered from the leaf up.
+    pub fn from_branches<'a>(
+        branches: impl IntoIterator<Item = (u64, [u8; 32], &'a [[u8; 32]])>,
+    ) -> Result<Self, MultiProofError> {
+        let mut indices = Vec::new();
+        let mut leaves = Vec::new();
+        let mut nodes = BTreeMap::new();
+        let mut insert = |index: u64, value: [u8; 32]| match nodes.insert(index, value) {
+            Some(existing) if existing != value => Err(MultiProofError::ConflictingNode(index)),
+            _ => Ok(()),
+        };
+
+        for (gindex, leaf, branch) in branches {
+            if gindex == 0 {
+                return Err(MultiProofError::ZeroGindex);
+            }
+            let depth = gindex.ilog2();
+            if branch.len() != depth as usize {
+                return Err(MultiProofError::BranchDepthMismatch {
+                    gindex,
+                    len: branch.len(),
+                    depth,
+                });
+            }
+            insert(gindex, leaf)?;
+            for (sibling, value) in branch_indices(gindex).zip(branch) {
+                insert(sibling, *value)?;
+            }
+            indices.push(gindex);
+            leaves.push(leaf);
+        }
+        check_indices(&indices)?;
+
+        let proof = helper_indices(&indices)
+            .into_iter()
+            .map(|index| {
+                nodes
+                    .get(&index)
+                    .copied()
+                    .ok_or(MultiProofError::MissingNode(index))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            indices,
+            leaves,
+            proof,
+        })
+    }
+
+    /// Compute the root implied by the leaves and helper nodes.
+    pub fn calculate_root(&self) -> Result<[u8; 32], MultiProofError> {
+        if self.leaves.len() != self.indices.len() {
+            return Err(MultiProofError::LeafCountMismatch {
+                expected: self.indices.len(),
+                got: self.leaves.len(),
+            });
+        }
+        check_indices(&self.indices)?;
+        let helpers = helper_indices(&self.indices);
+        if helpers.len() != self.proof.len() {
+            return Err(MultiProofError::HelperCountMismatch {
+                expected: helpers.len(),
+                got: self.proof.len(),
+            });
+        }
+
+        let mut nodes: BTreeMap<u64, [u8; 32]> = self
+            .indices
+            .iter()
+            .copied()
+            .zip(self.leaves.iter().copied())
+            .chain(helpers.into_iter().zip(self.proof.iter().copied()))
+            .collect();
+
+        // Hash up from the deepest nodes; every parent is shallower than its
+        // children, so popping the largest index visits children first.
+        let mut pending: BTreeSet<u64> = nodes.keys().copied().filter(|g| *g > 1).collect();
+        while let Some(index) = pending.pop_last() {
+            let parent = index / 2;
+            if nodes.contains_key(&parent) {
+                continue;
+            }
+            let (Some(left), Some(right)) =
+                (nodes.get(&(parent * 2)), nodes.get(&(parent * 2 + 1)))
+            else {
+                return Err(MultiProofError::MissingNode(index ^ 1));
+            };
+            nodes.insert(parent, hash_pair(left, right));
+            if parent > 1 {
+                pending.insert(parent);
+            }
+        }
+
+        nodes
+            .get(&1)
+            .copied()
+            .ok_or(MultiProofError::MissingNode(1))
+    }
+
+    /// Verify the multiproof against `root`, for exactly the leaves at
+    /// `gindices` in that order.
+    ///
+    /// The proof's own `indices` are not trusted: a proof for other positions
+    /// can hash to the same root.
+    pub fn verify(&self, root: [u8; 32], gindices: &[u64]) -> Result<(), MultiProofError> {
+        if self.indices != gindices {
+            return Err(MultiProofError::GindexMismatch {
+                expected: gindices.to_vec(),
+                got: self.indices.clone(),
+            });
+        }
+        let computed = self.calculate_root()?;
+        if computed != root {
+            return Err(MultiProofError::RootMismatch {
+                computed: hex::encode(computed),
+                expected: hex::encode(root),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Calldata needed for separate branches versus one multiproof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CalldataComparison {
+    /// Total sibling hashes across the separate branches
+    pub separate_hashes: usize,
+    /// Helper hashes in the multiproof
+    pub multiproof_hashes: usize,
+}
+
+impl CalldataComparison {
+    /// Compare separate branches against the equivalent multiproof.
+    pub fn new<'a>(
+        branches: impl IntoIterator<Item = &'a [[u8; 32]]>,
+        multiproof: &MultiProof,
+    ) -> Self {
+        Self {
+            separate_hashes: branches.into_iter().map(<[_]>::len).sum(),
+            multiproof_hashes: multiproof.proof.len(),
+        }
+    }
+
+    /// Hashes saved by the multiproof
+    pub fn saved_hashes(&self) -> usize {
+        self.separate_hashes.saturating_sub(self.multiproof_hashes)
+    }
+
+    /// Calldata bytes saved by the multiproof
+    pub fn saved_bytes(&self) -> usize {
+        self.saved_hashes() * 32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Full binary tree over `leaves`, indexed by gindex.
+    fn build_tree(leaves: &[[u8; 32]]) -> Vec<[u8; 32]> {
+        let width = leaves.len();
+        assert!(width.is_power_of_two());
+        let mut tree = vec![[0u8; 32]; 2 * width];
+        tree[width..].copy_from_slice(leaves);
+        for i in (1..width).rev() {
+            tree[i] = hash_pair(&tree[2 * i], &tree[2 * i + 1]);
+        }
+        tree
+    }
+
+    fn branch(tree: &[[u8; 32]], gindex: u64) -> Vec<[u8; 32]> {
+        branch_indices(gindex).map(|g| tree[g as usize]).collect()
+    }
+
+    fn sample_tree() -> Vec<[u8; 32]> {
+        let leaves: Vec<[u8; 32]> = (0..16u8).map(|i| [i + 1; 32]).collect();
+        build_tree(&leaves)
+    }
+
+    #[test]
+    fn test_helper_indices_match_spec_example() {
+        // 8 and 9 are siblings and only need 5; 14 needs 15 and 6
+        assert_eq!(helper_indices(&[8, 9, 14]), vec![15, 6, 5]);
+        assert_eq!(helper_indices(&[2]), vec![3]);
+        assert_eq!(helper_indices(&[2, 3]), Vec::<u64>::new());
+        assert_eq!(helper_indices(&[1]), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn test_multiproof_roundtrip() {
+        let tree = sample_tree();
+        let indices = [16u64, 17, 21, 30, 6];
+        let branches: Vec<Vec<[u8; 32]>> = indices.iter().map(|g| branch(&tree, *g)).collect();
+
+        let multiproof = MultiProof::from_branches(
+            indices
+                .iter()
+                .zip(&branches)
+                .map(|(g, b)| (*g, tree[*g as usize], b.as_slice())),
+        )
+        .expect("should build multiproof");
+
+        assert_eq!(multiproof.proof.len(), helper_indices(&indices).len());
+        multiproof.verify(tree[1], &indices).expect("should verify");
+
+        let comparison = CalldataComparison::new(branches.iter().map(Vec::as_slice), &multiproof);
+        assert_eq!(comparison.separate_hashes, 4 + 4 + 4 + 4 + 2);
+        assert!(comparison.saved_hashes() > 0);
+        assert_eq!(comparison.saved_bytes(), comparison.saved_hashes() * 32);
+    }
+
+    #[test]
+    fn test_multiproof_rejects_tampering() {
+        let tree = sample_tree();
+        let indices = [18u64, 25];
+        let branches: Vec<Vec<[u8; 32]>> = indices.iter().map(|g| branch(&tree, *g)).collect();
+        let multiproof = MultiProof::from_branches(
+            indices
+                .iter()
+                .zip(&branches)
+                .map(|(g, b)| (*g, tree[*g as usize], b.as_slice())),
+        )
+        .unwrap();
+
+        let mut bad_leaf = multiproof.clone();
+        bad_leaf.leaves[1][0] ^= 1;
+        assert!(matches!(
+            bad_leaf.verify(tree[1], &indices),
+            Err(MultiProofError::RootMismatch { .. })
+        ));
+
+        let mut short = multiproof.clone();
+        short.proof.pop();
+        assert!(matches!(
+            short.verify(tree[1], &indices),
+            Err(MultiProofError::HelperCountMismatch { .. })
+        ));
+
+        assert!(multiproof.verify([0u8; 32], &indices).is_err());
+
+        // The same nodes claimed for other positions
+        let mut moved = multiproof.clone();
+        moved.indices = vec![25, 18];
+        moved.leaves.reverse();
+        assert!(matches!(
+            moved.verify(tree[1], &indices),
+            Err(MultiProofError::GindexMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_multiproof_rejects_forged_descendant_leaf() {
+        let tree = sample_tree();
+        // Leaf 2 alone determines the root; a leaf below it is never hashed
+        let forged = MultiProof {
+            indices: vec![2, 4],
+            leaves: vec![tree[2], [0xee; 32]],
+            proof: vec![tree[3]],
+        };
+        assert_eq!(
+            forged.verify(tree[1], &[2, 4]),
+            Err(MultiProofError::OverlappingGindices {
+                ancestor: 2,
+                descendant: 4
+            })
+        );
+        assert_eq!(
+            MultiProof::from_branches([
+                (2, tree[2], branch(&tree, 2).as_slice()),
+                (4, [0xee; 32], branch(&tree, 4).as_slice()),
+            ]),
+            Err(MultiProofError::OverlappingGindices {
+                ancestor: 2,
+                descendant: 4
+            })
+        );
+
+        let repeated = MultiProof {
+            indices: vec![20, 20],
+            leaves: vec![tree[20], [0xee; 32]],
+            proof: branch(&tree, 20),
+        };
+        assert!(matches!(
+            repeated.calculate_root(),
+            Err(MultiProofError::OverlappingGindices { .. })
+        ));
+    }
+
+    #[test]
+    fn test_from_branches_rejects_inconsistent_input() {
+        let tree = sample_tree();
+        let good = branch(&tree, 20);
+
+        let short = &good[..3];
+        assert_eq!(
+            MultiProof::from_branches([(20, tree[20], short)]),
+            Err(MultiProofError::BranchDepthMismatch {
+                gindex: 20,
+                len: 3,
+                depth: 4
+            })
+        );
+
+        // Two branches that disagree on a shared sibling
+        let mut other = branch(&tree, 21);
+        other[3][0] ^= 1;
+        assert!(matches!(
+            MultiProof::from_branches([
+                (20, tree[20], good.as_slice()),
+                (21, tree[21], other.as_slice())
+            ]),
+            Err(MultiProofError::ConflictingNode(_))
+        ));
+    }
+}
@@ -7,7 +7,10 @@
 //! 3. Proof of `validators[source].activation_epoch`
 
 use crate::beacon_state::{BeaconBlockHeader, MinimalBeaconState};
+use crate::fork::{ForkName, ForkRegistry, ProofLengths, FAR_FUTURE_EPOCH};
 use crate::gindex::GindexCalculator;
+use crate::multiproof::{CalldataComparison, MultiProof, MultiProofError};
+use crate::schema::{PathStep, SchemaError, StateLimits};
 use serde::{Deserialize, Serialize};
 use ssz_rs::prelude::*;
 use thiserror::Error;
@@ -32,6 +35,102 @@ fn bytes_to_nodes(bytes: &[[u8; 32]]) -> Vec<Node> {
     bytes.iter().map(|b| bytes_to_node(*b)).collect()
 }
 
+/// Gindices of a bundle's consolidation, credentials and activation epoch
+/// proofs from the block root, under the given state limits
+fn bundle_gindices(
+    bundle: &ConsolidationProofBundle,
+    limits: &StateLimits,
+) -> Result<[u64; 3], ProofError> {
+    let gindex =
+        |path: [PathStep; 4]| GindexCalculator::block_root_gindex(ForkName::Electra, limits, &path);
+    Ok([
+        gindex([
+            "state_root".into(),
+            "pending_consolidations".into(),
+            bundle.consolidation_index.into(),
+            "source_index".into(),
+        ])?,
+        gindex([
+            "state_root".into(),
+            "validators".into(),
+            bundle.source_index.into(),
+            "withdrawal_credentials".into(),
+        ])?,
+        gindex([
+            "state_root".into(),
+            "validators".into(),
+            bundle.source_index.into(),
+            "activation_epoch".into(),
+        ])?,
+    ])
+}
+
+/// Gindices of a target proof's target_index, credentials and effective
+/// balance proofs from the block root
+fn target_gindices(
+    consolidation_index: u64,
+    target: &TargetProof,
+    limits: &StateLimits,
+) -> Result<[u64; 3], ProofError> {
+    let validator_field = |field| {
+        GindexCalculator::validator_field_gindex_for(
+            ForkName::Electra,
+            limits,
+            target.target_index,
+            field,
+        )
+    };
+    Ok([
+        GindexCalculator::block_root_gindex(
+            ForkName::Electra,
+            limits,
+            &[
+                "state_root".into(),
+                "pending_consolidations".into(),
+                consolidation_index.into(),
+                "target_index".into(),
+            ],
+        )?,
+        validator_field("withdrawal_credentials")?,
+        validator_field("effective_balance")?,
+    ])
+}
+
+/// Gindices of an exit bundle's credentials, activation epoch, exit epoch,
+/// withdrawable epoch and effective balance proofs from the block root
+fn exit_bundle_gindices(
+    bundle: &ExitedValidatorProofBundle,
+    limits: &StateLimits,
+) -> Result<[u64; 5], ProofError> {
+    let gindex = |field| {
+        GindexCalculator::validator_field_gindex_for(
+            ForkName::Electra,
+            limits,
+            bundle.source_index,
+            field,
+        )
+    };
+    Ok([
+        gindex("withdrawal_credentials")?,
+        gindex("activation_epoch")?,
+        gindex("exit_epoch")?,
+        gindex("withdrawable_epoch")?,
+        gindex("effective_balance")?,
+    ])
+}
+
+/// Recipient address of 0x01/0x02 withdrawal credentials
+fn recipient_from_credentials(credentials: &[u8; 32]) -> Option<[u8; 20]> {
+    let prefix = credentials[0];
+    if prefix == 0x01 || prefix == 0x02 {
+        let mut addr = [0u8; 20];
+        addr.copy_from_slice(&credentials[12..32]);
+        Some(addr)
+    } else {
+        None
+    }
+}
+
 /// Errors that can occur during proof generation.
 #[derive(Error, Debug)]
 pub enum ProofError {
@@ -49,6 +148,15 @@ pub enum ProofError {
 
     #[error("Merkleization error: {0}")]
     MerkleizationError(#[from] MerkleizationError),
+
+    #[error("Invalid proof path: {0}")]
+    Schema(#[from] SchemaError),
+
+    #[error("Multiproof error: {0}")]
+    MultiProof(#[from] MultiProofError),
+
+    #[error("Validator {0} has not exited with a zero effective balance")]
+    ValidatorNotDrained(u64),
 }
 
 /// A complete proof bundle for claiming a consolidation reward.
@@ -81,19 +189,249 @@ pub struct ConsolidationProofBundle {
     /// Merkle proof for validators[source].activation_epoch
     #[serde(with = "proof_vec_serde")]
     pub proof_activation_epoch: Vec<[u8; 32]>,
+
+    /// Optional facts about the consolidation's target validator
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<TargetProof>,
+}
+
+/// Proofs about the target validator of a pending consolidation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetProof {
+    /// Target validator index
+    pub target_index: u64,
+
+    /// Target validator's withdrawal credentials
+    #[serde(with = "hex::serde")]
+    pub target_credentials: [u8; 32],
+
+    /// Target validator's effective balance in gwei
+    pub target_effective_balance: u64,
+
+    /// Merkle proof for pending_consolidations[i].target_index
+    #[serde(with = "proof_vec_serde")]
+    pub proof_target_index: Vec<[u8; 32]>,
+
+    /// Merkle proof for validators[target].withdrawal_credentials
+    #[serde(with = "proof_vec_serde")]
+    pub proof_target_credentials: Vec<[u8; 32]>,
+
+    /// Merkle proof for validators[target].effective_balance
+    #[serde(with = "proof_vec_serde")]
+    pub proof_target_effective_balance: Vec<[u8; 32]>,
+}
+
+impl TargetProof {
+    /// Whether the target has 0x02 (compounding) withdrawal credentials.
+    pub fn is_compounding(&self) -> bool {
+        self.target_credentials[0] == 0x02
+    }
+
+    /// Verify the three proofs against a block root.
+    fn verify(
+        &self,
+        consolidation_index: u64,
+        block_root: [u8; 32],
+        limits: &StateLimits,
+    ) -> Result<(), ProofError> {
+        let gindices = target_gindices(consolidation_index, self, limits)?;
+        let proofs = [
+            (
+                "Target index",
+                ssz_u64_to_bytes32(self.target_index),
+                &self.proof_target_index,
+            ),
+            (
+                "Target credentials",
+                self.target_credentials,
+                &self.proof_target_credentials,
+            ),
+            (
+                "Target effective balance",
+                ssz_u64_to_bytes32(self.target_effective_balance),
+                &self.proof_target_effective_balance,
+            ),
+        ];
+        for (gindex, (name, leaf, branch)) in gindices.into_iter().zip(proofs) {
+            ssz_rs::proofs::is_valid_merkle_branch_for_generalized_index(
+                bytes_to_node(leaf),
+                &bytes_to_nodes(branch),
+                gindex as usize,
+                bytes_to_node(block_root),
+            )
+            .map_err(|e| ProofError::ProofGenerationFailed(format!("{name} proof invalid: {e}")))?;
+        }
+        Ok(())
+    }
 }
 
 impl ConsolidationProofBundle {
     /// Get the expected recipient address from withdrawal credentials.
     pub fn recipient_address(&self) -> Option<[u8; 20]> {
-        let prefix = self.source_credentials[0];
-        if prefix == 0x01 || prefix == 0x02 {
-            let mut addr = [0u8; 20];
-            addr.copy_from_slice(&self.source_credentials[12..32]);
-            Some(addr)
-        } else {
-            None
+        recipient_from_credentials(&self.source_credentials)
+    }
+
+    /// Whether source and target withdraw to the same execution address, or
+    /// `None` without a target proof.
+    pub fn shares_owner_with_target(&self) -> Option<bool> {
+        let target = self.target.as_ref()?;
+        Some(matches!(
+            (
+                self.recipient_address(),
+                recipient_from_credentials(&target.target_credentials),
+            ),
+            (Some(source), Some(target)) if source == target
+        ))
+    }
+
+    /// Combine the three proofs into one multiproof against the block root,
+    /// using gindices for a state with the given limits.
+    ///
+    /// Leaves are ordered consolidation, credentials, activation epoch.
+    pub fn to_multiproof(&self, limits: &StateLimits) -> Result<MultiProof, ProofError> {
+        let [consolidation_gindex, credentials_gindex, activation_gindex] =
+            bundle_gindices(self, limits)?;
+        Ok(MultiProof::from_branches([
+            (
+                consolidation_gindex,
+                ssz_u64_to_bytes32(self.source_index),
+                self.proof_consolidation.as_slice(),
+            ),
+            (
+                credentials_gindex,
+                self.source_credentials,
+                self.proof_credentials.as_slice(),
+            ),
+            (
+                activation_gindex,
+                ssz_u64_to_bytes32(self.activation_epoch),
+                self.proof_activation_epoch.as_slice(),
+            ),
+        ])?)
+    }
+
+    /// Verify a multiproof from [`Self::to_multiproof`] against `block_root`,
+    /// at the gindices of this bundle's consolidation and validator.
+    pub fn verify_multiproof(
+        &self,
+        multiproof: &MultiProof,
+        block_root: [u8; 32],
+        limits: &StateLimits,
+    ) -> Result<(), ProofError> {
+        Ok(multiproof.verify(block_root, &bundle_gindices(self, limits)?)?)
+    }
+
+    /// Calldata of the three separate proofs versus [`Self::to_multiproof`].
+    pub fn calldata_comparison(
+        &self,
+        limits: &StateLimits,
+    ) -> Result<CalldataComparison, ProofError> {
+        let multiproof = self.to_multiproof(limits)?;
+        Ok(CalldataComparison::new(
+            [
+                self.proof_consolidation.as_slice(),
+                self.proof_credentials.as_slice(),
+                self.proof_activation_epoch.as_slice(),
+            ],
+            &multiproof,
+        ))
+    }
+}
+
+/// Proof that a validator has exited and been drained to a zero effective balance.
+///
+/// Once a consolidation is processed its entry leaves `pending_consolidations`,
+/// but the source validator keeps an exit epoch and, its balance having moved
+/// to the target, a zero effective balance. This bundle evidences both from
+/// `validators[source]` alone.
+///
+/// It is not evidence of a consolidation on its own: a voluntary exit whose
+/// balance has been fully withdrawn looks the same. Pair it with a proof that
+/// the source was in `pending_consolidations` at an earlier state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExitedValidatorProofBundle {
+    /// Beacon timestamp for EIP-4788 lookup
+    pub beacon_timestamp: u64,
+
+    /// Source validator index
+    pub source_index: u64,
+
+    /// Source validator's activation epoch
+    pub activation_epoch: u64,
+
+    /// Source validator's exit epoch
+    pub exit_epoch: u64,
+
+    /// Source validator's withdrawable epoch
+    pub withdrawable_epoch: u64,
+
+    /// Source validator's effective balance in gwei, zero once consolidated
+    pub effective_balance: u64,
+
+    /// Source validator's withdrawal credentials
+    #[serde(with = "hex::serde")]
+    pub source_credentials: [u8; 32],
+
+    /// Merkle proof for validators[source].withdrawal_credentials
+    #[serde(with = "proof_vec_serde")]
+    pub proof_credentials: Vec<[u8; 32]>,
+
+    /// Merkle proof for validators[source].activation_epoch
+    #[serde(with = "proof_vec_serde")]
+    pub proof_activation_epoch: Vec<[u8; 32]>,
+
+    /// Merkle proof for validators[source].exit_epoch
+    #[serde(with = "proof_vec_serde")]
+    pub proof_exit_epoch: Vec<[u8; 32]>,
+
+    /// Merkle proof for validators[source].withdrawable_epoch
+    #[serde(with = "proof_vec_serde")]
+    pub proof_withdrawable_epoch: Vec<[u8; 32]>,
+
+    /// Merkle proof for validators[source].effective_balance
+    #[serde(with = "proof_vec_serde")]
+    pub proof_effective_balance: Vec<[u8; 32]>,
+}
+
+impl ExitedValidatorProofBundle {
+    /// Get the expected recipient address from withdrawal credentials.
+    pub fn recipient_address(&self) -> Option<[u8; 20]> {
+        recipient_from_credentials(&self.source_credentials)
+    }
+
+    /// Check the proven values describe a validator that has exited with no
+    /// effective balance left, whether by consolidation or a withdrawn exit.
+    pub fn check_exited_and_drained(&self) -> Result<(), ProofError> {
+        if self.exit_epoch == FAR_FUTURE_EPOCH
+            || self.withdrawable_epoch == FAR_FUTURE_EPOCH
+            || self.effective_balance != 0
+        {
+            return Err(ProofError::ValidatorNotDrained(self.source_index));
         }
+        Ok(())
+    }
+
+    /// Leaves and branches in the order of [`exit_bundle_gindices`]
+    fn leaves_and_branches(&self) -> [([u8; 32], &[[u8; 32]]); 5] {
+        [
+            (self.source_credentials, self.proof_credentials.as_slice()),
+            (
+                ssz_u64_to_bytes32(self.activation_epoch),
+                self.proof_activation_epoch.as_slice(),
+            ),
+            (
+                ssz_u64_to_bytes32(self.exit_epoch),
+                self.proof_exit_epoch.as_slice(),
+            ),
+            (
+                ssz_u64_to_bytes32(self.withdrawable_epoch),
+                self.proof_withdrawable_epoch.as_slice(),
+            ),
+            (
+                ssz_u64_to_bytes32(self.effective_balance),
+                self.proof_effective_balance.as_slice(),
+            ),
+        ]
     }
 }
 
@@ -115,6 +453,11 @@ impl ProofGenerator {
         )
     }
 
+    /// Get the expected proof lengths for each provable fork of the Gnosis schedule.
+    pub fn expected_proof_lengths_by_fork() -> Vec<(ForkName, ProofLengths)> {
+        ForkRegistry::gnosis().expected_proof_lengths()
+    }
+
     /// Get the expected proof lengths for the test state (MinimalBeaconState).
     pub fn test_proof_lengths() -> (u32, u32) {
         (
@@ -230,79 +573,42 @@ impl ProofGenerator {
             proof_consolidation: full_consolidation_proof,
             proof_credentials: full_credentials_proof,
             proof_activation_epoch: full_activation_proof,
+            target: None,
         })
     }
 
     /// Verify that a proof bundle is valid against a block root using test state gindices.
     ///
-    /// This uses the test state tree depths (smaller than production).
+    /// This uses the test state tree depths (smaller than production). The
+    /// target proof, if present, is verified as well.
     pub fn verify_proof_bundle_test(
         bundle: &ConsolidationProofBundle,
         block_root: [u8; 32],
     ) -> Result<(), ProofError> {
-        let block_root_node = bytes_to_node(block_root);
-
-        // Verify consolidation proof using test gindex
-        let consolidation_gindex =
-            GindexCalculator::test_consolidation_source_gindex(bundle.consolidation_index);
-        let consolidation_leaf = bytes_to_node(ssz_u64_to_bytes32(bundle.source_index));
-        let consolidation_branch = bytes_to_nodes(&bundle.proof_consolidation);
-
-        ssz_rs::proofs::is_valid_merkle_branch_for_generalized_index(
-            consolidation_leaf,
-            &consolidation_branch,
-            consolidation_gindex as usize,
-            block_root_node,
-        )
-        .map_err(|e| {
-            ProofError::ProofGenerationFailed(format!("Consolidation proof invalid: {e}"))
-        })?;
-
-        // Verify credentials proof using test gindex
-        let credentials_gindex =
-            GindexCalculator::test_validator_credentials_gindex(bundle.source_index);
-        let credentials_leaf = bytes_to_node(bundle.source_credentials);
-        let credentials_branch = bytes_to_nodes(&bundle.proof_credentials);
-
-        ssz_rs::proofs::is_valid_merkle_branch_for_generalized_index(
-            credentials_leaf,
-            &credentials_branch,
-            credentials_gindex as usize,
-            block_root_node,
-        )
-        .map_err(|e| {
-            ProofError::ProofGenerationFailed(format!("Credentials proof invalid: {e}"))
-        })?;
-
-        // Verify activation epoch proof using test gindex
-        let activation_gindex =
-            GindexCalculator::test_validator_activation_epoch_gindex(bundle.source_index);
-        let activation_leaf = bytes_to_node(ssz_u64_to_bytes32(bundle.activation_epoch));
-        let activation_branch = bytes_to_nodes(&bundle.proof_activation_epoch);
-
-        ssz_rs::proofs::is_valid_merkle_branch_for_generalized_index(
-            activation_leaf,
-            &activation_branch,
-            activation_gindex as usize,
-            block_root_node,
-        )
-        .map_err(|e| {
-            ProofError::ProofGenerationFailed(format!("Activation epoch proof invalid: {e}"))
-        })?;
-
-        Ok(())
+        Self::verify_proof_bundle_with(bundle, block_root, &StateLimits::TEST)
     }
 
     /// Verify that a proof bundle is valid against a block root using production gindices.
+    ///
+    /// The target proof, if present, is verified as well.
     pub fn verify_proof_bundle(
         bundle: &ConsolidationProofBundle,
         block_root: [u8; 32],
+    ) -> Result<(), ProofError> {
+        Self::verify_proof_bundle_with(bundle, block_root, &StateLimits::PRESET)
+    }
+
+    fn verify_proof_bundle_with(
+        bundle: &ConsolidationProofBundle,
+        block_root: [u8; 32],
+        limits: &StateLimits,
     ) -> Result<(), ProofError> {
         let block_root_node = bytes_to_node(block_root);
 
+        let [consolidation_gindex, credentials_gindex, activation_gindex] =
+            bundle_gindices(bundle, limits)?;
+
         // Verify consolidation proof
-        let consolidation_gindex =
-            GindexCalculator::consolidation_source_gindex(bundle.consolidation_index);
         let consolidation_leaf = bytes_to_node(ssz_u64_to_bytes32(bundle.source_index));
         let consolidation_branch = bytes_to_nodes(&bundle.proof_consolidation);
 
@@ -317,8 +623,6 @@ impl ProofGenerator {
         })?;
 
         // Verify credentials proof
-        let credentials_gindex =
-            GindexCalculator::validator_credentials_gindex(bundle.source_index);
         let credentials_leaf = bytes_to_node(bundle.source_credentials);
         let credentials_branch = bytes_to_nodes(&bundle.proof_credentials);
 
@@ -333,8 +637,6 @@ impl ProofGenerator {
         })?;
 
         // Verify activation epoch proof
-        let activation_gindex =
-            GindexCalculator::validator_activation_epoch_gindex(bundle.source_index);
         let activation_leaf = bytes_to_node(ssz_u64_to_bytes32(bundle.activation_epoch));
         let activation_branch = bytes_to_nodes(&bundle.proof_activation_epoch);
 
@@ -348,8 +650,64 @@ impl ProofGenerator {
             ProofError::ProofGenerationFailed(format!("Activation epoch proof invalid: {e}"))
         })?;
 
+        if let Some(target) = &bundle.target {
+            target.verify(bundle.consolidation_index, block_root, limits)?;
+        }
+
         Ok(())
     }
+
+    /// Verify an exited-validator bundle against a block root using production gindices.
+    ///
+    /// Checks the proofs and that the proven values show a completed exit.
+    pub fn verify_exit_proof_bundle(
+        bundle: &ExitedValidatorProofBundle,
+        block_root: [u8; 32],
+    ) -> Result<(), ProofError> {
+        Self::verify_exit_proof_bundle_with(bundle, block_root, &StateLimits::PRESET)
+    }
+
+    /// Verify an exited-validator bundle against a block root using test state gindices.
+    pub fn verify_exit_proof_bundle_test(
+        bundle: &ExitedValidatorProofBundle,
+        block_root: [u8; 32],
+    ) -> Result<(), ProofError> {
+        Self::verify_exit_proof_bundle_with(bundle, block_root, &StateLimits::TEST)
+    }
+
+    fn verify_exit_proof_bundle_with(
+        bundle: &ExitedValidatorProofBundle,
+        block_root: [u8; 32],
+        limits: &StateLimits,
+    ) -> Result<(), ProofError> {
+        let block_root_node = bytes_to_node(block_root);
+        let gindices = exit_bundle_gindices(bundle, limits)?;
+        let fields = [
+            "Credentials",
+            "Activation epoch",
+            "Exit epoch",
+            "Withdrawable epoch",
+            "Effective balance",
+        ];
+
+        for ((gindex, (leaf, branch)), field) in gindices
+            .into_iter()
+            .zip(bundle.leaves_and_branches())
+            .zip(fields)
+        {
+            ssz_rs::proofs::is_valid_merkle_branch_for_generalized_index(
+                bytes_to_node(leaf),
+                &bytes_to_nodes(branch),
+                gindex as usize,
+                block_root_node,
+            )
+            .map_err(|e| {
+                ProofError::ProofGenerationFailed(format!("{field} proof invalid: {e}"))
+            })?;
+        }
+
+        bundle.check_exited_and_drained()
+    }
 }
 
 /// Intermediate proof bundle from state root (without header wrapping)
@@ -431,6 +789,7 @@ mod tests {
             proof_consolidation: vec![],
             proof_credentials: vec![],
             proof_activation_epoch: vec![],
+            target: None,
         };
 
         assert_eq!(bundle.recipient_address(), Some([0xab; 20]));
@@ -447,6 +806,7 @@ mod tests {
             proof_consolidation: vec![],
             proof_credentials: vec![],
             proof_activation_epoch: vec![],
+            target: None,
         };
 
         assert_eq!(bundle.recipient_address(), None);
@@ -466,9 +826,11 @@ mod tests {
             proof_consolidation: vec![[0xaa; 32], [0xbb; 32]],
             proof_credentials: vec![[0xcc; 32]],
             proof_activation_epoch: vec![[0xdd; 32]],
+            target: None,
         };
 
         let json = serde_json::to_string(&bundle).unwrap();
+        assert!(!json.contains("target"), "absent target proof is omitted");
         let decoded: ConsolidationProofBundle = serde_json::from_str(&json).unwrap();
 
         assert_eq!(decoded.beacon_timestamp, bundle.beacon_timestamp);
@@ -476,6 +838,52 @@ mod tests {
         assert_eq!(decoded.proof_consolidation, bundle.proof_consolidation);
     }
 
+    #[test]
+    fn test_exit_bundle_check_and_json_roundtrip() {
+        let mut creds = [0u8; 32];
+        creds[0] = 0x02;
+        creds[12..].copy_from_slice(&[0xab; 20]);
+
+        let bundle = ExitedValidatorProofBundle {
+            beacon_timestamp: 12345,
+            source_index: 42,
+            activation_epoch: 100,
+            exit_epoch: 500,
+            withdrawable_epoch: 756,
+            effective_balance: 0,
+            source_credentials: creds,
+            proof_credentials: vec![[0xaa; 32]],
+            proof_activation_epoch: vec![[0xbb; 32]],
+            proof_exit_epoch: vec![[0xcc; 32]],
+            proof_withdrawable_epoch: vec![[0xdd; 32]],
+            proof_effective_balance: vec![[0xee; 32]],
+        };
+        assert!(bundle.check_exited_and_drained().is_ok());
+        assert_eq!(bundle.recipient_address(), Some([0xab; 20]));
+
+        let json = serde_json::to_string(&bundle).unwrap();
+        let decoded: ExitedValidatorProofBundle = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded.exit_epoch, 500);
+        assert_eq!(
+            decoded.proof_effective_balance,
+            bundle.proof_effective_balance
+        );
+
+        let active = ExitedValidatorProofBundle {
+            exit_epoch: FAR_FUTURE_EPOCH,
+            ..bundle.clone()
+        };
+        assert!(matches!(
+            active.check_exited_and_drained(),
+            Err(ProofError::ValidatorNotDrained(42))
+        ));
+        let funded = ExitedValidatorProofBundle {
+            effective_balance: 32_000_000_000,
+            ..bundle
+        };
+        assert!(funded.check_exited_and_drained().is_err());
+    }
+
     #[test]
     fn test_ssz_u64_to_bytes32() {
         let bytes = ssz_u64_to_bytes32(42);
@@ -495,6 +903,20 @@ mod tests {
         assert_eq!(validator_len, 53);
     }
 
+    #[test]
+    fn test_expected_proof_lengths_by_fork_start_at_electra() {
+        let (consolidation_len, validator_len) = ProofGenerator::expected_proof_lengths();
+        let by_fork = ProofGenerator::expected_proof_lengths_by_fork();
+        assert_eq!(by_fork[0].0, ForkName::Electra);
+        assert_eq!(
+            by_fork[0].1,
+            ProofLengths {
+                consolidation: consolidation_len,
+                validator: validator_len
+            }
+        );
+    }
+
     #[test]
     fn test_expected_proof_lengths_test_state() {
         let (consolidation_len, validator_len) = ProofGenerator::test_proof_lengths();
@@ -642,6 +1064,58 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bundle_multiproof_verifies_and_saves_calldata() {
+        let mut state = MinimalBeaconState::default();
+        for i in 0..4u8 {
+            let mut validator = Validator::default();
+            validator.withdrawal_credentials[0] = 0x01;
+            validator.withdrawal_credentials[12..].copy_from_slice(&[i; 20]);
+            validator.activation_epoch = 100 + i as u64;
+            state.validators.push(validator);
+            state.balances.push(32_000_000_000);
+        }
+        state.pending_consolidations.push(PendingConsolidation {
+            source_index: 3,
+            target_index: 0,
+        });
+
+        let header = BeaconBlockHeader {
+            slot: 1000,
+            proposer_index: 0,
+            parent_root: [0u8; 32],
+            state_root: state.hash_tree_root().expect("hash state").into(),
+            body_root: [1u8; 32],
+        };
+        let block_root: [u8; 32] = header.hash_tree_root().expect("hash header").into();
+        let bundle = ProofGenerator::generate_full_proof_bundle(&header, &state, 0, 0).unwrap();
+
+        let multiproof = bundle.to_multiproof(&StateLimits::TEST).unwrap();
+        assert_eq!(multiproof.leaves[1], bundle.source_credentials);
+        bundle
+            .verify_multiproof(&multiproof, block_root, &StateLimits::TEST)
+            .expect("multiproof should verify");
+        assert!(bundle
+            .verify_multiproof(&multiproof, [0xaa; 32], &StateLimits::TEST)
+            .is_err());
+
+        // Credentials and activation epoch share everything above the
+        // validator's field tree; all three share the header levels.
+        // Multiproof: 4 validator fields + 11 list + 2 * 5 state
+        // + 8 consolidation list + 3 header = 36
+        let (consolidation_len, validator_len) = ProofGenerator::test_proof_lengths();
+        let comparison = bundle.calldata_comparison(&StateLimits::TEST).unwrap();
+        assert_eq!(
+            comparison.separate_hashes,
+            (consolidation_len + 2 * validator_len) as usize
+        );
+        assert_eq!(comparison.multiproof_hashes, 36);
+        assert_eq!(
+            comparison.saved_hashes(),
+            comparison.separate_hashes - comparison.multiproof_hashes
+        );
+    }
+
     #[test]
     fn test_proof_verification_with_wrong_block_root() {
         // Create a state with test data
@@ -674,5 +1148,24 @@ mod tests {
         let wrong_root = [0xaa; 32];
         let result = ProofGenerator::verify_proof_bundle_test(&bundle, wrong_root);
         assert!(result.is_err(), "Should fail with wrong block root");
+
+        // The correct root verifies
+        let block_root: [u8; 32] = header.hash_tree_root().expect("hash header").into();
+        ProofGenerator::verify_proof_bundle_test(&bundle, block_root).unwrap();
+
+        // An index beyond the list limit is rejected rather than aliasing another leaf
+        let mut out_of_range = bundle;
+        out_of_range.consolidation_index = 64;
+        let result = ProofGenerator::verify_proof_bundle_test(&out_of_range, block_root);
+        assert!(
+            matches!(
+                result,
+                Err(ProofError::Schema(SchemaError::IndexOutOfBounds {
+                    index: 64,
+                    length: 64
+                }))
+            ),
+            "Should reject out-of-range index, got {result:?}"
+        );
     }
 }
@@ -0,0 +1,295 @@
+//! Historical State Scanning
+//!
+//! Helpers for walking a window of beacon states looking for pending
+//! consolidations. Shared by the `fetch-and-prove` CLI and the service's
+//! backfill so both step through history and handle pruned nodes the same way.
+
+use crate::beacon_client::{BeaconClient, BeaconClientError};
+use crate::types::PendingConsolidationJson;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Order in which scan slots are visited
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ScanDirection {
+    /// Oldest slot first
+    #[default]
+    Forward,
+    /// Newest slot first
+    Reverse,
+}
+
+impl ScanDirection {
+    /// Lowercase name, as accepted by [`FromStr`]
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Forward => "forward",
+            Self::Reverse => "reverse",
+        }
+    }
+}
+
+impl fmt::Display for ScanDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ScanDirection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "forward" => Ok(Self::Forward),
+            "reverse" => Ok(Self::Reverse),
+            other => Err(format!(
+                "invalid scan direction `{other}`; expected forward or reverse"
+            )),
+        }
+    }
+}
+
+/// Errors from historical state lookups
+#[derive(Debug, Error)]
+pub enum ScanError {
+    /// The block exists but the node no longer serves its state
+    #[error("beacon header exists at slot {0} but beacon state is unavailable; historical state lookups appear pruned on this node")]
+    Pruned(u64),
+
+    /// Every slot in the lookback window was empty
+    #[error("no beacon state found at or before slot {requested_slot} within lower bound {minimum_slot}")]
+    NoStateInWindow {
+        requested_slot: u64,
+        minimum_slot: u64,
+    },
+
+    /// Beacon API error
+    #[error(transparent)]
+    Client(#[from] BeaconClientError),
+}
+
+/// Build the list of slots to visit in `scan_start_slot..=scan_end_slot`
+///
+/// Slots are spaced `scan_step_slots` apart. The window end and `requested_slot`
+/// are always included, so the newest state is never skipped by the stride.
+#[must_use]
+pub fn build_scan_slots(
+    scan_start_slot: u64,
+    scan_end_slot: u64,
+    scan_step_slots: u64,
+    scan_direction: ScanDirection,
+    requested_slot: u64,
+) -> Vec<u64> {
+    let mut slots = Vec::new();
+    let mut slot = scan_start_slot;
+    while slot <= scan_end_slot {
+        slots.push(slot);
+        match slot.checked_add(scan_step_slots) {
+            Some(next) if next > slot => slot = next,
+            _ => break,
+        }
+    }
+
+    if slots.last().copied() != Some(scan_end_slot) {
+        slots.push(scan_end_slot);
+    }
+
+    if !slots.contains(&requested_slot) {
+        slots.push(requested_slot);
+    }
+
+    slots.sort_unstable();
+    slots.dedup();
+
+    if scan_direction == ScanDirection::Reverse {
+        slots.reverse();
+    }
+
+    slots
+}
+
+/// Fetch `pending_consolidations` at `requested_slot`, walking back over
+/// skipped slots until `minimum_slot`
+///
+/// Returns the slot actually read alongside the list.
+///
+/// # Errors
+/// - [`ScanError::Pruned`] if a block exists at the slot but its state is gone
+/// - [`ScanError::NoStateInWindow`] if no slot in the window has a state
+/// - [`ScanError::Client`] for any other beacon API failure
+pub async fn fetch_pending_consolidations_at_or_before(
+    client: &BeaconClient,
+    requested_slot: u64,
+    minimum_slot: u64,
+) -> Result<(u64, Vec<PendingConsolidationJson>), ScanError> {
+    let mut slot = requested_slot;
+
+    loop {
+        match client.get_pending_consolidations(&slot.to_string()).await {
+            Ok(pending_consolidations) => return Ok((slot, pending_consolidations)),
+            Err(BeaconClientError::StateNotFound(_)) => {
+                match client.get_header(&slot.to_string()).await {
+                    Ok(_) => return Err(ScanError::Pruned(slot)),
+                    Err(BeaconClientError::HeaderNotFound(_)) if slot > minimum_slot => {
+                        slot -= 1;
+                    }
+                    Err(BeaconClientError::HeaderNotFound(_)) => {
+                        return Err(ScanError::NoStateInWindow {
+                            requested_slot,
+                            minimum_slot,
+                        });
+                    }
+                    Err(error) => return Err(error.into()),
+                }
+            }
+            Err(error) => return Err(error.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_scan_slots_uses_step_and_includes_requested_slot() {
+        let slots = build_scan_slots(100, 140, 16, ScanDirection::Forward, 133);
+        assert_eq!(slots, vec![100, 116, 132, 133, 140]);
+    }
+
+    #[test]
+    fn build_scan_slots_reverses_order_when_requested() {
+        let slots = build_scan_slots(100, 140, 16, ScanDirection::Reverse, 132);
+        assert_eq!(slots, vec![140, 132, 116, 100]);
+    }
+
+    #[test]
+    fn scan_direction_round_trips_through_str() {
+        for direction in [ScanDirection::Forward, ScanDirection::Reverse] {
+            assert_eq!(direction.to_string().parse(), Ok(direction));
+        }
+        assert!("sideways".parse::<ScanDirection>().is_err());
+    }
+
+    #[tokio::test]
+    async fn fetch_pending_consolidations_walks_back_to_previous_available_slot() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        let response_json = r#"{
+            "data": [
+                {"source_index": "42", "target_index": "100"}
+            ]
+        }"#;
+
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/states/120/pending_consolidations"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/headers/120"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/states/119/pending_consolidations"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(response_json))
+            .mount(&mock_server)
+            .await;
+
+        let client = BeaconClient::new(mock_server.uri());
+        let (resolved_slot, pending) = fetch_pending_consolidations_at_or_before(&client, 120, 110)
+            .await
+            .unwrap();
+
+        assert_eq!(resolved_slot, 119);
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].source_index, 42);
+    }
+
+    #[tokio::test]
+    async fn fetch_pending_consolidations_errors_when_no_state_exists_in_window() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+
+        for slot in [120_u64, 119, 118] {
+            Mock::given(method("GET"))
+                .and(path(format!(
+                    "/eth/v1/beacon/states/{slot}/pending_consolidations"
+                )))
+                .respond_with(ResponseTemplate::new(404))
+                .mount(&mock_server)
+                .await;
+            Mock::given(method("GET"))
+                .and(path(format!("/eth/v1/beacon/headers/{slot}")))
+                .respond_with(ResponseTemplate::new(404))
+                .mount(&mock_server)
+                .await;
+        }
+
+        let client = BeaconClient::new(mock_server.uri());
+        let error = fetch_pending_consolidations_at_or_before(&client, 120, 118)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            error,
+            ScanError::NoStateInWindow {
+                requested_slot: 120,
+                minimum_slot: 118
+            }
+        ));
+        assert!(error
+            .to_string()
+            .contains("no beacon state found at or before slot 120 within lower bound 118"));
+    }
+
+    #[tokio::test]
+    async fn fetch_pending_consolidations_detects_pruned_historical_state() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let mock_server = MockServer::start().await;
+        let header_json = r#"{
+            "data": {
+                "header": {
+                    "message": {
+                        "slot": "120",
+                        "proposer_index": "42",
+                        "parent_root": "0x0101010101010101010101010101010101010101010101010101010101010101",
+                        "state_root": "0x0202020202020202020202020202020202020202020202020202020202020202",
+                        "body_root": "0x0303030303030303030303030303030303030303030303030303030303030303"
+                    }
+                }
+            }
+        }"#;
+
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/states/120/pending_consolidations"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/headers/120"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(header_json))
+            .mount(&mock_server)
+            .await;
+
+        let client = BeaconClient::new(mock_server.uri());
+        let error = fetch_pending_consolidations_at_or_before(&client, 120, 100)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, ScanError::Pruned(120)));
+        assert!(error
+            .to_string()
+            .contains("historical state lookups appear pruned on this node"));
+    }
+}
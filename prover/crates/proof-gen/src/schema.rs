@@ -0,0 +1,583 @@
+//! SSZ Schema Descriptions
+//!
+//! Declarative descriptions of the SSZ types proofs descend through, from
+//! which generalized indices are derived for any path, e.g.
+//! `["state_root", "validators", i, "exit_epoch"]` from a block root. Only
+//! the shape matters for merkleization: container field order, list limits,
+//! vector lengths and the byte size of packed basic types.
+//!
+//! The BeaconState schema is built per fork and per set of list limits, so
+//! the Gnosis preset and the small test state share one description.
+
+use crate::beacon_state::gnosis_limits;
+use crate::fork::{ForkError, ForkName};
+use crate::types::preset;
+use thiserror::Error;
+
+/// Errors from resolving a path against a schema
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SchemaError {
+    #[error("{container} has no field {field}")]
+    UnknownField {
+        container: &'static str,
+        field: String,
+    },
+
+    #[error("Index {index} out of bounds for length {length}")]
+    IndexOutOfBounds { index: u64, length: u64 },
+
+    #[error("Cannot apply {step} to {ty}")]
+    InvalidStep { step: String, ty: String },
+
+    #[error("Generalized index overflows 64 bits")]
+    Overflow,
+
+    #[error(transparent)]
+    Fork(#[from] ForkError),
+}
+
+/// One step of a path through nested SSZ types
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathStep {
+    /// Container field by name
+    Field(&'static str),
+    /// List or vector element
+    Index(u64),
+    /// Length mix-in of a list
+    Length,
+}
+
+impl From<&'static str> for PathStep {
+    fn from(field: &'static str) -> Self {
+        Self::Field(field)
+    }
+}
+
+impl From<u64> for PathStep {
+    fn from(index: u64) -> Self {
+        Self::Index(index)
+    }
+}
+
+impl std::fmt::Display for PathStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Field(name) => write!(f, "field {name}"),
+            Self::Index(index) => write!(f, "index {index}"),
+            Self::Length => f.write_str("length"),
+        }
+    }
+}
+
+/// Shape of an SSZ type, as far as merkleization is concerned
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SszType {
+    /// Basic type of the given byte size (`uint64` is 8, `bool` is 1)
+    Basic(usize),
+    /// `Vector[elem, length]`, also used for fixed byte arrays
+    Vector(Box<SszType>, u64),
+    /// `List[elem, limit]`
+    List(Box<SszType>, u64),
+    /// `Bitvector[length]`
+    Bitvector(u64),
+    /// Container with named fields, in order
+    Container(&'static str, Vec<(&'static str, SszType)>),
+}
+
+impl SszType {
+    /// `uint64`
+    pub const U64: Self = Self::Basic(8);
+    /// `boolean`
+    pub const BOOL: Self = Self::Basic(1);
+
+    /// `Vector[byte, length]`: `Bytes32`, `BLSPubkey`, ...
+    #[must_use]
+    pub fn bytes(length: u64) -> Self {
+        Self::Vector(Box::new(Self::Basic(1)), length)
+    }
+
+    /// `Vector[elem, length]`
+    #[must_use]
+    pub fn vector(elem: Self, length: u64) -> Self {
+        Self::Vector(Box::new(elem), length)
+    }
+
+    /// `List[elem, limit]`
+    #[must_use]
+    pub fn list(elem: Self, limit: u64) -> Self {
+        Self::List(Box::new(elem), limit)
+    }
+
+    /// Number of fields if this is a container
+    #[must_use]
+    pub fn field_count(&self) -> Option<usize> {
+        match self {
+            Self::Container(_, fields) => Some(fields.len()),
+            _ => None,
+        }
+    }
+
+    /// Generalized index of `path` relative to this type's root
+    ///
+    /// # Errors
+    /// Returns error if a step does not apply to the type it reaches, an
+    /// index exceeds the list limit or vector length, or the gindex overflows
+    pub fn gindex(&self, path: &[PathStep]) -> Result<u64, SchemaError> {
+        let mut gindex = 1u64;
+        let mut ty = self;
+
+        for step in path {
+            let (subtree_depth, position, next) = match (ty, step) {
+                (Self::Container(name, fields), PathStep::Field(field)) => {
+                    let index = fields.iter().position(|(f, _)| f == field).ok_or_else(|| {
+                        SchemaError::UnknownField {
+                            container: name,
+                            field: (*field).to_string(),
+                        }
+                    })?;
+                    (depth(fields.len() as u64), index as u64, &fields[index].1)
+                }
+                (Self::Vector(elem, length), PathStep::Index(index)) => {
+                    let (depth, position) = element_position(elem, *length, *index)?;
+                    (depth, position, elem.as_ref())
+                }
+                (Self::List(elem, limit), PathStep::Index(index)) => {
+                    // Data root is the left child of the list root
+                    gindex = descend(gindex, 1, 0)?;
+                    let (depth, position) = element_position(elem, *limit, *index)?;
+                    (depth, position, elem.as_ref())
+                }
+                (Self::List(..), PathStep::Length) => (1, 1, &Self::U64),
+                _ => {
+                    return Err(SchemaError::InvalidStep {
+                        step: step.to_string(),
+                        ty: ty.describe(),
+                    })
+                }
+            };
+            gindex = descend(gindex, subtree_depth, position)?;
+            ty = next;
+        }
+
+        Ok(gindex)
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Self::Basic(size) => format!("uint{}", size * 8),
+            Self::Vector(_, length) => format!("Vector[{length}]"),
+            Self::List(_, limit) => format!("List[{limit}]"),
+            Self::Bitvector(length) => format!("Bitvector[{length}]"),
+            Self::Container(name, _) => (*name).to_string(),
+        }
+    }
+}
+
+/// Depth of a tree with `leaves` leaves: `ceil(log2(leaves))`
+fn depth(leaves: u64) -> u32 {
+    leaves.max(1).next_power_of_two().trailing_zeros()
+}
+
+/// Tree depth and leaf position of element `index` among `length` elements
+///
+/// Basic elements are packed several to a 32-byte chunk.
+fn element_position(elem: &SszType, length: u64, index: u64) -> Result<(u32, u64), SchemaError> {
+    if index >= length {
+        return Err(SchemaError::IndexOutOfBounds { index, length });
+    }
+    match elem {
+        SszType::Basic(size) => {
+            let per_chunk = (32 / size) as u64;
+            Ok((depth(length.div_ceil(per_chunk)), index / per_chunk))
+        }
+        _ => Ok((depth(length), index)),
+    }
+}
+
+/// Descend `depth` levels from `gindex` to the leaf at `position`
+fn descend(gindex: u64, depth: u32, position: u64) -> Result<u64, SchemaError> {
+    gindex
+        .checked_mul(1u64.checked_shl(depth).ok_or(SchemaError::Overflow)?)
+        .and_then(|base| base.checked_add(position))
+        .ok_or(SchemaError::Overflow)
+}
+
+// ============================================================================
+// Consensus schemas
+// ============================================================================
+
+/// List and vector limits of a BeaconState
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateLimits {
+    pub slots_per_historical_root: u64,
+    pub historical_roots_limit: u64,
+    pub eth1_data_votes_limit: u64,
+    pub validator_registry_limit: u64,
+    pub epochs_per_historical_vector: u64,
+    pub epochs_per_slashings_vector: u64,
+    pub sync_committee_size: u64,
+    pub pending_deposits_limit: u64,
+    pub pending_partial_withdrawals_limit: u64,
+    pub pending_consolidations_limit: u64,
+    pub proposer_lookahead_length: u64,
+}
+
+impl StateLimits {
+    /// Limits of [`GnosisBeaconState`](crate::GnosisBeaconState)
+    pub const GNOSIS: Self = Self {
+        slots_per_historical_root: gnosis_limits::SLOTS_PER_HISTORICAL_ROOT as u64,
+        historical_roots_limit: gnosis_limits::HISTORICAL_ROOTS_LIMIT as u64,
+        eth1_data_votes_limit: gnosis_limits::ETH1_DATA_VOTES_LIMIT as u64,
+        validator_registry_limit: gnosis_limits::VALIDATOR_REGISTRY_LIMIT as u64,
+        epochs_per_historical_vector: gnosis_limits::EPOCHS_PER_HISTORICAL_VECTOR as u64,
+        epochs_per_slashings_vector: gnosis_limits::EPOCHS_PER_SLASHINGS_VECTOR as u64,
+        sync_committee_size: 512,
+        pending_deposits_limit: gnosis_limits::PENDING_DEPOSITS_LIMIT as u64,
+        pending_partial_withdrawals_limit: gnosis_limits::PENDING_PARTIAL_WITHDRAWALS_LIMIT as u64,
+        pending_consolidations_limit: gnosis_limits::PENDING_CONSOLIDATIONS_LIMIT as u64,
+        proposer_lookahead_length: gnosis_limits::PROPOSER_LOOKAHEAD_LENGTH as u64,
+    };
+
+    /// Gnosis limits, with the registry and consolidation limits of the
+    /// configured preset
+    pub const PRESET: Self = Self {
+        validator_registry_limit: preset::VALIDATOR_REGISTRY_LIMIT as u64,
+        pending_consolidations_limit: preset::PENDING_CONSOLIDATIONS_LIMIT as u64,
+        ..Self::GNOSIS
+    };
+
+    /// Limits of `MinimalBeaconState`, used for test vectors
+    pub const TEST: Self = Self {
+        slots_per_historical_root: 64,
+        historical_roots_limit: 1024,
+        eth1_data_votes_limit: 32,
+        validator_registry_limit: 1024,
+        epochs_per_historical_vector: 64,
+        epochs_per_slashings_vector: 64,
+        sync_committee_size: 512,
+        pending_deposits_limit: 256,
+        pending_partial_withdrawals_limit: 256,
+        pending_consolidations_limit: 64,
+        proposer_lookahead_length: 32,
+    };
+}
+
+/// `BeaconBlockHeader`, with `state_root` expanded to `state` so paths can
+/// descend from the block root into the state
+#[must_use]
+pub fn beacon_block_header(state: SszType) -> SszType {
+    SszType::Container(
+        "BeaconBlockHeader",
+        vec![
+            ("slot", SszType::U64),
+            ("proposer_index", SszType::U64),
+            ("parent_root", SszType::bytes(32)),
+            ("state_root", state),
+            ("body_root", SszType::bytes(32)),
+        ],
+    )
+}
+
+/// `Validator`
+#[must_use]
+pub fn validator() -> SszType {
+    SszType::Container(
+        "Validator",
+        vec![
+            ("pubkey", SszType::bytes(48)),
+            ("withdrawal_credentials", SszType::bytes(32)),
+            ("effective_balance", SszType::U64),
+            ("slashed", SszType::BOOL),
+            ("activation_eligibility_epoch", SszType::U64),
+            ("activation_epoch", SszType::U64),
+            ("exit_epoch", SszType::U64),
+            ("withdrawable_epoch", SszType::U64),
+        ],
+    )
+}
+
+/// `PendingConsolidation`
+#[must_use]
+pub fn pending_consolidation() -> SszType {
+    SszType::Container(
+        "PendingConsolidation",
+        vec![
+            ("source_index", SszType::U64),
+            ("target_index", SszType::U64),
+        ],
+    )
+}
+
+/// `HistoricalSummary`
+#[must_use]
+pub fn historical_summary() -> SszType {
+    SszType::Container(
+        "HistoricalSummary",
+        vec![
+            ("block_summary_root", SszType::bytes(32)),
+            ("state_summary_root", SszType::bytes(32)),
+        ],
+    )
+}
+
+fn checkpoint() -> SszType {
+    SszType::Container(
+        "Checkpoint",
+        vec![("epoch", SszType::U64), ("root", SszType::bytes(32))],
+    )
+}
+
+fn eth1_data() -> SszType {
+    SszType::Container(
+        "Eth1Data",
+        vec![
+            ("deposit_root", SszType::bytes(32)),
+            ("deposit_count", SszType::U64),
+            ("block_hash", SszType::bytes(32)),
+        ],
+    )
+}
+
+fn sync_committee(size: u64) -> SszType {
+    SszType::Container(
+        "SyncCommittee",
+        vec![
+            ("pubkeys", SszType::vector(SszType::bytes(48), size)),
+            ("aggregate_pubkey", SszType::bytes(48)),
+        ],
+    )
+}
+
+fn execution_payload_header() -> SszType {
+    SszType::Container(
+        "ExecutionPayloadHeader",
+        vec![
+            ("parent_hash", SszType::bytes(32)),
+            ("fee_recipient", SszType::bytes(20)),
+            ("state_root", SszType::bytes(32)),
+            ("receipts_root", SszType::bytes(32)),
+            ("logs_bloom", SszType::bytes(256)),
+            ("prev_randao", SszType::bytes(32)),
+            ("block_number", SszType::U64),
+            ("gas_limit", SszType::U64),
+            ("gas_used", SszType::U64),
+            ("timestamp", SszType::U64),
+            ("extra_data", SszType::list(SszType::Basic(1), 32)),
+            ("base_fee_per_gas", SszType::Basic(32)),
+            ("block_hash", SszType::bytes(32)),
+            ("transactions_root", SszType::bytes(32)),
+            ("withdrawals_root", SszType::bytes(32)),
+            ("blob_gas_used", SszType::U64),
+            ("excess_blob_gas", SszType::U64),
+        ],
+    )
+}
+
+fn pending_deposit() -> SszType {
+    SszType::Container(
+        "PendingDeposit",
+        vec![
+            ("pubkey", SszType::bytes(48)),
+            ("withdrawal_credentials", SszType::bytes(32)),
+            ("amount", SszType::U64),
+            ("signature", SszType::bytes(96)),
+            ("slot", SszType::U64),
+        ],
+    )
+}
+
+fn pending_partial_withdrawal() -> SszType {
+    SszType::Container(
+        "PendingPartialWithdrawal",
+        vec![
+            ("validator_index", SszType::U64),
+            ("amount", SszType::U64),
+            ("withdrawable_epoch", SszType::U64),
+        ],
+    )
+}
+
+/// `BeaconState` of `fork` with the given limits
+///
+/// # Errors
+/// Returns [`ForkError::Unsupported`] for forks before Electra
+pub fn beacon_state(fork: ForkName, limits: &StateLimits) -> Result<SszType, ForkError> {
+    let roots = |length| SszType::vector(SszType::bytes(32), length);
+    let per_validator = |elem| SszType::list(elem, limits.validator_registry_limit);
+
+    let mut fields = match fork {
+        ForkName::Electra | ForkName::Fulu => vec![
+            ("genesis_time", SszType::U64),
+            ("genesis_validators_root", SszType::bytes(32)),
+            ("slot", SszType::U64),
+            (
+                "fork",
+                SszType::Container(
+                    "Fork",
+                    vec![
+                        ("previous_version", SszType::bytes(4)),
+                        ("current_version", SszType::bytes(4)),
+                        ("epoch", SszType::U64),
+                    ],
+                ),
+            ),
+            (
+                "latest_block_header",
+                beacon_block_header(SszType::bytes(32)),
+            ),
+            ("block_roots", roots(limits.slots_per_historical_root)),
+            ("state_roots", roots(limits.slots_per_historical_root)),
+            (
+                "historical_roots",
+                SszType::list(SszType::bytes(32), limits.historical_roots_limit),
+            ),
+            ("eth1_data", eth1_data()),
+            (
+                "eth1_data_votes",
+                SszType::list(eth1_data(), limits.eth1_data_votes_limit),
+            ),
+            ("eth1_deposit_index", SszType::U64),
+            ("validators", per_validator(validator())),
+            ("balances", per_validator(SszType::U64)),
+            ("randao_mixes", roots(limits.epochs_per_historical_vector)),
+            (
+                "slashings",
+                SszType::vector(SszType::U64, limits.epochs_per_slashings_vector),
+            ),
+            (
+                "previous_epoch_participation",
+                per_validator(SszType::Basic(1)),
+            ),
+            (
+                "current_epoch_participation",
+                per_validator(SszType::Basic(1)),
+            ),
+            ("justification_bits", SszType::Bitvector(4)),
+            ("previous_justified_checkpoint", checkpoint()),
+            ("current_justified_checkpoint", checkpoint()),
+            ("finalized_checkpoint", checkpoint()),
+            ("inactivity_scores", per_validator(SszType::U64)),
+            (
+                "current_sync_committee",
+                sync_committee(limits.sync_committee_size),
+            ),
+            (
+                "next_sync_committee",
+                sync_committee(limits.sync_committee_size),
+            ),
+            (
+                "latest_execution_payload_header",
+                execution_payload_header(),
+            ),
+            ("next_withdrawal_index", SszType::U64),
+            ("next_withdrawal_validator_index", SszType::U64),
+            (
+                "historical_summaries",
+                SszType::list(historical_summary(), limits.historical_roots_limit),
+            ),
+            ("deposit_requests_start_index", SszType::U64),
+            ("deposit_balance_to_consume", SszType::U64),
+            ("exit_balance_to_consume", SszType::U64),
+            ("earliest_exit_epoch", SszType::U64),
+            ("consolidation_balance_to_consume", SszType::U64),
+            ("earliest_consolidation_epoch", SszType::U64),
+            (
+                "pending_deposits",
+                SszType::list(pending_deposit(), limits.pending_deposits_limit),
+            ),
+            (
+                "pending_partial_withdrawals",
+                SszType::list(
+                    pending_partial_withdrawal(),
+                    limits.pending_partial_withdrawals_limit,
+                ),
+            ),
+            (
+                "pending_consolidations",
+                SszType::list(pending_consolidation(), limits.pending_consolidations_limit),
+            ),
+        ],
+        fork => return Err(ForkError::Unsupported(fork)),
+    };
+
+    if fork >= ForkName::Fulu {
+        fields.push((
+            "proposer_lookahead",
+            SszType::vector(SszType::U64, limits.proposer_lookahead_length),
+        ));
+    }
+
+    Ok(SszType::Container("BeaconState", fields))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fork::StateLayout;
+
+    #[test]
+    fn test_state_schema_matches_layouts() {
+        for (fork, layout) in [
+            (ForkName::Electra, StateLayout::ELECTRA),
+            (ForkName::Fulu, StateLayout::FULU),
+        ] {
+            let state = beacon_state(fork, &StateLimits::PRESET).unwrap();
+            assert_eq!(state.field_count(), Some(layout.field_count));
+            assert_eq!(
+                state.gindex(&["validators".into()]).unwrap(),
+                layout.validators_gindex()
+            );
+            assert_eq!(
+                state.gindex(&["pending_consolidations".into()]).unwrap(),
+                layout.pending_consolidations_gindex()
+            );
+        }
+        assert_eq!(
+            beacon_state(ForkName::Deneb, &StateLimits::PRESET),
+            Err(ForkError::Unsupported(ForkName::Deneb))
+        );
+    }
+
+    #[test]
+    fn test_list_and_packed_gindices() {
+        let state = beacon_state(ForkName::Electra, &StateLimits::TEST).unwrap();
+
+        // validators (75) -> data (2) -> [3] in 2^10 leaves -> exit_epoch (8 + 6)
+        let exit_epoch = state
+            .gindex(&["validators".into(), 3.into(), "exit_epoch".into()])
+            .unwrap();
+        assert_eq!(exit_epoch, (((75 * 2) << 10) + 3) * 8 + 6);
+
+        // balances: four u64 per chunk, 1024 / 4 = 256 chunks
+        let balance = state.gindex(&["balances".into(), 9.into()]).unwrap();
+        assert_eq!(balance, ((76 * 2) << 8) + 2);
+
+        let length = state
+            .gindex(&["validators".into(), PathStep::Length])
+            .unwrap();
+        assert_eq!(length, 75 * 2 + 1);
+    }
+
+    #[test]
+    fn test_invalid_paths() {
+        let state = beacon_state(ForkName::Electra, &StateLimits::TEST).unwrap();
+        assert_eq!(
+            state.gindex(&["validator".into()]),
+            Err(SchemaError::UnknownField {
+                container: "BeaconState",
+                field: "validator".to_string()
+            })
+        );
+        assert_eq!(
+            state.gindex(&["pending_consolidations".into(), 64.into()]),
+            Err(SchemaError::IndexOutOfBounds {
+                index: 64,
+                length: 64
+            })
+        );
+        assert!(matches!(
+            state.gindex(&["slot".into(), 0.into()]),
+            Err(SchemaError::InvalidStep { .. })
+        ));
+    }
+}
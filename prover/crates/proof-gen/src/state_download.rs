@@ -0,0 +1,700 @@
+//! Streaming Beacon State Download
+//!
+//! Full Gnosis debug states run to hundreds of MB. [`BeaconClient::download_state`]
+//! streams them chunk by chunk into a file (or memory), enforcing a size limit,
+//! decoding gzip/zstd `Content-Encoding`, reporting progress, and resuming with
+//! `Range` requests where the server advertises `Accept-Ranges: bytes`. The
+//! result is a [`StateBytes`], memory-mapped when downloaded to an anonymous
+//! temporary file, so SSZ decoding reads from the page cache instead of a heap
+//! copy.
+
+use crate::beacon_client::{BeaconClient, BeaconClientError};
+use memmap2::Mmap;
+use reqwest::header::{
+    HeaderMap, ACCEPT, ACCEPT_ENCODING, ACCEPT_RANGES, CONTENT_ENCODING, CONTENT_LENGTH, RANGE,
+};
+use reqwest::StatusCode;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use tokio::time::sleep;
+use tracing::{info, instrument, warn};
+
+/// Default cap on a decoded state
+pub const DEFAULT_MAX_STATE_SIZE: u64 = 4 << 30;
+
+/// Default spacing between progress reports
+pub const DEFAULT_PROGRESS_INTERVAL: u64 = 64 << 20;
+
+/// Where a downloaded state is written
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum DownloadTarget {
+    /// Heap buffer
+    Memory,
+    /// Anonymous temporary file, removed once the [`StateBytes`] is dropped
+    #[default]
+    TempFile,
+    /// File at this path, kept after the download
+    File(PathBuf),
+}
+
+/// Options for [`BeaconClient::download_state`]
+#[derive(Debug, Clone)]
+pub struct DownloadOptions {
+    pub target: DownloadTarget,
+    /// Abort once the decoded state exceeds this many bytes
+    pub max_size: Option<u64>,
+    /// Continue a partial [`DownloadTarget::File`] from its current length
+    ///
+    /// Only meaningful for immutable state ids (a state root or finalized slot).
+    pub resume: bool,
+    /// Log progress every this many bytes
+    pub progress_interval: u64,
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        Self {
+            target: DownloadTarget::default(),
+            max_size: Some(DEFAULT_MAX_STATE_SIZE),
+            resume: false,
+            progress_interval: DEFAULT_PROGRESS_INTERVAL,
+        }
+    }
+}
+
+/// SSZ-encoded state bytes, in memory or memory-mapped from disk
+#[derive(Debug)]
+pub enum StateBytes {
+    Memory(Vec<u8>),
+    Mapped(Mmap),
+}
+
+impl StateBytes {
+    /// Read a previously downloaded state file
+    ///
+    /// # Errors
+    /// Returns error if the file cannot be read
+    pub fn open(path: &Path) -> io::Result<Self> {
+        Ok(Self::Memory(std::fs::read(path)?))
+    }
+
+    /// Memory-map a file from `tempfile::tempfile`
+    ///
+    /// Only for the anonymous temp files of [`DownloadTarget::TempFile`]: they
+    /// are unlinked on creation and `file` is their only handle, so nothing
+    /// else can truncate or rewrite them while mapped.
+    #[allow(unsafe_code)]
+    fn map_temp_file(file: File) -> io::Result<Self> {
+        if file.metadata()?.len() == 0 {
+            return Ok(Self::Memory(Vec::new()));
+        }
+        // SAFETY: the map is read-only and no other handle to the unlinked
+        // file exists; the mapping outlives `file` but not the inode.
+        let map = unsafe { Mmap::map(&file)? };
+        Ok(Self::Mapped(map))
+    }
+
+    /// Decode an SSZ value directly from the (possibly mapped) bytes
+    ///
+    /// # Errors
+    /// Returns error if the bytes are not a valid encoding of `T`
+    pub fn decode<T: ssz_rs::Deserialize>(&self) -> Result<T, ssz_rs::DeserializeError> {
+        T::deserialize(self)
+    }
+
+    /// Copy into an owned buffer (free for [`StateBytes::Memory`])
+    #[must_use]
+    pub fn into_vec(self) -> Vec<u8> {
+        match self {
+            Self::Memory(bytes) => bytes,
+            Self::Mapped(map) => map.to_vec(),
+        }
+    }
+}
+
+impl Deref for StateBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Memory(bytes) => bytes,
+            Self::Mapped(map) => map,
+        }
+    }
+}
+
+impl AsRef<[u8]> for StateBytes {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl BeaconClient {
+    /// Stream a beacon state (`/eth/v2/debug/beacon/states/{state_id}`) to `options.target`
+    ///
+    /// With retries configured, a download interrupted mid-body continues from
+    /// the bytes already written if the server accepts ranges, and restarts
+    /// otherwise.
+    ///
+    /// # Errors
+    /// - [`BeaconClientError::StateNotFound`] if the node has no such state
+    /// - [`BeaconClientError::SizeLimitExceeded`] if the state is larger than `max_size`
+    /// - [`BeaconClientError::Io`] if the target cannot be written
+    /// - any request error from [`BeaconClient::get_state_ssz`]
+    #[instrument(skip(self, options))]
+    pub async fn download_state(
+        &self,
+        state_id: &str,
+        options: &DownloadOptions,
+    ) -> Result<StateBytes, BeaconClientError> {
+        self.failover(|base_url| self.stream_state(base_url, state_id, options))
+            .await
+    }
+
+    async fn stream_state(
+        &self,
+        base_url: &str,
+        state_id: &str,
+        options: &DownloadOptions,
+    ) -> Result<StateBytes, BeaconClientError> {
+        let url = format!("{base_url}/eth/v2/debug/beacon/states/{state_id}");
+        let mut sink = Sink::open(state_id, options)?;
+        let mut interruptions = 0;
+
+        loop {
+            let offset = sink.written;
+            let response = self
+                .send_with(&url, self.state_timeout, |request| {
+                    let request = request.header(ACCEPT, "application/octet-stream");
+                    if offset > 0 {
+                        // Offsets count decoded bytes, so resume uncompressed
+                        request
+                            .header(ACCEPT_ENCODING, "identity")
+                            .header(RANGE, format!("bytes={offset}-"))
+                    } else {
+                        request.header(ACCEPT_ENCODING, "gzip, zstd, identity")
+                    }
+                })
+                .await;
+            let response = match response {
+                Err(BeaconClientError::Permanent { status: 416, .. }) if offset > 0 => {
+                    warn!(
+                        state_id,
+                        offset, "Resume offset rejected; restarting download"
+                    );
+                    sink.restart()?;
+                    continue;
+                }
+                other => other?,
+            };
+
+            if response.status() == StatusCode::NOT_FOUND {
+                return Err(BeaconClientError::StateNotFound(
+                    state_id.parse().unwrap_or(0),
+                ));
+            }
+            if offset > 0 && response.status() != StatusCode::PARTIAL_CONTENT {
+                warn!(
+                    state_id,
+                    offset, "Server ignored Range; restarting download"
+                );
+                sink.restart()?;
+            }
+
+            let encoding = ContentEncoding::from_headers(response.headers())?;
+            let ranges = response.status() == StatusCode::PARTIAL_CONTENT
+                || accepts_byte_ranges(response.headers());
+            if encoding == ContentEncoding::Identity {
+                if let Some(remaining) = content_length(response.headers()) {
+                    sink.set_total(sink.written + remaining)?;
+                }
+            }
+
+            match copy_body(response, &mut sink, encoding).await {
+                Ok(()) => break,
+                Err(error) if error.is_retryable() && interruptions < self.retry.max_retries => {
+                    warn!(
+                        state_id,
+                        %error,
+                        written = sink.written,
+                        resume = ranges,
+                        "State download interrupted"
+                    );
+                    if !ranges {
+                        sink.restart()?;
+                    }
+                    sleep(self.retry.backoff(interruptions)).await;
+                    interruptions += 1;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        info!(state_id, bytes = sink.written, "State downloaded");
+        Ok(sink.finish()?)
+    }
+}
+
+async fn copy_body(
+    mut response: reqwest::Response,
+    sink: &mut Sink,
+    encoding: ContentEncoding,
+) -> Result<(), BeaconClientError> {
+    let mut decoder = Decoder::new(encoding, sink).map_err(sink_error)?;
+    while let Some(chunk) = response.chunk().await? {
+        decoder.write_all(&chunk).map_err(sink_error)?;
+    }
+    decoder.finish().map_err(sink_error)
+}
+
+/// Supported `Content-Encoding`s
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ContentEncoding {
+    Identity,
+    Gzip,
+    Zstd,
+}
+
+impl ContentEncoding {
+    fn from_headers(headers: &HeaderMap) -> Result<Self, BeaconClientError> {
+        let value = headers
+            .get(CONTENT_ENCODING)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.trim().to_ascii_lowercase());
+        match value.as_deref() {
+            None | Some("" | "identity") => Ok(Self::Identity),
+            Some("gzip" | "x-gzip") => Ok(Self::Gzip),
+            Some("zstd") => Ok(Self::Zstd),
+            Some(other) => Err(BeaconClientError::InvalidResponse(format!(
+                "Unsupported Content-Encoding: {other}"
+            ))),
+        }
+    }
+}
+
+fn accepts_byte_ranges(headers: &HeaderMap) -> bool {
+    headers
+        .get(ACCEPT_RANGES)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.trim().eq_ignore_ascii_case("bytes"))
+}
+
+fn content_length(headers: &HeaderMap) -> Option<u64> {
+    headers.get(CONTENT_LENGTH)?.to_str().ok()?.parse().ok()
+}
+
+/// Streaming decompressor in front of a [`Sink`]
+enum Decoder<'a> {
+    Identity(&'a mut Sink),
+    Gzip(flate2::write::GzDecoder<&'a mut Sink>),
+    Zstd(zstd::stream::write::Decoder<'static, &'a mut Sink>),
+}
+
+impl<'a> Decoder<'a> {
+    fn new(encoding: ContentEncoding, sink: &'a mut Sink) -> io::Result<Self> {
+        Ok(match encoding {
+            ContentEncoding::Identity => Self::Identity(sink),
+            ContentEncoding::Gzip => Self::Gzip(flate2::write::GzDecoder::new(sink)),
+            ContentEncoding::Zstd => Self::Zstd(zstd::stream::write::Decoder::new(sink)?),
+        })
+    }
+
+    fn write_all(&mut self, chunk: &[u8]) -> io::Result<()> {
+        match self {
+            Self::Identity(sink) => sink.write_all(chunk),
+            Self::Gzip(decoder) => decoder.write_all(chunk),
+            Self::Zstd(decoder) => decoder.write_all(chunk),
+        }
+    }
+
+    fn finish(self) -> io::Result<()> {
+        match self {
+            Self::Identity(sink) => sink.flush(),
+            Self::Gzip(decoder) => decoder.finish()?.flush(),
+            Self::Zstd(mut decoder) => decoder.flush(),
+        }
+    }
+}
+
+/// Raised through `io::Write` when the decoded state outgrows `max_size`
+#[derive(Debug)]
+struct SizeLimitError(u64);
+
+impl fmt::Display for SizeLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "state exceeds {} bytes", self.0)
+    }
+}
+
+impl std::error::Error for SizeLimitError {}
+
+fn sink_error(error: io::Error) -> BeaconClientError {
+    match error
+        .get_ref()
+        .and_then(|inner| inner.downcast_ref::<SizeLimitError>())
+    {
+        Some(SizeLimitError(limit)) => BeaconClientError::SizeLimitExceeded { limit: *limit },
+        None => BeaconClientError::Io(error),
+    }
+}
+
+enum Storage {
+    Memory(Vec<u8>),
+    /// Anonymous temp file, memory-mapped once complete
+    TempFile(BufWriter<File>),
+    /// File at a caller's path, read back once complete
+    File(BufWriter<File>),
+}
+
+/// Size-limited destination for decoded state bytes that reports progress
+struct Sink {
+    storage: Storage,
+    state_id: String,
+    written: u64,
+    total: Option<u64>,
+    max_size: Option<u64>,
+    progress_interval: u64,
+    next_progress: u64,
+}
+
+impl Sink {
+    fn open(state_id: &str, options: &DownloadOptions) -> io::Result<Self> {
+        let (storage, written) = match &options.target {
+            DownloadTarget::Memory => (Storage::Memory(Vec::new()), 0),
+            DownloadTarget::TempFile => {
+                (Storage::TempFile(BufWriter::new(tempfile::tempfile()?)), 0)
+            }
+            DownloadTarget::File(path) => {
+                let mut file = OpenOptions::new()
+                    .read(true)
+                    .write(true)
+                    .create(true)
+                    .truncate(false)
+                    .open(path)?;
+                let written = if options.resume {
+                    file.seek(SeekFrom::End(0))?
+                } else {
+                    file.set_len(0)?;
+                    0
+                };
+                (Storage::File(BufWriter::new(file)), written)
+            }
+        };
+
+        let progress_interval = options.progress_interval.max(1);
+        Ok(Self {
+            storage,
+            state_id: state_id.to_string(),
+            written,
+            total: None,
+            max_size: options.max_size,
+            progress_interval,
+            next_progress: written + progress_interval,
+        })
+    }
+
+    /// Record the expected size, failing early if it is over the limit
+    fn set_total(&mut self, total: u64) -> Result<(), BeaconClientError> {
+        if let Some(limit) = self.max_size.filter(|&limit| total > limit) {
+            return Err(BeaconClientError::SizeLimitExceeded { limit });
+        }
+        self.total = Some(total);
+        metrics::gauge!("beacon_state_download_total_bytes").set(total as f64);
+        Ok(())
+    }
+
+    fn restart(&mut self) -> io::Result<()> {
+        match &mut self.storage {
+            Storage::Memory(bytes) => bytes.clear(),
+            Storage::TempFile(writer) | Storage::File(writer) => {
+                writer.flush()?;
+                let file = writer.get_mut();
+                file.set_len(0)?;
+                file.seek(SeekFrom::Start(0))?;
+            }
+        }
+        self.written = 0;
+        self.total = None;
+        self.next_progress = self.progress_interval;
+        Ok(())
+    }
+
+    fn finish(self) -> io::Result<StateBytes> {
+        match self.storage {
+            Storage::Memory(bytes) => Ok(StateBytes::Memory(bytes)),
+            Storage::TempFile(writer) => StateBytes::map_temp_file(
+                writer
+                    .into_inner()
+                    .map_err(io::IntoInnerError::into_error)?,
+            ),
+            Storage::File(writer) => {
+                let mut file = writer
+                    .into_inner()
+                    .map_err(io::IntoInnerError::into_error)?;
+                let mut bytes = Vec::new();
+                file.seek(SeekFrom::Start(0))?;
+                file.read_to_end(&mut bytes)?;
+                Ok(StateBytes::Memory(bytes))
+            }
+        }
+    }
+}
+
+impl Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.written + buf.len() as u64;
+        if let Some(limit) = self.max_size.filter(|&limit| written > limit) {
+            return Err(io::Error::other(SizeLimitError(limit)));
+        }
+
+        match &mut self.storage {
+            Storage::Memory(bytes) => bytes.extend_from_slice(buf),
+            Storage::TempFile(writer) | Storage::File(writer) => writer.write_all(buf)?,
+        }
+        self.written = written;
+        metrics::gauge!("beacon_state_download_bytes").set(written as f64);
+
+        if written >= self.next_progress {
+            info!(
+                state_id = %self.state_id,
+                downloaded_mb = written >> 20,
+                total_mb = self.total.map(|total| total >> 20),
+                "State download progress"
+            );
+            self.next_progress = written + self.progress_interval;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.storage {
+            Storage::Memory(_) => Ok(()),
+            Storage::TempFile(writer) | Storage::File(writer) => writer.flush(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const STATE_PATH: &str = "/eth/v2/debug/beacon/states/100";
+
+    fn state_body() -> Vec<u8> {
+        (0..10_000u32).flat_map(u32::to_le_bytes).collect()
+    }
+
+    async fn serve(response: ResponseTemplate) -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(STATE_PATH))
+            .respond_with(response)
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn options(target: DownloadTarget) -> DownloadOptions {
+        DownloadOptions {
+            target,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_download_to_temp_file_is_memory_mapped() {
+        let server = serve(ResponseTemplate::new(200).set_body_bytes(state_body())).await;
+
+        let client = BeaconClient::new(server.uri());
+        let state = client
+            .download_state("100", &options(DownloadTarget::TempFile))
+            .await
+            .unwrap();
+
+        assert!(matches!(state, StateBytes::Mapped(_)));
+        assert_eq!(&state[..], &state_body()[..]);
+    }
+
+    #[tokio::test]
+    async fn test_download_to_file_can_be_reopened() {
+        let server = serve(ResponseTemplate::new(200).set_body_bytes(state_body())).await;
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("state.ssz");
+
+        let client = BeaconClient::new(server.uri());
+        let state = client
+            .download_state("100", &options(DownloadTarget::File(file.clone())))
+            .await
+            .unwrap();
+
+        // Others can write the file at a path, so it is not mapped
+        assert!(matches!(state, StateBytes::Memory(_)));
+        assert_eq!(&state[..], &state_body()[..]);
+        assert_eq!(&StateBytes::open(&file).unwrap()[..], &state_body()[..]);
+    }
+
+    #[tokio::test]
+    async fn test_download_decodes_gzip_and_zstd() {
+        use flate2::write::GzEncoder;
+
+        let mut gzip = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gzip.write_all(&state_body()).unwrap();
+        let bodies = [
+            ("gzip", gzip.finish().unwrap()),
+            ("zstd", zstd::encode_all(&state_body()[..], 0).unwrap()),
+        ];
+
+        for (encoding, body) in bodies {
+            let server = serve(
+                ResponseTemplate::new(200)
+                    .insert_header("Content-Encoding", encoding)
+                    .set_body_bytes(body),
+            )
+            .await;
+
+            let client = BeaconClient::new(server.uri());
+            let state = client
+                .download_state("100", &options(DownloadTarget::Memory))
+                .await
+                .unwrap();
+            assert_eq!(state.into_vec(), state_body(), "{encoding}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_download_enforces_size_limit() {
+        let server = serve(ResponseTemplate::new(200).set_body_bytes(state_body())).await;
+        let client = BeaconClient::new(server.uri());
+
+        // Rejected up front from Content-Length
+        let error = client
+            .download_state(
+                "100",
+                &DownloadOptions {
+                    max_size: Some(1024),
+                    ..options(DownloadTarget::Memory)
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            BeaconClientError::SizeLimitExceeded { limit: 1024 }
+        ));
+
+        // Compressed bodies are checked while decoding
+        let compressed = zstd::encode_all(&state_body()[..], 0).unwrap();
+        let server = serve(
+            ResponseTemplate::new(200)
+                .insert_header("Content-Encoding", "zstd")
+                .set_body_bytes(compressed),
+        )
+        .await;
+        let error = BeaconClient::new(server.uri())
+            .download_state(
+                "100",
+                &DownloadOptions {
+                    max_size: Some(1024),
+                    ..options(DownloadTarget::TempFile)
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            BeaconClientError::SizeLimitExceeded { limit: 1024 }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_download_resumes_partial_file_with_range() {
+        let body = state_body();
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(STATE_PATH))
+            .and(header("Range", "bytes=4000-"))
+            .and(header("Accept-Encoding", "identity"))
+            .respond_with(ResponseTemplate::new(206).set_body_bytes(body[4000..].to_vec()))
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("state.ssz");
+        std::fs::write(&file, &body[..4000]).unwrap();
+
+        let state = BeaconClient::new(server.uri())
+            .download_state(
+                "100",
+                &DownloadOptions {
+                    resume: true,
+                    ..options(DownloadTarget::File(file))
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(&state[..], &body[..]);
+    }
+
+    #[tokio::test]
+    async fn test_download_restarts_when_range_ignored() {
+        let server = serve(ResponseTemplate::new(200).set_body_bytes(state_body())).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("state.ssz");
+        std::fs::write(&file, b"stale partial download").unwrap();
+
+        let state = BeaconClient::new(server.uri())
+            .download_state(
+                "100",
+                &DownloadOptions {
+                    resume: true,
+                    ..options(DownloadTarget::File(file))
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(&state[..], &state_body()[..]);
+    }
+
+    #[tokio::test]
+    async fn test_download_rejects_unknown_encoding() {
+        let server = serve(
+            ResponseTemplate::new(200)
+                .insert_header("Content-Encoding", "br")
+                .set_body_bytes(vec![1, 2, 3]),
+        )
+        .await;
+
+        let error = BeaconClient::new(server.uri())
+            .download_state("100", &options(DownloadTarget::Memory))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, BeaconClientError::InvalidResponse(_)));
+    }
+
+    #[test]
+    fn test_state_bytes_decode_reads_ssz() {
+        use crate::types::PendingConsolidation;
+
+        let consolidation = PendingConsolidation {
+            source_index: 42,
+            target_index: 100,
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("value.ssz");
+        std::fs::write(&file, ssz_rs::serialize(&consolidation).unwrap()).unwrap();
+
+        let bytes = StateBytes::open(&file).unwrap();
+        assert_eq!(
+            bytes.decode::<PendingConsolidation>().unwrap(),
+            consolidation
+        );
+    }
+}
@@ -4,22 +4,60 @@
 //! approach. This works with any list limits (including gnosis's 2^40 validators)
 //! without allocating full Merkle trees.
 
-use crate::beacon_state::{BeaconBlockHeader, PendingConsolidation, Validator};
-use crate::proof::{ConsolidationProofBundle, ProofError};
-use crate::sparse_proof::{mix_in_length, prove_against_leaf_chunks, prove_small_container_field};
+use crate::beacon_state::{
+    BeaconBlockHeader, GnosisBeaconState, HistoricalSummary, PendingConsolidation, PendingDeposit,
+    PendingPartialWithdrawal, Validator, VersionedGnosisState,
+};
+use crate::fork::{ForkError, ForkName, StateLayout};
+use crate::multiproof::MultiProof;
+use crate::proof::{ConsolidationProofBundle, ExitedValidatorProofBundle, ProofError, TargetProof};
+use crate::schema::{self, PathStep, SchemaError, StateLimits};
+use crate::sparse_proof::{
+    mix_in_length, prove_against_leaf_chunks, prove_list_element, prove_small_container_field,
+};
 use ssz_rs::prelude::*;
+use tracing::warn;
+
+/// Proof of a single leaf against a block root, from [`StateProver::prove_path`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathProof {
+    /// Chunk at the end of the path
+    pub leaf: [u8; 32],
+    /// Sibling hashes from the leaf up to the block root
+    pub branch: Vec<[u8; 32]>,
+    /// Generalized index of the leaf from the block root
+    pub gindex: u64,
+}
 
-/// Number of fields in the Electra BeaconState (constant across presets)
-const BEACON_STATE_FIELD_COUNT: usize = 37;
-
-/// Validators field index in BeaconState
-const VALIDATORS_FIELD_INDEX: usize = 11;
+impl PathProof {
+    /// Verify the proof against a block root.
+    pub fn verify(&self, block_root: [u8; 32]) -> Result<(), ProofError> {
+        let branch: Vec<Node> = self.branch.iter().copied().map(Node::from).collect();
+        ssz_rs::proofs::is_valid_merkle_branch_for_generalized_index(
+            Node::from(self.leaf),
+            &branch,
+            self.gindex as usize,
+            Node::from(block_root),
+        )
+        .map_err(|e| ProofError::ProofGenerationFailed(format!("Path proof invalid: {e}")))
+    }
+}
 
-/// Pending consolidations field index in BeaconState
-const PENDING_CONSOLIDATIONS_FIELD_INDEX: usize = 36;
+/// State lists beyond validators and pending consolidations that
+/// [`StateProver::prove_path`] can descend into
+struct StateLists {
+    balances: Vec<u64>,
+    pending_deposits: Vec<PendingDeposit>,
+    pending_partial_withdrawals: Vec<PendingPartialWithdrawal>,
+    historical_summaries: Vec<HistoricalSummary>,
+}
 
 /// A sparse proof generator that builds proofs layer-by-layer.
 pub struct StateProver {
+    fork: ForkName,
+    layout: StateLayout,
+    limits: StateLimits,
+    lists: Option<StateLists>,
     field_roots: Vec<[u8; 32]>,
     validator_hashes: Vec<[u8; 32]>,
     validator_count: usize,
@@ -33,6 +71,8 @@ pub struct StateProver {
 
 impl StateProver {
     /// Create a new StateProver from pre-computed field roots and element data.
+    ///
+    /// The field roots must follow the Electra layout.
     pub fn new(
         field_roots: Vec<[u8; 32]>,
         validators: Vec<Validator>,
@@ -40,10 +80,34 @@ impl StateProver {
         validators_tree_depth: u32,
         consolidations_tree_depth: u32,
     ) -> Result<Self, ProofError> {
-        if field_roots.len() != BEACON_STATE_FIELD_COUNT {
+        Self::for_fork(
+            ForkName::Electra,
+            field_roots,
+            validators,
+            consolidations,
+            validators_tree_depth,
+            consolidations_tree_depth,
+        )
+    }
+
+    /// Create a StateProver for a state with the given fork's layout.
+    ///
+    /// List limits other than the validator registry and pending
+    /// consolidations are taken from the Gnosis preset.
+    pub fn for_fork(
+        fork: ForkName,
+        field_roots: Vec<[u8; 32]>,
+        validators: Vec<Validator>,
+        consolidations: Vec<PendingConsolidation>,
+        validators_tree_depth: u32,
+        consolidations_tree_depth: u32,
+    ) -> Result<Self, ProofError> {
+        let layout = StateLayout::for_fork(fork)
+            .ok_or_else(|| SchemaError::from(ForkError::Unsupported(fork)))?;
+        if field_roots.len() != layout.field_count {
             return Err(ProofError::ProofGenerationFailed(format!(
                 "Expected {} field roots, got {}",
-                BEACON_STATE_FIELD_COUNT,
+                layout.field_count,
                 field_roots.len()
             )));
         }
@@ -67,7 +131,17 @@ impl StateProver {
         let validator_count = validators.len();
         let consolidation_count = consolidations.len();
 
+        let limits = StateLimits {
+            validator_registry_limit: 1 << validators_tree_depth,
+            pending_consolidations_limit: 1 << consolidations_tree_depth,
+            ..StateLimits::GNOSIS
+        };
+
         Ok(Self {
+            fork,
+            layout,
+            limits,
+            lists: None,
             field_roots,
             validator_hashes,
             validator_count,
@@ -80,9 +154,70 @@ impl StateProver {
         })
     }
 
+    /// Create a StateProver from a decoded Gnosis state.
+    pub fn from_gnosis_state(state: &GnosisBeaconState) -> Result<Self, ProofError> {
+        let field_roots = state
+            .field_roots()
+            .map_err(ProofError::MerkleizationError)?;
+        let mut prover = Self::new(
+            field_roots,
+            state.validators.to_vec(),
+            state.pending_consolidations.to_vec(),
+            GnosisBeaconState::VALIDATORS_TREE_DEPTH,
+            GnosisBeaconState::PENDING_CONSOLIDATIONS_TREE_DEPTH,
+        )?;
+        prover.lists = Some(StateLists {
+            balances: state.balances.to_vec(),
+            pending_deposits: state.pending_deposits.to_vec(),
+            pending_partial_withdrawals: state.pending_partial_withdrawals.to_vec(),
+            historical_summaries: state.historical_summaries.to_vec(),
+        });
+        Ok(prover)
+    }
+
+    /// Create a StateProver from a Gnosis state of any supported fork.
+    ///
+    /// Warns if the fork's gindices differ from those the contract hardcodes,
+    /// since proofs from such a state would not verify on-chain.
+    pub fn from_state(state: &VersionedGnosisState) -> Result<Self, ProofError> {
+        let mismatches = state.layout().contract_mismatches();
+        if !mismatches.is_empty() {
+            let details: Vec<String> = mismatches.iter().map(ToString::to_string).collect();
+            warn!(
+                fork = %state.fork(),
+                mismatches = %details.join("; "),
+                "State layout differs from the contract's gindices"
+            );
+        }
+
+        let field_roots = state
+            .field_roots()
+            .map_err(ProofError::MerkleizationError)?;
+        let mut prover = Self::for_fork(
+            state.fork(),
+            field_roots,
+            state.validators().to_vec(),
+            state.pending_consolidations().to_vec(),
+            GnosisBeaconState::VALIDATORS_TREE_DEPTH,
+            GnosisBeaconState::PENDING_CONSOLIDATIONS_TREE_DEPTH,
+        )?;
+        prover.lists = Some(StateLists {
+            balances: state.balances().to_vec(),
+            pending_deposits: state.pending_deposits().to_vec(),
+            pending_partial_withdrawals: state.pending_partial_withdrawals().to_vec(),
+            historical_summaries: state.historical_summaries().to_vec(),
+        });
+        Ok(prover)
+    }
+
+    /// Layout of the state this prover was built from.
+    pub fn layout(&self) -> StateLayout {
+        self.layout
+    }
+
     /// Compute the state root from the field roots.
     pub fn compute_state_root(&self) -> [u8; 32] {
-        let depth = 6u32;
+        let depth = self.layout.tree_depth();
         let (_proof, root) = prove_against_leaf_chunks(&self.field_roots, 0, depth);
         root
     }
@@ -117,9 +252,12 @@ impl StateProver {
         let mut length_bytes = [0u8; 32];
         length_bytes[..8].copy_from_slice(&(self.consolidation_count as u64).to_le_bytes());
 
-        // Layer 4: pending_consolidations field in state container (depth 6)
-        let (state_proof, _) =
-            prove_against_leaf_chunks(&self.field_roots, PENDING_CONSOLIDATIONS_FIELD_INDEX, 6);
+        // Layer 4: pending_consolidations field in state container (depth 6 in Electra)
+        let (state_proof, _) = prove_against_leaf_chunks(
+            &self.field_roots,
+            self.layout.pending_consolidations_field_index,
+            self.layout.tree_depth(),
+        );
 
         let mut full_proof = inner_proof;
         full_proof.extend_from_slice(&list_data_proof);
@@ -156,8 +294,11 @@ impl StateProver {
         let mut length_bytes = [0u8; 32];
         length_bytes[..8].copy_from_slice(&(self.validator_count as u64).to_le_bytes());
 
-        let (state_proof, _) =
-            prove_against_leaf_chunks(&self.field_roots, VALIDATORS_FIELD_INDEX, 6);
+        let (state_proof, _) = prove_against_leaf_chunks(
+            &self.field_roots,
+            self.layout.validators_field_index,
+            self.layout.tree_depth(),
+        );
 
         let mut full_proof = inner_proof;
         full_proof.extend_from_slice(&list_data_proof);
@@ -194,8 +335,11 @@ impl StateProver {
         let mut length_bytes = [0u8; 32];
         length_bytes[..8].copy_from_slice(&(self.validator_count as u64).to_le_bytes());
 
-        let (state_proof, _) =
-            prove_against_leaf_chunks(&self.field_roots, VALIDATORS_FIELD_INDEX, 6);
+        let (state_proof, _) = prove_against_leaf_chunks(
+            &self.field_roots,
+            self.layout.validators_field_index,
+            self.layout.tree_depth(),
+        );
 
         let mut full_proof = inner_proof;
         full_proof.extend_from_slice(&list_data_proof);
@@ -205,6 +349,154 @@ impl StateProver {
         Ok((full_proof, inner_leaf))
     }
 
+    /// Prove any leaf reachable from the block root.
+    ///
+    /// `path` starts at the header, e.g. `["state_root", "balances", i]` or
+    /// `["state_root", "pending_deposits", i, "amount"]`. Within the state it
+    /// can end at any field, or descend into `validators`,
+    /// `pending_consolidations`, `balances` (packed four per chunk),
+    /// `pending_deposits`, `pending_partial_withdrawals` and
+    /// `historical_summaries`. The last four need a prover built from a full
+    /// state.
+    pub fn prove_path(
+        &self,
+        header: &BeaconBlockHeader,
+        path: &[PathStep],
+    ) -> Result<PathProof, ProofError> {
+        let state_schema =
+            schema::beacon_state(self.fork, &self.limits).map_err(SchemaError::from)?;
+        let gindex = schema::beacon_block_header(state_schema).gindex(path)?;
+
+        let (leaf, branch) = match path.split_first() {
+            Some((PathStep::Field("state_root"), state_path)) => {
+                let (leaf, mut branch) = self.prove_state_path(state_path)?;
+                let (header_proof, _, _) =
+                    prove_small_container_field(header, &["state_root".into()])?;
+                branch.extend(header_proof);
+                (leaf, branch)
+            }
+            _ => {
+                let (branch, leaf, _) = prove_small_container_field(header, &ssz_path(path))?;
+                (leaf, branch)
+            }
+        };
+
+        Ok(PathProof {
+            leaf,
+            branch,
+            gindex,
+        })
+    }
+
+    /// Prove several paths from the block root as one compact multiproof.
+    pub fn prove_multi(
+        &self,
+        header: &BeaconBlockHeader,
+        paths: &[&[PathStep]],
+    ) -> Result<MultiProof, ProofError> {
+        let proofs = paths
+            .iter()
+            .map(|path| self.prove_path(header, path))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(MultiProof::from_branches(proofs.iter().map(|proof| {
+            (proof.gindex, proof.leaf, proof.branch.as_slice())
+        }))?)
+    }
+
+    /// Leaf and branch up to the state root for a path within the state.
+    fn prove_state_path(&self, path: &[PathStep]) -> Result<([u8; 32], Vec<[u8; 32]>), ProofError> {
+        let Some((&PathStep::Field(field), rest)) = path.split_first() else {
+            return Ok((self.compute_state_root(), Vec::new()));
+        };
+
+        let state_schema =
+            schema::beacon_state(self.fork, &self.limits).map_err(SchemaError::from)?;
+        let depth = self.layout.tree_depth();
+        let field_index = (state_schema.gindex(&[field.into()])? - (1 << depth)) as usize;
+
+        let (leaf, mut branch) = if rest.is_empty() {
+            (self.field_roots[field_index], Vec::new())
+        } else {
+            self.prove_in_field(field, rest)?
+        };
+        let (state_proof, _) = prove_against_leaf_chunks(&self.field_roots, field_index, depth);
+        branch.extend(state_proof);
+        Ok((leaf, branch))
+    }
+
+    /// Leaf and branch up to the root of the state field `field`.
+    fn prove_in_field(
+        &self,
+        field: &str,
+        path: &[PathStep],
+    ) -> Result<([u8; 32], Vec<[u8; 32]>), ProofError> {
+        let lists = || {
+            self.lists.as_ref().ok_or_else(|| {
+                ProofError::ProofGenerationFailed(format!(
+                    "{field} is only available when proving from a full state"
+                ))
+            })
+        };
+        let depth = |limit: u64| limit.next_power_of_two().trailing_zeros();
+
+        match field {
+            "validators" => prove_list_path(
+                &self.validators,
+                &self.validator_hashes,
+                self.validators_tree_depth,
+                path,
+            ),
+            "pending_consolidations" => prove_list_path(
+                &self.consolidations,
+                &self.consolidation_hashes,
+                self.consolidations_tree_depth,
+                path,
+            ),
+            "balances" => {
+                let balances = &lists()?.balances;
+                let chunks: Vec<[u8; 32]> = balances
+                    .chunks(4)
+                    .map(|group| {
+                        let mut chunk = [0u8; 32];
+                        for (i, balance) in group.iter().enumerate() {
+                            chunk[i * 8..(i + 1) * 8].copy_from_slice(&balance.to_le_bytes());
+                        }
+                        chunk
+                    })
+                    .collect();
+                let chunk_depth = depth(self.limits.validator_registry_limit.div_ceil(4));
+                match path {
+                    [PathStep::Index(index)] if (*index as usize) < balances.len() => {
+                        let position = *index as usize / 4;
+                        let (branch, _) =
+                            prove_list_element(&chunks, position, chunk_depth, balances.len());
+                        Ok((chunks[position], branch))
+                    }
+                    [PathStep::Length] => {
+                        Ok(prove_list_length(&chunks, chunk_depth, balances.len()))
+                    }
+                    _ => Err(unsupported_path(field, path)),
+                }
+            }
+            "pending_deposits" => {
+                let elements = &lists()?.pending_deposits;
+                let depth = depth(self.limits.pending_deposits_limit);
+                prove_list_path(elements, &hash_elements(elements)?, depth, path)
+            }
+            "pending_partial_withdrawals" => {
+                let elements = &lists()?.pending_partial_withdrawals;
+                let depth = depth(self.limits.pending_partial_withdrawals_limit);
+                prove_list_path(elements, &hash_elements(elements)?, depth, path)
+            }
+            "historical_summaries" => {
+                let elements = &lists()?.historical_summaries;
+                let depth = depth(self.limits.historical_roots_limit);
+                prove_list_path(elements, &hash_elements(elements)?, depth, path)
+            }
+            _ => Err(unsupported_path(field, path)),
+        }
+    }
+
     /// Generate full proof bundle from block root for a given consolidation.
     pub fn generate_full_proof_bundle(
         &self,
@@ -260,8 +552,179 @@ impl StateProver {
             proof_consolidation: full_consolidation_proof,
             proof_credentials: full_credentials_proof,
             proof_activation_epoch: full_activation_proof,
+            target: None,
+        })
+    }
+
+    /// Generate proofs of the target validator of `pending_consolidations[i]`
+    /// from block root, to attach as [`ConsolidationProofBundle::target`].
+    pub fn generate_target_proof(
+        &self,
+        header: &BeaconBlockHeader,
+        consolidation_index: usize,
+    ) -> Result<TargetProof, ProofError> {
+        let consolidation = self.consolidations.get(consolidation_index).ok_or(
+            ProofError::ConsolidationIndexOutOfBounds(
+                consolidation_index,
+                self.consolidation_count,
+            ),
+        )?;
+        let target_index = consolidation.target_index;
+        let target = self.validators.get(target_index as usize).ok_or(
+            ProofError::ValidatorIndexOutOfBounds(target_index, self.validators.len()),
+        )?;
+
+        let prove_validator_field = |field| {
+            self.prove_path(
+                header,
+                &[
+                    "state_root".into(),
+                    "validators".into(),
+                    PathStep::Index(target_index),
+                    PathStep::Field(field),
+                ],
+            )
+            .map(|proof| proof.branch)
+        };
+        let proof_target_index = self
+            .prove_path(
+                header,
+                &[
+                    "state_root".into(),
+                    "pending_consolidations".into(),
+                    PathStep::Index(consolidation_index as u64),
+                    "target_index".into(),
+                ],
+            )?
+            .branch;
+
+        Ok(TargetProof {
+            target_index,
+            target_credentials: target.withdrawal_credentials,
+            target_effective_balance: target.effective_balance,
+            proof_target_index,
+            proof_target_credentials: prove_validator_field("withdrawal_credentials")?,
+            proof_target_effective_balance: prove_validator_field("effective_balance")?,
         })
     }
+
+    /// Generate an exited-validator proof bundle from block root for
+    /// `validators[source_index]`.
+    ///
+    /// Fails with [`ProofError::ValidatorNotDrained`] unless the validator has
+    /// an exit epoch and zero effective balance. See
+    /// [`ExitedValidatorProofBundle`] for why this alone does not show a
+    /// consolidation.
+    pub fn generate_exit_proof_bundle(
+        &self,
+        header: &BeaconBlockHeader,
+        source_index: usize,
+        beacon_timestamp: u64,
+    ) -> Result<ExitedValidatorProofBundle, ProofError> {
+        let validator =
+            self.validators
+                .get(source_index)
+                .ok_or(ProofError::ValidatorIndexOutOfBounds(
+                    source_index as u64,
+                    self.validators.len(),
+                ))?;
+
+        let prove_field = |field| {
+            self.prove_path(
+                header,
+                &[
+                    "state_root".into(),
+                    "validators".into(),
+                    PathStep::Index(source_index as u64),
+                    PathStep::Field(field),
+                ],
+            )
+            .map(|proof| proof.branch)
+        };
+
+        let bundle = ExitedValidatorProofBundle {
+            beacon_timestamp,
+            source_index: source_index as u64,
+            activation_epoch: validator.activation_epoch,
+            exit_epoch: validator.exit_epoch,
+            withdrawable_epoch: validator.withdrawable_epoch,
+            effective_balance: validator.effective_balance,
+            source_credentials: validator.withdrawal_credentials,
+            proof_credentials: prove_field("withdrawal_credentials")?,
+            proof_activation_epoch: prove_field("activation_epoch")?,
+            proof_exit_epoch: prove_field("exit_epoch")?,
+            proof_withdrawable_epoch: prove_field("withdrawable_epoch")?,
+            proof_effective_balance: prove_field("effective_balance")?,
+        };
+        bundle.check_exited_and_drained()?;
+        Ok(bundle)
+    }
+}
+
+/// Leaf and branch up to a list's root for `[i, ...]` or `[length]`.
+fn prove_list_path<T: SimpleSerialize>(
+    elements: &[T],
+    element_hashes: &[[u8; 32]],
+    depth: u32,
+    path: &[PathStep],
+) -> Result<([u8; 32], Vec<[u8; 32]>), ProofError> {
+    match path.split_first() {
+        Some((&PathStep::Index(index), rest)) => {
+            let index = index as usize;
+            let element = elements.get(index).ok_or_else(|| {
+                ProofError::ProofGenerationFailed(format!(
+                    "Index {index} beyond list length {}",
+                    elements.len()
+                ))
+            })?;
+            let (leaf, mut branch) = if rest.is_empty() {
+                (element_hashes[index], Vec::new())
+            } else {
+                let (branch, leaf, _) = prove_small_container_field(element, &ssz_path(rest))?;
+                (leaf, branch)
+            };
+            let (list_proof, _) = prove_list_element(element_hashes, index, depth, elements.len());
+            branch.extend(list_proof);
+            Ok((leaf, branch))
+        }
+        Some((PathStep::Length, [])) => {
+            Ok(prove_list_length(element_hashes, depth, elements.len()))
+        }
+        _ => Err(ProofError::ProofGenerationFailed(format!(
+            "Cannot prove {path:?} within a list"
+        ))),
+    }
+}
+
+/// Length chunk of a list and its single sibling, the data root.
+fn prove_list_length(chunks: &[[u8; 32]], depth: u32, length: usize) -> ([u8; 32], Vec<[u8; 32]>) {
+    let (_, data_root) = prove_against_leaf_chunks(chunks, 0, depth);
+    let mut length_bytes = [0u8; 32];
+    length_bytes[..8].copy_from_slice(&(length as u64).to_le_bytes());
+    (length_bytes, vec![data_root])
+}
+
+fn hash_elements<T: SimpleSerialize>(elements: &[T]) -> Result<Vec<[u8; 32]>, ProofError> {
+    let mut hashes = Vec::with_capacity(elements.len());
+    for element in elements {
+        hashes.push(element.hash_tree_root()?.into());
+    }
+    Ok(hashes)
+}
+
+fn unsupported_path(field: &str, path: &[PathStep]) -> ProofError {
+    ProofError::ProofGenerationFailed(format!("Cannot prove {path:?} within {field}"))
+}
+
+/// Convert a schema path to an ssz_rs path for proofs within small containers.
+fn ssz_path(path: &[PathStep]) -> Vec<PathElement> {
+    path.iter()
+        .map(|step| match *step {
+            PathStep::Field(field) => PathElement::Field(field.to_string()),
+            PathStep::Index(index) => PathElement::Index(index as usize),
+            PathStep::Length => PathElement::Length,
+        })
+        .collect()
 }
 
 /// Compute the hash tree root of a list given element hashes and limits.
@@ -273,8 +736,9 @@ pub fn compute_list_root(element_hashes: &[[u8; 32]], tree_depth: u32, length: u
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::beacon_state::MinimalBeaconState;
+    use crate::beacon_state::{GnosisFuluBeaconState, MinimalBeaconState};
     use crate::gindex::GindexCalculator;
+    use crate::proof::ProofGenerator;
 
     fn make_validator(index: u8) -> Validator {
         let mut v = Validator::default();
@@ -430,6 +894,299 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_gnosis_state_prover_matches_ssz_rs() {
+        let mut state = GnosisBeaconState {
+            slot: 2000,
+            ..GnosisBeaconState::default()
+        };
+        for i in 0..5u8 {
+            state.validators.push(make_validator(i));
+            state.balances.push(32_000_000_000);
+        }
+        state.pending_consolidations.push(PendingConsolidation {
+            source_index: 4,
+            target_index: 1,
+        });
+
+        let state_root: [u8; 32] = state.hash_tree_root().unwrap().into();
+        let prover = StateProver::from_gnosis_state(&state).expect("should create prover");
+        assert_eq!(prover.compute_state_root(), state_root);
+
+        let (proof, leaf) = prover
+            .prove_consolidation_source_index(0)
+            .expect("should generate proof");
+        let branch: Vec<Node> = proof
+            .iter()
+            .map(|b| Node::try_from(b.as_slice()).unwrap())
+            .collect();
+        ssz_rs::proofs::is_valid_merkle_branch_for_generalized_index(
+            Node::try_from(leaf.as_slice()).unwrap(),
+            &branch,
+            GindexCalculator::concat_gindices(&[100, 2, 1 << 18, 2]) as usize,
+            Node::try_from(state_root.as_slice()).unwrap(),
+        )
+        .expect("consolidation proof should verify against gnosis state root");
+    }
+
+    #[test]
+    fn test_fulu_state_prover_uses_fulu_layout() {
+        let mut state = GnosisFuluBeaconState::default();
+        for i in 0..3u8 {
+            state.validators.push(make_validator(i));
+        }
+        state.proposer_lookahead[0] = 2;
+
+        let state_root: [u8; 32] = state.hash_tree_root().unwrap().into();
+        let prover = StateProver::from_state(&VersionedGnosisState::Fulu(Box::new(state)))
+            .expect("should create prover");
+        assert_eq!(prover.layout(), StateLayout::FULU);
+        assert_eq!(prover.compute_state_root(), state_root);
+
+        let (proof, leaf) = prover
+            .prove_validator_credentials(2)
+            .expect("should generate proof");
+        let branch: Vec<Node> = proof
+            .iter()
+            .map(|b| Node::try_from(b.as_slice()).unwrap())
+            .collect();
+        let gindex = GindexCalculator::concat_gindices(&[
+            StateLayout::FULU.validators_gindex(),
+            2,
+            (1 << 40) + 2,
+            9,
+        ]);
+        ssz_rs::proofs::is_valid_merkle_branch_for_generalized_index(
+            Node::try_from(leaf.as_slice()).unwrap(),
+            &branch,
+            gindex as usize,
+            Node::try_from(state_root.as_slice()).unwrap(),
+        )
+        .expect("credentials proof should verify against fulu state root");
+    }
+
+    #[test]
+    fn test_for_fork_rejects_wrong_field_count() {
+        let result =
+            StateProver::for_fork(ForkName::Fulu, vec![[0u8; 32]; 37], vec![], vec![], 40, 18);
+        assert!(result.is_err());
+    }
+
+    fn gnosis_state_with_lists() -> GnosisBeaconState {
+        let mut state = GnosisBeaconState {
+            slot: 3000,
+            ..GnosisBeaconState::default()
+        };
+        for i in 0..6u8 {
+            state.validators.push(make_validator(i));
+            state.balances.push(32_000_000_000 + i as u64);
+        }
+        state.pending_consolidations.push(PendingConsolidation {
+            source_index: 5,
+            target_index: 0,
+        });
+        state.pending_deposits.push(PendingDeposit {
+            amount: 1_000_000_000,
+            slot: 2990,
+            ..PendingDeposit::default()
+        });
+        state
+            .pending_partial_withdrawals
+            .push(PendingPartialWithdrawal {
+                validator_index: 3,
+                amount: 7,
+                withdrawable_epoch: 99,
+            });
+        state.historical_summaries.push(HistoricalSummary {
+            block_summary_root: [0xaa; 32],
+            state_summary_root: [0xbb; 32],
+        });
+        state
+    }
+
+    fn header_for(state_root: [u8; 32]) -> BeaconBlockHeader {
+        BeaconBlockHeader {
+            slot: 3000,
+            proposer_index: 1,
+            parent_root: [0x11; 32],
+            state_root,
+            body_root: [0x22; 32],
+        }
+    }
+
+    #[test]
+    fn test_prove_path_verifies_against_block_root() {
+        let state = gnosis_state_with_lists();
+        let state_root: [u8; 32] = state.hash_tree_root().unwrap().into();
+        let header = header_for(state_root);
+        let block_root: [u8; 32] = header.hash_tree_root().unwrap().into();
+        let prover = StateProver::from_gnosis_state(&state).expect("should create prover");
+
+        let paths: Vec<Vec<PathStep>> = vec![
+            vec!["slot".into()],
+            vec!["state_root".into()],
+            vec!["state_root".into(), "slot".into()],
+            vec!["state_root".into(), "balances".into(), 5.into()],
+            vec!["state_root".into(), "balances".into(), PathStep::Length],
+            vec![
+                "state_root".into(),
+                "validators".into(),
+                2.into(),
+                "withdrawal_credentials".into(),
+            ],
+            vec![
+                "state_root".into(),
+                "pending_consolidations".into(),
+                0.into(),
+            ],
+            vec![
+                "state_root".into(),
+                "pending_deposits".into(),
+                0.into(),
+                "amount".into(),
+            ],
+            vec![
+                "state_root".into(),
+                "pending_partial_withdrawals".into(),
+                0.into(),
+                "validator_index".into(),
+            ],
+            vec![
+                "state_root".into(),
+                "pending_partial_withdrawals".into(),
+                PathStep::Length,
+            ],
+            vec!["state_root".into(), "historical_summaries".into(), 0.into()],
+        ];
+        for path in paths {
+            let proof = prover
+                .prove_path(&header, &path)
+                .unwrap_or_else(|e| panic!("should prove {path:?}: {e}"));
+            proof
+                .verify(block_root)
+                .unwrap_or_else(|e| panic!("proof for {path:?} should verify: {e}"));
+        }
+    }
+
+    #[test]
+    fn test_prove_path_leaves() {
+        let state = gnosis_state_with_lists();
+        let state_root: [u8; 32] = state.hash_tree_root().unwrap().into();
+        let header = header_for(state_root);
+        let prover = StateProver::from_gnosis_state(&state).expect("should create prover");
+
+        // balances[5] sits in the second chunk at byte offset 8
+        let proof = prover
+            .prove_path(&header, &["state_root".into(), "balances".into(), 5.into()])
+            .unwrap();
+        assert_eq!(proof.leaf[8..16], 32_000_000_005u64.to_le_bytes());
+
+        let proof = prover
+            .prove_path(
+                &header,
+                &[
+                    "state_root".into(),
+                    "pending_deposits".into(),
+                    0.into(),
+                    "amount".into(),
+                ],
+            )
+            .unwrap();
+        assert_eq!(proof.leaf[..8], 1_000_000_000u64.to_le_bytes());
+
+        let proof = prover
+            .prove_path(
+                &header,
+                &[
+                    "state_root".into(),
+                    "historical_summaries".into(),
+                    0.into(),
+                    "state_summary_root".into(),
+                ],
+            )
+            .unwrap();
+        assert_eq!(proof.leaf, [0xbb; 32]);
+        assert_eq!(
+            proof.gindex,
+            GindexCalculator::concat_gindices(&[11, 64 + 27, 2, 1 << 24, 3])
+        );
+    }
+
+    #[test]
+    fn test_prove_multi_verifies_against_block_root() {
+        let state = gnosis_state_with_lists();
+        let state_root: [u8; 32] = state.hash_tree_root().unwrap().into();
+        let header = header_for(state_root);
+        let block_root: [u8; 32] = header.hash_tree_root().unwrap().into();
+        let prover = StateProver::from_gnosis_state(&state).expect("should create prover");
+
+        let credentials: &[PathStep] = &[
+            "state_root".into(),
+            "validators".into(),
+            5.into(),
+            "withdrawal_credentials".into(),
+        ];
+        let activation: &[PathStep] = &[
+            "state_root".into(),
+            "validators".into(),
+            5.into(),
+            "activation_epoch".into(),
+        ];
+        let balance: &[PathStep] = &["state_root".into(), "balances".into(), 5.into()];
+        let multiproof = prover
+            .prove_multi(&header, &[credentials, activation, balance])
+            .expect("should build multiproof");
+        let gindices: Vec<u64> = [credentials, activation, balance]
+            .iter()
+            .map(|path| prover.prove_path(&header, path).unwrap().gindex)
+            .collect();
+        multiproof
+            .verify(block_root, &gindices)
+            .expect("should verify");
+
+        let single = prover.prove_path(&header, balance).unwrap();
+        assert_eq!(multiproof.leaves[2], single.leaf);
+    }
+
+    #[test]
+    fn test_prove_path_rejects_missing_elements() {
+        let state = gnosis_state_with_lists();
+        let state_root: [u8; 32] = state.hash_tree_root().unwrap().into();
+        let header = header_for(state_root);
+        let prover = StateProver::from_gnosis_state(&state).expect("should create prover");
+
+        assert!(prover
+            .prove_path(
+                &header,
+                &["state_root".into(), "pending_deposits".into(), 1.into()]
+            )
+            .is_err());
+        assert!(matches!(
+            prover.prove_path(&header, &["state_root".into(), "no_such_field".into()]),
+            Err(ProofError::Schema(_))
+        ));
+
+        // Without the full state only validators and consolidations are available
+        let field_roots = prover.field_roots.clone();
+        let partial = StateProver::new(
+            field_roots,
+            state.validators.to_vec(),
+            state.pending_consolidations.to_vec(),
+            GnosisBeaconState::VALIDATORS_TREE_DEPTH,
+            GnosisBeaconState::PENDING_CONSOLIDATIONS_TREE_DEPTH,
+        )
+        .unwrap();
+        assert!(partial
+            .prove_path(&header, &["state_root".into(), "balances".into(), 0.into()])
+            .is_err());
+        assert!(partial
+            .prove_path(
+                &header,
+                &["state_root".into(), "validators".into(), 0.into()]
+            )
+            .is_ok());
+    }
+
     #[test]
     fn test_consolidation_proof_verifies_against_state_root() {
         let mut state = MinimalBeaconState {
@@ -567,6 +1324,114 @@ mod tests {
         .expect("activation epoch proof should verify");
     }
 
+    #[test]
+    fn test_target_proof_verifies_with_bundle() {
+        let mut state = MinimalBeaconState {
+            slot: 1000,
+            ..MinimalBeaconState::default()
+        };
+        for i in 0..4u8 {
+            state.validators.push(make_validator(i));
+            state.balances.push(32_000_000_000);
+        }
+        // Target 3 is compounding and withdraws to the source's address
+        state.validators[3].withdrawal_credentials = state.validators[1].withdrawal_credentials;
+        state.validators[3].withdrawal_credentials[0] = 0x02;
+        state.validators[3].effective_balance = 64_000_000_000;
+        state.pending_consolidations.push(PendingConsolidation {
+            source_index: 0,
+            target_index: 2,
+        });
+        state.pending_consolidations.push(PendingConsolidation {
+            source_index: 1,
+            target_index: 3,
+        });
+
+        let header = BeaconBlockHeader {
+            slot: state.slot,
+            proposer_index: 0,
+            parent_root: [0u8; 32],
+            state_root: state.hash_tree_root().unwrap().into(),
+            body_root: [1u8; 32],
+        };
+        let block_root: [u8; 32] = header.hash_tree_root().unwrap().into();
+        let prover = state_prover_from_minimal(&state);
+
+        let mut bundle = prover.generate_full_proof_bundle(&header, 1, 0).unwrap();
+        bundle.target = Some(prover.generate_target_proof(&header, 1).unwrap());
+        let target = bundle.target.as_ref().unwrap();
+        assert_eq!(target.target_index, 3);
+        assert_eq!(target.target_effective_balance, 64_000_000_000);
+        assert!(target.is_compounding());
+        assert_eq!(bundle.shares_owner_with_target(), Some(true));
+        ProofGenerator::verify_proof_bundle_test(&bundle, block_root)
+            .expect("bundle with target should verify");
+
+        // A wrong target fact fails the whole bundle
+        let mut tampered = bundle.clone();
+        tampered.target.as_mut().unwrap().target_effective_balance = 32_000_000_000;
+        assert!(ProofGenerator::verify_proof_bundle_test(&tampered, block_root).is_err());
+
+        let mut other = prover.generate_full_proof_bundle(&header, 0, 0).unwrap();
+        other.target = Some(prover.generate_target_proof(&header, 0).unwrap());
+        assert_eq!(other.shares_owner_with_target(), Some(false));
+        assert!(!other.target.as_ref().unwrap().is_compounding());
+        ProofGenerator::verify_proof_bundle_test(&other, block_root).unwrap();
+
+        assert!(prover.generate_target_proof(&header, 5).is_err());
+    }
+
+    #[test]
+    fn test_exit_proof_bundle_verifies_against_block_root() {
+        let mut state = MinimalBeaconState {
+            slot: 1000,
+            ..MinimalBeaconState::default()
+        };
+        for i in 0..4u8 {
+            state.validators.push(make_validator(i));
+            state.balances.push(32_000_000_000);
+        }
+        // Validator 3 was the source of a processed consolidation
+        state.validators[3].exit_epoch = 120;
+        state.validators[3].withdrawable_epoch = 376;
+        state.validators[3].effective_balance = 0;
+
+        let header = BeaconBlockHeader {
+            slot: state.slot,
+            proposer_index: 0,
+            parent_root: [0u8; 32],
+            state_root: state.hash_tree_root().unwrap().into(),
+            body_root: [1u8; 32],
+        };
+        let block_root: [u8; 32] = header.hash_tree_root().unwrap().into();
+        let prover = state_prover_from_minimal(&state);
+
+        let bundle = prover
+            .generate_exit_proof_bundle(&header, 3, 1234567890)
+            .expect("should generate exit bundle");
+        assert_eq!(bundle.exit_epoch, 120);
+        assert_eq!(bundle.effective_balance, 0);
+        assert_eq!(
+            bundle.proof_exit_epoch.len(),
+            GindexCalculator::test_validator_proof_length() as usize
+        );
+        ProofGenerator::verify_exit_proof_bundle_test(&bundle, block_root)
+            .expect("exit bundle should verify");
+        assert!(ProofGenerator::verify_exit_proof_bundle_test(&bundle, [0xaa; 32]).is_err());
+
+        // Tampering with a proven value breaks its proof
+        let mut tampered = bundle.clone();
+        tampered.withdrawable_epoch = 377;
+        assert!(ProofGenerator::verify_exit_proof_bundle_test(&tampered, block_root).is_err());
+
+        // Active validators cannot produce an exit bundle
+        assert!(matches!(
+            prover.generate_exit_proof_bundle(&header, 0, 0),
+            Err(ProofError::ValidatorNotDrained(0))
+        ));
+        assert!(prover.generate_exit_proof_bundle(&header, 9, 0).is_err());
+    }
+
     #[test]
     fn test_full_proof_bundle_verifies_against_block_root() {
         let mut state = MinimalBeaconState {
@@ -0,0 +1,493 @@
+//! Beacon State Sources
+//!
+//! Loads SSZ-encoded beacon states from a live node or from local dumps, so
+//! proofs can be generated fully offline. Every [`StateSource`] yields the same
+//! [`StateBytes`], which [`parse_state`] decodes for [`StateProver`] using the
+//! container layout of the state's fork.
+//!
+//! Supported files:
+//! - `.ssz`: raw SSZ
+//! - `.ssz_snappy`: snappy framed (as written by clients) or raw block format
+//! - `.era`: e2store archive; the state record is read, blocks are skipped
+
+use crate::beacon_client::{BeaconClient, BeaconClientError};
+use crate::beacon_state::{GnosisBeaconState, GnosisFuluBeaconState, VersionedGnosisState};
+use crate::fork::{ForkError, ForkName, ForkRegistry};
+use crate::proof::ProofError;
+use crate::state_download::{DownloadOptions, StateBytes};
+use crate::state_prover::StateProver;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tracing::{info, instrument};
+
+/// Stream identifier chunk that opens every snappy framed stream
+const SNAPPY_STREAM_IDENTIFIER: &[u8] = b"\xff\x06\x00\x00sNaPpY";
+
+/// e2store record header: type (2), length (4, little-endian), reserved (2)
+const E2STORE_HEADER_LEN: usize = 8;
+
+/// e2store `Version` record, first in every era file
+const E2STORE_VERSION: [u8; 2] = [0x65, 0x32];
+
+/// Era `CompressedBeaconState` record (snappy framed SSZ)
+const ERA_COMPRESSED_STATE: [u8; 2] = [0x02, 0x00];
+
+/// Errors from loading and parsing beacon states
+#[derive(Debug, Error)]
+pub enum StateSourceError {
+    #[error(transparent)]
+    Beacon(#[from] BeaconClientError),
+
+    #[error("Failed to read {}: {source}", .path.display())]
+    Io { path: PathBuf, source: io::Error },
+
+    #[error(
+        "Unsupported state file {} (expected .ssz, .ssz_snappy or .era)",
+        .0.display()
+    )]
+    UnsupportedFile(PathBuf),
+
+    #[error("Snappy decompression failed for {}: {message}", .path.display())]
+    Snappy { path: PathBuf, message: String },
+
+    #[error("Invalid era file {}: {message}", .path.display())]
+    InvalidEra { path: PathBuf, message: String },
+
+    #[error("State is at slot {actual}, requested slot {requested}")]
+    SlotMismatch { requested: u64, actual: u64 },
+
+    #[error("Invalid SSZ state: {0}")]
+    Decode(String),
+
+    #[error(transparent)]
+    Fork(#[from] ForkError),
+
+    #[error(transparent)]
+    Proof(#[from] ProofError),
+}
+
+/// Where beacon states are loaded from
+#[derive(Debug, Clone)]
+pub enum StateSource {
+    /// `/eth/v2/debug/beacon/states` on a live node
+    Beacon {
+        client: Box<BeaconClient>,
+        options: DownloadOptions,
+    },
+    /// Uncompressed SSZ dump
+    SszFile(PathBuf),
+    /// Snappy-compressed SSZ dump
+    SnappyFile(PathBuf),
+    /// Era archive holding one state
+    EraFile(PathBuf),
+}
+
+impl StateSource {
+    /// Download states from a live node
+    #[must_use]
+    pub fn beacon(client: BeaconClient, options: DownloadOptions) -> Self {
+        Self::Beacon {
+            client: Box::new(client),
+            options,
+        }
+    }
+
+    /// Choose a file source from the extension (`.ssz`, `.ssz_snappy`, `.era`)
+    ///
+    /// # Errors
+    /// Returns [`StateSourceError::UnsupportedFile`] for any other extension
+    pub fn from_path(path: impl Into<PathBuf>) -> Result<Self, StateSourceError> {
+        let path = path.into();
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("ssz") => Ok(Self::SszFile(path)),
+            Some("ssz_snappy") => Ok(Self::SnappyFile(path)),
+            Some("era") => Ok(Self::EraFile(path)),
+            _ => Err(StateSourceError::UnsupportedFile(path)),
+        }
+    }
+
+    /// Load the SSZ-encoded state
+    ///
+    /// `state_id` selects the state on a live node. Files hold a single state,
+    /// so there a numeric `state_id` is only checked against the state's slot
+    /// and named ids (`head`, `finalized`, roots) are accepted as-is.
+    ///
+    /// # Errors
+    /// Returns error if the state cannot be fetched or read, or is at another slot
+    #[instrument(skip(self))]
+    pub async fn load(&self, state_id: &str) -> Result<StateBytes, StateSourceError> {
+        let bytes = match self {
+            Self::Beacon { client, options } => client.download_state(state_id, options).await?,
+            Self::SszFile(path) => StateBytes::open(path).map_err(io_error(path))?,
+            Self::SnappyFile(path) => {
+                let compressed = std::fs::read(path).map_err(io_error(path))?;
+                StateBytes::Memory(decompress_snappy(path, &compressed)?)
+            }
+            Self::EraFile(path) => StateBytes::Memory(read_era_state(path)?),
+        };
+
+        let slot = state_slot(&bytes)?;
+        if let Ok(requested) = state_id.parse::<u64>() {
+            if requested != slot {
+                return Err(StateSourceError::SlotMismatch {
+                    requested,
+                    actual: slot,
+                });
+            }
+        }
+        info!(slot, bytes = bytes.len(), "Loaded beacon state");
+        Ok(bytes)
+    }
+
+    /// Load and decode the state
+    ///
+    /// # Errors
+    /// See [`StateSource::load`] and [`parse_state`]
+    pub async fn load_state(
+        &self,
+        state_id: &str,
+    ) -> Result<VersionedGnosisState, StateSourceError> {
+        parse_state(&self.load(state_id).await?)
+    }
+
+    /// Load the state and build a [`StateProver`] over it
+    ///
+    /// # Errors
+    /// See [`StateSource::load_state`]; also fails if the prover cannot be built
+    pub async fn load_prover(&self, state_id: &str) -> Result<StateProver, StateSourceError> {
+        let state = self.load_state(state_id).await?;
+        Ok(StateProver::from_state(&state)?)
+    }
+}
+
+/// Decode an SSZ-encoded Gnosis state with the layout of its fork
+///
+/// The fork is looked up in the Gnosis schedule by `fork.current_version`,
+/// read from the encoding before decoding.
+///
+/// # Errors
+/// Returns [`StateSourceError::Fork`] for unknown or pre-Electra versions, and
+/// [`StateSourceError::Decode`] if `bytes` is not a valid encoding
+pub fn parse_state(bytes: &[u8]) -> Result<VersionedGnosisState, StateSourceError> {
+    let (fork, _) = ForkRegistry::gnosis().layout_for_version(state_fork_version(bytes)?)?;
+    let decode_error = |e: ssz_rs::DeserializeError| StateSourceError::Decode(format!("{e:?}"));
+    Ok(match fork {
+        ForkName::Fulu => VersionedGnosisState::Fulu(Box::new(
+            ssz_rs::deserialize::<GnosisFuluBeaconState>(bytes).map_err(decode_error)?,
+        )),
+        _ => VersionedGnosisState::Electra(Box::new(
+            ssz_rs::deserialize::<GnosisBeaconState>(bytes).map_err(decode_error)?,
+        )),
+    })
+}
+
+/// `fork.current_version` of an SSZ-encoded state, read without decoding it
+fn state_fork_version(bytes: &[u8]) -> Result<[u8; 4], StateSourceError> {
+    let offset = GnosisBeaconState::FORK_VERSION_OFFSET;
+    bytes
+        .get(offset..offset + 4)
+        .and_then(|version| version.try_into().ok())
+        .ok_or_else(|| {
+            StateSourceError::Decode(format!("state is only {} bytes long", bytes.len()))
+        })
+}
+
+/// Slot of an SSZ-encoded state, read without decoding it
+fn state_slot(bytes: &[u8]) -> Result<u64, StateSourceError> {
+    let offset = GnosisBeaconState::SLOT_OFFSET;
+    bytes
+        .get(offset..offset + 8)
+        .and_then(|slot| slot.try_into().ok())
+        .map(u64::from_le_bytes)
+        .ok_or_else(|| {
+            StateSourceError::Decode(format!("state is only {} bytes long", bytes.len()))
+        })
+}
+
+fn io_error(path: &Path) -> impl FnOnce(io::Error) -> StateSourceError + '_ {
+    move |source| StateSourceError::Io {
+        path: path.to_path_buf(),
+        source,
+    }
+}
+
+/// Decompress snappy data in either the framed or the raw block format
+fn decompress_snappy(path: &Path, compressed: &[u8]) -> Result<Vec<u8>, StateSourceError> {
+    let snappy_error = |message: String| StateSourceError::Snappy {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    if compressed.starts_with(SNAPPY_STREAM_IDENTIFIER) {
+        let mut decompressed = Vec::new();
+        snap::read::FrameDecoder::new(compressed)
+            .read_to_end(&mut decompressed)
+            .map_err(|e| snappy_error(e.to_string()))?;
+        Ok(decompressed)
+    } else {
+        snap::raw::Decoder::new()
+            .decompress_vec(compressed)
+            .map_err(|e| snappy_error(e.to_string()))
+    }
+}
+
+/// Find and decompress the `CompressedBeaconState` record of an era file
+fn read_era_state(path: &Path) -> Result<Vec<u8>, StateSourceError> {
+    let invalid = |message: String| StateSourceError::InvalidEra {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    let mut reader = BufReader::new(File::open(path).map_err(io_error(path))?);
+    let mut first = true;
+    loop {
+        let mut header = [0u8; E2STORE_HEADER_LEN];
+        match reader.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                return Err(invalid("no BeaconState record".to_string()));
+            }
+            Err(e) => return Err(io_error(path)(e)),
+        }
+
+        let kind = [header[0], header[1]];
+        let length = u32::from_le_bytes([header[2], header[3], header[4], header[5]]);
+        if header[6..] != [0, 0] {
+            return Err(invalid(format!(
+                "record type {kind:02x?} has non-zero reserved bytes"
+            )));
+        }
+        if first && kind != E2STORE_VERSION {
+            return Err(invalid("missing e2store version record".to_string()));
+        }
+        first = false;
+
+        if kind == ERA_COMPRESSED_STATE {
+            let mut compressed = Vec::new();
+            reader
+                .by_ref()
+                .take(u64::from(length))
+                .read_to_end(&mut compressed)
+                .map_err(io_error(path))?;
+            if compressed.len() != length as usize {
+                return Err(invalid("truncated BeaconState record".to_string()));
+            }
+            return decompress_snappy(path, &compressed);
+        }
+        reader
+            .seek_relative(i64::from(length))
+            .map_err(io_error(path))?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::beacon_state::{PendingConsolidation, Validator};
+    use ssz_rs::prelude::*;
+    use std::io::Write;
+
+    const SLOT: u64 = 8192;
+
+    const ELECTRA_VERSION: [u8; 4] = [0x05, 0x00, 0x00, 0x64];
+
+    fn sample_state() -> GnosisBeaconState {
+        let mut state = GnosisBeaconState {
+            slot: SLOT,
+            ..Default::default()
+        };
+        state.fork.current_version = ELECTRA_VERSION;
+        for i in 0..3u8 {
+            let mut validator = Validator {
+                activation_epoch: u64::from(i),
+                ..Default::default()
+            };
+            validator.withdrawal_credentials[0] = 0x01;
+            state.validators.push(validator);
+            state.balances.push(32_000_000_000);
+        }
+        state.pending_consolidations.push(PendingConsolidation {
+            source_index: 2,
+            target_index: 0,
+        });
+        state
+    }
+
+    fn framed(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = snap::write::FrameEncoder::new(Vec::new());
+        encoder.write_all(bytes).unwrap();
+        encoder.into_inner().unwrap()
+    }
+
+    fn e2store_record(kind: [u8; 2], data: &[u8]) -> Vec<u8> {
+        let mut record = kind.to_vec();
+        record.extend_from_slice(&u32::try_from(data.len()).unwrap().to_le_bytes());
+        record.extend_from_slice(&[0, 0]);
+        record.extend_from_slice(data);
+        record
+    }
+
+    fn era_file(state_ssz: &[u8]) -> Vec<u8> {
+        let mut era = e2store_record(E2STORE_VERSION, &[]);
+        // A compressed block precedes the state in real era files
+        era.extend(e2store_record([0x01, 0x00], &framed(b"block")));
+        era.extend(e2store_record(ERA_COMPRESSED_STATE, &framed(state_ssz)));
+        era.extend(e2store_record([0x69, 0x32], &[0; 24]));
+        era
+    }
+
+    #[tokio::test]
+    async fn test_every_file_source_yields_the_same_state() {
+        let state = sample_state();
+        let encoded = ssz_rs::serialize(&state).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+
+        let files = [
+            ("state.ssz", encoded.clone()),
+            ("framed.ssz_snappy", framed(&encoded)),
+            (
+                "raw.ssz_snappy",
+                snap::raw::Encoder::new().compress_vec(&encoded).unwrap(),
+            ),
+            ("gnosis-00001.era", era_file(&encoded)),
+        ];
+
+        let expected_root: [u8; 32] = state.hash_tree_root().unwrap().into();
+        for (name, contents) in files {
+            let path = dir.path().join(name);
+            std::fs::write(&path, contents).unwrap();
+            let source = StateSource::from_path(&path).unwrap();
+
+            let bytes = source.load(&SLOT.to_string()).await.unwrap();
+            assert_eq!(&*bytes, encoded.as_slice(), "{name}");
+            assert_eq!(
+                source.load_state("head").await.unwrap(),
+                VersionedGnosisState::Electra(Box::new(state.clone())),
+                "{name}"
+            );
+            let prover = source.load_prover("finalized").await.unwrap();
+            assert_eq!(prover.compute_state_root(), expected_root, "{name}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_beacon_source_downloads_state() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let encoded = ssz_rs::serialize(&sample_state()).unwrap();
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/eth/v2/debug/beacon/states/{SLOT}")))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(encoded.clone()))
+            .mount(&server)
+            .await;
+
+        let source =
+            StateSource::beacon(BeaconClient::new(server.uri()), DownloadOptions::default());
+        let bytes = source.load(&SLOT.to_string()).await.unwrap();
+        assert_eq!(&*bytes, encoded.as_slice());
+    }
+
+    #[tokio::test]
+    async fn test_file_slot_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.ssz");
+        std::fs::write(&path, ssz_rs::serialize(&sample_state()).unwrap()).unwrap();
+
+        let result = StateSource::SszFile(path).load("100").await;
+        assert!(matches!(
+            result,
+            Err(StateSourceError::SlotMismatch {
+                requested: 100,
+                actual: SLOT
+            })
+        ));
+    }
+
+    #[test]
+    fn test_from_path_rejects_unknown_extensions() {
+        assert!(matches!(
+            StateSource::from_path("state.json"),
+            Err(StateSourceError::UnsupportedFile(_))
+        ));
+        assert!(matches!(
+            StateSource::from_path("dump/state.ssz"),
+            Ok(StateSource::SszFile(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_era_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let cases = [
+            ("no-version.era", e2store_record(ERA_COMPRESSED_STATE, &[])),
+            ("no-state.era", e2store_record(E2STORE_VERSION, &[])),
+            ("truncated.era", {
+                let mut era = e2store_record(E2STORE_VERSION, &[]);
+                let mut record = e2store_record(ERA_COMPRESSED_STATE, &[1, 2, 3, 4]);
+                record.truncate(record.len() - 2);
+                era.extend(record);
+                era
+            }),
+        ];
+
+        for (name, contents) in cases {
+            let path = dir.path().join(name);
+            std::fs::write(&path, contents).unwrap();
+            let result = StateSource::EraFile(path).load("head").await;
+            assert!(
+                matches!(result, Err(StateSourceError::InvalidEra { .. })),
+                "{name}: {result:?}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_short_state_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("short.ssz");
+        std::fs::write(&path, [0u8; 16]).unwrap();
+
+        let result = StateSource::SszFile(path).load("head").await;
+        assert!(matches!(result, Err(StateSourceError::Decode(_))));
+    }
+
+    #[test]
+    fn test_parse_state_picks_layout_from_fork_version() {
+        let mut fulu = GnosisFuluBeaconState {
+            slot: SLOT,
+            ..Default::default()
+        };
+        fulu.fork.previous_version = ELECTRA_VERSION;
+        fulu.fork.current_version = [0x06, 0x00, 0x00, 0x64];
+        fulu.proposer_lookahead[31] = 7;
+        let parsed = parse_state(&ssz_rs::serialize(&fulu).unwrap()).unwrap();
+        assert_eq!(parsed.fork(), ForkName::Fulu);
+        assert_eq!(parsed, VersionedGnosisState::Fulu(Box::new(fulu)));
+
+        let parsed = parse_state(&ssz_rs::serialize(&sample_state()).unwrap()).unwrap();
+        assert_eq!(parsed.fork(), ForkName::Electra);
+        assert_eq!(parsed.validators().len(), 3);
+    }
+
+    #[test]
+    fn test_parse_state_rejects_unsupported_forks() {
+        let mut state = sample_state();
+        state.fork.current_version = [0x04, 0x00, 0x00, 0x64];
+        assert!(matches!(
+            parse_state(&ssz_rs::serialize(&state).unwrap()),
+            Err(StateSourceError::Fork(ForkError::Unsupported(
+                ForkName::Deneb
+            )))
+        ));
+
+        state.fork.current_version = [0x05, 0x00, 0x00, 0x01];
+        assert!(matches!(
+            parse_state(&ssz_rs::serialize(&state).unwrap()),
+            Err(StateSourceError::Fork(ForkError::UnknownVersion(_)))
+        ));
+    }
+}
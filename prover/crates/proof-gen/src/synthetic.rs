@@ -0,0 +1,553 @@
+//! Synthetic Gnosis States
+//!
+//! Generates Electra [`GnosisBeaconState`]s at Gnosis scale (hundreds of
+//! thousands of validators, long pending consolidation queues) together with
+//! the header of the block that produced them, for benchmarks and end-to-end
+//! tests of the state parser and prover.
+//!
+//! A state is a pure function of its [`SyntheticStateConfig`], seed included,
+//! so tests can regenerate it instead of checking in a multi-hundred-megabyte
+//! file. All fields are consistent with the validator set:
+//!
+//! - `balances`, epoch participation and `inactivity_scores` have one entry
+//!   per validator, and both sync committees are drawn from the registry
+//! - Withdrawal credentials mix BLS (`0x00`), execution (`0x01`) and
+//!   compounding (`0x02`) prefixes; activation epochs are spread around
+//!   `max_epoch` but never after the state's epoch
+//! - `historical_summaries` has one entry per period completed since Capella
+//! - Pending consolidations follow the Electra rules: distinct sources with
+//!   execution credentials, active for `SHARD_COMMITTEE_PERIOD` and exiting;
+//!   active compounding targets that are not sources themselves
+//!
+//! Pubkeys and signatures are random bytes rather than BLS points.
+
+use crate::beacon_block::ExecutionPayloadHeader;
+use crate::beacon_state::{
+    BeaconBlockHeader, Checkpoint, Eth1Data, Fork, GnosisBeaconState, HistoricalSummary,
+    PendingConsolidation, SyncCommittee, Validator,
+};
+use crate::fork::{ForkName, ForkRegistry, FAR_FUTURE_EPOCH};
+use crate::historical::summary_index;
+use crate::types::preset;
+use ssz_rs::prelude::*;
+use thiserror::Error;
+
+/// Genesis time of Gnosis Chain
+pub const GNOSIS_GENESIS_TIME: u64 = 1_638_993_340;
+
+/// Genesis validators root of Gnosis Chain
+pub const GNOSIS_GENESIS_VALIDATORS_ROOT: [u8; 32] = [
+    0xf5, 0xdc, 0xb5, 0x56, 0x4e, 0x82, 0x9a, 0xab, 0x27, 0x26, 0x4b, 0x9b, 0xec, 0xd5, 0xdf, 0xaa,
+    0x01, 0x70, 0x85, 0x61, 0x12, 0x24, 0xcb, 0x30, 0x36, 0xf5, 0x73, 0x36, 0x8d, 0xbb, 0x9d, 0x47,
+];
+
+/// First Electra slot on Gnosis: epoch 1337856 * 16
+pub const GNOSIS_ELECTRA_SLOT: u64 = 21_405_696;
+
+/// Balance of a full, non-compounding Gnosis validator (1 GNO) in gwei
+const MIN_ACTIVATION_BALANCE: u64 = 32_000_000_000;
+/// Largest effective balance of a compounding Gnosis validator (64 GNO)
+const MAX_EFFECTIVE_BALANCE_ELECTRA: u64 = 2_048_000_000_000;
+/// Epochs a validator must be active before it can consolidate
+const SHARD_COMMITTEE_PERIOD: u64 = 256;
+/// Epochs between exit and withdrawability
+const MIN_VALIDATOR_WITHDRAWABILITY_DELAY: u64 = 256;
+/// Delay of an exit initiated now: `1 + MAX_SEED_LOOKAHEAD`
+const EXIT_DELAY: u64 = 5;
+/// Consolidations sharing an exit epoch, standing in for the churn limit
+const CONSOLIDATIONS_PER_EPOCH: u64 = 8;
+/// Slots per `historical_summaries` entry
+const SLOTS_PER_HISTORICAL_ROOT: u64 = 8192;
+/// `deposit_requests_start_index` before the first deposit request
+const UNSET_DEPOSIT_REQUESTS_START_INDEX: u64 = u64::MAX;
+
+/// Errors from generating a synthetic state
+#[derive(Debug, Error)]
+pub enum SyntheticStateError {
+    #[error("Invalid synthetic state config: {0}")]
+    InvalidConfig(String),
+
+    #[error("Only {available} validators can be consolidation {role}s, {needed} needed")]
+    NotEnoughValidators {
+        role: &'static str,
+        needed: usize,
+        available: usize,
+    },
+
+    #[error("Merkleization error: {0}")]
+    MerkleizationError(#[from] MerkleizationError),
+}
+
+/// Shape of a synthetic state
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyntheticStateConfig {
+    /// PRNG seed; equal configs generate byte-identical states
+    pub seed: u64,
+    /// Number of validators
+    pub validators: usize,
+    /// Number of pending consolidations
+    pub pending_consolidations: usize,
+    /// Slot of the state and its header, at or after Electra
+    pub slot: u64,
+    /// The contract's eligibility cutoff that activation epochs are spread around
+    pub max_epoch: u64,
+    /// Activation epochs are drawn from `max_epoch ± epoch_spread`
+    pub epoch_spread: u64,
+    /// Relative weights of `0x00`, `0x01` and `0x02` withdrawal credentials
+    pub credential_weights: [u32; 3],
+}
+
+impl Default for SyntheticStateConfig {
+    /// Roughly the size of Gnosis mainnet at the Electra fork
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            validators: 500_000,
+            pending_consolidations: 1_000,
+            slot: GNOSIS_ELECTRA_SLOT,
+            max_epoch: 1_300_000,
+            epoch_spread: 50_000,
+            credential_weights: [20, 60, 20],
+        }
+    }
+}
+
+/// A generated state and the header of the block it is the post-state of
+#[derive(Debug, Clone)]
+pub struct SyntheticState {
+    pub state: GnosisBeaconState,
+    /// `state.latest_block_header` with the state root filled in
+    pub header: BeaconBlockHeader,
+    /// `hash_tree_root(header)`, as returned by the EIP-4788 oracle
+    pub block_root: [u8; 32],
+}
+
+impl SyntheticState {
+    /// Beacon timestamp of the header's slot, the EIP-4788 lookup key
+    pub fn timestamp(&self) -> u64 {
+        self.state.genesis_time + self.header.slot * preset::SECONDS_PER_SLOT
+    }
+}
+
+impl SyntheticStateConfig {
+    /// Generate the state and its header
+    ///
+    /// # Errors
+    /// Returns error if the config is inconsistent (no validators, a slot
+    /// before Electra, too many consolidations) or the validator set has too
+    /// few eligible consolidation sources or targets
+    pub fn generate(&self) -> Result<SyntheticState, SyntheticStateError> {
+        self.validate()?;
+        let mut rng = SplitMix64::new(self.seed);
+        let epoch = self.slot / preset::SLOTS_PER_EPOCH;
+
+        let registry = ForkRegistry::gnosis();
+        let version = |name| registry.by_name(name).map(|fork| fork.version);
+        let mut state = GnosisBeaconState {
+            genesis_time: GNOSIS_GENESIS_TIME,
+            genesis_validators_root: GNOSIS_GENESIS_VALIDATORS_ROOT,
+            slot: self.slot,
+            fork: Fork {
+                previous_version: version(ForkName::Deneb).unwrap_or_default(),
+                current_version: version(ForkName::Electra).unwrap_or_default(),
+                epoch: registry
+                    .by_name(ForkName::Electra)
+                    .map_or(0, |fork| fork.epoch),
+            },
+            eth1_deposit_index: self.validators as u64,
+            next_withdrawal_index: rng.next_u64() >> 24,
+            next_withdrawal_validator_index: rng.below(self.validators as u64),
+            deposit_requests_start_index: UNSET_DEPOSIT_REQUESTS_START_INDEX,
+            earliest_exit_epoch: epoch + EXIT_DELAY,
+            earliest_consolidation_epoch: epoch + EXIT_DELAY,
+            ..GnosisBeaconState::default()
+        };
+
+        for root in state.block_roots.iter_mut() {
+            *root = rng.bytes32();
+        }
+        for root in state.state_roots.iter_mut() {
+            *root = rng.bytes32();
+        }
+        for mix in state.randao_mixes.iter_mut() {
+            *mix = rng.bytes32();
+        }
+        // Gnosis started `historical_summaries` at Capella, not genesis
+        for _ in 0..summary_index(&registry, self.slot).unwrap_or(0) {
+            state.historical_summaries.push(HistoricalSummary {
+                block_summary_root: rng.bytes32(),
+                state_summary_root: rng.bytes32(),
+            });
+        }
+        state.eth1_data = Eth1Data {
+            deposit_root: rng.bytes32(),
+            deposit_count: self.validators as u64,
+            block_hash: rng.bytes32(),
+        };
+        state.previous_justified_checkpoint = checkpoint(&mut rng, epoch.saturating_sub(2));
+        state.current_justified_checkpoint = checkpoint(&mut rng, epoch.saturating_sub(1));
+        state.finalized_checkpoint = checkpoint(&mut rng, epoch.saturating_sub(2));
+        state.latest_execution_payload_header = ExecutionPayloadHeader {
+            parent_hash: rng.bytes32(),
+            state_root: rng.bytes32(),
+            receipts_root: rng.bytes32(),
+            prev_randao: rng.bytes32(),
+            block_number: self.slot,
+            gas_limit: 17_000_000,
+            timestamp: GNOSIS_GENESIS_TIME + self.slot * preset::SECONDS_PER_SLOT,
+            block_hash: rng.bytes32(),
+            transactions_root: rng.bytes32(),
+            withdrawals_root: rng.bytes32(),
+            ..ExecutionPayloadHeader::default()
+        };
+
+        let lowest_epoch = self.max_epoch.saturating_sub(self.epoch_spread);
+        let highest_epoch = self.max_epoch.saturating_add(self.epoch_spread).min(epoch);
+        for _ in 0..self.validators {
+            let validator = self.validator(&mut rng, lowest_epoch, highest_epoch);
+            state
+                .balances
+                .push(validator.effective_balance + rng.below(1_000_000_000));
+            state.previous_epoch_participation.push(rng.below(8) as u8);
+            state.current_epoch_participation.push(rng.below(8) as u8);
+            state.inactivity_scores.push(0);
+            state.validators.push(validator);
+        }
+
+        state.current_sync_committee = sync_committee(&mut rng, &state.validators);
+        state.next_sync_committee = sync_committee(&mut rng, &state.validators);
+
+        self.queue_consolidations(&mut rng, &mut state, epoch)?;
+
+        state.latest_block_header = BeaconBlockHeader {
+            slot: self.slot,
+            proposer_index: rng.below(self.validators as u64),
+            parent_root: state.block_roots
+                [(self.slot.saturating_sub(1) % SLOTS_PER_HISTORICAL_ROOT) as usize],
+            state_root: [0u8; 32],
+            body_root: rng.bytes32(),
+        };
+        let header = BeaconBlockHeader {
+            state_root: state.hash_tree_root()?.into(),
+            ..state.latest_block_header.clone()
+        };
+        let block_root = header.hash_tree_root()?.into();
+
+        Ok(SyntheticState {
+            state,
+            header,
+            block_root,
+        })
+    }
+
+    fn validate(&self) -> Result<(), SyntheticStateError> {
+        let invalid = |message: String| Err(SyntheticStateError::InvalidConfig(message));
+        let electra_epoch = GNOSIS_ELECTRA_SLOT / preset::SLOTS_PER_EPOCH;
+        let epoch = self.slot / preset::SLOTS_PER_EPOCH;
+        if self.validators == 0 {
+            return invalid("at least one validator is required".into());
+        }
+        if epoch < electra_epoch {
+            return invalid(format!("slot {} is before Electra", self.slot));
+        }
+        if self.pending_consolidations > preset::PENDING_CONSOLIDATIONS_LIMIT {
+            return invalid(format!(
+                "{} pending consolidations exceed the limit of {}",
+                self.pending_consolidations,
+                preset::PENDING_CONSOLIDATIONS_LIMIT
+            ));
+        }
+        if self.max_epoch.saturating_sub(self.epoch_spread) > epoch {
+            return invalid(format!(
+                "activation epochs from {} are after the state's epoch {epoch}",
+                self.max_epoch.saturating_sub(self.epoch_spread)
+            ));
+        }
+        if self.credential_weights.iter().all(|weight| *weight == 0) {
+            return invalid("credential weights are all zero".into());
+        }
+        Ok(())
+    }
+
+    fn validator(&self, rng: &mut SplitMix64, lowest_epoch: u64, highest_epoch: u64) -> Validator {
+        let mut validator = Validator::default();
+        rng.fill(&mut validator.pubkey);
+
+        let prefix = rng.weighted(&self.credential_weights) as u8;
+        validator.withdrawal_credentials = rng.bytes32();
+        validator.withdrawal_credentials[0] = prefix;
+        if prefix != 0x00 {
+            validator.withdrawal_credentials[1..12].fill(0);
+        }
+        validator.effective_balance = if prefix == 0x02 {
+            (MIN_ACTIVATION_BALANCE * (1 + rng.below(64))).min(MAX_EFFECTIVE_BALANCE_ELECTRA)
+        } else {
+            MIN_ACTIVATION_BALANCE
+        };
+
+        validator.activation_epoch = lowest_epoch + rng.below(highest_epoch - lowest_epoch + 1);
+        validator.activation_eligibility_epoch =
+            validator.activation_epoch.saturating_sub(1 + rng.below(16));
+        validator.exit_epoch = FAR_FUTURE_EPOCH;
+        validator.withdrawable_epoch = FAR_FUTURE_EPOCH;
+        validator
+    }
+
+    /// Pick consolidation sources and targets and start the sources' exits
+    fn queue_consolidations(
+        &self,
+        rng: &mut SplitMix64,
+        state: &mut GnosisBeaconState,
+        epoch: u64,
+    ) -> Result<(), SyntheticStateError> {
+        if self.pending_consolidations == 0 {
+            return Ok(());
+        }
+
+        let mut sources: Vec<usize> = (0..state.validators.len())
+            .filter(|&i| {
+                let validator = &state.validators[i];
+                validator.withdrawal_credentials[0] != 0x00
+                    && validator.activation_epoch + SHARD_COMMITTEE_PERIOD <= epoch
+            })
+            .collect();
+        if sources.len() < self.pending_consolidations {
+            return Err(SyntheticStateError::NotEnoughValidators {
+                role: "source",
+                needed: self.pending_consolidations,
+                available: sources.len(),
+            });
+        }
+        // Partial Fisher-Yates: the first `pending_consolidations` are distinct
+        for i in 0..self.pending_consolidations {
+            let j = i + rng.below((sources.len() - i) as u64) as usize;
+            sources.swap(i, j);
+        }
+        sources.truncate(self.pending_consolidations);
+
+        let mut is_source = vec![false; state.validators.len()];
+        for &source in &sources {
+            is_source[source] = true;
+        }
+        let targets: Vec<usize> = (0..state.validators.len())
+            .filter(|&i| !is_source[i] && state.validators[i].withdrawal_credentials[0] == 0x02)
+            .collect();
+        if targets.is_empty() {
+            return Err(SyntheticStateError::NotEnoughValidators {
+                role: "target",
+                needed: 1,
+                available: 0,
+            });
+        }
+
+        for (i, &source) in sources.iter().enumerate() {
+            let exit_epoch = epoch + EXIT_DELAY + i as u64 / CONSOLIDATIONS_PER_EPOCH;
+            let validator = &mut state.validators[source];
+            validator.exit_epoch = exit_epoch;
+            validator.withdrawable_epoch = exit_epoch + MIN_VALIDATOR_WITHDRAWABILITY_DELAY;
+            state.earliest_consolidation_epoch = exit_epoch;
+
+            let target = targets[rng.below(targets.len() as u64) as usize];
+            state.pending_consolidations.push(PendingConsolidation {
+                source_index: source as u64,
+                target_index: target as u64,
+            });
+        }
+        Ok(())
+    }
+}
+
+fn checkpoint(rng: &mut SplitMix64, epoch: u64) -> Checkpoint {
+    Checkpoint {
+        epoch,
+        root: rng.bytes32(),
+    }
+}
+
+fn sync_committee(rng: &mut SplitMix64, validators: &[Validator]) -> SyncCommittee {
+    let mut committee = SyncCommittee::default();
+    for pubkey in committee.pubkeys.iter_mut() {
+        let member = rng.below(validators.len() as u64) as usize;
+        *pubkey = validators[member].pubkey.clone();
+    }
+    rng.fill(&mut committee.aggregate_pubkey);
+    committee
+}
+
+/// SplitMix64, a small seedable PRNG with a stable output sequence
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..bound`; `bound` must be non-zero
+    fn below(&mut self, bound: u64) -> u64 {
+        ((u128::from(self.next_u64()) * u128::from(bound)) >> 64) as u64
+    }
+
+    /// Index drawn with probability proportional to its weight
+    fn weighted(&mut self, weights: &[u32]) -> usize {
+        let total: u64 = weights.iter().map(|w| u64::from(*w)).sum();
+        let mut pick = self.below(total);
+        for (i, weight) in weights.iter().enumerate() {
+            if pick < u64::from(*weight) {
+                return i;
+            }
+            pick -= u64::from(*weight);
+        }
+        weights.len() - 1
+    }
+
+    fn fill(&mut self, bytes: &mut [u8]) {
+        for chunk in bytes.chunks_mut(8) {
+            chunk.copy_from_slice(&self.next_u64().to_le_bytes()[..chunk.len()]);
+        }
+    }
+
+    fn bytes32(&mut self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        self.fill(&mut bytes);
+        bytes
+    }
+}
+
+// Gnosis constants throughout: fork versions, 5-second slots, the Capella slot
+#[cfg(all(test, feature = "gnosis", not(feature = "minimal")))]
+mod tests {
+    use super::*;
+    use crate::state_prover::StateProver;
+    use crate::state_source::parse_state;
+    use crate::ProofGenerator;
+
+    fn small_config(seed: u64) -> SyntheticStateConfig {
+        SyntheticStateConfig {
+            seed,
+            validators: 2_000,
+            pending_consolidations: 50,
+            ..SyntheticStateConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_same_seed_same_state() {
+        let a = small_config(7).generate().unwrap();
+        let b = small_config(7).generate().unwrap();
+        assert_eq!(a.state, b.state);
+        assert_eq!(a.block_root, b.block_root);
+
+        let c = small_config(8).generate().unwrap();
+        assert_ne!(a.block_root, c.block_root);
+    }
+
+    #[test]
+    fn test_state_follows_config() {
+        let config = small_config(1);
+        let synthetic = config.generate().unwrap();
+        let state = &synthetic.state;
+        let epoch = config.slot / preset::SLOTS_PER_EPOCH;
+
+        assert_eq!(state.validators.len(), config.validators);
+        assert_eq!(state.balances.len(), config.validators);
+        assert_eq!(state.inactivity_scores.len(), config.validators);
+        assert_eq!(
+            state.pending_consolidations.len(),
+            config.pending_consolidations
+        );
+        assert_eq!(state.fork.current_version, [0x05, 0x00, 0x00, 0x64]);
+        // Periods from Capella (slot 10_379_264) to Electra
+        assert_eq!(state.historical_summaries.len(), 1346);
+
+        for prefix in [0x00, 0x01, 0x02] {
+            assert!(state
+                .validators
+                .iter()
+                .any(|v| v.withdrawal_credentials[0] == prefix));
+        }
+        let activations = state.validators.iter().map(|v| v.activation_epoch);
+        assert!(activations.clone().any(|e| e <= config.max_epoch));
+        assert!(activations.clone().any(|e| e > config.max_epoch));
+        assert!(activations.clone().all(|e| e <= epoch));
+
+        let mut sources = std::collections::HashSet::new();
+        for consolidation in state.pending_consolidations.iter() {
+            let source = &state.validators[consolidation.source_index as usize];
+            let target = &state.validators[consolidation.target_index as usize];
+            assert!(sources.insert(consolidation.source_index));
+            assert_ne!(source.withdrawal_credentials[0], 0x00);
+            assert!(source.exit_epoch > epoch && source.exit_epoch != FAR_FUTURE_EPOCH);
+            assert_eq!(target.withdrawal_credentials[0], 0x02);
+            assert_eq!(target.exit_epoch, FAR_FUTURE_EPOCH);
+        }
+    }
+
+    #[test]
+    fn test_header_matches_state() {
+        let synthetic = small_config(2).generate().unwrap();
+        let state_root: [u8; 32] = synthetic.state.hash_tree_root().unwrap().into();
+        assert_eq!(synthetic.header.state_root, state_root);
+        assert_eq!(
+            synthetic.block_root,
+            <[u8; 32]>::from(synthetic.header.hash_tree_root().unwrap())
+        );
+        assert_eq!(
+            synthetic.timestamp(),
+            GNOSIS_GENESIS_TIME + GNOSIS_ELECTRA_SLOT * 5
+        );
+    }
+
+    #[test]
+    fn test_ssz_parses_and_proves() {
+        let synthetic = small_config(3).generate().unwrap();
+        let bytes = ssz_rs::serialize(&synthetic.state).unwrap();
+        let parsed = parse_state(&bytes).unwrap();
+        assert_eq!(parsed.fork(), ForkName::Electra);
+
+        let prover = StateProver::from_state(&parsed).unwrap();
+        assert_eq!(prover.compute_state_root(), synthetic.header.state_root);
+        for index in [0, 49] {
+            let bundle = prover
+                .generate_full_proof_bundle(&synthetic.header, index, synthetic.timestamp())
+                .unwrap();
+            ProofGenerator::verify_proof_bundle(&bundle, synthetic.block_root).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_rejects_inconsistent_configs() {
+        let config = |f: fn(&mut SyntheticStateConfig)| {
+            let mut config = small_config(0);
+            f(&mut config);
+            config.generate()
+        };
+        assert!(matches!(
+            config(|c| c.slot = GNOSIS_ELECTRA_SLOT - 1),
+            Err(SyntheticStateError::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            config(|c| c.validators = 0),
+            Err(SyntheticStateError::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            config(|c| c.max_epoch = 2_000_000),
+            Err(SyntheticStateError::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            config(|c| c.credential_weights = [1, 1, 0]),
+            Err(SyntheticStateError::NotEnoughValidators { role: "target", .. })
+        ));
+        assert!(matches!(
+            config(|c| c.pending_consolidations = 1_999),
+            Err(SyntheticStateError::NotEnoughValidators { role: "source", .. })
+        ));
+    }
+}
@@ -0,0 +1,310 @@
+//! Synthetic Light-Client Data
+//!
+//! Bootstraps and `LightClientUpdate`s for a made-up Gnosis chain, signed by
+//! sync committees with known secret keys, for tests of [`LightClient`] and of
+//! services that verify headers with it against a mocked beacon node.
+//!
+//! Only the light-client fields of the attested states are set; every other
+//! state field is zero. Committee `i` has secret keys
+//! `i * 10_000 + 1 ..= i * 10_000 + 512` and is the current committee of
+//! period `i` after [`SyntheticLightClientChain::start`].
+
+use crate::beacon_block::SYNC_COMMITTEE_SIZE;
+use crate::fork::{ForkName, ForkRegistry};
+use crate::light_client::{
+    hash_to_g2, header_root, LightClient, LightClientBootstrap, LightClientConfig,
+    LightClientUpdate, SyncAggregate, SyncCommitteeKeys, EPOCHS_PER_SYNC_COMMITTEE_PERIOD,
+};
+use crate::sparse_proof::{hash_pair, prove_against_leaf_chunks};
+use crate::types::{preset, BeaconBlockHeader};
+use bls12_381::{G1Affine, G1Projective, G2Affine, Scalar};
+use serde_json::{json, Value};
+
+/// Spec gindices from the state root, Electra
+pub(crate) const CURRENT_SYNC_COMMITTEE_GINDEX: u64 = 86;
+pub(crate) const NEXT_SYNC_COMMITTEE_GINDEX: u64 = 87;
+pub(crate) const FINALIZED_ROOT_GINDEX: u64 = 169;
+
+/// Sync committee with secret keys `offset + 1 ..= offset + 512`
+pub struct SyntheticCommittee {
+    secret_keys: Vec<Scalar>,
+    /// Public keys of the members and their aggregate
+    pub keys: SyncCommitteeKeys,
+}
+
+impl SyntheticCommittee {
+    /// Committee with secret keys `offset + 1 ..= offset + 512`
+    #[must_use]
+    pub fn new(offset: u64) -> Self {
+        let secret_keys: Vec<Scalar> = (1..=SYNC_COMMITTEE_SIZE as u64)
+            .map(|i| Scalar::from(offset + i))
+            .collect();
+        // Consecutive secret keys: each public key is the previous plus the generator
+        let generator = G1Projective::generator();
+        let mut point = generator * Scalar::from(offset + 1);
+        let mut aggregate = G1Projective::identity();
+        let mut pubkeys = Vec::with_capacity(SYNC_COMMITTEE_SIZE);
+        for _ in 0..SYNC_COMMITTEE_SIZE {
+            pubkeys.push(G1Affine::from(point).to_compressed());
+            aggregate += point;
+            point += generator;
+        }
+        Self {
+            secret_keys,
+            keys: SyncCommitteeKeys {
+                pubkeys,
+                aggregate_pubkey: G1Affine::from(aggregate).to_compressed(),
+            },
+        }
+    }
+
+    /// Sign with the first `participants` members
+    #[must_use]
+    pub fn sign(&self, signing_root: [u8; 32], participants: usize) -> SyncAggregate {
+        let secret = self.secret_keys[..participants]
+            .iter()
+            .fold(Scalar::zero(), |sum, key| sum + key);
+        let mut bits = [0u8; SYNC_COMMITTEE_SIZE / 8];
+        for i in 0..participants {
+            bits[i / 8] |= 1 << (i % 8);
+        }
+        SyncAggregate {
+            sync_committee_bits: bits,
+            sync_committee_signature: G2Affine::from(hash_to_g2(&signing_root) * secret)
+                .to_compressed(),
+        }
+    }
+}
+
+/// The 64 top-level fields of an Electra state; only the light-client fields
+/// are set
+struct SyntheticState {
+    fields: Vec<[u8; 32]>,
+}
+
+impl SyntheticState {
+    fn new(
+        current: &SyncCommitteeKeys,
+        next: &SyncCommitteeKeys,
+        finalized_root: [u8; 32],
+    ) -> Self {
+        let mut fields = vec![[0u8; 32]; 64];
+        // finalized_checkpoint: (epoch, root), epoch left at zero
+        fields[(FINALIZED_ROOT_GINDEX / 2 - 64) as usize] = hash_pair(&[0u8; 32], &finalized_root);
+        fields[(CURRENT_SYNC_COMMITTEE_GINDEX - 64) as usize] = current.hash_tree_root();
+        fields[(NEXT_SYNC_COMMITTEE_GINDEX - 64) as usize] = next.hash_tree_root();
+        Self { fields }
+    }
+
+    fn root(&self) -> [u8; 32] {
+        prove_against_leaf_chunks(&self.fields, 0, 6).1
+    }
+
+    fn branch(&self, gindex: u64) -> Vec<[u8; 32]> {
+        if gindex == FINALIZED_ROOT_GINDEX {
+            let mut branch = vec![[0u8; 32]];
+            branch.extend(prove_against_leaf_chunks(&self.fields, (gindex / 2 - 64) as usize, 6).0);
+            branch
+        } else {
+            prove_against_leaf_chunks(&self.fields, (gindex - 64) as usize, 6).0
+        }
+    }
+}
+
+/// Header at `slot` with fixed parent and body roots
+#[must_use]
+pub fn synthetic_header(slot: u64, state_root: [u8; 32]) -> BeaconBlockHeader {
+    BeaconBlockHeader {
+        slot,
+        proposer_index: slot % 97,
+        parent_root: [0x01; 32],
+        state_root,
+        body_root: [0x02; 32],
+    }
+}
+
+/// First slot of the first full sync committee period after Electra
+fn start_slot() -> u64 {
+    let electra = ForkRegistry::gnosis()
+        .by_name(ForkName::Electra)
+        .expect("Gnosis schedules Electra")
+        .epoch;
+    (electra / EPOCHS_PER_SYNC_COMMITTEE_PERIOD + 1)
+        * EPOCHS_PER_SYNC_COMMITTEE_PERIOD
+        * preset::SLOTS_PER_EPOCH
+}
+
+fn period_slots() -> u64 {
+    EPOCHS_PER_SYNC_COMMITTEE_PERIOD * preset::SLOTS_PER_EPOCH
+}
+
+/// Three sync committee periods of a synthetic chain
+pub struct SyntheticLightClientChain {
+    /// Gnosis light-client config
+    pub config: LightClientConfig,
+    /// Committee `i` is current in period `i`
+    pub committees: Vec<SyntheticCommittee>,
+    /// First slot of period 0
+    pub start: u64,
+}
+
+impl Default for SyntheticLightClientChain {
+    fn default() -> Self {
+        Self {
+            config: LightClientConfig::gnosis(),
+            committees: (0..3)
+                .map(|i| SyntheticCommittee::new(i * 10_000))
+                .collect(),
+            start: start_slot(),
+        }
+    }
+}
+
+impl SyntheticLightClientChain {
+    /// Chain starting at the first full sync committee period after Electra
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bootstrap at `start + 16` with committee 0 current, and its block root
+    ///
+    /// # Panics
+    /// Panics if the header cannot be merkleized
+    #[must_use]
+    pub fn bootstrap(&self) -> ([u8; 32], LightClientBootstrap) {
+        let state = SyntheticState::new(
+            &self.committees[0].keys,
+            &self.committees[1].keys,
+            [0u8; 32],
+        );
+        let header = synthetic_header(self.start + 16, state.root());
+        let bootstrap = LightClientBootstrap {
+            header: header.clone(),
+            current_sync_committee: self.committees[0].keys.clone(),
+            current_sync_committee_branch: state.branch(CURRENT_SYNC_COMMITTEE_GINDEX),
+        };
+        (header_root(&header).unwrap(), bootstrap)
+    }
+
+    /// Update in `period` (relative to the start) signed by the first
+    /// `participants` members of committee `signer`, carrying committee
+    /// `period + 1` as the next one
+    ///
+    /// The finalized and attested headers are `finalized_offset` and
+    /// `attested_offset` slots into the period.
+    ///
+    /// # Panics
+    /// Panics if a header cannot be merkleized
+    #[must_use]
+    pub fn update(
+        &self,
+        period: u64,
+        finalized_offset: u64,
+        attested_offset: u64,
+        signer: usize,
+        participants: usize,
+    ) -> LightClientUpdate {
+        let period_start = self.start + period * period_slots();
+        let finalized = synthetic_header(period_start + finalized_offset, [0x0f; 32]);
+        let next = &self.committees[(period + 1) as usize % self.committees.len()].keys;
+        let state = SyntheticState::new(
+            &self.committees[period as usize].keys,
+            next,
+            header_root(&finalized).unwrap(),
+        );
+        let attested = synthetic_header(period_start + attested_offset, state.root());
+        let signature_slot = attested.slot + 1;
+        let signing_root = self
+            .config
+            .signing_root(header_root(&attested).unwrap(), signature_slot)
+            .unwrap();
+        LightClientUpdate {
+            attested_header: attested,
+            next_sync_committee: Some(next.clone()),
+            next_sync_committee_branch: state.branch(NEXT_SYNC_COMMITTEE_GINDEX),
+            finalized_header: Some(finalized),
+            finality_branch: state.branch(FINALIZED_ROOT_GINDEX),
+            sync_aggregate: self.committees[signer].sign(signing_root, participants),
+            signature_slot,
+        }
+    }
+
+    /// Light client bootstrapped from [`Self::bootstrap`]
+    ///
+    /// # Panics
+    /// Panics if the bootstrap does not verify
+    #[must_use]
+    pub fn light_client(&self) -> LightClient {
+        let (trusted, bootstrap) = self.bootstrap();
+        LightClient::bootstrap(self.config.clone(), trusted, &bootstrap).unwrap()
+    }
+}
+
+fn hex_json(bytes: &[u8]) -> Value {
+    Value::String(format!("0x{}", hex::encode(bytes)))
+}
+
+/// Beacon API JSON of a light-client header
+#[must_use]
+pub fn header_json(header: &BeaconBlockHeader) -> Value {
+    json!({
+        "beacon": {
+            "slot": header.slot.to_string(),
+            "proposer_index": header.proposer_index.to_string(),
+            "parent_root": hex_json(&header.parent_root),
+            "state_root": hex_json(&header.state_root),
+            "body_root": hex_json(&header.body_root),
+        },
+        "execution": {},
+        "execution_branch": []
+    })
+}
+
+fn committee_json(committee: &SyncCommitteeKeys) -> Value {
+    json!({
+        "pubkeys": committee.pubkeys.iter().map(|pubkey| hex_json(pubkey)).collect::<Vec<_>>(),
+        "aggregate_pubkey": hex_json(&committee.aggregate_pubkey),
+    })
+}
+
+fn branch_json(branch: &[[u8; 32]], depth: usize) -> Value {
+    let zeros = vec![[0u8; 32]; depth];
+    let branch = if branch.is_empty() { &zeros } else { branch };
+    branch.iter().map(|node| hex_json(node)).collect()
+}
+
+/// Beacon API JSON of an update, with zeroed absent fields
+#[must_use]
+pub fn update_json(update: &LightClientUpdate) -> Value {
+    let zero_committee = SyncCommitteeKeys {
+        pubkeys: vec![[0u8; 48]; SYNC_COMMITTEE_SIZE],
+        aggregate_pubkey: [0u8; 48],
+    };
+    json!({
+        "attested_header": header_json(&update.attested_header),
+        "next_sync_committee": committee_json(
+            update.next_sync_committee.as_ref().unwrap_or(&zero_committee)
+        ),
+        "next_sync_committee_branch": branch_json(&update.next_sync_committee_branch, 6),
+        "finalized_header": header_json(
+            update.finalized_header.as_ref().unwrap_or(&BeaconBlockHeader::default())
+        ),
+        "finality_branch": branch_json(&update.finality_branch, 7),
+        "sync_aggregate": {
+            "sync_committee_bits": hex_json(&update.sync_aggregate.sync_committee_bits),
+            "sync_committee_signature": hex_json(&update.sync_aggregate.sync_committee_signature),
+        },
+        "signature_slot": update.signature_slot.to_string(),
+    })
+}
+
+/// Beacon API JSON of a bootstrap
+#[must_use]
+pub fn bootstrap_json(bootstrap: &LightClientBootstrap) -> Value {
+    json!({
+        "header": header_json(&bootstrap.header),
+        "current_sync_committee": committee_json(&bootstrap.current_sync_committee),
+        "current_sync_committee_branch": branch_json(&bootstrap.current_sync_committee_branch, 6),
+    })
+}
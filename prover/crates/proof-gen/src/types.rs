@@ -107,6 +107,57 @@ pub struct ValidatorInfo {
     pub activation_epoch: u64,
 }
 
+/// Validator status as reported by the Beacon API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidatorStatus {
+    PendingInitialized,
+    PendingQueued,
+    ActiveOngoing,
+    ActiveExiting,
+    ActiveSlashed,
+    ExitedUnslashed,
+    ExitedSlashed,
+    WithdrawalPossible,
+    WithdrawalDone,
+}
+
+/// Full validator record from the Beacon API validators endpoints
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidatorRecord {
+    /// Validator index
+    pub index: u64,
+    /// Current balance in gwei
+    pub balance: u64,
+    /// Lifecycle status
+    pub status: ValidatorStatus,
+    /// BLS public key (48 bytes)
+    pub pubkey: [u8; 48],
+    /// Withdrawal credentials (32 bytes)
+    pub withdrawal_credentials: [u8; 32],
+    /// Effective balance in gwei
+    pub effective_balance: u64,
+    /// Whether the validator has been slashed
+    pub slashed: bool,
+    /// Epoch when validator became eligible for activation
+    pub activation_eligibility_epoch: u64,
+    /// Epoch when validator activated
+    pub activation_epoch: u64,
+    /// Epoch when validator exited
+    pub exit_epoch: u64,
+    /// Epoch when validator can withdraw
+    pub withdrawable_epoch: u64,
+}
+
+impl From<&ValidatorRecord> for ValidatorInfo {
+    fn from(record: &ValidatorRecord) -> Self {
+        Self {
+            withdrawal_credentials: record.withdrawal_credentials,
+            activation_epoch: record.activation_epoch,
+        }
+    }
+}
+
 /// Beacon block header
 #[derive(Debug, Clone, Default, PartialEq, Eq, SimpleSerialize)]
 pub struct BeaconBlockHeader {
@@ -0,0 +1,131 @@
+//! Light-Client Fixture Recorder
+//!
+//! Records real Gnosis light-client responses for the `proof-gen` light-client
+//! tests: a bootstrap early in the previous sync committee period, the updates
+//! of that period and the current one (so applying them rotates the sync
+//! committee once), and the latest finality update. Response bodies are
+//! written exactly as served.
+
+use anyhow::{bail, Context, Result};
+use clap::Parser;
+use proof_gen::beacon_client::BeaconClient;
+use proof_gen::light_client::{sync_committee_period, EPOCHS_PER_SYNC_COMMITTEE_PERIOD};
+use proof_gen::types::preset::SLOTS_PER_EPOCH;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Parser, Debug)]
+#[command(name = "record-light-client-fixtures")]
+#[command(about = "Record Gnosis light-client responses as proof-gen test fixtures")]
+struct Args {
+    /// Beacon API base URL of a node serving the light-client endpoints
+    #[arg(
+        long,
+        env = "GNOSIS_BEACON_URL",
+        default_value = "https://rpc.gnosischain.com/beacon"
+    )]
+    beacon_url: String,
+
+    /// Output directory
+    #[arg(long, default_value = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../proof-gen/tests/fixtures/light_client/gnosis"
+    ))]
+    out_dir: PathBuf,
+
+    /// Epochs into the period to search for a block with a bootstrap
+    #[arg(long, default_value_t = 64)]
+    max_epochs: u64,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let base_url = args.beacon_url.trim_end_matches('/');
+    let http = reqwest::Client::new();
+    let client = BeaconClient::new(base_url.to_string());
+
+    let finality_update = fetch(
+        &http,
+        base_url,
+        "eth/v1/beacon/light_client/finality_update",
+    )
+    .await
+    .context("Failed to fetch the finality update")?;
+    let finalized_slot: u64 = serde_json::from_str::<serde_json::Value>(&finality_update)?["data"]
+        ["finalized_header"]["beacon"]["slot"]
+        .as_str()
+        .context("Finality update has no finalized slot")?
+        .parse()?;
+    let period = sync_committee_period(finalized_slot)
+        .checked_sub(1)
+        .context("No sync committee period before the current one")?;
+    println!("Finalized slot {finalized_slot}; bootstrapping in period {period}");
+
+    let first_epoch = period * EPOCHS_PER_SYNC_COMMITTEE_PERIOD;
+    let mut bootstrap = None;
+    for epoch in first_epoch..first_epoch + args.max_epochs {
+        let Ok((root, header)) = client
+            .get_header_with_root(&(epoch * SLOTS_PER_EPOCH).to_string())
+            .await
+        else {
+            continue;
+        };
+        if header.slot != epoch * SLOTS_PER_EPOCH {
+            continue;
+        }
+        let root = format!("0x{}", hex::encode(root));
+        let path = format!("eth/v1/beacon/light_client/bootstrap/{root}");
+        if let Ok(body) = fetch(&http, base_url, &path).await {
+            println!("Bootstrap at slot {} (block {root})", header.slot);
+            bootstrap = Some((root, body));
+            break;
+        }
+    }
+    let Some((trusted_block_root, bootstrap)) = bootstrap else {
+        bail!(
+            "No bootstrap served in the first {} epochs of period {period}",
+            args.max_epochs
+        );
+    };
+
+    let updates = fetch(
+        &http,
+        base_url,
+        &format!("eth/v1/beacon/light_client/updates?start_period={period}&count=2"),
+    )
+    .await
+    .context("Failed to fetch light client updates")?;
+
+    fs::create_dir_all(&args.out_dir)?;
+    write(&args.out_dir, "bootstrap.json", &bootstrap)?;
+    write(&args.out_dir, "updates.json", &updates)?;
+    write(&args.out_dir, "finality_update.json", &finality_update)?;
+    let manifest = serde_json::json!({
+        "trusted_block_root": trusted_block_root,
+        "start_period": period,
+    });
+    write(
+        &args.out_dir,
+        "manifest.json",
+        &serde_json::to_string_pretty(&manifest)?,
+    )?;
+    Ok(())
+}
+
+async fn fetch(http: &reqwest::Client, base_url: &str, path: &str) -> Result<String> {
+    let response = http
+        .get(format!("{base_url}/{path}"))
+        .header(reqwest::header::ACCEPT, "application/json")
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(response.text().await?)
+}
+
+fn write(dir: &Path, name: &str, contents: &str) -> Result<()> {
+    let path = dir.join(name);
+    fs::write(&path, contents).with_context(|| format!("Failed to write {}", path.display()))?;
+    println!("Wrote {}", path.display());
+    Ok(())
+}
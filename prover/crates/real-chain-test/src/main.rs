@@ -1,9 +1,14 @@
 use anyhow::{Context, Result};
-use clap::{Parser, ValueEnum};
+use clap::Parser;
 use proof_gen::{
-    beacon_client::{BeaconClient, BeaconClientError},
+    beacon_client::BeaconClient,
+    scan::{build_scan_slots, fetch_pending_consolidations_at_or_before, ScanDirection},
+    state_download::DownloadOptions,
+    state_prover::StateProver,
+    state_source::{parse_state, StateSource},
     types::preset::{SECONDS_PER_SLOT, SLOTS_PER_EPOCH},
-    FinalityCheckpoints, PendingConsolidationJson, ValidatorInfo,
+    BeaconBlockHeader, BundleContext, FinalityCheckpoints, FullBeaconBlockHeader,
+    PendingConsolidationJson, ProofBundleV1, ValidatorInfo,
 };
 use ssz_rs::HashTreeRoot;
 use std::{
@@ -62,7 +67,7 @@ struct Args {
     scan_step_slots: u64,
 
     /// Historical scan direction. `reverse` is handy when you want the latest non-empty state first.
-    #[arg(long, value_enum, default_value_t = ScanDirection::Forward)]
+    #[arg(long, default_value_t = ScanDirection::Forward)]
     scan_direction: ScanDirection,
 
     /// Stop a historical scan after collecting this many non-empty states.
@@ -97,10 +102,20 @@ struct Args {
     #[arg(long)]
     watch_event_log_output: Option<PathBuf>,
 
+    /// Load the full state from a local `.ssz`, `.ssz_snappy` or `.era` file instead of the
+    /// debug endpoint. Its slot must match the resolved state slot.
+    #[arg(long)]
+    state_file: Option<PathBuf>,
+
     /// Maximum number of pending consolidations to inspect in detail
     #[arg(long, default_value_t = 25)]
     max_consolidations: usize,
 
+    /// Prove the inspected consolidations and write one `ProofBundleV1` JSON file
+    /// each into this directory. Needs the full state.
+    #[arg(long)]
+    bundle_dir: Option<PathBuf>,
+
     /// Where to write the JSON snapshot
     #[arg(long, default_value = "real_chain_snapshot.json")]
     output: PathBuf,
@@ -138,12 +153,6 @@ struct SnapshotMetadata {
     notes: Vec<String>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
-enum ScanDirection {
-    Forward,
-    Reverse,
-}
-
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
 struct ScanHit {
     requested_slot: u64,
@@ -226,18 +235,31 @@ async fn main() -> Result<()> {
         resolved_state_id
     );
 
-    let mut consolidations = Vec::new();
-    for (index, consolidation) in pending_consolidations
+    let selected: Vec<&PendingConsolidationJson> = pending_consolidations
         .iter()
         .take(args.max_consolidations)
-        .enumerate()
-    {
-        let validator = client
-            .get_validator_info(&resolved_state_id, consolidation.source_index)
+        .collect();
+    let source_indices: Vec<u64> = selected.iter().map(|c| c.source_index).collect();
+    let validators: BTreeMap<u64, ValidatorInfo> = if source_indices.is_empty() {
+        BTreeMap::new()
+    } else {
+        client
+            .get_validators(&resolved_state_id, &source_indices, &[])
             .await
+            .context("Failed to fetch source validators")?
+            .iter()
+            .map(|record| (record.index, ValidatorInfo::from(record)))
+            .collect()
+    };
+
+    let mut consolidations = Vec::with_capacity(selected.len());
+    for (index, consolidation) in selected.into_iter().enumerate() {
+        let validator = validators
+            .get(&consolidation.source_index)
+            .cloned()
             .with_context(|| {
                 format!(
-                    "Failed to fetch validator {} for consolidation index {}",
+                    "Validator {} for consolidation index {} not found in state",
                     consolidation.source_index, index
                 )
             })?;
@@ -266,9 +288,18 @@ async fn main() -> Result<()> {
     }
     println!();
 
-    println!("🌲 Attempting debug-state SSZ fetch...");
-    let (state_size_bytes, debug_state_available, mut notes) = match client
-        .get_state_ssz(&resolved_state_id)
+    let state_source = match &args.state_file {
+        Some(path) => {
+            println!("🌲 Loading state file {}...", path.display());
+            StateSource::from_path(path)?
+        }
+        None => {
+            println!("🌲 Attempting debug-state SSZ fetch...");
+            StateSource::beacon(client.clone(), DownloadOptions::default())
+        }
+    };
+    let (state_size_bytes, debug_state_available, mut notes) = match state_source
+        .load(&resolved_state_id)
         .await
     {
         Ok(state_ssz) => {
@@ -277,27 +308,43 @@ async fn main() -> Result<()> {
                 state_ssz.len(),
                 state_ssz.len() as f64 / 1_000_000.0
             );
-            (
-                Some(state_ssz.len()),
-                true,
-                vec![
-                    "Debug state endpoint is available for this beacon node; full proof generation can proceed here.".to_string(),
-                ],
-            )
+            let note = match &args.state_file {
+                    Some(path) => format!(
+                        "Full state loaded from {}; proof generation can proceed offline.",
+                        path.display()
+                    ),
+                    None => "Debug state endpoint is available for this beacon node; full proof generation can proceed here.".to_string(),
+                };
+            let mut notes = vec![note];
+            if let Some(dir) = &args.bundle_dir {
+                println!("🧮 Proving {} consolidations...", consolidations.len());
+                let written = write_proof_bundles(
+                    dir,
+                    &state_ssz,
+                    &header,
+                    beacon_timestamp,
+                    consolidations.len(),
+                )?;
+                println!("   Wrote {} bundles to {}\n", written.len(), dir.display());
+                notes.push(format!(
+                    "Wrote {} validated ProofBundleV1 files to {}.",
+                    written.len(),
+                    dir.display()
+                ));
+            }
+            (Some(state_ssz.len()), true, notes)
         }
         Err(error) => {
-            println!("   Debug state fetch unavailable: {error}\n");
+            println!("   Full state unavailable: {error}\n");
             (
-                None,
-                false,
-                vec![
-                    format!(
-                        "Debug state endpoint unavailable for state {resolved_state_id}: {error}"
-                    ),
-                    "Standard Electra endpoints still confirmed pending consolidations + validator metadata.".to_string(),
-                    "Full proof generation remains blocked until /eth/v2/debug/beacon/states/{state_id} is reachable.".to_string(),
-                ],
-            )
+                    None,
+                    false,
+                    vec![
+                        format!("Full state unavailable for state {resolved_state_id}: {error}"),
+                        "Standard Electra endpoints still confirmed pending consolidations + validator metadata.".to_string(),
+                        "Full proof generation needs /eth/v2/debug/beacon/states/{state_id} or a --state-file dump (.ssz, .ssz_snappy, .era).".to_string(),
+                    ],
+                )
         }
     };
 
@@ -362,7 +409,9 @@ async fn main() -> Result<()> {
         beacon_node: args.beacon_url.clone(),
         requested_state_id: args.state_id,
         resolved_state_id,
-        state_source: if debug_state_available {
+        state_source: if debug_state_available && args.state_file.is_some() {
+            "standard endpoints + state file".to_string()
+        } else if debug_state_available {
             "standard endpoints + debug SSZ".to_string()
         } else {
             "standard endpoints only".to_string()
@@ -964,6 +1013,43 @@ fn normalize_path_for_comparison(path: &Path) -> Result<PathBuf> {
     Ok(normalized)
 }
 
+/// Prove the first `count` pending consolidations of an SSZ state and write
+/// each as a validated `ProofBundleV1` JSON file in `dir`
+fn write_proof_bundles(
+    dir: &Path,
+    state_ssz: &[u8],
+    header: &BeaconBlockHeader,
+    beacon_timestamp: u64,
+    count: usize,
+) -> Result<Vec<PathBuf>> {
+    let prover = StateProver::from_state(&parse_state(state_ssz)?)?;
+    let header = FullBeaconBlockHeader {
+        slot: header.slot,
+        proposer_index: header.proposer_index,
+        parent_root: header.parent_root,
+        state_root: header.state_root,
+        body_root: header.body_root,
+    };
+    let block_root: [u8; 32] = header
+        .hash_tree_root()
+        .map_err(|e| anyhow::anyhow!("Failed to compute block root: {e:?}"))?
+        .into();
+    let context = BundleContext::gnosis(block_root, header.slot);
+
+    fs::create_dir_all(dir)?;
+    let mut written = Vec::with_capacity(count);
+    for index in 0..count {
+        let bundle = prover.generate_full_proof_bundle(&header, index, beacon_timestamp)?;
+        let file = ProofBundleV1::new(context, bundle);
+        file.validate()
+            .with_context(|| format!("Bundle for consolidation {index} failed validation"))?;
+        let path = dir.join(format!("consolidation_{index}.json"));
+        file.write_file(&path)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
 async fn resolve_slot(state_id: &str, finalized_slot: u64, client: &BeaconClient) -> Result<u64> {
     match state_id {
         "finalized" => Ok(finalized_slot),
@@ -1060,86 +1146,6 @@ fn validate_scan_hit_limit(scan_hit_limit: Option<usize>) -> Result<()> {
     Ok(())
 }
 
-async fn fetch_pending_consolidations_at_or_before(
-    client: &BeaconClient,
-    requested_slot: u64,
-    minimum_slot: u64,
-) -> Result<(u64, Vec<PendingConsolidationJson>)> {
-    let mut slot = requested_slot;
-
-    loop {
-        match client.get_pending_consolidations(&slot.to_string()).await {
-            Ok(pending_consolidations) => return Ok((slot, pending_consolidations)),
-            Err(BeaconClientError::StateNotFound(_)) => {
-                match client.get_header(&slot.to_string()).await {
-                    Ok(_) => {
-                        anyhow::bail!(
-                        "beacon header exists at slot {} but beacon state is unavailable; historical state lookups appear pruned on this node",
-                        slot
-                    );
-                    }
-                    Err(BeaconClientError::HeaderNotFound(_)) if slot > minimum_slot => {
-                        slot -= 1;
-                    }
-                    Err(BeaconClientError::HeaderNotFound(_)) => {
-                        anyhow::bail!(
-                            "no beacon state found at or before slot {} within lower bound {}",
-                            requested_slot,
-                            minimum_slot
-                        );
-                    }
-                    Err(error) => return Err(error.into()),
-                }
-            }
-            Err(error) => return Err(error.into()),
-        }
-    }
-}
-
-fn build_scan_slots(
-    scan_start_slot: u64,
-    scan_end_slot: u64,
-    scan_step_slots: u64,
-    scan_direction: ScanDirection,
-    requested_slot: u64,
-) -> Vec<u64> {
-    let mut slots = Vec::new();
-    let mut slot = scan_start_slot;
-    while slot <= scan_end_slot {
-        slots.push(slot);
-        match slot.checked_add(scan_step_slots) {
-            Some(next) if next > slot => slot = next,
-            _ => break,
-        }
-    }
-
-    if slots.last().copied() != Some(scan_end_slot) {
-        slots.push(scan_end_slot);
-    }
-
-    if !slots.contains(&requested_slot) {
-        slots.push(requested_slot);
-    }
-
-    slots.sort_unstable();
-    slots.dedup();
-
-    if scan_direction == ScanDirection::Reverse {
-        slots.reverse();
-    }
-
-    slots
-}
-
-impl ScanDirection {
-    fn as_str(self) -> &'static str {
-        match self {
-            Self::Forward => "forward",
-            Self::Reverse => "reverse",
-        }
-    }
-}
-
 fn min_hit_slot(non_empty_slots: &[ScanHit]) -> Option<u64> {
     non_empty_slots.iter().map(|hit| hit.slot).min()
 }
@@ -1197,6 +1203,69 @@ fn count_credential_prefixes(consolidations: &[ConsolidationSnapshot]) -> BTreeM
 mod tests {
     use super::*;
 
+    #[test]
+    fn write_proof_bundles_writes_valid_files() {
+        use proof_gen::beacon_state::{GnosisBeaconState, PendingConsolidation, Validator};
+
+        // First Electra slot on Gnosis
+        let slot = 1_337_856 * SLOTS_PER_EPOCH;
+        let mut state = GnosisBeaconState {
+            slot,
+            ..Default::default()
+        };
+        state.fork.current_version = [0x05, 0x00, 0x00, 0x64];
+        for i in 0..3u8 {
+            let mut validator = Validator {
+                activation_epoch: u64::from(i),
+                ..Default::default()
+            };
+            validator.withdrawal_credentials[0] = 0x01;
+            state.validators.push(validator);
+            state.balances.push(32_000_000_000);
+        }
+        for source_index in [2, 1] {
+            state.pending_consolidations.push(PendingConsolidation {
+                source_index,
+                target_index: 0,
+            });
+        }
+        let header = BeaconBlockHeader {
+            slot,
+            proposer_index: 4,
+            parent_root: [0x01; 32],
+            state_root: state.hash_tree_root().unwrap().into(),
+            body_root: [0x02; 32],
+        };
+        let block_root: [u8; 32] = header.hash_tree_root().unwrap().into();
+
+        let dir = std::env::temp_dir().join(format!("proof-bundles-{}", std::process::id()));
+        let written = write_proof_bundles(
+            &dir,
+            &ssz_rs::serialize(&state).unwrap(),
+            &header,
+            1_700_000_000,
+            2,
+        )
+        .unwrap();
+        assert_eq!(written.len(), 2);
+
+        for (index, path) in written.iter().enumerate() {
+            let file = ProofBundleV1::load_file(path, None).unwrap();
+            assert_eq!(file.chain_id, 100);
+            assert_eq!(file.block_root, block_root);
+            assert_eq!(file.slot, slot);
+            assert_eq!(file.bundle.consolidation_index, index as u64);
+        }
+        assert_eq!(
+            ProofBundleV1::load_file(&written[1], None)
+                .unwrap()
+                .bundle
+                .source_index,
+            1
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
     #[test]
     fn resolve_slot_for_literal_slot() {
         let runtime = tokio::runtime::Runtime::new().unwrap();
@@ -1374,18 +1443,6 @@ mod tests {
         validate_scan_hit_limit(Some(3)).unwrap();
     }
 
-    #[test]
-    fn build_scan_slots_uses_step_and_includes_requested_slot() {
-        let slots = build_scan_slots(100, 140, 16, ScanDirection::Forward, 133);
-        assert_eq!(slots, vec![100, 116, 132, 133, 140]);
-    }
-
-    #[test]
-    fn build_scan_slots_reverses_order_when_requested() {
-        let slots = build_scan_slots(100, 140, 16, ScanDirection::Reverse, 132);
-        assert_eq!(slots, vec![140, 132, 116, 100]);
-    }
-
     #[test]
     fn hit_bounds_are_chronological_even_for_reverse_scan_order() {
         let hits = vec![
@@ -1931,7 +1988,14 @@ mod tests {
             "--watch-progress-output",
             output.to_str().unwrap(),
         ]);
-        let client = BeaconClient::new(mock_server.uri());
+        let client = BeaconClient::builder()
+            .endpoint(mock_server.uri())
+            .retry(proof_gen::beacon_client::RetryPolicy {
+                max_retries: 0,
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
 
         let error = watch_finalized_state(&args, &client, &sample_finality(100))
             .await
@@ -2163,116 +2227,4 @@ mod tests {
             .to_string()
             .contains("--watch-finalized currently requires --state-id finalized"));
     }
-
-    #[tokio::test]
-    async fn fetch_pending_consolidations_walks_back_to_previous_available_slot() {
-        use wiremock::matchers::{method, path};
-        use wiremock::{Mock, MockServer, ResponseTemplate};
-
-        let mock_server = MockServer::start().await;
-        let response_json = r#"{
-            "data": [
-                {"source_index": "42", "target_index": "100"}
-            ]
-        }"#;
-
-        Mock::given(method("GET"))
-            .and(path("/eth/v1/beacon/states/120/pending_consolidations"))
-            .respond_with(ResponseTemplate::new(404))
-            .mount(&mock_server)
-            .await;
-        Mock::given(method("GET"))
-            .and(path("/eth/v1/beacon/headers/120"))
-            .respond_with(ResponseTemplate::new(404))
-            .mount(&mock_server)
-            .await;
-
-        Mock::given(method("GET"))
-            .and(path("/eth/v1/beacon/states/119/pending_consolidations"))
-            .respond_with(ResponseTemplate::new(200).set_body_string(response_json))
-            .mount(&mock_server)
-            .await;
-
-        let client = BeaconClient::new(mock_server.uri());
-        let (resolved_slot, pending) = fetch_pending_consolidations_at_or_before(&client, 120, 110)
-            .await
-            .unwrap();
-
-        assert_eq!(resolved_slot, 119);
-        assert_eq!(pending.len(), 1);
-        assert_eq!(pending[0].source_index, 42);
-    }
-
-    #[tokio::test]
-    async fn fetch_pending_consolidations_errors_when_no_state_exists_in_window() {
-        use wiremock::matchers::{method, path};
-        use wiremock::{Mock, MockServer, ResponseTemplate};
-
-        let mock_server = MockServer::start().await;
-
-        for slot in [120_u64, 119, 118] {
-            Mock::given(method("GET"))
-                .and(path(format!(
-                    "/eth/v1/beacon/states/{slot}/pending_consolidations"
-                )))
-                .respond_with(ResponseTemplate::new(404))
-                .mount(&mock_server)
-                .await;
-            Mock::given(method("GET"))
-                .and(path(format!("/eth/v1/beacon/headers/{slot}")))
-                .respond_with(ResponseTemplate::new(404))
-                .mount(&mock_server)
-                .await;
-        }
-
-        let client = BeaconClient::new(mock_server.uri());
-        let error = fetch_pending_consolidations_at_or_before(&client, 120, 118)
-            .await
-            .unwrap_err();
-
-        assert!(error
-            .to_string()
-            .contains("no beacon state found at or before slot 120 within lower bound 118"));
-    }
-
-    #[tokio::test]
-    async fn fetch_pending_consolidations_detects_pruned_historical_state() {
-        use wiremock::matchers::{method, path};
-        use wiremock::{Mock, MockServer, ResponseTemplate};
-
-        let mock_server = MockServer::start().await;
-        let header_json = r#"{
-            "data": {
-                "header": {
-                    "message": {
-                        "slot": "120",
-                        "proposer_index": "42",
-                        "parent_root": "0x0101010101010101010101010101010101010101010101010101010101010101",
-                        "state_root": "0x0202020202020202020202020202020202020202020202020202020202020202",
-                        "body_root": "0x0303030303030303030303030303030303030303030303030303030303030303"
-                    }
-                }
-            }
-        }"#;
-
-        Mock::given(method("GET"))
-            .and(path("/eth/v1/beacon/states/120/pending_consolidations"))
-            .respond_with(ResponseTemplate::new(404))
-            .mount(&mock_server)
-            .await;
-        Mock::given(method("GET"))
-            .and(path("/eth/v1/beacon/headers/120"))
-            .respond_with(ResponseTemplate::new(200).set_body_string(header_json))
-            .mount(&mock_server)
-            .await;
-
-        let client = BeaconClient::new(mock_server.uri());
-        let error = fetch_pending_consolidations_at_or_before(&client, 120, 100)
-            .await
-            .unwrap_err();
-
-        assert!(error
-            .to_string()
-            .contains("historical state lookups appear pruned on this node"));
-    }
 }
@@ -0,0 +1,801 @@
+//! Admin API
+//!
+//! Operator actions on a running relayer, mounted under `/admin`.
+//!
+//! Every request must carry `Authorization: Bearer <token>`. Each action, and
+//! each rejected request, is written to the audit log: a structured `tracing`
+//! event with target `audit` and, if configured, a JSONL file. For mTLS,
+//! terminate TLS at a reverse proxy in front of the API listener.
+
+use crate::state::{AppState, ClaimStatus};
+use axum::{
+    extract::{OriginalUri, Path, Request, State},
+    http::{header::AUTHORIZATION, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+/// Largest epoch range accepted by a single backfill request
+pub const MAX_BACKFILL_EPOCHS: u64 = 10_000;
+
+/// Admin API configuration
+#[derive(Debug, Clone)]
+pub struct AdminConfig {
+    /// Bearer token required on every admin request
+    pub token: String,
+    /// JSONL audit log file (tracing only if `None`)
+    pub audit_log_path: Option<PathBuf>,
+}
+
+/// Audit log sink
+#[derive(Debug)]
+pub struct AuditLog {
+    file: Option<Mutex<tokio::fs::File>>,
+}
+
+/// Entry written for every admin request
+#[derive(Debug, Serialize)]
+struct AuditEntry<'a> {
+    timestamp: u64,
+    action: &'a str,
+    params: serde_json::Value,
+    status: u16,
+    message: &'a str,
+}
+
+impl AuditLog {
+    /// Open the audit log, creating the file if needed
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be opened for appending.
+    pub async fn open(path: Option<&std::path::Path>) -> std::io::Result<Self> {
+        let file = match path {
+            Some(path) => Some(Mutex::new(
+                tokio::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .await?,
+            )),
+            None => None,
+        };
+        Ok(Self { file })
+    }
+
+    /// Record an admin action and its outcome
+    pub async fn record(
+        &self,
+        action: &str,
+        params: serde_json::Value,
+        status: StatusCode,
+        message: &str,
+    ) {
+        tracing::info!(
+            target: "audit",
+            action,
+            %params,
+            status = status.as_u16(),
+            message,
+            "Admin action"
+        );
+
+        let Some(file) = &self.file else {
+            return;
+        };
+        let entry = AuditEntry {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            action,
+            params,
+            status: status.as_u16(),
+            message,
+        };
+        let Ok(mut line) = serde_json::to_vec(&entry) else {
+            return;
+        };
+        line.push(b'\n');
+
+        let mut file = file.lock().await;
+        if let Err(e) = async {
+            file.write_all(&line).await?;
+            file.flush().await
+        }
+        .await
+        {
+            tracing::error!(error = %e, "Failed to write audit log");
+        }
+    }
+}
+
+/// State shared by admin handlers
+#[derive(Clone)]
+struct AdminState {
+    app: AppState,
+    audit: Arc<AuditLog>,
+    token: Arc<str>,
+}
+
+/// Result of an admin action
+#[derive(Debug, Serialize, Deserialize)]
+struct ActionResponse {
+    action: String,
+    ok: bool,
+    message: String,
+}
+
+/// Re-prove request body
+#[derive(Debug, Deserialize)]
+struct ReproveRequest {
+    /// Beacon state id (slot, `0x`-prefixed state root, or `head`/`finalized`)
+    state_id: String,
+    /// Re-prove `Submitted` or `Confirmed` records too
+    #[serde(default)]
+    force: bool,
+}
+
+/// Backfill request body
+#[derive(Debug, Deserialize)]
+struct BackfillRequest {
+    from_epoch: u64,
+    to_epoch: u64,
+}
+
+/// Create the admin router (to be nested under `/admin`)
+pub fn create_admin_router(app: AppState, config: &AdminConfig, audit: Arc<AuditLog>) -> Router {
+    let state = AdminState {
+        app,
+        audit,
+        token: Arc::from(config.token.as_str()),
+    };
+
+    Router::new()
+        .route(
+            "/consolidations/{source_index}/retry",
+            post(retry_consolidation),
+        )
+        .route(
+            "/consolidations/{source_index}/reprove",
+            post(reprove_consolidation),
+        )
+        .route(
+            "/consolidations/{source_index}/ignore",
+            post(ignore_source).delete(unignore_source),
+        )
+        .route("/submitter/pause", post(pause_submitter))
+        .route("/submitter/resume", post(resume_submitter))
+        .route("/backfill", post(backfill))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .with_state(state)
+}
+
+/// Reject requests without the configured bearer token
+async fn require_token(State(admin): State<AdminState>, request: Request, next: Next) -> Response {
+    let authorized = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| constant_time_eq(token.as_bytes(), admin.token.as_bytes()));
+
+    if !authorized {
+        // Nested routers see a stripped path; log the one the client sent
+        let path = request
+            .extensions()
+            .get::<OriginalUri>()
+            .map_or_else(|| request.uri().path(), |uri| uri.path());
+        admin
+            .audit
+            .record(
+                "unauthorized",
+                json!({ "method": request.method().as_str(), "path": path }),
+                StatusCode::UNAUTHORIZED,
+                "missing or invalid bearer token",
+            )
+            .await;
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+
+    next.run(request).await
+}
+
+/// Compare two byte strings without short-circuiting on the first mismatch
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Audit an action and build its response
+async fn respond(
+    admin: &AdminState,
+    action: &str,
+    params: serde_json::Value,
+    status: StatusCode,
+    message: String,
+) -> (StatusCode, Json<ActionResponse>) {
+    admin.audit.record(action, params, status, &message).await;
+    (
+        status,
+        Json(ActionResponse {
+            action: action.to_string(),
+            ok: status.is_success(),
+            message,
+        }),
+    )
+}
+
+/// Move a `Failed` record back to `Detected` so it is proven and submitted again
+async fn retry_consolidation(
+    State(admin): State<AdminState>,
+    Path(source_index): Path<u64>,
+) -> (StatusCode, Json<ActionResponse>) {
+    let params = json!({ "source_index": source_index });
+    let (status, message) = match admin.app.get_consolidation(source_index) {
+        None => (
+            StatusCode::NOT_FOUND,
+            format!("consolidation {source_index} not tracked"),
+        ),
+        Some(record) if record.status != ClaimStatus::Failed => (
+            StatusCode::CONFLICT,
+            format!(
+                "consolidation {source_index} is {:?}, not Failed",
+                record.status
+            ),
+        ),
+        Some(mut record) => {
+            record.status = ClaimStatus::Detected;
+            record.tx_hash = None;
+            record.error = None;
+            admin.app.upsert_consolidation(record);
+            (
+                StatusCode::OK,
+                format!("consolidation {source_index} queued for retry"),
+            )
+        }
+    };
+    respond(&admin, "retry", params, status, message).await
+}
+
+/// Rebuild the proof for a record against an explicit beacon state
+///
+/// Records already sent to the contract are only re-proven with `force`.
+async fn reprove_consolidation(
+    State(admin): State<AdminState>,
+    Path(source_index): Path<u64>,
+    Json(request): Json<ReproveRequest>,
+) -> (StatusCode, Json<ActionResponse>) {
+    let params = json!({
+        "source_index": source_index,
+        "state_id": request.state_id,
+        "force": request.force,
+    });
+    let (status, message) = match admin.app.get_consolidation(source_index) {
+        None => (
+            StatusCode::NOT_FOUND,
+            format!("consolidation {source_index} not tracked"),
+        ),
+        Some(_) if !is_valid_state_id(&request.state_id) => (
+            StatusCode::BAD_REQUEST,
+            format!(
+                "invalid state_id {:?}: expected head, finalized, a slot or a 0x-prefixed 32-byte state root",
+                request.state_id
+            ),
+        ),
+        Some(record)
+            if matches!(
+                record.status,
+                ClaimStatus::Submitted | ClaimStatus::Confirmed
+            ) && !request.force =>
+        {
+            (
+                StatusCode::CONFLICT,
+                format!(
+                    "consolidation {source_index} is {:?}; set force to re-prove it",
+                    record.status
+                ),
+            )
+        }
+        Some(mut record) => {
+            admin
+                .app
+                .request_reprove(source_index, request.state_id.clone());
+            record.status = ClaimStatus::Detected;
+            record.tx_hash = None;
+            record.error = None;
+            admin.app.upsert_consolidation(record);
+            (
+                StatusCode::OK,
+                format!(
+                    "consolidation {source_index} will be re-proven at state {}",
+                    request.state_id
+                ),
+            )
+        }
+    };
+    respond(&admin, "reprove", params, status, message).await
+}
+
+/// Whether `state_id` is `head`, `finalized`, a slot or a `0x`-prefixed state root
+fn is_valid_state_id(state_id: &str) -> bool {
+    match state_id {
+        "head" | "finalized" => true,
+        _ => match state_id.strip_prefix("0x") {
+            Some(root) => root.len() == 64 && root.bytes().all(|b| b.is_ascii_hexdigit()),
+            None => state_id.parse::<u64>().is_ok(),
+        },
+    }
+}
+
+/// Exclude a source index from tracking and claiming
+async fn ignore_source(
+    State(admin): State<AdminState>,
+    Path(source_index): Path<u64>,
+) -> (StatusCode, Json<ActionResponse>) {
+    let message = if admin.app.ignore_source(source_index) {
+        format!("source index {source_index} ignored")
+    } else {
+        format!("source index {source_index} was already ignored")
+    };
+    respond(
+        &admin,
+        "ignore",
+        json!({ "source_index": source_index }),
+        StatusCode::OK,
+        message,
+    )
+    .await
+}
+
+/// Clear the ignored mark on a source index
+async fn unignore_source(
+    State(admin): State<AdminState>,
+    Path(source_index): Path<u64>,
+) -> (StatusCode, Json<ActionResponse>) {
+    let (status, message) = if admin.app.unignore_source(source_index) {
+        (
+            StatusCode::OK,
+            format!("source index {source_index} no longer ignored"),
+        )
+    } else {
+        (
+            StatusCode::NOT_FOUND,
+            format!("source index {source_index} was not ignored"),
+        )
+    };
+    respond(
+        &admin,
+        "unignore",
+        json!({ "source_index": source_index }),
+        status,
+        message,
+    )
+    .await
+}
+
+/// Stop submitting claims until resumed
+async fn pause_submitter(State(admin): State<AdminState>) -> (StatusCode, Json<ActionResponse>) {
+    admin.app.set_submitter_paused(true);
+    respond(
+        &admin,
+        "pause_submitter",
+        json!({}),
+        StatusCode::OK,
+        "submitter paused".to_string(),
+    )
+    .await
+}
+
+/// Resume claim submission
+async fn resume_submitter(State(admin): State<AdminState>) -> (StatusCode, Json<ActionResponse>) {
+    admin.app.set_submitter_paused(false);
+    respond(
+        &admin,
+        "resume_submitter",
+        json!({}),
+        StatusCode::OK,
+        "submitter resumed".to_string(),
+    )
+    .await
+}
+
+/// Queue an inclusive epoch range for the scanner to rescan
+async fn backfill(
+    State(admin): State<AdminState>,
+    Json(request): Json<BackfillRequest>,
+) -> (StatusCode, Json<ActionResponse>) {
+    let BackfillRequest {
+        from_epoch,
+        to_epoch,
+    } = request;
+    let params = json!({ "from_epoch": from_epoch, "to_epoch": to_epoch });
+
+    let (status, message) = if from_epoch > to_epoch {
+        (
+            StatusCode::BAD_REQUEST,
+            "from_epoch must not exceed to_epoch".to_string(),
+        )
+    } else if to_epoch - from_epoch >= MAX_BACKFILL_EPOCHS {
+        (
+            StatusCode::BAD_REQUEST,
+            format!("range exceeds {MAX_BACKFILL_EPOCHS} epochs"),
+        )
+    } else {
+        admin.app.enqueue_backfill(from_epoch, to_epoch);
+        (
+            StatusCode::ACCEPTED,
+            format!("backfill of epochs {from_epoch}..={to_epoch} queued"),
+        )
+    };
+    respond(&admin, "backfill", params, status, message).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::ConsolidationRecord;
+    use axum::body::Body;
+    use axum::http::Method;
+    use tower::ServiceExt;
+
+    const TOKEN: &str = "test-admin-token";
+
+    fn router(app: AppState, audit: AuditLog) -> Router {
+        create_admin_router(
+            app,
+            &AdminConfig {
+                token: TOKEN.to_string(),
+                audit_log_path: None,
+            },
+            Arc::new(audit),
+        )
+    }
+
+    async fn call(
+        router: &Router,
+        method: Method,
+        uri: &str,
+        token: Option<&str>,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, Option<ActionResponse>) {
+        let mut request = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            request = request.header(AUTHORIZATION, format!("Bearer {token}"));
+        }
+        let body = match body {
+            Some(body) => {
+                request = request.header("content-type", "application/json");
+                Body::from(body.to_string())
+            }
+            None => Body::empty(),
+        };
+
+        let response = router
+            .clone()
+            .oneshot(request.body(body).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).ok())
+    }
+
+    fn record(source_index: u64, status: ClaimStatus) -> ConsolidationRecord {
+        ConsolidationRecord {
+            source_index,
+            target_index: source_index + 1,
+            epoch_seen: 100,
+            status,
+            tx_hash: Some("0xdead".to_string()),
+            error: Some("reverted".to_string()),
+            anchor_block_root: None,
+            state_seen: None,
+        }
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abc", b"abd"));
+        assert!(!constant_time_eq(b"abc", b"abcd"));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_missing_or_wrong_token() {
+        let app = AppState::new();
+        let router = router(app.clone(), AuditLog::open(None).await.unwrap());
+
+        let (status, _) = call(&router, Method::POST, "/submitter/pause", None, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        let (status, _) = call(
+            &router,
+            Method::POST,
+            "/submitter/pause",
+            Some("wrong"),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(!app.submitter_paused());
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_resume_submitter() {
+        let app = AppState::new();
+        let router = router(app.clone(), AuditLog::open(None).await.unwrap());
+
+        let (status, body) =
+            call(&router, Method::POST, "/submitter/pause", Some(TOKEN), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.unwrap().ok);
+        assert!(app.submitter_paused());
+
+        call(
+            &router,
+            Method::POST,
+            "/submitter/resume",
+            Some(TOKEN),
+            None,
+        )
+        .await;
+        assert!(!app.submitter_paused());
+    }
+
+    #[tokio::test]
+    async fn test_retry_only_failed_records() {
+        let app = AppState::new();
+        app.upsert_consolidation(record(1, ClaimStatus::Failed));
+        app.upsert_consolidation(record(2, ClaimStatus::Confirmed));
+        let router = router(app.clone(), AuditLog::open(None).await.unwrap());
+
+        let (status, _) = call(
+            &router,
+            Method::POST,
+            "/consolidations/1/retry",
+            Some(TOKEN),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let retried = app.get_consolidation(1).unwrap();
+        assert_eq!(retried.status, ClaimStatus::Detected);
+        assert_eq!(retried.error, None);
+        assert_eq!(retried.tx_hash, None);
+
+        let (status, body) = call(
+            &router,
+            Method::POST,
+            "/consolidations/2/retry",
+            Some(TOKEN),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(!body.unwrap().ok);
+
+        let (status, _) = call(
+            &router,
+            Method::POST,
+            "/consolidations/3/retry",
+            Some(TOKEN),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_reprove_records_state_id() {
+        let app = AppState::new();
+        app.upsert_consolidation(record(1, ClaimStatus::Failed));
+        let router = router(app.clone(), AuditLog::open(None).await.unwrap());
+
+        let (status, _) = call(
+            &router,
+            Method::POST,
+            "/consolidations/1/reprove",
+            Some(TOKEN),
+            Some(json!({ "state_id": "123456" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            app.get_consolidation(1).unwrap().status,
+            ClaimStatus::Detected
+        );
+        assert_eq!(app.take_reprove_request(1).as_deref(), Some("123456"));
+    }
+
+    #[tokio::test]
+    async fn test_reprove_rejects_invalid_state_ids() {
+        let app = AppState::new();
+        app.upsert_consolidation(record(1, ClaimStatus::Failed));
+        let router = router(app.clone(), AuditLog::open(None).await.unwrap());
+
+        let root = format!("0x{}", "ab".repeat(32));
+        let not_hex = format!("0x{}", "zz".repeat(32));
+        for (state_id, valid) in [
+            ("head", true),
+            ("finalized", true),
+            ("123456", true),
+            (root.as_str(), true),
+            ("", false),
+            ("genesis", false),
+            ("-1", false),
+            ("0x1234", false),
+            (&root[2..], false),
+            (not_hex.as_str(), false),
+        ] {
+            let (status, _) = call(
+                &router,
+                Method::POST,
+                "/consolidations/1/reprove",
+                Some(TOKEN),
+                Some(json!({ "state_id": state_id })),
+            )
+            .await;
+            let expected = if valid {
+                StatusCode::OK
+            } else {
+                StatusCode::BAD_REQUEST
+            };
+            assert_eq!(status, expected, "{state_id}");
+            assert_eq!(app.take_reprove_request(1).is_some(), valid, "{state_id}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reprove_sent_records_needs_force() {
+        let app = AppState::new();
+        app.upsert_consolidation(record(1, ClaimStatus::Submitted));
+        app.upsert_consolidation(record(2, ClaimStatus::Confirmed));
+        let router = router(app.clone(), AuditLog::open(None).await.unwrap());
+
+        for source_index in [1, 2] {
+            let uri = format!("/consolidations/{source_index}/reprove");
+            let (status, body) = call(
+                &router,
+                Method::POST,
+                &uri,
+                Some(TOKEN),
+                Some(json!({ "state_id": "head" })),
+            )
+            .await;
+            assert_eq!(status, StatusCode::CONFLICT);
+            assert!(body.unwrap().message.contains("force"));
+            assert_ne!(
+                app.get_consolidation(source_index).unwrap().status,
+                ClaimStatus::Detected
+            );
+            assert_eq!(app.take_reprove_request(source_index), None);
+
+            let (status, _) = call(
+                &router,
+                Method::POST,
+                &uri,
+                Some(TOKEN),
+                Some(json!({ "state_id": "head", "force": true })),
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+            let record = app.get_consolidation(source_index).unwrap();
+            assert_eq!(record.status, ClaimStatus::Detected);
+            assert_eq!(record.tx_hash, None);
+            assert_eq!(
+                app.take_reprove_request(source_index).as_deref(),
+                Some("head")
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ignore_and_unignore() {
+        let app = AppState::new();
+        let router = router(app.clone(), AuditLog::open(None).await.unwrap());
+
+        let (status, _) = call(
+            &router,
+            Method::POST,
+            "/consolidations/9/ignore",
+            Some(TOKEN),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(app.is_ignored(9));
+
+        let (status, _) = call(
+            &router,
+            Method::DELETE,
+            "/consolidations/9/ignore",
+            Some(TOKEN),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(!app.is_ignored(9));
+
+        let (status, _) = call(
+            &router,
+            Method::DELETE,
+            "/consolidations/9/ignore",
+            Some(TOKEN),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_backfill_validation_and_queueing() {
+        let app = AppState::new();
+        let router = router(app.clone(), AuditLog::open(None).await.unwrap());
+
+        let (status, _) = call(
+            &router,
+            Method::POST,
+            "/backfill",
+            Some(TOKEN),
+            Some(json!({ "from_epoch": 20, "to_epoch": 10 })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = call(
+            &router,
+            Method::POST,
+            "/backfill",
+            Some(TOKEN),
+            Some(json!({ "from_epoch": 0, "to_epoch": MAX_BACKFILL_EPOCHS })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(app.next_backfill(), None);
+
+        let (status, _) = call(
+            &router,
+            Method::POST,
+            "/backfill",
+            Some(TOKEN),
+            Some(json!({ "from_epoch": 10, "to_epoch": 20 })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        assert_eq!(app.next_backfill(), Some((10, 20)));
+    }
+
+    #[tokio::test]
+    async fn test_audit_log_records_actions_and_rejections() {
+        let path = std::env::temp_dir().join(format!("admin-audit-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let app = AppState::new();
+        let router = router(app, AuditLog::open(Some(&path)).await.unwrap());
+
+        call(&router, Method::POST, "/submitter/pause", Some(TOKEN), None).await;
+        call(&router, Method::POST, "/submitter/resume", None, None).await;
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let entries: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["action"], "pause_submitter");
+        assert_eq!(entries[0]["status"], 200);
+        assert_eq!(entries[1]["action"], "unauthorized");
+        assert_eq!(entries[1]["params"]["path"], "/submitter/resume");
+        std::fs::remove_file(path).unwrap();
+    }
+}
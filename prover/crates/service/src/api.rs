@@ -1,19 +1,41 @@
 //! REST API Endpoints
 //!
-//! Health, status, and consolidation query endpoints.
+//! Health, status, consolidation query and bundle file validation endpoints.
+//!
+//! Handlers are registered through `utoipa_axum` so the OpenAPI document served
+//! at `/openapi.json` is generated from the same route table.
 
-use crate::state::AppState;
+use crate::state::{AppState, BeaconEndpointStatus, ConsolidationRecord, FinalizedAnchor};
 use axum::{
+    body::Bytes,
     extract::{Path, State},
     http::StatusCode,
     routing::get,
     Json, Router,
 };
+use proof_gen::ProofBundleV1;
 use serde::Serialize;
-
-/// Run the main API server
-pub async fn run_server(listen: String, state: AppState) -> anyhow::Result<()> {
-    let app = create_router(state);
+use utoipa::{OpenApi, ToSchema};
+use utoipa_axum::{router::OpenApiRouter, routes};
+
+/// OpenAPI document metadata; paths are added by [`api_router`]
+#[derive(OpenApi)]
+#[openapi(info(
+    title = "Consolidation Incentives Service",
+    description = "Consolidation detection and reward claim status"
+))]
+struct ApiDoc;
+
+/// Run the main API server, nesting the admin router under `/admin` if given
+pub async fn run_server(
+    listen: String,
+    state: AppState,
+    admin: Option<Router>,
+) -> anyhow::Result<()> {
+    let mut app = create_router(state);
+    if let Some(admin) = admin {
+        app = app.nest("/admin", admin);
+    }
 
     let listener = tokio::net::TcpListener::bind(&listen).await?;
     tracing::info!(address = %listen, "API server listening");
@@ -35,14 +57,22 @@ pub async fn run_metrics_server(listen: String, state: AppState) -> anyhow::Resu
     Ok(())
 }
 
+/// Public API routes together with their OpenAPI description
+fn api_router() -> OpenApiRouter<AppState> {
+    OpenApiRouter::with_openapi(ApiDoc::openapi())
+        .routes(routes!(health))
+        .routes(routes!(status))
+        .routes(routes!(list_consolidations))
+        .routes(routes!(get_consolidation))
+        .routes(routes!(validate_bundle))
+        .routes(routes!(metrics))
+}
+
 /// Create the main API router
 pub fn create_router(state: AppState) -> Router {
-    Router::new()
-        .route("/health", get(health))
-        .route("/status", get(status))
-        .route("/consolidations", get(list_consolidations))
-        .route("/consolidations/{source_index}", get(get_consolidation))
-        .route("/metrics", get(metrics))
+    let (router, spec) = api_router().split_for_parts();
+    router
+        .route("/openapi.json", get(move || async move { Json(spec) }))
         .with_state(state)
 }
 
@@ -54,13 +84,22 @@ pub fn create_metrics_router(state: AppState) -> Router {
 }
 
 /// Health check response
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct HealthResponse {
+    /// `healthy` or `degraded`
     status: &'static str,
     slots_behind: u64,
 }
 
 /// Health check endpoint
+#[utoipa::path(
+    get,
+    path = "/health",
+    responses(
+        (status = 200, description = "Within 64 slots of head", body = HealthResponse),
+        (status = 503, description = "More than 64 slots behind head", body = HealthResponse),
+    )
+)]
 async fn health(State(state): State<AppState>) -> (StatusCode, Json<HealthResponse>) {
     let healthy = state.is_healthy();
     let status_code = if healthy {
@@ -78,18 +117,26 @@ async fn health(State(state): State<AppState>) -> (StatusCode, Json<HealthRespon
 }
 
 /// Status response
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 struct StatusResponse {
     current_slot: u64,
     current_epoch: u64,
     head_slot: u64,
     slots_behind: u64,
     uptime_secs: u64,
+    finalized_anchor: Option<FinalizedAnchor>,
     consolidations: crate::state::StatusCounts,
+    submitter_paused: bool,
+    beacon_endpoints: Vec<BeaconEndpointStatus>,
     last_error: Option<String>,
 }
 
 /// Status endpoint
+#[utoipa::path(
+    get,
+    path = "/status",
+    responses((status = 200, description = "Sync and claim status", body = StatusResponse))
+)]
 async fn status(State(state): State<AppState>) -> Json<StatusResponse> {
     Json(StatusResponse {
         current_slot: state.current_slot(),
@@ -97,30 +144,118 @@ async fn status(State(state): State<AppState>) -> Json<StatusResponse> {
         head_slot: state.head_slot(),
         slots_behind: state.slots_behind(),
         uptime_secs: state.uptime_secs(),
+        finalized_anchor: state.finalized_anchor(),
         consolidations: state.status_counts(),
+        submitter_paused: state.submitter_paused(),
+        beacon_endpoints: state.beacon_endpoints(),
         last_error: state.last_error(),
     })
 }
 
 /// List all consolidations
-async fn list_consolidations(
-    State(state): State<AppState>,
-) -> Json<Vec<crate::state::ConsolidationRecord>> {
+#[utoipa::path(
+    get,
+    path = "/consolidations",
+    responses((status = 200, description = "All tracked consolidations", body = Vec<ConsolidationRecord>))
+)]
+async fn list_consolidations(State(state): State<AppState>) -> Json<Vec<ConsolidationRecord>> {
     Json(state.all_consolidations())
 }
 
 /// Get a single consolidation by source index
+#[utoipa::path(
+    get,
+    path = "/consolidations/{source_index}",
+    params(("source_index" = u64, Path, description = "Source validator index")),
+    responses(
+        (status = 200, description = "Tracked consolidation", body = ConsolidationRecord),
+        (status = 404, description = "Source index not tracked"),
+    )
+)]
 async fn get_consolidation(
     State(state): State<AppState>,
     Path(source_index): Path<u64>,
-) -> Result<Json<crate::state::ConsolidationRecord>, StatusCode> {
+) -> Result<Json<ConsolidationRecord>, StatusCode> {
     state
         .get_consolidation(source_index)
         .map(Json)
         .ok_or(StatusCode::NOT_FOUND)
 }
 
+/// Context and claim of a valid bundle file
+#[derive(Serialize, ToSchema)]
+struct BundleSummary {
+    version: u32,
+    chain_id: u64,
+    /// Contract the bundle is bound to, if any (0x-prefixed hex)
+    contract_address: Option<String>,
+    fork: String,
+    /// Block root the proofs verify against (0x-prefixed hex)
+    block_root: String,
+    slot: u64,
+    beacon_timestamp: u64,
+    consolidation_index: u64,
+    source_index: u64,
+    /// Reward recipient from the source credentials (0x-prefixed hex)
+    recipient: Option<String>,
+    has_target_proof: bool,
+}
+
+/// Why a bundle file was rejected
+#[derive(Serialize, ToSchema)]
+struct BundleRejection {
+    error: String,
+}
+
+/// Validate a `ProofBundleV1` file, JSON or SSZ
+///
+/// Plain bundles without chain context are rejected, as there is nothing to
+/// check their proofs against.
+#[utoipa::path(
+    post,
+    path = "/bundles/validate",
+    request_body(content = Vec<u8>, description = "Bundle file, JSON or SSZ", content_type = "application/octet-stream"),
+    responses(
+        (status = 200, description = "Bundle is well formed and its proofs verify", body = BundleSummary),
+        (status = 422, description = "Bundle is malformed or its proofs don't verify", body = BundleRejection),
+    )
+)]
+async fn validate_bundle(
+    body: Bytes,
+) -> Result<Json<BundleSummary>, (StatusCode, Json<BundleRejection>)> {
+    let file = ProofBundleV1::load(&body, None).map_err(|e| {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(BundleRejection {
+                error: e.to_string(),
+            }),
+        )
+    })?;
+    let to_hex = |bytes: &[u8]| format!("0x{}", hex::encode(bytes));
+    Ok(Json(BundleSummary {
+        version: file.version,
+        chain_id: file.chain_id,
+        contract_address: file.contract_address.map(|address| to_hex(&address)),
+        fork: file.fork.to_string(),
+        block_root: to_hex(&file.block_root),
+        slot: file.slot,
+        beacon_timestamp: file.bundle.beacon_timestamp,
+        consolidation_index: file.bundle.consolidation_index,
+        source_index: file.bundle.source_index,
+        recipient: file
+            .bundle
+            .recipient_address()
+            .map(|address| to_hex(&address)),
+        has_target_proof: file.bundle.target.is_some(),
+    }))
+}
+
 /// Prometheus metrics endpoint
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    responses((status = 200, description = "Prometheus text exposition", body = String, content_type = "text/plain"))
+)]
 async fn metrics(State(state): State<AppState>) -> String {
     use metrics::{describe_counter, describe_gauge, describe_histogram};
 
@@ -260,6 +395,15 @@ mod tests {
         state.set_current_slot(100);
         state.set_current_epoch(6);
         state.set_head_slot(120);
+        state.set_beacon_endpoints(vec![BeaconEndpointStatus {
+            url: "http://beacon-a:5052".to_string(),
+            successes: 3,
+            failures: 1,
+            consecutive_failures: 0,
+            last_latency_ms: Some(12),
+            last_error: None,
+            preferred: true,
+        }]);
 
         let Json(response) = status(State(state)).await;
 
@@ -267,6 +411,136 @@ mod tests {
         assert_eq!(response.current_epoch, 6);
         assert_eq!(response.head_slot, 120);
         assert_eq!(response.slots_behind, 20);
+        assert_eq!(response.beacon_endpoints.len(), 1);
+        assert!(response.beacon_endpoints[0].preferred);
+    }
+
+    #[tokio::test]
+    async fn test_openapi_served_and_documents_schemas() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let response = create_router(AppState::new())
+            .oneshot(Request::get("/openapi.json").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let spec: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert!(spec["openapi"].as_str().unwrap().starts_with("3."));
+        for schema in [
+            "StatusResponse",
+            "HealthResponse",
+            "ConsolidationRecord",
+            "StatusCounts",
+            "ClaimStatus",
+            "BeaconEndpointStatus",
+            "BundleSummary",
+            "BundleRejection",
+        ] {
+            assert!(
+                spec["components"]["schemas"].get(schema).is_some(),
+                "missing schema {schema}"
+            );
+        }
+    }
+
+    /// Every documented operation must be routed, and every public route documented
+    #[tokio::test]
+    async fn test_openapi_matches_routes() {
+        use axum::body::Body;
+        use axum::http::{Method, Request};
+        use tower::ServiceExt;
+
+        let (_, spec) = api_router().split_for_parts();
+        let router = create_router(AppState::new());
+
+        let mut documented: Vec<String> = spec.paths.paths.keys().cloned().collect();
+        documented.sort();
+        assert_eq!(
+            documented,
+            [
+                "/bundles/validate",
+                "/consolidations",
+                "/consolidations/{source_index}",
+                "/health",
+                "/metrics",
+                "/status",
+            ]
+        );
+
+        for (path, item) in &spec.paths.paths {
+            let uri = path.replace("{source_index}", "1");
+            let methods = [
+                (Method::GET, item.get.is_some()),
+                (Method::POST, item.post.is_some()),
+                (Method::PUT, item.put.is_some()),
+                (Method::DELETE, item.delete.is_some()),
+            ];
+            for (method, documented) in methods {
+                let response = router
+                    .clone()
+                    .oneshot(
+                        Request::builder()
+                            .method(method.clone())
+                            .uri(&uri)
+                            .body(Body::empty())
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                let routed = response.status() != StatusCode::METHOD_NOT_ALLOWED;
+                assert_eq!(routed, documented, "{method} {path}");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validate_bundle_rejects_unusable_files() {
+        use axum::body::Body;
+        use axum::http::Request;
+        use tower::ServiceExt;
+
+        let plain = serde_json::json!({
+            "beacon_timestamp": 0,
+            "consolidation_index": 0,
+            "source_index": 0,
+            "activation_epoch": 0,
+            "source_credentials": hex::encode([0u8; 32]),
+            "proof_consolidation": [],
+            "proof_credentials": [],
+            "proof_activation_epoch": [],
+        });
+        let future = serde_json::json!({ "version": 2 });
+
+        let router = create_router(AppState::new());
+        for (body, expected) in [
+            (vec![1, 0, 0, 0, 0xff], "Invalid bundle SSZ"),
+            (plain.to_string().into_bytes(), "chain context"),
+            (future.to_string().into_bytes(), "version 2"),
+        ] {
+            let response = router
+                .clone()
+                .oneshot(
+                    Request::post("/bundles/validate")
+                        .body(Body::from(body))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let rejection: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+            let error = rejection["error"].as_str().unwrap();
+            assert!(error.contains(expected), "{error}");
+        }
     }
 
     #[tokio::test]
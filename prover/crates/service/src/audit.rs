@@ -0,0 +1,392 @@
+//! Claim Audit
+//!
+//! `consolidation-service audit` decodes a `claimReward` transaction, by hash
+//! or from its raw input, re-verifies its proofs and prints the outcome as
+//! JSON. Used to investigate claims made by other relayers.
+
+use alloy::{
+    consensus::Transaction as _,
+    eips::BlockId,
+    primitives::{Bytes, B256},
+    providers::{Provider, ProviderBuilder},
+    rpc::types::{Transaction, TransactionReceipt},
+};
+use anyhow::{Context, Result};
+use contract_bindings::audit::{audit_claim, ClaimAudit, ClaimReceipt};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Arguments of the `audit` subcommand
+#[derive(clap::Args, Debug)]
+#[command(group(clap::ArgGroup::new("claim").required(true).args(["tx", "input"])))]
+pub struct AuditArgs {
+    /// Claim transaction hash; the transaction and receipt are fetched from --rpc-url
+    #[arg(long)]
+    pub tx: Option<B256>,
+
+    /// Raw claimReward calldata (0x-hex), e.g. copied from a block explorer
+    #[arg(long)]
+    pub input: Option<Bytes>,
+
+    /// Receipt JSON (an eth_getTransactionReceipt result) for --input
+    #[arg(long, requires = "input")]
+    pub receipt: Option<PathBuf>,
+
+    /// Block root (0x-hex) to re-verify the proofs against
+    #[arg(long)]
+    pub block_root: Option<B256>,
+}
+
+/// Run the audit and print it to stdout
+pub async fn run(args: &AuditArgs, rpc_url: &str) -> Result<()> {
+    let audit = audit(args, rpc_url).await?;
+    println!("{}", serde_json::to_string_pretty(&audit)?);
+    Ok(())
+}
+
+async fn audit(args: &AuditArgs, rpc_url: &str) -> Result<ClaimAudit> {
+    let (input, receipt) = match (args.tx, &args.input) {
+        (Some(hash), _) => fetch_claim(hash, rpc_url).await?,
+        (None, Some(input)) => (
+            input.clone(),
+            args.receipt.as_deref().map(read_receipt).transpose()?,
+        ),
+        (None, None) => anyhow::bail!("Either --tx or --input is required"),
+    };
+    let block_root = args.block_root.map(|root| root.0);
+    audit_claim(&input, block_root, receipt.as_ref()).context("Failed to decode claim")
+}
+
+fn read_receipt(path: &Path) -> Result<ClaimReceipt> {
+    let json = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let receipt: TransactionReceipt =
+        serde_json::from_str(&json).context("Receipt is not a transaction receipt")?;
+    Ok(ClaimReceipt::from(&receipt))
+}
+
+/// Fetch a claim's input and receipt (`None` while pending).
+///
+/// Receipts don't carry revert data, so a failed claim is re-executed to
+/// recover it; see [`revert_data`].
+async fn fetch_claim(hash: B256, rpc_url: &str) -> Result<(Bytes, Option<ClaimReceipt>)> {
+    let url = rpc_url.parse().context("Invalid RPC URL")?;
+    let provider = ProviderBuilder::new().connect_http(url);
+
+    let tx = provider
+        .get_transaction_by_hash(hash)
+        .await
+        .context("Failed to fetch transaction")?
+        .with_context(|| format!("Transaction {hash} not found"))?;
+    let input = tx.input().clone();
+
+    let Some(receipt) = provider
+        .get_transaction_receipt(hash)
+        .await
+        .context("Failed to fetch receipt")?
+    else {
+        return Ok((input, None));
+    };
+
+    let mut claim_receipt = ClaimReceipt::from(&receipt);
+    if !claim_receipt.status {
+        let block_number = receipt.block_number.unwrap_or_default();
+        claim_receipt.revert_data = revert_data(&provider, hash, tx, block_number).await;
+    }
+    Ok((input, Some(claim_receipt)))
+}
+
+/// Revert data of the failed transaction `tx` (`hash`), mined in `block_number`
+///
+/// `debug_traceTransaction` re-executes it at its position in the block, after
+/// the transactions before it, such as a competing relayer claiming the same
+/// source. Nodes without the debug namespace fall back to `eth_call` on the
+/// parent block, which does not see those transactions: a claim that lost to
+/// one earlier in its block may then replay without `AlreadyClaimed`, or
+/// without reverting at all.
+async fn revert_data(
+    provider: &impl Provider,
+    hash: B256,
+    tx: Transaction,
+    block_number: u64,
+) -> Option<Bytes> {
+    match traced_revert_data(provider, hash).await {
+        Ok(data) => return data,
+        Err(e) => warn!(
+            error = %e,
+            "debug_traceTransaction unavailable; replaying on the parent block, which ignores earlier transactions in the block"
+        ),
+    }
+    let replay = provider
+        .call(tx.into_request())
+        .block(BlockId::number(block_number.saturating_sub(1)))
+        .await;
+    replay
+        .err()
+        .and_then(|e| e.as_error_resp().and_then(|resp| resp.as_revert_data()))
+}
+
+/// Revert data from a `callTracer` trace of `hash`, `None` if the top-level
+/// call did not fail
+async fn traced_revert_data(provider: &impl Provider, hash: B256) -> Result<Option<Bytes>> {
+    #[derive(Deserialize)]
+    struct CallFrame {
+        output: Option<Bytes>,
+        error: Option<String>,
+    }
+
+    let frame: CallFrame = provider
+        .raw_request(
+            "debug_traceTransaction".into(),
+            (hash, serde_json::json!({ "tracer": "callTracer" })),
+        )
+        .await?;
+    Ok(frame.error.and(frame.output))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::{
+        primitives::{Address, U256},
+        sol_types::{SolError, SolEvent},
+    };
+    use contract_bindings::{
+        audit::{ClaimOutcome, ProofCheck},
+        encode_claim, ConsolidationIncentives, RewardClaimed,
+    };
+    use proof_gen::ConsolidationProofBundle;
+    use serde_json::json;
+    use wiremock::{
+        matchers::{body_partial_json, method},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    const CONTRACT: &str = "0x1111111111111111111111111111111111111111";
+    const TX_HASH: &str = "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+    const BLOCK_HASH: &str = "0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+    const SENDER: &str = "0x2222222222222222222222222222222222222222";
+
+    fn sample_bundle() -> ConsolidationProofBundle {
+        let mut source_credentials = [0u8; 32];
+        source_credentials[0] = 0x01;
+        source_credentials[12..].fill(0x42);
+        ConsolidationProofBundle {
+            beacon_timestamp: 1_700_000_000,
+            consolidation_index: 3,
+            source_index: 42,
+            activation_epoch: 1_000,
+            source_credentials,
+            proof_consolidation: vec![[0xaa; 32]; 29],
+            proof_credentials: vec![[0xbb; 32]; 53],
+            proof_activation_epoch: vec![[0xcc; 32]; 53],
+            target: None,
+        }
+    }
+
+    fn receipt_json(status: bool, logs: serde_json::Value) -> serde_json::Value {
+        json!({
+            "transactionHash": TX_HASH,
+            "transactionIndex": "0x0",
+            "blockHash": BLOCK_HASH,
+            "blockNumber": "0x10",
+            "from": SENDER,
+            "to": CONTRACT,
+            "cumulativeGasUsed": "0x5208",
+            "gasUsed": "0x5208",
+            "effectiveGasPrice": "0x1",
+            "contractAddress": null,
+            "logs": logs,
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "type": "0x0",
+            "status": if status { "0x1" } else { "0x0" },
+        })
+    }
+
+    fn reward_log_json() -> serde_json::Value {
+        let log = RewardClaimed {
+            sourceIndex: 42,
+            recipient: Address::repeat_byte(0x42),
+            amount: U256::from(1_000u64),
+        }
+        .encode_log_data();
+        json!({
+            "address": CONTRACT,
+            "topics": log.topics(),
+            "data": log.data,
+            "blockHash": BLOCK_HASH,
+            "blockNumber": "0x10",
+            "transactionHash": TX_HASH,
+            "transactionIndex": "0x0",
+            "logIndex": "0x0",
+            "removed": false,
+        })
+    }
+
+    fn args(tx: Option<B256>, input: Option<Bytes>, receipt: Option<PathBuf>) -> AuditArgs {
+        AuditArgs {
+            tx,
+            input,
+            receipt,
+            block_root: Some(B256::repeat_byte(0x11)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_audit_raw_input_with_receipt_file() {
+        let path = std::env::temp_dir().join(format!("audit-receipt-{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            receipt_json(true, json!([reward_log_json()])).to_string(),
+        )
+        .unwrap();
+
+        let input = encode_claim(&sample_bundle());
+        let audit = audit(
+            &args(None, Some(input), Some(path.clone())),
+            "http://unused",
+        )
+        .await
+        .unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(audit.bundle.source_index, 42);
+        assert_eq!(audit.recipient, Some(Address::repeat_byte(0x42)));
+        assert!(matches!(audit.proofs, Some(ProofCheck::Invalid(_))));
+        assert_eq!(
+            audit.outcome,
+            Some(ClaimOutcome::Rewarded {
+                source_index: 42,
+                recipient: Address::repeat_byte(0x42),
+                amount: U256::from(1_000u64),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_audit_rejects_non_claim_input() {
+        let input = Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]);
+        assert!(audit(&args(None, Some(input), None), "http://unused")
+            .await
+            .is_err());
+    }
+
+    fn rpc(method_name: &str, result: serde_json::Value) -> Mock {
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "method": method_name })))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({ "jsonrpc": "2.0", "id": 0, "result": result })),
+            )
+    }
+
+    /// Serve a failed claim transaction and its receipt
+    async fn mount_failed_claim(server: &MockServer) {
+        let input = encode_claim(&sample_bundle());
+        rpc(
+            "eth_getTransactionByHash",
+            json!({
+                "hash": TX_HASH,
+                "nonce": "0x0",
+                "blockHash": BLOCK_HASH,
+                "blockNumber": "0x10",
+                "transactionIndex": "0x0",
+                "from": SENDER,
+                "to": CONTRACT,
+                "value": "0x0",
+                "gasPrice": "0x1",
+                "gas": "0x100000",
+                "input": input,
+                "v": "0xeb",
+                "r": "0x1",
+                "s": "0x1",
+                "chainId": "0x64",
+                "type": "0x0",
+            }),
+        )
+        .mount(server)
+        .await;
+        rpc("eth_getTransactionReceipt", receipt_json(false, json!([])))
+            .mount(server)
+            .await;
+    }
+
+    fn already_claimed() -> Bytes {
+        Bytes::from(ConsolidationIncentives::AlreadyClaimed { sourceIndex: 42 }.abi_encode())
+    }
+
+    async fn audit_tx(server: &MockServer) -> ClaimAudit {
+        let hash: B256 = TX_HASH.parse().unwrap();
+        audit(&args(Some(hash), None, None), &server.uri())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_audit_tx_traces_failed_claim_in_its_block() {
+        let server = MockServer::start().await;
+        mount_failed_claim(&server).await;
+        // On the parent block the claim would have succeeded; an earlier
+        // transaction in the same block claimed the source first
+        rpc("eth_call", json!("0x")).mount(&server).await;
+        rpc(
+            "debug_traceTransaction",
+            json!({
+                "type": "CALL",
+                "from": SENDER,
+                "to": CONTRACT,
+                "gas": "0x100000",
+                "gasUsed": "0x5208",
+                "input": "0x",
+                "output": already_claimed(),
+                "error": "execution reverted",
+            }),
+        )
+        .mount(&server)
+        .await;
+
+        let audit = audit_tx(&server).await;
+
+        let Some(ClaimOutcome::Reverted { reason }) = audit.outcome else {
+            panic!("expected a reverted claim, got {:?}", audit.outcome);
+        };
+        assert!(reason.unwrap().contains("AlreadyClaimed"));
+    }
+
+    #[tokio::test]
+    async fn test_audit_tx_replays_failed_claim_without_debug_namespace() {
+        let server = MockServer::start().await;
+        mount_failed_claim(&server).await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "method": "debug_traceTransaction" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "error": { "code": -32601, "message": "the method debug_traceTransaction does not exist" },
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "method": "eth_call" })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "error": {
+                    "code": 3,
+                    "message": "execution reverted",
+                    "data": already_claimed(),
+                },
+            })))
+            .mount(&server)
+            .await;
+
+        let audit = audit_tx(&server).await;
+
+        assert_eq!(audit.bundle.consolidation_index, 3);
+        let Some(ClaimOutcome::Reverted { reason }) = audit.outcome else {
+            panic!("expected a reverted claim, got {:?}", audit.outcome);
+        };
+        assert!(reason.unwrap().contains("AlreadyClaimed"));
+    }
+}
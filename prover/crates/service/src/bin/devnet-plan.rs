@@ -8,24 +8,11 @@ use alloy::{
 };
 use anyhow::{Context, Result};
 use clap::{Parser, ValueEnum};
+use contract_bindings::ConsolidationIncentives;
 use serde::Deserialize;
 use std::{fmt::Write as _, fs, path::PathBuf};
 
 sol! {
-    #[sol(rpc)]
-    contract ConsolidationIncentives {
-        function claimReward(
-            uint64 beaconTimestamp,
-            uint64 consolidationIndex,
-            uint64 sourceIndex,
-            uint64 activationEpoch,
-            bytes32 sourceCredentials,
-            bytes32[] calldata proofConsolidation,
-            bytes32[] calldata proofCredentials,
-            bytes32[] calldata proofActivationEpoch
-        ) external;
-    }
-
     #[sol(rpc)]
     contract MockBeaconRootsOracle {
         function setRoot(uint256 timestamp, bytes32 root) external;
@@ -2,22 +2,67 @@
 //!
 //! REST API and auto-submitter for consolidation reward claims.
 
+mod admin;
 mod api;
+mod audit;
 mod scanner;
 mod state;
 mod submitter;
+mod webhook;
 
 use anyhow::Result;
 use clap::Parser;
+use proof_gen::beacon_client::{BeaconClientBuilder, ClientMode, RetryPolicy};
+use proof_gen::BeaconClient;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[derive(Parser, Debug)]
 #[command(name = "consolidation-service")]
 #[command(about = "Auto-submitter service for Gnosis consolidation incentives")]
 struct Args {
-    /// Beacon node URL
-    #[arg(long, env = "BEACON_URL", default_value = "http://localhost:5052")]
-    beacon_url: String,
+    /// Beacon node URLs (comma-separated)
+    #[arg(
+        long = "beacon-url",
+        env = "BEACON_URL",
+        value_delimiter = ',',
+        default_value = "http://localhost:5052"
+    )]
+    beacon_urls: Vec<String>,
+
+    /// How to use several beacon nodes [default: single for one URL, failover otherwise]
+    #[arg(long, env = "BEACON_MODE", value_enum)]
+    beacon_mode: Option<BeaconMode>,
+
+    /// Beacon nodes that must agree in quorum mode [default: majority]
+    #[arg(long, env = "BEACON_QUORUM")]
+    beacon_quorum: Option<usize>,
+
+    /// Rotate failover away from beacon nodes slower than this (milliseconds)
+    #[arg(long, env = "BEACON_LATENCY_THRESHOLD_MS")]
+    beacon_latency_threshold_ms: Option<u64>,
+
+    /// Timeout for each beacon request attempt (seconds)
+    #[arg(long, env = "BEACON_TIMEOUT_SECS", default_value_t = 30)]
+    beacon_timeout_secs: u64,
+
+    /// Retries for beacon timeouts, connection errors, 429 and 5xx (honours Retry-After)
+    #[arg(long, env = "BEACON_MAX_RETRIES", default_value_t = 3)]
+    beacon_max_retries: u32,
+
+    /// Extra headers sent to beacon nodes, as `Name: value` (comma-separated)
+    #[arg(long = "beacon-header", env = "BEACON_HEADERS", value_delimiter = ',')]
+    beacon_headers: Vec<String>,
+
+    /// HTTP basic auth for beacon nodes, as `user:password`
+    #[arg(long, env = "BEACON_BASIC_AUTH")]
+    beacon_basic_auth: Option<String>,
+
+    /// Scan on beacon node finality events, polling only while the stream is down
+    #[arg(long, env = "BEACON_EVENTS", default_value_t = true, action = clap::ArgAction::Set)]
+    beacon_events: bool,
 
     /// Gnosis RPC URL
     #[arg(long, env = "RPC_URL", default_value = "https://rpc.gnosis.gateway.fm")]
@@ -31,6 +76,23 @@ struct Args {
     #[arg(long, env = "PRIVATE_KEY")]
     private_key: Option<String>,
 
+    /// Skip submissions while the gas price is above this (gwei)
+    #[arg(long, env = "MAX_GAS_PRICE_GWEI", default_value_t = 100)]
+    max_gas_price_gwei: u64,
+
+    /// Confirmations to wait for before a claim counts as confirmed (0 = once included)
+    #[arg(long, env = "CONFIRMATIONS", default_value_t = 1)]
+    confirmations: u64,
+
+    /// Pause the submitter when the signer balance drops below this (wei);
+    /// resume with `POST /admin/submitter/resume`
+    #[arg(long, env = "MIN_BALANCE_WEI", default_value_t = 0)]
+    min_balance_wei: u128,
+
+    /// Seconds between submitter rounds
+    #[arg(long, env = "SUBMIT_INTERVAL_SECS", default_value_t = 30)]
+    submit_interval_secs: u64,
+
     /// API listen address
     #[arg(long, env = "LISTEN", default_value = "0.0.0.0:8080")]
     listen: String,
@@ -38,6 +100,123 @@ struct Args {
     /// Metrics listen address
     #[arg(long, env = "METRICS_LISTEN", default_value = "0.0.0.0:9090")]
     metrics_listen: String,
+
+    /// Bearer token for the /admin API (admin API disabled if unset)
+    #[arg(long, env = "ADMIN_TOKEN")]
+    admin_token: Option<String>,
+
+    /// JSONL audit log for admin actions
+    #[arg(long, env = "AUDIT_LOG")]
+    audit_log: Option<PathBuf>,
+
+    /// Epoch to backfill from on startup (e.g. the program snapshot epoch)
+    #[arg(long, env = "BACKFILL_START_EPOCH")]
+    backfill_start_epoch: Option<u64>,
+
+    /// Slot stride between historical states visited while backfilling
+    #[arg(long, env = "BACKFILL_STEP_SLOTS", default_value_t = 16)]
+    backfill_step_slots: u64,
+
+    /// File persisting the last scanned finalized epoch; backfill resumes after it
+    #[arg(long, env = "SCAN_CURSOR")]
+    scan_cursor: Option<PathBuf>,
+
+    /// Webhook endpoints receiving claim lifecycle events (comma-separated)
+    #[arg(long, env = "WEBHOOK_URLS", value_delimiter = ',')]
+    webhook_urls: Vec<String>,
+
+    /// Shared secret for webhook HMAC-SHA256 signatures
+    #[arg(long, env = "WEBHOOK_SECRET")]
+    webhook_secret: Option<String>,
+
+    /// JSONL file for webhook deliveries that exhausted their retries
+    #[arg(long, env = "WEBHOOK_DEAD_LETTER")]
+    webhook_dead_letter: Option<PathBuf>,
+
+    /// Contract maxEpoch, enables the deadline-approaching notification
+    #[arg(long, env = "MAX_EPOCH")]
+    max_epoch: Option<u64>,
+
+    /// Epochs before maxEpoch at which to notify
+    #[arg(long, env = "DEADLINE_WARNING_EPOCHS", default_value_t = 1080)]
+    deadline_warning_epochs: u64,
+
+    /// Trusted block root (0x-hex) to bootstrap a light client from; finalized
+    /// anchors and backfilled states must then be on the signed chain
+    #[arg(long, env = "LIGHT_CLIENT_CHECKPOINT")]
+    light_client_checkpoint: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// One-off commands; without one the service runs
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Decode a claimReward transaction, re-verify its proofs and report the outcome
+    Audit(audit::AuditArgs),
+}
+
+/// Beacon node dispatch mode, see [`ClientMode`]
+#[derive(clap::ValueEnum, Debug, Clone, Copy)]
+enum BeaconMode {
+    Single,
+    Failover,
+    Quorum,
+}
+
+impl Args {
+    fn client_mode(&self) -> ClientMode {
+        let endpoints = self.beacon_urls.len();
+        match self.beacon_mode {
+            Some(BeaconMode::Single) => ClientMode::Single,
+            Some(BeaconMode::Failover) => ClientMode::Failover,
+            Some(BeaconMode::Quorum) => ClientMode::Quorum {
+                threshold: self.beacon_quorum.unwrap_or(endpoints / 2 + 1),
+            },
+            None if endpoints > 1 => ClientMode::Failover,
+            None => ClientMode::Single,
+        }
+    }
+
+    fn beacon_client(&self) -> Result<BeaconClientBuilder> {
+        let mut builder = BeaconClient::builder()
+            .endpoints(&self.beacon_urls)
+            .mode(self.client_mode())
+            .request_timeout(Duration::from_secs(self.beacon_timeout_secs))
+            .retry(RetryPolicy {
+                max_retries: self.beacon_max_retries,
+                ..Default::default()
+            });
+        if let Some(threshold) = self.beacon_latency_threshold_ms {
+            builder = builder.latency_threshold(Duration::from_millis(threshold));
+        }
+        for header in &self.beacon_headers {
+            let (name, value) = header
+                .split_once(':')
+                .ok_or_else(|| anyhow::anyhow!("beacon header `{header}` is not `Name: value`"))?;
+            builder = builder.header(name.trim(), value.trim());
+        }
+        if let Some(credentials) = &self.beacon_basic_auth {
+            builder = match credentials.split_once(':') {
+                Some((user, password)) => builder.basic_auth(user, Some(password.to_string())),
+                None => builder.basic_auth(credentials, None),
+            };
+        }
+        Ok(builder)
+    }
+
+    fn light_client_checkpoint(&self) -> Result<Option<[u8; 32]>> {
+        self.light_client_checkpoint
+            .as_deref()
+            .map(|root| {
+                let bytes = hex::decode(root.strip_prefix("0x").unwrap_or(root))?;
+                <[u8; 32]>::try_from(bytes).map_err(|_| {
+                    anyhow::anyhow!("light client checkpoint `{root}` is not a 32-byte root")
+                })
+            })
+            .transpose()
+    }
 }
 
 #[tokio::main]
@@ -53,36 +232,113 @@ async fn main() -> Result<()> {
 
     let args = Args::parse();
 
+    if let Some(Command::Audit(audit_args)) = &args.command {
+        return audit::run(audit_args, &args.rpc_url).await;
+    }
+
     tracing::info!("Starting consolidation incentives service");
-    tracing::info!(beacon_url = %args.beacon_url, "Beacon node");
+    tracing::info!(
+        beacon_urls = ?args.beacon_urls,
+        mode = ?args.client_mode(),
+        "Beacon nodes"
+    );
     tracing::info!(listen = %args.listen, "API server");
     tracing::info!(metrics_listen = %args.metrics_listen, "Metrics server");
 
+    // Warn early if a scheduled fork moves gindices the contract hardcodes
+    proof_gen::ForkRegistry::gnosis().check_contract_compatibility();
+
     // Initialize application state
     let app_state = state::AppState::new();
 
+    // Admin API (only mounted when a token is configured)
+    let admin_router = match &args.admin_token {
+        Some(token) => {
+            let config = admin::AdminConfig {
+                token: token.clone(),
+                audit_log_path: args.audit_log.clone(),
+            };
+            let audit = admin::AuditLog::open(config.audit_log_path.as_deref()).await?;
+            tracing::info!("Admin API enabled at /admin");
+            Some(admin::create_admin_router(
+                app_state.clone(),
+                &config,
+                Arc::new(audit),
+            ))
+        }
+        None => {
+            tracing::info!("Admin API disabled (no ADMIN_TOKEN)");
+            None
+        }
+    };
+
     // Start API + metrics servers
-    let api_handle = tokio::spawn(api::run_server(args.listen.clone(), app_state.clone()));
+    let api_handle = tokio::spawn(api::run_server(
+        args.listen.clone(),
+        app_state.clone(),
+        admin_router,
+    ));
     let metrics_handle = tokio::spawn(api::run_metrics_server(
         args.metrics_listen.clone(),
         app_state.clone(),
     ));
 
-    // Start beacon scanner (detection only; proof+submission require deployed contract)
+    // Start webhook dispatcher (subscribe before any producer starts)
+    if !args.webhook_urls.is_empty() {
+        let dispatcher = Arc::new(webhook::WebhookDispatcher::new(webhook::WebhookConfig {
+            urls: args.webhook_urls.clone(),
+            secret: args.webhook_secret.clone(),
+            dead_letter_path: args.webhook_dead_letter.clone(),
+            ..Default::default()
+        })?);
+        tokio::spawn(dispatcher.run(app_state.subscribe_events()));
+    }
+
+    // Submitter, when a contract and signer are configured
+    let submitter = match (&args.contract_address, &args.private_key) {
+        (Some(contract_address), Some(private_key)) => Some(submitter::Submitter::with_signer(
+            submitter::SubmitterConfig {
+                rpc_url: args.rpc_url.clone(),
+                contract_address: contract_address.clone(),
+                private_key: Some(private_key.clone()),
+                max_gas_price_gwei: args.max_gas_price_gwei,
+                confirmations: args.confirmations,
+                min_balance_wei: alloy::primitives::U256::from(args.min_balance_wei),
+            },
+        )?),
+        _ => {
+            tracing::info!("Submitter disabled (no CONTRACT_ADDRESS or PRIVATE_KEY)");
+            None
+        }
+    };
+
+    // Start beacon scanner (proves detected consolidations only if there is a submitter)
     let scanner = scanner::Scanner::new(
         scanner::ScannerConfig {
-            beacon_url: args.beacon_url.clone(),
+            beacon: args.beacon_client()?,
+            use_events: args.beacon_events,
+            max_epoch: args.max_epoch,
+            deadline_warning_epochs: args.deadline_warning_epochs,
+            backfill_start_epoch: args.backfill_start_epoch,
+            backfill_step_slots: args.backfill_step_slots,
+            cursor_path: args.scan_cursor.clone(),
+            light_client_checkpoint: args.light_client_checkpoint()?,
+            prove_claims: submitter.is_some(),
             ..Default::default()
         },
         app_state.clone(),
-    );
+    )?;
     let scanner_handle = tokio::spawn(async move {
         if let Err(e) = scanner.run().await {
             tracing::error!(error = %e, "Scanner exited");
         }
     });
 
-    // TODO: Start submitter when contract is deployed
+    if let Some(submitter) = submitter {
+        let state = app_state.clone();
+        let interval = Duration::from_secs(args.submit_interval_secs);
+        tokio::spawn(async move { submitter.run(state, interval).await });
+    }
 
     // Wait for shutdown
     tokio::select! {
@@ -1,32 +1,84 @@
 //! Beacon Chain Scanner
 //!
 //! Continuously monitors the beacon chain for new consolidations.
+//!
+//! On startup the scanner can backfill historical finalized states from a
+//! configured epoch (or a persisted cursor) so consolidations that entered and
+//! left `pending_consolidations` while the service was down are still seen.
+//!
+//! With `prove_claims` set, detected consolidations are then proven against
+//! the state they were seen in and handed to the submitter as `ProofBuilt`.
 
-use crate::state::{AppState, ClaimStatus, ConsolidationRecord};
+use crate::state::{AppState, ClaimStatus, ConsolidationRecord, FinalizedAnchor, LifecycleEvent};
 use anyhow::Result;
-use proof_gen::{BeaconClient, PendingConsolidationJson};
-use std::sync::atomic::{AtomicU64, Ordering};
+use proof_gen::beacon_client::{BeaconClientBuilder, BeaconClientError};
+use proof_gen::events::{BeaconEvent, EventTopic, SubscriptionUpdate};
+use proof_gen::light_client::LightClientError;
+use proof_gen::scan::{
+    build_scan_slots, fetch_pending_consolidations_at_or_before, ScanDirection, ScanError,
+};
+use proof_gen::state_source::parse_state;
+use proof_gen::types::preset::SECONDS_PER_SLOT;
+use proof_gen::types::BeaconBlockHeader;
+use proof_gen::{
+    BeaconClient, BundleContext, FinalityCheckpoints, FullBeaconBlockHeader, LightClient,
+    LightClientConfig, PendingConsolidationJson, ProofBundleV1, StateProver,
+};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::Duration;
-use tokio::time::sleep;
-use tracing::{error, info, instrument};
+use tokio::sync::{mpsc, Mutex};
+use tokio::time::{sleep, sleep_until, timeout_at, Instant};
+use tracing::{debug, error, info, instrument, warn};
 
 /// Scanner configuration
 #[derive(Debug, Clone)]
 pub struct ScannerConfig {
-    /// Beacon node URL
-    pub beacon_url: String,
-    /// Polling interval
+    /// Beacon nodes, dispatch mode, timeouts, retries and auth
+    pub beacon: BeaconClientBuilder,
+    /// Polling interval, used while no event stream is connected
     pub poll_interval: Duration,
+    /// Scan on the beacon node's `finalized_checkpoint` events instead of polling
+    pub use_events: bool,
+    /// With events connected, scan anyway after this long without a finalized checkpoint
+    pub event_timeout: Duration,
     /// Slots per epoch (Gnosis = 16)
     pub slots_per_epoch: u64,
+    /// Contract `maxEpoch`, if known; enables the deadline warning
+    pub max_epoch: Option<u64>,
+    /// Warn once the finalized epoch is within this many epochs of `max_epoch`
+    pub deadline_warning_epochs: u64,
+    /// First epoch to backfill on startup (e.g. the program snapshot epoch)
+    pub backfill_start_epoch: Option<u64>,
+    /// Slot stride between states visited while backfilling
+    pub backfill_step_slots: u64,
+    /// File persisting the last fully scanned finalized epoch
+    pub cursor_path: Option<PathBuf>,
+    /// Trusted block root to bootstrap a light client from; finalized anchors
+    /// and backfilled states must then be on the chain the sync committee signed
+    pub light_client_checkpoint: Option<[u8; 32]>,
+    /// Build proof bundles for detected consolidations (needs the debug state endpoint)
+    pub prove_claims: bool,
 }
 
 impl Default for ScannerConfig {
     fn default() -> Self {
         Self {
-            beacon_url: "http://localhost:5052".to_string(),
+            beacon: BeaconClient::builder().endpoint("http://localhost:5052"),
             poll_interval: Duration::from_secs(5),
+            use_events: true,
+            // 1.5 Gnosis epochs
+            event_timeout: Duration::from_secs(120),
             slots_per_epoch: 16,
+            max_epoch: None,
+            // ~1 day at 16 slots x 5s
+            deadline_warning_epochs: 1080,
+            backfill_start_epoch: None,
+            backfill_step_slots: 16,
+            cursor_path: None,
+            light_client_checkpoint: None,
+            prove_claims: false,
         }
     }
 }
@@ -37,18 +89,29 @@ pub struct Scanner {
     client: BeaconClient,
     state: AppState,
     last_finalized_epoch: AtomicU64,
+    deadline_warned: AtomicBool,
+    light_client: Mutex<Option<LightClient>>,
+    /// Set once the startup backfill missed a state; the cursor then stays
+    /// before it so the next start rescans from there
+    cursor_held: AtomicBool,
 }
 
 impl Scanner {
     /// Create a new scanner
-    pub fn new(config: ScannerConfig, state: AppState) -> Self {
-        let client = BeaconClient::new(&config.beacon_url);
-        Self {
+    ///
+    /// # Errors
+    /// Returns error if the beacon client configuration is invalid
+    pub fn new(config: ScannerConfig, state: AppState) -> Result<Self> {
+        let client = config.beacon.clone().build()?;
+        Ok(Self {
             config,
             client,
             state,
             last_finalized_epoch: AtomicU64::new(0),
-        }
+            deadline_warned: AtomicBool::new(false),
+            light_client: Mutex::new(None),
+            cursor_held: AtomicBool::new(false),
+        })
     }
 
     /// Run the scanner loop
@@ -56,6 +119,21 @@ impl Scanner {
     pub async fn run(&self) -> Result<()> {
         info!("Starting beacon chain scanner");
 
+        if let Err(e) = self.startup_backfill().await {
+            error!(error = %e, "Startup backfill failed; continuing with live scanning");
+            self.state.set_error(Some(e.to_string()));
+            self.cursor_held.store(true, Ordering::Relaxed);
+        }
+
+        let mut events = self.config.use_events.then(|| {
+            self.client.subscribe_events(&[
+                EventTopic::Head,
+                EventTopic::FinalizedCheckpoint,
+                EventTopic::ChainReorg,
+            ])
+        });
+        let mut streaming = false;
+
         loop {
             if let Err(e) = self.poll_once().await {
                 error!(error = %e, "Scanner poll failed");
@@ -64,10 +142,92 @@ impl Scanner {
                 self.state.set_error(None);
             }
 
-            sleep(self.config.poll_interval).await;
+            while let Some((from_epoch, to_epoch)) = self.state.next_backfill() {
+                self.backfill(from_epoch, to_epoch).await;
+            }
+
+            if self.config.prove_claims {
+                self.prove_detected().await;
+            }
+
+            self.publish_beacon_health();
+
+            match events.as_mut() {
+                Some(events) => self.wait_for_event(events, &mut streaming).await,
+                None => sleep(self.config.poll_interval).await,
+            }
+        }
+    }
+
+    /// Wait until the next scan is due
+    ///
+    /// While the event stream is connected that is the next finalized
+    /// checkpoint, or `event_timeout` as a safety net. Otherwise it is
+    /// `poll_interval`, cut short if the stream (re)connects.
+    async fn wait_for_event(
+        &self,
+        events: &mut mpsc::Receiver<SubscriptionUpdate>,
+        streaming: &mut bool,
+    ) {
+        let wait = if *streaming {
+            self.config.event_timeout
+        } else {
+            self.config.poll_interval
+        };
+        let deadline = Instant::now() + wait;
+
+        loop {
+            let update = match timeout_at(deadline, events.recv()).await {
+                Ok(Some(update)) => update,
+                Ok(None) => {
+                    // Subscription task is gone; keep polling
+                    *streaming = false;
+                    sleep_until(deadline).await;
+                    return;
+                }
+                Err(_) => return,
+            };
+
+            match update {
+                SubscriptionUpdate::Connected { endpoint } => {
+                    info!(%endpoint, "Beacon event stream connected");
+                    *streaming = true;
+                }
+                SubscriptionUpdate::Disconnected { error } => {
+                    warn!(%error, "Beacon event stream dropped; falling back to polling");
+                    *streaming = false;
+                    return;
+                }
+                SubscriptionUpdate::Event(BeaconEvent::FinalizedCheckpoint(checkpoint)) => {
+                    debug!(epoch = checkpoint.epoch, "Finalized checkpoint event");
+                    return;
+                }
+                SubscriptionUpdate::Event(BeaconEvent::Head(head)) => {
+                    self.state.set_head_slot(head.slot);
+                }
+                SubscriptionUpdate::Event(BeaconEvent::ChainReorg(reorg)) => {
+                    warn!(
+                        slot = reorg.slot,
+                        depth = reorg.depth,
+                        new_head = %format!("0x{}", hex::encode(reorg.new_head_block)),
+                        "Chain reorg"
+                    );
+                }
+            }
         }
     }
 
+    /// Copy beacon endpoint statistics into the shared state for `/status`
+    fn publish_beacon_health(&self) {
+        self.state.set_beacon_endpoints(
+            self.client
+                .endpoint_health()
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        );
+    }
+
     /// Single poll iteration
     async fn poll_once(&self) -> Result<()> {
         // Get current head
@@ -77,23 +237,25 @@ impl Scanner {
         // Get finality checkpoints
         let checkpoints = self.client.get_finality_checkpoints().await?;
         let finalized_epoch = checkpoints.finalized_epoch;
-
-        // Calculate finalized slot
-        let finalized_slot = finalized_epoch * self.config.slots_per_epoch;
-        self.state.set_current_slot(finalized_slot);
         self.state.set_current_epoch(finalized_epoch);
 
+        self.check_deadline(finalized_epoch);
+
         // Only process each finalized epoch once
         let last = self.last_finalized_epoch.load(Ordering::Relaxed);
         if finalized_epoch <= last {
             return Ok(());
         }
 
+        let anchor = self.resolve_finalized_anchor(&checkpoints).await?;
+        self.state.set_current_slot(anchor.slot);
+
         // NOTE: This uses the standard Beacon API endpoint introduced in Electra.
-        // This avoids requiring the debug SSZ state endpoint.
+        // This avoids requiring the debug SSZ state endpoint. The state is
+        // addressed by root so it is exactly the one the anchor block commits to.
         let consolidations = self
             .client
-            .get_pending_consolidations(&finalized_slot.to_string())
+            .get_pending_consolidations(&anchor.state_root)
             .await?;
 
         if consolidations.is_empty() {
@@ -101,28 +263,539 @@ impl Scanner {
         } else {
             info!(
                 epoch = finalized_epoch,
+                slot = anchor.slot,
                 count = consolidations.len(),
                 "Fetched pending consolidations"
             );
-            self.process_consolidations(consolidations, finalized_epoch);
+            self.process_consolidations(
+                consolidations,
+                finalized_epoch,
+                Some(&anchor.block_root),
+                Some(&anchor.state_root),
+            );
         }
 
+        self.state.set_finalized_anchor(anchor);
         self.last_finalized_epoch
             .store(finalized_epoch, Ordering::Relaxed);
+        self.save_cursor(finalized_epoch);
 
         Ok(())
     }
 
+    /// Resolve the finalized checkpoint to the block it points at
+    ///
+    /// The epoch's first slot may be empty, so the block is looked up by
+    /// `finalized_root` rather than `finalized_epoch * slots_per_epoch`. Before
+    /// the first checkpoint (zero root) the node's `finalized` header is used.
+    async fn resolve_finalized_anchor(
+        &self,
+        checkpoints: &FinalityCheckpoints,
+    ) -> Result<FinalizedAnchor> {
+        let checkpoint_root = checkpoints.finalized_root;
+        let block_id = if checkpoint_root == [0u8; 32] {
+            "finalized".to_string()
+        } else {
+            format!("0x{}", hex::encode(checkpoint_root))
+        };
+
+        // Rejects a header that does not hash to the checkpoint root
+        let (block_root, header) = self.client.get_header_with_root(&block_id).await?;
+        self.verify_with_light_client(block_root, header.slot)
+            .await?;
+
+        let epoch_start_slot = checkpoints.finalized_epoch * self.config.slots_per_epoch;
+        if header.slot != epoch_start_slot {
+            debug!(
+                epoch_start_slot,
+                block_slot = header.slot,
+                "Finalized checkpoint slot was skipped; anchoring to earlier block"
+            );
+        }
+
+        Ok(FinalizedAnchor {
+            epoch: checkpoints.finalized_epoch,
+            slot: header.slot,
+            block_root: format!("0x{}", hex::encode(block_root)),
+            state_root: format!("0x{}", hex::encode(header.state_root)),
+        })
+    }
+
+    /// Check `block_root` at `slot` against the sync committee, if a light
+    /// client is configured
+    ///
+    /// The light client is bootstrapped from `light_client_checkpoint` on first
+    /// use. Blocks at or before its latest signed header are accepted when they
+    /// are that header or one of its ancestors; the light client only catches
+    /// up with the node's light-client updates for newer blocks. Returns the
+    /// verified header, or `None` without a light client.
+    async fn verify_with_light_client(
+        &self,
+        block_root: [u8; 32],
+        slot: u64,
+    ) -> Result<Option<BeaconBlockHeader>> {
+        let Some(checkpoint) = self.config.light_client_checkpoint else {
+            return Ok(None);
+        };
+        let mut light_client = self.light_client.lock().await;
+        if light_client.is_none() {
+            let bootstrapped =
+                LightClient::bootstrap_from(&self.client, LightClientConfig::gnosis(), checkpoint)
+                    .await?;
+            info!(
+                slot = bootstrapped.finalized_header().slot,
+                "Light client bootstrapped"
+            );
+            *light_client = Some(bootstrapped);
+        }
+        let light_client = light_client
+            .as_mut()
+            .expect("light client bootstrapped above");
+        if slot > light_client.optimistic_header().slot
+            && light_client.verify_block_root(block_root).is_err()
+        {
+            light_client.sync(&self.client).await?;
+        }
+        let header = light_client
+            .verify_ancestor(&self.client, block_root, slot)
+            .await?;
+        Ok(Some(header))
+    }
+
+    /// Epoch the startup backfill begins at: after the stored cursor, but never
+    /// before `backfill_start_epoch`. `None` disables the backfill.
+    fn backfill_start(&self) -> Option<u64> {
+        let cursor = self.config.cursor_path.as_deref().and_then(load_cursor);
+        match (cursor, self.config.backfill_start_epoch) {
+            (Some(cursor), Some(start)) => Some(cursor.saturating_add(1).max(start)),
+            (Some(cursor), None) => Some(cursor.saturating_add(1)),
+            (None, start) => start,
+        }
+    }
+
+    /// Scan from the backfill start to the current finalized epoch
+    async fn startup_backfill(&self) -> Result<()> {
+        let Some(start_epoch) = self.backfill_start() else {
+            return Ok(());
+        };
+
+        let finalized_epoch = self
+            .client
+            .get_finality_checkpoints()
+            .await?
+            .finalized_epoch;
+        if start_epoch > finalized_epoch {
+            debug!(start_epoch, finalized_epoch, "Nothing to backfill");
+            return Ok(());
+        }
+
+        self.scan_epochs(start_epoch, finalized_epoch, true).await;
+        self.last_finalized_epoch
+            .store(finalized_epoch, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Scan an inclusive epoch range requested by an operator
+    async fn backfill(&self, from_epoch: u64, to_epoch: u64) {
+        self.scan_epochs(from_epoch, to_epoch, false).await;
+    }
+
+    /// Visit historical states in `from_epoch..=to_epoch`, oldest first
+    ///
+    /// States are sampled every `backfill_step_slots` using the same slot plan
+    /// as `fetch-and-prove`; skipped slots fall back to the previous state in the
+    /// stride. With a light client, only states of verified blocks are read.
+    /// Pruned or failing states are logged and skipped so one bad state does
+    /// not abort the range. With `advance_cursor`, the cursor is moved
+    /// forward as each state is processed, up to the first state that failed;
+    /// it is then held before that state for the rest of the run.
+    #[instrument(skip(self))]
+    async fn scan_epochs(&self, from_epoch: u64, to_epoch: u64, advance_cursor: bool) {
+        let slots_per_epoch = self.config.slots_per_epoch;
+        let step = self.config.backfill_step_slots.max(1);
+        let start_slot = from_epoch * slots_per_epoch;
+        let end_slot = to_epoch * slots_per_epoch;
+        let slots = build_scan_slots(start_slot, end_slot, step, ScanDirection::Forward, end_slot);
+
+        info!(slots = slots.len(), step, "Starting backfill");
+
+        let mut pruned = 0usize;
+        for slot in slots {
+            let minimum_slot = slot.saturating_sub(step - 1).max(start_slot);
+            match self.fetch_backfill_state(slot, minimum_slot).await {
+                Ok((resolved_slot, state_id, consolidations)) => {
+                    self.process_consolidations(
+                        consolidations,
+                        resolved_slot / slots_per_epoch,
+                        None,
+                        Some(&state_id),
+                    );
+                    if advance_cursor {
+                        self.save_cursor(slot / slots_per_epoch);
+                    }
+                    continue;
+                }
+                Err(e) => {
+                    if let Some(ScanError::Pruned(pruned_slot)) = e.downcast_ref::<ScanError>() {
+                        pruned += 1;
+                        debug!(slot = pruned_slot, "State pruned; skipping");
+                    } else {
+                        warn!(slot, error = %e, "Backfill state failed; skipping");
+                    }
+                }
+            }
+
+            if advance_cursor {
+                self.hold_cursor_before(slot / slots_per_epoch);
+            }
+        }
+
+        if pruned > 0 {
+            warn!(
+                pruned,
+                "Beacon node has pruned historical states; use an archive node to cover them"
+            );
+        }
+        info!("Backfill complete");
+    }
+
+    /// Read `pending_consolidations` for a backfill slot, walking back over
+    /// skipped slots until `minimum_slot`; returns the slot and id of the
+    /// state read
+    ///
+    /// With a light client configured, the latest block at or before `slot`
+    /// must be on the chain the sync committee signed, and the list is read
+    /// from that block's state root rather than by slot.
+    async fn fetch_backfill_state(
+        &self,
+        slot: u64,
+        minimum_slot: u64,
+    ) -> Result<(u64, String, Vec<PendingConsolidationJson>)> {
+        if self.config.light_client_checkpoint.is_none() {
+            let (slot, consolidations) =
+                fetch_pending_consolidations_at_or_before(&self.client, slot, minimum_slot).await?;
+            return Ok((slot, slot.to_string(), consolidations));
+        }
+
+        let mut block_slot = slot;
+        let (block_root, header) = loop {
+            match self
+                .client
+                .get_header_with_root(&block_slot.to_string())
+                .await
+            {
+                Ok(found) => break found,
+                Err(e) if e.is_not_found() && block_slot > minimum_slot => block_slot -= 1,
+                Err(e) if e.is_not_found() => {
+                    return Err(ScanError::NoStateInWindow {
+                        requested_slot: slot,
+                        minimum_slot,
+                    }
+                    .into());
+                }
+                Err(e) => return Err(e.into()),
+            }
+        };
+        self.verify_with_light_client(block_root, header.slot)
+            .await?;
+
+        let state_id = format!("0x{}", hex::encode(header.state_root));
+        match self.client.get_pending_consolidations(&state_id).await {
+            Ok(consolidations) => Ok((header.slot, state_id, consolidations)),
+            Err(e) if e.is_not_found() => Err(ScanError::Pruned(header.slot).into()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Stop advancing the cursor and move it back before `epoch` if needed
+    ///
+    /// Only the first call has an effect, so the cursor ends up before the
+    /// earliest epoch that was not scanned.
+    fn hold_cursor_before(&self, epoch: u64) {
+        if self.cursor_held.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        let Some(path) = &self.config.cursor_path else {
+            return;
+        };
+        warn!(epoch, "Holding scan cursor before an unscanned epoch");
+        match epoch.checked_sub(1) {
+            Some(previous) if load_cursor(path).is_some_and(|cursor| cursor > previous) => {
+                write_cursor(path, previous);
+            }
+            // Nothing before epoch 0: forget the cursor and restart from the configured epoch
+            None if path.exists() => {
+                if let Err(e) = std::fs::remove_file(path) {
+                    warn!(path = %path.display(), error = %e, "Failed to reset scan cursor");
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Persist the last fully scanned finalized epoch, unless the cursor is held
+    fn save_cursor(&self, epoch: u64) {
+        let Some(path) = &self.config.cursor_path else {
+            return;
+        };
+        if self.cursor_held.load(Ordering::Relaxed) {
+            debug!(epoch, "Scan cursor held; not advancing");
+            return;
+        }
+        write_cursor(path, epoch);
+    }
+
+    /// Build proof bundles for `Detected` records
+    ///
+    /// Records are proven against the state an operator asked for via
+    /// `/admin/consolidations/{i}/reprove`, else the state they were seen in,
+    /// else the finalized anchor; each state is downloaded once. Records whose
+    /// source is not in `pending_consolidations` there, or that cannot be
+    /// proven, are marked `Failed`. When the beacon node is unavailable they
+    /// stay `Detected` and are retried on the next round.
+    async fn prove_detected(&self) {
+        let anchor_state = self.state.finalized_anchor().map(|a| a.state_root);
+        let mut by_state: BTreeMap<String, Vec<u64>> = BTreeMap::new();
+        let mut requested = BTreeSet::new();
+        for record in self.state.all_consolidations() {
+            let source_index = record.source_index;
+            if record.status != ClaimStatus::Detected || self.state.is_ignored(source_index) {
+                continue;
+            }
+            let state_id = if let Some(state_id) = self.state.take_reprove_request(source_index) {
+                requested.insert(source_index);
+                state_id
+            } else if let Some(state_id) = record.state_seen.or_else(|| anchor_state.clone()) {
+                state_id
+            } else {
+                continue;
+            };
+            by_state.entry(state_id).or_default().push(source_index);
+        }
+
+        for (state_id, sources) in by_state {
+            self.prove_sources(&state_id, &sources, &requested).await;
+        }
+    }
+
+    /// Prove `sources` at `state_id` and record each bundle or failure
+    ///
+    /// On a transient error the sources stay `Detected`, and those in
+    /// `requested` keep their re-prove request.
+    async fn prove_sources(&self, state_id: &str, sources: &[u64], requested: &BTreeSet<u64>) {
+        let bundles = match self.prove_at_state(state_id, sources).await {
+            Ok(bundles) => bundles,
+            Err(e) if is_transient(&e) => {
+                warn!(%state_id, error = %e, "Failed to prove consolidations; retrying next round");
+                for &source_index in sources {
+                    if requested.contains(&source_index) {
+                        self.state
+                            .request_reprove(source_index, state_id.to_string());
+                    }
+                    self.state.update_claim_status(
+                        source_index,
+                        ClaimStatus::Detected,
+                        None,
+                        Some(format!("{e:#}")),
+                    );
+                }
+                return;
+            }
+            Err(e) => {
+                warn!(%state_id, error = %e, "Failed to prove consolidations");
+                sources
+                    .iter()
+                    .map(|&source| (source, Err(format!("{e:#}"))))
+                    .collect()
+            }
+        };
+        for (source_index, bundle) in bundles {
+            match bundle {
+                Ok(bundle) => {
+                    info!(source = source_index, %state_id, "Proof built");
+                    self.state.record_proof(bundle);
+                }
+                Err(error) => {
+                    warn!(source = source_index, %state_id, %error, "Proof failed");
+                    self.state.update_claim_status(
+                        source_index,
+                        ClaimStatus::Failed,
+                        None,
+                        Some(error),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Prove the pending consolidations of `sources` in the state `state_id`
+    ///
+    /// The state must be the post-state of a block; its header is looked up
+    /// by slot and checked against the state root. A slot without a block
+    /// stands for the state of the latest block before it, which still lists
+    /// every consolidation pending at the slot (epoch processing only removes
+    /// entries). With a light client, the
+    /// block and the child that fixes the EIP-4788 timestamp must be on the
+    /// signed chain, and their verified headers are used. Bundles carry the
+    /// block as their Gnosis context.
+    async fn prove_at_state(
+        &self,
+        state_id: &str,
+        sources: &[u64],
+    ) -> Result<Vec<(u64, Result<ProofBundleV1, String>)>> {
+        let state_id = match state_id.parse::<u64>() {
+            Ok(slot) => self.block_state_at_or_before(slot).await?,
+            Err(_) => state_id.to_string(),
+        };
+        let state_id = state_id.as_str();
+        let state = parse_state(&self.client.get_state_ssz(state_id).await?)?;
+        let prover = StateProver::from_state(&state)?;
+        let (block_root, header) = self
+            .client
+            .get_header_with_root(&state.slot().to_string())
+            .await?;
+        let header = self
+            .verify_with_light_client(block_root, header.slot)
+            .await?
+            .unwrap_or(header);
+        if header.slot != state.slot() || header.state_root != prover.compute_state_root() {
+            anyhow::bail!(
+                "state {state_id} at slot {} is not the post-state of a block",
+                state.slot()
+            );
+        }
+        let beacon_timestamp = self
+            .child_block_timestamp(block_root, header.slot, state.genesis_time())
+            .await?;
+        let context = BundleContext::gnosis(block_root, header.slot);
+        let header = FullBeaconBlockHeader {
+            slot: header.slot,
+            proposer_index: header.proposer_index,
+            parent_root: header.parent_root,
+            state_root: header.state_root,
+            body_root: header.body_root,
+        };
+
+        let pending = state.pending_consolidations();
+        Ok(sources
+            .iter()
+            .map(|&source_index| {
+                let bundle = pending
+                    .iter()
+                    .position(|c| c.source_index == source_index)
+                    .ok_or_else(|| {
+                        format!("source {source_index} is not pending at state {state_id}")
+                    })
+                    .and_then(|index| {
+                        prover
+                            .generate_full_proof_bundle(&header, index, beacon_timestamp)
+                            .map(|bundle| ProofBundleV1::new(context, bundle))
+                            .map_err(|e| e.to_string())
+                    });
+                (source_index, bundle)
+            })
+            .collect())
+    }
+
+    /// State root of the latest block at or before `slot`, within four epochs
+    async fn block_state_at_or_before(&self, slot: u64) -> Result<String> {
+        let minimum_slot = slot.saturating_sub(4 * self.config.slots_per_epoch);
+        for block_slot in (minimum_slot..=slot).rev() {
+            match self
+                .client
+                .get_header_with_root(&block_slot.to_string())
+                .await
+            {
+                Ok((_, header)) => return Ok(format!("0x{}", hex::encode(header.state_root))),
+                Err(e) if e.is_not_found() => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        anyhow::bail!("no block in slots {minimum_slot}..={slot}")
+    }
+
+    /// EIP-4788 timestamp under which `block_root` is stored
+    ///
+    /// The beacon roots contract records each block root as the parent root of
+    /// the next block, keyed by that block's timestamp, so this is the slot time
+    /// of the first block after `slot`. With a light client, that block must be
+    /// on the signed chain.
+    async fn child_block_timestamp(
+        &self,
+        block_root: [u8; 32],
+        slot: u64,
+        genesis_time: u64,
+    ) -> Result<u64> {
+        for child_slot in slot + 1..=slot + 4 * self.config.slots_per_epoch {
+            match self
+                .client
+                .get_header_with_root(&child_slot.to_string())
+                .await
+            {
+                Ok((child_root, child)) => {
+                    let child = self
+                        .verify_with_light_client(child_root, child.slot)
+                        .await?
+                        .unwrap_or(child);
+                    if child.parent_root != block_root {
+                        anyhow::bail!(
+                            "block at slot {child_slot} does not build on 0x{}",
+                            hex::encode(block_root)
+                        );
+                    }
+                    return Ok(genesis_time + child.slot * SECONDS_PER_SLOT);
+                }
+                Err(e) if e.is_not_found() => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        anyhow::bail!("no block builds on slot {slot} yet")
+    }
+
+    /// Publish `DeadlineApproaching` once the finalized epoch enters the warning window
+    fn check_deadline(&self, finalized_epoch: u64) {
+        let Some(max_epoch) = self.config.max_epoch else {
+            return;
+        };
+        let epochs_remaining = max_epoch.saturating_sub(finalized_epoch);
+        if epochs_remaining > self.config.deadline_warning_epochs
+            || self.deadline_warned.swap(true, Ordering::Relaxed)
+        {
+            return;
+        }
+
+        warn!(
+            finalized_epoch,
+            max_epoch, epochs_remaining, "Claim deadline approaching"
+        );
+        self.state
+            .publish_event(LifecycleEvent::DeadlineApproaching {
+                finalized_epoch,
+                max_epoch,
+                epochs_remaining,
+            });
+    }
+
     /// Process new consolidations found in beacon state
-    #[allow(dead_code)]
-    fn process_consolidations(&self, consolidations: Vec<PendingConsolidationJson>, epoch: u64) {
+    ///
+    /// `anchor_block_root` is the block whose state the list was read from,
+    /// when known, and `state_seen` the id of that state.
+    fn process_consolidations(
+        &self,
+        consolidations: Vec<PendingConsolidationJson>,
+        epoch: u64,
+        anchor_block_root: Option<&str>,
+        state_seen: Option<&str>,
+    ) {
         for PendingConsolidationJson {
             source_index,
             target_index,
         } in consolidations
         {
-            // Skip if already tracked
-            if self.state.get_consolidation(source_index).is_some() {
+            // Skip if already tracked or excluded by an operator
+            if self.state.is_ignored(source_index)
+                || self.state.get_consolidation(source_index).is_some()
+            {
                 continue;
             }
 
@@ -140,21 +813,1153 @@ impl Scanner {
                 status: ClaimStatus::Detected,
                 tx_hash: None,
                 error: None,
+                anchor_block_root: anchor_block_root.map(str::to_string),
+                state_seen: state_seen.map(str::to_string),
             };
 
             self.state.upsert_consolidation(record);
+            self.state
+                .publish_event(LifecycleEvent::ConsolidationDetected {
+                    source_index,
+                    target_index,
+                    epoch,
+                });
         }
     }
 }
 
+/// Whether proving may succeed on a later round: the beacon node timed out,
+/// was unreachable or overloaded, or no quorum was reached
+fn is_transient(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        if let Some(e) = cause.downcast_ref::<BeaconClientError>() {
+            e.is_retryable()
+        } else if let Some(LightClientError::Beacon(e)) = cause.downcast_ref::<LightClientError>() {
+            e.is_retryable()
+        } else {
+            false
+        }
+    })
+}
+
+/// Read a cursor file; missing or unreadable cursors restart from the configured epoch
+fn load_cursor(path: &Path) -> Option<u64> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    match contents.trim().parse() {
+        Ok(epoch) => Some(epoch),
+        Err(e) => {
+            warn!(path = %path.display(), error = %e, "Ignoring malformed scan cursor");
+            None
+        }
+    }
+}
+
+/// Write a cursor file
+fn write_cursor(path: &Path, epoch: u64) {
+    // Write-then-rename so a crash never leaves a truncated cursor
+    let tmp = path.with_extension("tmp");
+    if let Err(e) =
+        std::fs::write(&tmp, epoch.to_string()).and_then(|()| std::fs::rename(&tmp, path))
+    {
+        warn!(path = %path.display(), error = %e, "Failed to save scan cursor");
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proof_gen::beacon_client::ClientMode;
 
     #[test]
     fn test_scanner_config_default() {
         let config = ScannerConfig::default();
         assert_eq!(config.slots_per_epoch, 16);
         assert_eq!(config.poll_interval, Duration::from_secs(5));
+        assert!(config.use_events);
+        assert_eq!(config.max_epoch, None);
+    }
+
+    #[test]
+    fn test_process_consolidations_publishes_detected_once() {
+        let state = AppState::new();
+        let mut events = state.subscribe_events();
+        let scanner = Scanner::new(ScannerConfig::default(), state.clone()).unwrap();
+
+        let batch = vec![PendingConsolidationJson {
+            source_index: 5,
+            target_index: 6,
+        }];
+        scanner.process_consolidations(batch.clone(), 10, None, None);
+        scanner.process_consolidations(batch, 11, None, None);
+
+        assert_eq!(
+            events.try_recv().unwrap(),
+            LifecycleEvent::ConsolidationDetected {
+                source_index: 5,
+                target_index: 6,
+                epoch: 10,
+            }
+        );
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_process_consolidations_skips_ignored() {
+        let state = AppState::new();
+        state.ignore_source(5);
+        let scanner = Scanner::new(ScannerConfig::default(), state.clone()).unwrap();
+
+        scanner.process_consolidations(
+            vec![
+                PendingConsolidationJson {
+                    source_index: 5,
+                    target_index: 6,
+                },
+                PendingConsolidationJson {
+                    source_index: 7,
+                    target_index: 8,
+                },
+            ],
+            10,
+            None,
+            None,
+        );
+
+        assert!(state.get_consolidation(5).is_none());
+        assert!(state.get_consolidation(7).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_backfill_scans_each_epoch_and_skips_failures() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/states/32/pending_consolidations"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{"source_index": "11", "target_index": "12"}]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/states/48/pending_consolidations"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/states/64/pending_consolidations"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{"source_index": "13", "target_index": "14"}]
+            })))
+            .mount(&server)
+            .await;
+
+        let state = AppState::new();
+        let scanner = Scanner::new(
+            ScannerConfig {
+                beacon: BeaconClient::builder().endpoint(server.uri()).retry(
+                    proof_gen::beacon_client::RetryPolicy {
+                        max_retries: 0,
+                        ..Default::default()
+                    },
+                ),
+                ..Default::default()
+            },
+            state.clone(),
+        )
+        .unwrap();
+
+        scanner.backfill(2, 4).await;
+
+        assert_eq!(state.get_consolidation(11).unwrap().epoch_seen, 2);
+        assert_eq!(state.get_consolidation(13).unwrap().epoch_seen, 4);
+        assert_eq!(state.all_consolidations().len(), 2);
+    }
+
+    /// Mount head, finality and header mocks for a finalized checkpoint whose
+    /// block sits at `block_slot`; the checkpoint names `forged_root` instead
+    /// of the block, if set. Returns the block's root
+    async fn mount_finalized_block(
+        server: &wiremock::MockServer,
+        forged_root: Option<[u8; 32]>,
+        block_slot: u64,
+        state_root: [u8; 32],
+    ) -> [u8; 32] {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, ResponseTemplate};
+
+        let zero = format!("0x{}", "00".repeat(32));
+        let header = |slot: u64| {
+            header_response(&proof_gen::BeaconBlockHeader {
+                slot,
+                proposer_index: 1,
+                state_root,
+                ..Default::default()
+            })
+        };
+        let (block_root, block_json) = header(block_slot);
+        let checkpoint_root = forged_root.unwrap_or(block_root);
+
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/headers/head"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(header(80).1))
+            .mount(server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/states/head/finality_checkpoints"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": {
+                    "previous_justified": {"epoch": "3", "root": zero},
+                    "current_justified": {"epoch": "4", "root": zero},
+                    "finalized": {"epoch": "4", "root": format!("0x{}", hex::encode(checkpoint_root))}
+                }
+            })))
+            .mount(server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/eth/v1/beacon/headers/0x{}",
+                hex::encode(checkpoint_root)
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_json(block_json))
+            .mount(server)
+            .await;
+        block_root
+    }
+
+    #[tokio::test]
+    async fn test_poll_once_anchors_to_finalized_block_when_epoch_slot_skipped() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        // Epoch 4 starts at slot 64, which was skipped; the checkpoint block is at 62
+        let block_root = mount_finalized_block(&server, None, 62, [0xbb; 32]).await;
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/eth/v1/beacon/states/0x{}/pending_consolidations",
+                "bb".repeat(32)
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [{"source_index": "31", "target_index": "32"}]
+            })))
+            .mount(&server)
+            .await;
+
+        let state = AppState::new();
+        let scanner = Scanner::new(
+            ScannerConfig {
+                beacon: BeaconClient::builder().endpoint(server.uri()),
+                ..Default::default()
+            },
+            state.clone(),
+        )
+        .unwrap();
+
+        scanner.poll_once().await.unwrap();
+
+        let block_root = format!("0x{}", hex::encode(block_root));
+        assert_eq!(state.current_slot(), 62);
+        assert_eq!(state.current_epoch(), 4);
+        let anchor = state.finalized_anchor().unwrap();
+        assert_eq!(anchor.slot, 62);
+        assert_eq!(anchor.block_root, block_root);
+        assert_eq!(anchor.state_root, format!("0x{}", "bb".repeat(32)));
+
+        let record = state.get_consolidation(31).unwrap();
+        assert_eq!(record.epoch_seen, 4);
+        assert_eq!(record.anchor_block_root, Some(block_root));
+    }
+
+    #[tokio::test]
+    async fn test_poll_once_fails_over_and_publishes_beacon_health() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        mount_finalized_block(&server, None, 64, [0xbb; 32]).await;
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/eth/v1/beacon/states/0x{}/pending_consolidations",
+                "bb".repeat(32)
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": []
+            })))
+            .mount(&server)
+            .await;
+
+        let state = AppState::new();
+        let scanner = Scanner::new(
+            ScannerConfig {
+                beacon: BeaconClient::builder()
+                    .endpoints(["http://127.0.0.1:1".to_string(), server.uri()])
+                    .mode(ClientMode::Failover)
+                    .retry(proof_gen::beacon_client::RetryPolicy {
+                        max_retries: 0,
+                        ..Default::default()
+                    }),
+                ..Default::default()
+            },
+            state.clone(),
+        )
+        .unwrap();
+
+        scanner.poll_once().await.unwrap();
+        scanner.publish_beacon_health();
+
+        assert_eq!(state.current_epoch(), 4);
+        let endpoints = state.beacon_endpoints();
+        assert_eq!(endpoints.len(), 2);
+        assert_eq!(endpoints[0].failures, 1);
+        assert!(endpoints[0].last_error.is_some());
+        assert!(endpoints[1].preferred);
+        assert!(endpoints[1].successes >= 4);
+    }
+
+    fn finalized_event(epoch: u64) -> SubscriptionUpdate {
+        SubscriptionUpdate::Event(BeaconEvent::FinalizedCheckpoint(
+            proof_gen::events::FinalizedCheckpointEvent {
+                epoch,
+                block: [0; 32],
+                state: [0; 32],
+            },
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_event_returns_on_finality_and_tracks_head() {
+        let state = AppState::new();
+        let scanner = Scanner::new(
+            ScannerConfig {
+                poll_interval: Duration::from_secs(60),
+                event_timeout: Duration::from_secs(60),
+                ..Default::default()
+            },
+            state.clone(),
+        )
+        .unwrap();
+        let (tx, mut rx) = mpsc::channel(8);
+        let mut streaming = false;
+
+        tx.send(SubscriptionUpdate::Connected {
+            endpoint: "http://beacon".to_string(),
+        })
+        .await
+        .unwrap();
+        tx.send(SubscriptionUpdate::Event(BeaconEvent::Head(
+            proof_gen::events::HeadEvent {
+                slot: 321,
+                block: [0; 32],
+                state: [0; 32],
+                epoch_transition: false,
+            },
+        )))
+        .await
+        .unwrap();
+        tx.send(finalized_event(20)).await.unwrap();
+
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            scanner.wait_for_event(&mut rx, &mut streaming),
+        )
+        .await
+        .expect("finalized checkpoint should end the wait");
+        assert!(streaming);
+        assert_eq!(state.head_slot(), 321);
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_event_falls_back_to_polling_on_disconnect() {
+        let scanner = Scanner::new(
+            ScannerConfig {
+                poll_interval: Duration::from_millis(20),
+                event_timeout: Duration::from_secs(60),
+                ..Default::default()
+            },
+            AppState::new(),
+        )
+        .unwrap();
+        let (tx, mut rx) = mpsc::channel(8);
+        let mut streaming = true;
+
+        tx.send(SubscriptionUpdate::Disconnected {
+            error: "eof".to_string(),
+        })
+        .await
+        .unwrap();
+        scanner.wait_for_event(&mut rx, &mut streaming).await;
+        assert!(!streaming);
+
+        // Without a stream the wait is just the poll interval
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            scanner.wait_for_event(&mut rx, &mut streaming),
+        )
+        .await
+        .expect("polling wait should time out after poll_interval");
+        assert!(!streaming);
+    }
+
+    #[test]
+    fn test_new_rejects_mismatched_beacon_mode() {
+        let config = ScannerConfig {
+            beacon: BeaconClient::builder().endpoints(["http://a", "http://b"]),
+            ..Default::default()
+        };
+        assert!(Scanner::new(config.clone(), AppState::new()).is_err());
+        assert!(Scanner::new(
+            ScannerConfig {
+                beacon: config.beacon.mode(ClientMode::Quorum { threshold: 2 }),
+                ..Default::default()
+            },
+            AppState::new()
+        )
+        .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_poll_once_rejects_header_for_wrong_root() {
+        use wiremock::MockServer;
+
+        let server = MockServer::start().await;
+        mount_finalized_block(&server, Some([0xaa; 32]), 64, [0xbb; 32]).await;
+
+        let state = AppState::new();
+        let scanner = Scanner::new(
+            ScannerConfig {
+                beacon: BeaconClient::builder().endpoint(server.uri()),
+                ..Default::default()
+            },
+            state.clone(),
+        )
+        .unwrap();
+
+        let error = scanner.poll_once().await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<BeaconClientError>(),
+            Some(BeaconClientError::BlockRootMismatch { requested, .. }) if *requested == [0xaa; 32]
+        ));
+        assert_eq!(state.finalized_anchor(), None);
+        assert_eq!(scanner.last_finalized_epoch.load(Ordering::Relaxed), 0);
+    }
+
+    /// `/eth/v1/beacon/headers/{id}` response for `header`, and its root
+    fn header_response(header: &proof_gen::BeaconBlockHeader) -> ([u8; 32], serde_json::Value) {
+        use ssz_rs::prelude::*;
+
+        let root: [u8; 32] = header.hash_tree_root().unwrap().into();
+        let hex = |bytes: &[u8]| format!("0x{}", hex::encode(bytes));
+        let body = serde_json::json!({
+            "data": {
+                "root": hex(&root),
+                "header": {"message": {
+                    "slot": header.slot.to_string(),
+                    "proposer_index": header.proposer_index.to_string(),
+                    "parent_root": hex(&header.parent_root),
+                    "state_root": hex(&header.state_root),
+                    "body_root": hex(&header.body_root)
+                }}
+            }
+        });
+        (root, body)
+    }
+
+    async fn mount_json(server: &wiremock::MockServer, route: String, body: serde_json::Value) {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, ResponseTemplate};
+
+        Mock::given(method("GET"))
+            .and(path(route))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(server)
+            .await;
+    }
+
+    /// Light-client endpoints serving `update`, expected `expected` times
+    async fn mount_light_client_updates(
+        server: &wiremock::MockServer,
+        update: &proof_gen::light_client::LightClientUpdate,
+        expected: u64,
+    ) {
+        use proof_gen::light_client::sync_committee_period;
+        use proof_gen::synthetic_light_client::update_json;
+        use wiremock::matchers::{method, path, query_param};
+        use wiremock::{Mock, ResponseTemplate};
+
+        let period = sync_committee_period(update.attested_header.slot);
+        let versioned = serde_json::json!({"version": "electra", "data": update_json(update)});
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/light_client/updates"))
+            .and(query_param("start_period", period.to_string()))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!([versioned.clone()])),
+            )
+            .expect(expected)
+            .mount(server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/light_client/finality_update"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(versioned))
+            .expect(expected)
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_light_client_verifies_anchors_and_backfill_against_signed_headers() {
+        use proof_gen::synthetic_light_client::{
+            bootstrap_json, synthetic_header, SyntheticLightClientChain,
+        };
+        use wiremock::MockServer;
+
+        let chain = SyntheticLightClientChain::new();
+        let epoch = chain.start / 16;
+        // Backfilled block <- checkpoint block <- trusted bootstrap block
+        let grandparent = synthetic_header(chain.start, [0x0a; 32]);
+        let (grandparent_root, grandparent_json) = header_response(&grandparent);
+        let mut parent = synthetic_header(chain.start + 8, [0x0b; 32]);
+        parent.parent_root = grandparent_root;
+        let (parent_root, parent_json) = header_response(&parent);
+        let (_, mut bootstrap) = chain.bootstrap();
+        bootstrap.header.parent_root = parent_root;
+        let (trusted, _) = header_response(&bootstrap.header);
+        let update = chain.update(0, 32, 64, 0, 400);
+        let finalized = update.finalized_header.clone().unwrap();
+        let (finalized_root, finalized_json) = header_response(&finalized);
+
+        let server = MockServer::start().await;
+        let mount_node = |checkpoint_epoch: u64, checkpoint_root: [u8; 32]| {
+            let zero = format!("0x{}", "00".repeat(32));
+            let mut routes = vec![
+                ("head".to_string(), finalized_json.clone()),
+                (chain.start.to_string(), grandparent_json.clone()),
+                (
+                    format!("0x{}", hex::encode(grandparent_root)),
+                    grandparent_json.clone(),
+                ),
+                (
+                    format!("0x{}", hex::encode(parent_root)),
+                    parent_json.clone(),
+                ),
+                (
+                    format!("0x{}", hex::encode(finalized_root)),
+                    finalized_json.clone(),
+                ),
+            ]
+            .into_iter()
+            .map(|(id, body)| (format!("/eth/v1/beacon/headers/{id}"), body))
+            .collect::<Vec<_>>();
+            routes.push((
+                "/eth/v1/beacon/states/head/finality_checkpoints".to_string(),
+                serde_json::json!({
+                    "data": {
+                        "previous_justified": {"epoch": "0", "root": zero},
+                        "current_justified": {"epoch": "0", "root": zero},
+                        "finalized": {
+                            "epoch": checkpoint_epoch.to_string(),
+                            "root": format!("0x{}", hex::encode(checkpoint_root))
+                        }
+                    }
+                }),
+            ));
+            routes.push((
+                format!(
+                    "/eth/v1/beacon/light_client/bootstrap/0x{}",
+                    hex::encode(trusted)
+                ),
+                serde_json::json!({"version": "electra", "data": bootstrap_json(&bootstrap)}),
+            ));
+            for (state_root, source) in [([0x0a; 32], 41), ([0x0b; 32], 31), ([0x0f; 32], 51)] {
+                routes.push((
+                    format!(
+                        "/eth/v1/beacon/states/0x{}/pending_consolidations",
+                        hex::encode(state_root)
+                    ),
+                    serde_json::json!({
+                        "data": [{"source_index": source.to_string(), "target_index": "1"}]
+                    }),
+                ));
+            }
+            routes
+        };
+
+        let state = AppState::new();
+        let scanner = Scanner::new(
+            ScannerConfig {
+                beacon: BeaconClient::builder().endpoint(server.uri()),
+                light_client_checkpoint: Some(trusted),
+                ..Default::default()
+            },
+            state.clone(),
+        )
+        .unwrap();
+
+        // The checkpoint is an ancestor of the trusted header: no sync needed
+        for (route, body) in mount_node(epoch, parent_root) {
+            mount_json(&server, route, body).await;
+        }
+        mount_light_client_updates(&server, &update, 0).await;
+        scanner.poll_once().await.unwrap();
+        assert_eq!(state.finalized_anchor().unwrap().slot, parent.slot);
+        assert!(state.get_consolidation(31).is_some());
+
+        // Backfilled states are read from blocks on the verified chain
+        scanner.backfill(epoch, epoch).await;
+        assert!(state.get_consolidation(41).is_some());
+        server.verify().await;
+
+        // A checkpoint past the signed headers makes the light client catch up
+        server.reset().await;
+        for (route, body) in mount_node(finalized.slot / 16, finalized_root) {
+            mount_json(&server, route, body).await;
+        }
+        mount_light_client_updates(&server, &update, 1).await;
+        scanner.poll_once().await.unwrap();
+        assert_eq!(state.finalized_anchor().unwrap().slot, finalized.slot);
+        assert!(state.get_consolidation(51).is_some());
+        server.verify().await;
+    }
+
+    fn temp_cursor(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("scan-cursor-{name}-{}.txt", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_backfill_start_prefers_cursor_over_start_epoch() {
+        let cursor = temp_cursor("start");
+        let scanner = |start: Option<u64>| {
+            Scanner::new(
+                ScannerConfig {
+                    backfill_start_epoch: start,
+                    cursor_path: Some(cursor.clone()),
+                    ..Default::default()
+                },
+                AppState::new(),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(scanner(None).backfill_start(), None);
+        assert_eq!(scanner(Some(100)).backfill_start(), Some(100));
+
+        std::fs::write(&cursor, "250\n").unwrap();
+        assert_eq!(scanner(None).backfill_start(), Some(251));
+        assert_eq!(scanner(Some(100)).backfill_start(), Some(251));
+        assert_eq!(scanner(Some(300)).backfill_start(), Some(300));
+
+        std::fs::write(&cursor, "garbage").unwrap();
+        assert_eq!(scanner(Some(100)).backfill_start(), Some(100));
+        std::fs::remove_file(cursor).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_startup_backfill_resumes_from_cursor_and_holds_it_at_pruned_states() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let root = format!("0x{}", "11".repeat(32));
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/states/head/finality_checkpoints"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": {
+                    "previous_justified": {"epoch": "4", "root": root},
+                    "current_justified": {"epoch": "5", "root": root},
+                    "finalized": {"epoch": "4", "root": root}
+                }
+            })))
+            .mount(&server)
+            .await;
+        // Slot 32 has a block but its state is pruned
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/states/32/pending_consolidations"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/headers/32"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": {"header": {"message": {
+                    "slot": "32",
+                    "proposer_index": "1",
+                    "parent_root": root,
+                    "state_root": root,
+                    "body_root": root
+                }}}
+            })))
+            .mount(&server)
+            .await;
+        for (slot, source) in [(48, "21"), (64, "23")] {
+            Mock::given(method("GET"))
+                .and(path(format!(
+                    "/eth/v1/beacon/states/{slot}/pending_consolidations"
+                )))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "data": [{"source_index": source, "target_index": "1"}]
+                })))
+                .mount(&server)
+                .await;
+        }
+
+        let cursor = temp_cursor("resume");
+        std::fs::write(&cursor, "1").unwrap();
+        let state = AppState::new();
+        let scanner = Scanner::new(
+            ScannerConfig {
+                beacon: BeaconClient::builder().endpoint(server.uri()),
+                backfill_start_epoch: Some(0),
+                cursor_path: Some(cursor.clone()),
+                ..Default::default()
+            },
+            state.clone(),
+        )
+        .unwrap();
+
+        scanner.startup_backfill().await.unwrap();
+
+        // Later states are still scanned, but the cursor stays before the pruned one
+        assert_eq!(state.get_consolidation(21).unwrap().epoch_seen, 3);
+        assert_eq!(state.get_consolidation(23).unwrap().epoch_seen, 4);
+        assert_eq!(scanner.last_finalized_epoch.load(Ordering::Relaxed), 4);
+        assert_eq!(std::fs::read_to_string(&cursor).unwrap(), "1");
+
+        // Live polling does not move it past the gap either
+        scanner.save_cursor(5);
+        assert_eq!(std::fs::read_to_string(&cursor).unwrap(), "1");
+        assert_eq!(scanner.backfill_start(), Some(2));
+        std::fs::remove_file(cursor).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_startup_backfill_advances_cursor_when_every_state_scans() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let root = format!("0x{}", "11".repeat(32));
+        Mock::given(method("GET"))
+            .and(path("/eth/v1/beacon/states/head/finality_checkpoints"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": {
+                    "previous_justified": {"epoch": "4", "root": root},
+                    "current_justified": {"epoch": "5", "root": root},
+                    "finalized": {"epoch": "4", "root": root}
+                }
+            })))
+            .mount(&server)
+            .await;
+        for slot in [32, 48, 64] {
+            Mock::given(method("GET"))
+                .and(path(format!(
+                    "/eth/v1/beacon/states/{slot}/pending_consolidations"
+                )))
+                .respond_with(
+                    ResponseTemplate::new(200).set_body_json(serde_json::json!({"data": []})),
+                )
+                .mount(&server)
+                .await;
+        }
+
+        let cursor = temp_cursor("advance");
+        std::fs::write(&cursor, "1").unwrap();
+        let scanner = Scanner::new(
+            ScannerConfig {
+                beacon: BeaconClient::builder().endpoint(server.uri()),
+                cursor_path: Some(cursor.clone()),
+                ..Default::default()
+            },
+            AppState::new(),
+        )
+        .unwrap();
+
+        scanner.startup_backfill().await.unwrap();
+        assert_eq!(std::fs::read_to_string(&cursor).unwrap(), "4");
+
+        scanner.save_cursor(5);
+        assert_eq!(std::fs::read_to_string(&cursor).unwrap(), "5");
+        std::fs::remove_file(cursor).unwrap();
+    }
+
+    #[test]
+    fn test_deadline_warning_fires_once_inside_window() {
+        let state = AppState::new();
+        let mut events = state.subscribe_events();
+        let scanner = Scanner::new(
+            ScannerConfig {
+                max_epoch: Some(1000),
+                deadline_warning_epochs: 100,
+                ..Default::default()
+            },
+            state,
+        )
+        .unwrap();
+
+        scanner.check_deadline(800);
+        assert!(events.try_recv().is_err());
+
+        scanner.check_deadline(900);
+        scanner.check_deadline(950);
+        assert_eq!(
+            events.try_recv().unwrap(),
+            LifecycleEvent::DeadlineApproaching {
+                finalized_epoch: 900,
+                max_epoch: 1000,
+                epochs_remaining: 100,
+            }
+        );
+        assert!(events.try_recv().is_err());
+    }
+
+    /// Serve `synthetic` by state root, and its block and a child block two
+    /// slots later (the slot in between is skipped) by slot and by root;
+    /// returns the child's root
+    async fn mount_synthetic_state(
+        server: &wiremock::MockServer,
+        synthetic: &proof_gen::SyntheticState,
+    ) -> [u8; 32] {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, ResponseTemplate};
+
+        let block = proof_gen::BeaconBlockHeader {
+            slot: synthetic.header.slot,
+            proposer_index: synthetic.header.proposer_index,
+            parent_root: synthetic.header.parent_root,
+            state_root: synthetic.header.state_root,
+            body_root: synthetic.header.body_root,
+        };
+        let child = proof_gen::BeaconBlockHeader {
+            slot: block.slot + 2,
+            parent_root: synthetic.block_root,
+            ..block.clone()
+        };
+        let (block_root, block_json) = header_response(&block);
+        assert_eq!(block_root, synthetic.block_root);
+        let (child_root, child_json) = header_response(&child);
+
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/eth/v2/debug/beacon/states/0x{}",
+                hex::encode(synthetic.header.state_root)
+            )))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_bytes(ssz_rs::serialize(&synthetic.state).unwrap()),
+            )
+            .mount(server)
+            .await;
+        for (header, root, body) in [
+            (&block, block_root, block_json),
+            (&child, child_root, child_json),
+        ] {
+            mount_json(
+                server,
+                format!("/eth/v1/beacon/headers/{}", header.slot),
+                body.clone(),
+            )
+            .await;
+            mount_json(
+                server,
+                format!("/eth/v1/beacon/headers/0x{}", hex::encode(root)),
+                body,
+            )
+            .await;
+        }
+        child_root
+    }
+
+    fn synthetic_state() -> proof_gen::SyntheticState {
+        proof_gen::SyntheticStateConfig {
+            seed: 4,
+            validators: 2_000,
+            pending_consolidations: 10,
+            ..Default::default()
+        }
+        .generate()
+        .unwrap()
+    }
+
+    /// Source index of a validator that is not in the pending consolidations
+    fn non_pending_source(synthetic: &proof_gen::SyntheticState) -> u64 {
+        let pending = &synthetic.state.pending_consolidations;
+        (0..)
+            .find(|i| pending.iter().all(|c| c.source_index != *i))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_prove_detected_builds_bundles_against_anchor_state() {
+        let synthetic = synthetic_state();
+        let state_root = format!("0x{}", hex::encode(synthetic.header.state_root));
+        let server = wiremock::MockServer::start().await;
+        mount_synthetic_state(&server, &synthetic).await;
+
+        let state = AppState::new();
+        let scanner = Scanner::new(
+            ScannerConfig {
+                beacon: BeaconClient::builder().endpoint(server.uri()),
+                prove_claims: true,
+                ..Default::default()
+            },
+            state.clone(),
+        )
+        .unwrap();
+        state.set_finalized_anchor(FinalizedAnchor {
+            epoch: synthetic.header.slot / 16,
+            slot: synthetic.header.slot,
+            block_root: format!("0x{}", hex::encode(synthetic.block_root)),
+            state_root,
+        });
+
+        let pending = synthetic.state.pending_consolidations[3].clone();
+        let missing = non_pending_source(&synthetic);
+        scanner.process_consolidations(
+            vec![
+                PendingConsolidationJson {
+                    source_index: pending.source_index,
+                    target_index: pending.target_index,
+                },
+                PendingConsolidationJson {
+                    source_index: missing,
+                    target_index: pending.target_index,
+                },
+            ],
+            synthetic.header.slot / 16,
+            None,
+            None,
+        );
+        let mut events = state.subscribe_events();
+
+        scanner.prove_detected().await;
+
+        assert_eq!(
+            state
+                .get_consolidation(pending.source_index)
+                .unwrap()
+                .status,
+            ClaimStatus::ProofBuilt
+        );
+        let file = state.proof_bundle(pending.source_index).unwrap();
+        assert_eq!(file.block_root, synthetic.block_root);
+        assert_eq!(file.slot, synthetic.header.slot);
+        assert_eq!(file.bundle.consolidation_index, 3);
+        assert_eq!(
+            file.bundle.beacon_timestamp,
+            synthetic.timestamp() + 2 * SECONDS_PER_SLOT
+        );
+        proof_gen::ProofGenerator::verify_proof_bundle(&file.bundle, synthetic.block_root).unwrap();
+
+        let failed = state.get_consolidation(missing).unwrap();
+        assert_eq!(failed.status, ClaimStatus::Failed);
+        assert!(failed.error.unwrap().contains("not pending"));
+        assert_eq!(events.try_recv().unwrap().name(), "claim_failed");
+    }
+
+    #[tokio::test]
+    async fn test_prove_detected_uses_reprove_state() {
+        let synthetic = synthetic_state();
+        let server = wiremock::MockServer::start().await;
+        // Requested by slot; there is no finalized anchor to fall back on
+        let slot = synthetic.header.slot.to_string();
+        mount_synthetic_state(&server, &synthetic).await;
+
+        let state = AppState::new();
+        let scanner = Scanner::new(
+            ScannerConfig {
+                beacon: BeaconClient::builder().endpoint(server.uri()),
+                prove_claims: true,
+                ..Default::default()
+            },
+            state.clone(),
+        )
+        .unwrap();
+        let pending = synthetic.state.pending_consolidations[0].clone();
+        scanner.process_consolidations(
+            vec![PendingConsolidationJson {
+                source_index: pending.source_index,
+                target_index: pending.target_index,
+            }],
+            synthetic.header.slot / 16,
+            None,
+            None,
+        );
+
+        // Without an anchor or a re-prove request there is nothing to prove against
+        scanner.prove_detected().await;
+        assert_eq!(
+            state
+                .get_consolidation(pending.source_index)
+                .unwrap()
+                .status,
+            ClaimStatus::Detected
+        );
+
+        state.request_reprove(pending.source_index, slot);
+        scanner.prove_detected().await;
+
+        assert_eq!(
+            state
+                .get_consolidation(pending.source_index)
+                .unwrap()
+                .status,
+            ClaimStatus::ProofBuilt
+        );
+        let file = state.proof_bundle(pending.source_index).unwrap();
+        proof_gen::ProofGenerator::verify_proof_bundle(&file.bundle, synthetic.block_root).unwrap();
+        assert_eq!(state.take_reprove_request(pending.source_index), None);
+    }
+
+    #[tokio::test]
+    async fn test_prove_detected_uses_state_seen_and_retries_unavailable_node() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, ResponseTemplate};
+
+        let synthetic = synthetic_state();
+        let slot = synthetic.header.slot;
+        let seen = synthetic.state.pending_consolidations[1].clone();
+        let requested = synthetic.state.pending_consolidations[2].clone();
+        let server = wiremock::MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        let state = AppState::new();
+        let scanner = Scanner::new(
+            ScannerConfig {
+                beacon: BeaconClient::builder().endpoint(server.uri()).retry(
+                    proof_gen::beacon_client::RetryPolicy {
+                        max_retries: 0,
+                        ..Default::default()
+                    },
+                ),
+                prove_claims: true,
+                ..Default::default()
+            },
+            state.clone(),
+        )
+        .unwrap();
+        // Seen by a backfill at the skipped slot after the block; the
+        // finalized anchor has moved on to a state without it
+        scanner.process_consolidations(
+            vec![PendingConsolidationJson {
+                source_index: seen.source_index,
+                target_index: seen.target_index,
+            }],
+            slot / 16,
+            None,
+            Some(&(slot + 1).to_string()),
+        );
+        scanner.process_consolidations(
+            vec![PendingConsolidationJson {
+                source_index: requested.source_index,
+                target_index: requested.target_index,
+            }],
+            slot / 16,
+            None,
+            None,
+        );
+        state.set_finalized_anchor(FinalizedAnchor {
+            epoch: slot / 16 + 10,
+            slot: slot + 160,
+            block_root: format!("0x{}", "aa".repeat(32)),
+            state_root: format!("0x{}", "bb".repeat(32)),
+        });
+        state.request_reprove(requested.source_index, slot.to_string());
+
+        // The node is down: both stay Detected and the request is kept
+        scanner.prove_detected().await;
+        for source_index in [seen.source_index, requested.source_index] {
+            let record = state.get_consolidation(source_index).unwrap();
+            assert_eq!(record.status, ClaimStatus::Detected);
+            assert!(record.error.unwrap().contains("503"));
+        }
+
+        server.reset().await;
+        mount_synthetic_state(&server, &synthetic).await;
+        scanner.prove_detected().await;
+
+        for (source_index, index) in [(seen.source_index, 1), (requested.source_index, 2)] {
+            assert_eq!(
+                state.get_consolidation(source_index).unwrap().status,
+                ClaimStatus::ProofBuilt
+            );
+            let file = state.proof_bundle(source_index).unwrap();
+            assert_eq!(file.block_root, synthetic.block_root);
+            assert_eq!(file.bundle.consolidation_index, index);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prove_detected_only_proves_blocks_on_the_signed_chain() {
+        use proof_gen::synthetic_light_client::{
+            bootstrap_json, synthetic_header, SyntheticLightClientChain,
+        };
+
+        let synthetic = synthetic_state();
+        let slot = synthetic.header.slot.to_string();
+        let pending = synthetic.state.pending_consolidations[0].clone();
+        let chain = SyntheticLightClientChain::new();
+        // A block before the proven one that does not lead to it
+        let fork = synthetic_header(synthetic.header.slot - 1, [0x0c; 32]);
+        let (fork_root, fork_json) = header_response(&fork);
+
+        for (trusted_parent, proven) in [(None, true), (Some(fork_root), false)] {
+            let server = wiremock::MockServer::start().await;
+            let child_root = mount_synthetic_state(&server, &synthetic).await;
+            mount_json(
+                &server,
+                format!("/eth/v1/beacon/headers/0x{}", hex::encode(fork_root)),
+                fork_json.clone(),
+            )
+            .await;
+            // Trusted header <- child <- proven block, or <- fork
+            let (_, mut bootstrap) = chain.bootstrap();
+            bootstrap.header.parent_root = trusted_parent.unwrap_or(child_root);
+            let (trusted, _) = header_response(&bootstrap.header);
+            mount_json(
+                &server,
+                format!(
+                    "/eth/v1/beacon/light_client/bootstrap/0x{}",
+                    hex::encode(trusted)
+                ),
+                serde_json::json!({"version": "electra", "data": bootstrap_json(&bootstrap)}),
+            )
+            .await;
+
+            let state = AppState::new();
+            let scanner = Scanner::new(
+                ScannerConfig {
+                    beacon: BeaconClient::builder().endpoint(server.uri()),
+                    light_client_checkpoint: Some(trusted),
+                    prove_claims: true,
+                    ..Default::default()
+                },
+                state.clone(),
+            )
+            .unwrap();
+            scanner.process_consolidations(
+                vec![PendingConsolidationJson {
+                    source_index: pending.source_index,
+                    target_index: pending.target_index,
+                }],
+                synthetic.header.slot / 16,
+                None,
+                None,
+            );
+            state.request_reprove(pending.source_index, slot.clone());
+            scanner.prove_detected().await;
+
+            assert_eq!(state.proof_bundle(pending.source_index).is_some(), proven);
+            if !proven {
+                let record = state.get_consolidation(pending.source_index).unwrap();
+                assert_ne!(record.status, ClaimStatus::ProofBuilt);
+                assert!(record.error.unwrap().contains("has not been verified"));
+            }
+        }
     }
 }
@@ -2,15 +2,22 @@
 //!
 //! Thread-safe state for tracking consolidations and sync status.
 
-use dashmap::DashMap;
-use parking_lot::RwLock;
+use dashmap::{DashMap, DashSet};
+use parking_lot::{Mutex, RwLock};
+use proof_gen::beacon_client::EndpointHealth;
+use proof_gen::ProofBundleV1;
 use serde::Serialize;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
+use tokio::sync::broadcast;
+use utoipa::ToSchema;
+
+/// Capacity of the lifecycle event channel; slow subscribers past this lag
+const EVENT_CHANNEL_CAPACITY: usize = 1024;
 
 /// Status of a consolidation claim
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
-#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ClaimStatus {
     /// Detected in beacon state
@@ -26,7 +33,7 @@ pub enum ClaimStatus {
 }
 
 /// Record for a tracked consolidation
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 pub struct ConsolidationRecord {
     /// Source validator index
     pub source_index: u64,
@@ -40,6 +47,98 @@ pub struct ConsolidationRecord {
     pub tx_hash: Option<String>,
     /// Error message if failed
     pub error: Option<String>,
+    /// Block root of the finalized block whose state first showed this consolidation
+    pub anchor_block_root: Option<String>,
+    /// State id (state root, or slot) this consolidation was first seen in;
+    /// the proof is built against it
+    pub state_seen: Option<String>,
+}
+
+/// Finalized checkpoint block the scanner last read state from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct FinalizedAnchor {
+    /// Finalized checkpoint epoch
+    pub epoch: u64,
+    /// Slot of the checkpoint block; earlier than the epoch's first slot if that slot was skipped
+    pub slot: u64,
+    /// Checkpoint block root (0x-prefixed hex)
+    pub block_root: String,
+    /// State root of the checkpoint block (0x-prefixed hex)
+    pub state_root: String,
+}
+
+/// Request statistics for one configured beacon node
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct BeaconEndpointStatus {
+    /// Beacon node URL
+    pub url: String,
+    /// Requests the node answered
+    pub successes: u64,
+    /// Requests that failed (transport errors, bad status or bad payload)
+    pub failures: u64,
+    /// Failures since the last answered request
+    pub consecutive_failures: u64,
+    /// Latency of the most recent request in milliseconds
+    pub last_latency_ms: Option<u64>,
+    /// Most recent failure
+    pub last_error: Option<String>,
+    /// Whether failover currently tries this node first
+    pub preferred: bool,
+}
+
+impl From<EndpointHealth> for BeaconEndpointStatus {
+    fn from(health: EndpointHealth) -> Self {
+        Self {
+            url: health.url,
+            successes: health.successes,
+            failures: health.failures,
+            consecutive_failures: health.consecutive_failures,
+            last_latency_ms: health.last_latency_ms,
+            last_error: health.last_error,
+            preferred: health.preferred,
+        }
+    }
+}
+
+/// Claim lifecycle event published to subscribers (e.g. webhooks)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LifecycleEvent {
+    /// New pending consolidation found in a finalized state
+    ConsolidationDetected {
+        source_index: u64,
+        target_index: u64,
+        epoch: u64,
+    },
+    /// Claim transaction confirmed on-chain
+    ClaimConfirmed { source_index: u64, tx_hash: String },
+    /// Claim failed (proof, submission, or revert)
+    ClaimFailed { source_index: u64, error: String },
+    /// Finalized epoch is within the warning window of the contract `maxEpoch`
+    DeadlineApproaching {
+        finalized_epoch: u64,
+        max_epoch: u64,
+        epochs_remaining: u64,
+    },
+    /// Submitter paused because the signer balance is below the configured minimum
+    SubmitterPaused {
+        balance_wei: String,
+        min_balance_wei: String,
+    },
+}
+
+impl LifecycleEvent {
+    /// Stable event name, matching the serialized `type` tag
+    #[must_use]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ConsolidationDetected { .. } => "consolidation_detected",
+            Self::ClaimConfirmed { .. } => "claim_confirmed",
+            Self::ClaimFailed { .. } => "claim_failed",
+            Self::DeadlineApproaching { .. } => "deadline_approaching",
+            Self::SubmitterPaused { .. } => "submitter_paused",
+        }
+    }
 }
 
 /// Shared application state
@@ -58,10 +157,26 @@ struct AppStateInner {
     head_slot: AtomicU64,
     /// Tracked consolidations by source index
     consolidations: DashMap<u64, ConsolidationRecord>,
+    /// Latest proof bundle built for each source index
+    proof_bundles: DashMap<u64, ProofBundleV1>,
     /// Service start time
     start_time: std::time::Instant,
     /// Last error message
     last_error: RwLock<Option<String>>,
+    /// Finalized block the scanner is anchored to
+    finalized_anchor: RwLock<Option<FinalizedAnchor>>,
+    /// Per-node beacon client statistics
+    beacon_endpoints: RwLock<Vec<BeaconEndpointStatus>>,
+    /// Whether the submitter is paused
+    submitter_paused: AtomicBool,
+    /// Lifecycle event fan-out
+    events: broadcast::Sender<LifecycleEvent>,
+    /// Source indices excluded from tracking and claiming
+    ignored: DashSet<u64>,
+    /// Operator-requested re-proves: source index -> beacon state id
+    reprove_requests: DashMap<u64, String>,
+    /// Operator-requested backfill epoch ranges (inclusive), oldest first
+    backfill_queue: Mutex<VecDeque<(u64, u64)>>,
 }
 
 impl AppState {
@@ -74,8 +189,16 @@ impl AppState {
                 current_epoch: AtomicU64::new(0),
                 head_slot: AtomicU64::new(0),
                 consolidations: DashMap::new(),
+                proof_bundles: DashMap::new(),
                 start_time: std::time::Instant::now(),
                 last_error: RwLock::new(None),
+                finalized_anchor: RwLock::new(None),
+                beacon_endpoints: RwLock::new(Vec::new()),
+                submitter_paused: AtomicBool::new(false),
+                events: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+                ignored: DashSet::new(),
+                reprove_requests: DashMap::new(),
+                backfill_queue: Mutex::new(VecDeque::new()),
             }),
         }
     }
@@ -147,6 +270,68 @@ impl AppState {
             .map(|r| r.clone())
     }
 
+    /// Store the proof bundle for a tracked consolidation and mark it `ProofBuilt`
+    ///
+    /// Returns `false` if the source index is not tracked.
+    pub fn record_proof(&self, bundle: ProofBundleV1) -> bool {
+        let source_index = bundle.bundle.source_index;
+        if !self.update_claim_status(source_index, ClaimStatus::ProofBuilt, None, None) {
+            return false;
+        }
+        self.inner.proof_bundles.insert(source_index, bundle);
+        true
+    }
+
+    /// Get the latest proof bundle built for a source index
+    #[must_use]
+    pub fn proof_bundle(&self, source_index: u64) -> Option<ProofBundleV1> {
+        self.inner
+            .proof_bundles
+            .get(&source_index)
+            .map(|b| b.clone())
+    }
+
+    /// Update the status of a tracked consolidation
+    ///
+    /// Publishes `ClaimConfirmed` / `ClaimFailed` on transitions into those
+    /// states. Returns `false` if the source index is not tracked.
+    pub fn update_claim_status(
+        &self,
+        source_index: u64,
+        status: ClaimStatus,
+        tx_hash: Option<String>,
+        error: Option<String>,
+    ) -> bool {
+        let event = {
+            let Some(mut record) = self.inner.consolidations.get_mut(&source_index) else {
+                return false;
+            };
+            let changed = record.status != status;
+            record.status = status;
+            if tx_hash.is_some() {
+                record.tx_hash = tx_hash;
+            }
+            record.error = error;
+
+            match (changed, status) {
+                (true, ClaimStatus::Confirmed) => Some(LifecycleEvent::ClaimConfirmed {
+                    source_index,
+                    tx_hash: record.tx_hash.clone().unwrap_or_default(),
+                }),
+                (true, ClaimStatus::Failed) => Some(LifecycleEvent::ClaimFailed {
+                    source_index,
+                    error: record.error.clone().unwrap_or_default(),
+                }),
+                _ => None,
+            }
+        };
+
+        if let Some(event) = event {
+            self.publish_event(event);
+        }
+        true
+    }
+
     /// Get all consolidations
     #[must_use]
     pub fn all_consolidations(&self) -> Vec<ConsolidationRecord> {
@@ -183,6 +368,102 @@ impl AppState {
     pub fn last_error(&self) -> Option<String> {
         self.inner.last_error.read().clone()
     }
+
+    /// Set the finalized block the scanner is anchored to
+    pub fn set_finalized_anchor(&self, anchor: FinalizedAnchor) {
+        *self.inner.finalized_anchor.write() = Some(anchor);
+    }
+
+    /// Get the finalized block the scanner is anchored to
+    #[must_use]
+    pub fn finalized_anchor(&self) -> Option<FinalizedAnchor> {
+        self.inner.finalized_anchor.read().clone()
+    }
+
+    /// Replace the beacon node statistics snapshot
+    pub fn set_beacon_endpoints(&self, endpoints: Vec<BeaconEndpointStatus>) {
+        *self.inner.beacon_endpoints.write() = endpoints;
+    }
+
+    /// Get the latest beacon node statistics snapshot
+    #[must_use]
+    pub fn beacon_endpoints(&self) -> Vec<BeaconEndpointStatus> {
+        self.inner.beacon_endpoints.read().clone()
+    }
+
+    /// Check if the submitter is paused
+    #[must_use]
+    pub fn submitter_paused(&self) -> bool {
+        self.inner.submitter_paused.load(Ordering::Relaxed)
+    }
+
+    /// Pause or resume the submitter
+    pub fn set_submitter_paused(&self, paused: bool) {
+        self.inner.submitter_paused.store(paused, Ordering::Relaxed);
+    }
+
+    /// Pause the submitter for low balance, publishing `SubmitterPaused` once
+    pub fn pause_submitter_for_low_balance(&self, balance_wei: String, min_balance_wei: String) {
+        if !self.inner.submitter_paused.swap(true, Ordering::Relaxed) {
+            self.publish_event(LifecycleEvent::SubmitterPaused {
+                balance_wei,
+                min_balance_wei,
+            });
+        }
+    }
+
+    /// Mark a source index as ignored; returns `false` if it already was
+    pub fn ignore_source(&self, source_index: u64) -> bool {
+        self.inner.ignored.insert(source_index)
+    }
+
+    /// Clear the ignored mark; returns `false` if it was not set
+    pub fn unignore_source(&self, source_index: u64) -> bool {
+        self.inner.ignored.remove(&source_index).is_some()
+    }
+
+    /// Check if a source index is ignored
+    #[must_use]
+    pub fn is_ignored(&self, source_index: u64) -> bool {
+        self.inner.ignored.contains(&source_index)
+    }
+
+    /// Request that the proof for `source_index` be rebuilt against `state_id`
+    pub fn request_reprove(&self, source_index: u64, state_id: String) {
+        self.inner.reprove_requests.insert(source_index, state_id);
+    }
+
+    /// Take the pending re-prove state id for `source_index`, if any
+    pub fn take_reprove_request(&self, source_index: u64) -> Option<String> {
+        self.inner
+            .reprove_requests
+            .remove(&source_index)
+            .map(|(_, state_id)| state_id)
+    }
+
+    /// Queue an inclusive epoch range for the scanner to backfill
+    pub fn enqueue_backfill(&self, from_epoch: u64, to_epoch: u64) {
+        self.inner
+            .backfill_queue
+            .lock()
+            .push_back((from_epoch, to_epoch));
+    }
+
+    /// Pop the oldest queued backfill range
+    pub fn next_backfill(&self) -> Option<(u64, u64)> {
+        self.inner.backfill_queue.lock().pop_front()
+    }
+
+    /// Subscribe to lifecycle events
+    #[must_use]
+    pub fn subscribe_events(&self) -> broadcast::Receiver<LifecycleEvent> {
+        self.inner.events.subscribe()
+    }
+
+    /// Publish a lifecycle event (dropped if nobody is subscribed)
+    pub fn publish_event(&self, event: LifecycleEvent) {
+        let _ = self.inner.events.send(event);
+    }
 }
 
 impl Default for AppState {
@@ -192,7 +473,7 @@ impl Default for AppState {
 }
 
 /// Counts of consolidations by status
-#[derive(Debug, Default, Clone, Serialize)]
+#[derive(Debug, Default, Clone, Serialize, ToSchema)]
 pub struct StatusCounts {
     pub detected: usize,
     pub proof_built: usize,
@@ -241,6 +522,8 @@ mod tests {
                 status: ClaimStatus::Detected,
                 tx_hash: None,
                 error: None,
+                anchor_block_root: None,
+                state_seen: None,
             },
             ConsolidationRecord {
                 source_index: 43,
@@ -249,6 +532,8 @@ mod tests {
                 status: ClaimStatus::ProofBuilt,
                 tx_hash: None,
                 error: None,
+                anchor_block_root: None,
+                state_seen: None,
             },
             ConsolidationRecord {
                 source_index: 44,
@@ -257,6 +542,8 @@ mod tests {
                 status: ClaimStatus::Submitted,
                 tx_hash: Some("0x1234".to_string()),
                 error: None,
+                anchor_block_root: None,
+                state_seen: None,
             },
             ConsolidationRecord {
                 source_index: 45,
@@ -265,6 +552,8 @@ mod tests {
                 status: ClaimStatus::Confirmed,
                 tx_hash: Some("0x5678".to_string()),
                 error: None,
+                anchor_block_root: None,
+                state_seen: None,
             },
             ConsolidationRecord {
                 source_index: 46,
@@ -273,6 +562,8 @@ mod tests {
                 status: ClaimStatus::Failed,
                 tx_hash: None,
                 error: Some("boom".to_string()),
+                anchor_block_root: None,
+                state_seen: None,
             },
         ];
 
@@ -306,5 +597,118 @@ mod tests {
         assert_eq!(state.last_error(), None);
 
         assert!(state.uptime_secs() <= 1);
+
+        assert_eq!(state.finalized_anchor(), None);
+        let anchor = FinalizedAnchor {
+            epoch: 4,
+            slot: 62,
+            block_root: "0xaa".to_string(),
+            state_root: "0xbb".to_string(),
+        };
+        state.set_finalized_anchor(anchor.clone());
+        assert_eq!(state.finalized_anchor(), Some(anchor));
+    }
+
+    #[test]
+    fn test_update_claim_status_publishes_events() {
+        let state = AppState::new();
+        let mut events = state.subscribe_events();
+
+        assert!(!state.update_claim_status(7, ClaimStatus::Confirmed, None, None));
+
+        state.upsert_consolidation(ConsolidationRecord {
+            source_index: 7,
+            target_index: 8,
+            epoch_seen: 10,
+            status: ClaimStatus::Submitted,
+            tx_hash: Some("0xabc".to_string()),
+            error: None,
+            anchor_block_root: None,
+            state_seen: None,
+        });
+
+        assert!(state.update_claim_status(7, ClaimStatus::Confirmed, None, None));
+        assert_eq!(
+            events.try_recv().unwrap(),
+            LifecycleEvent::ClaimConfirmed {
+                source_index: 7,
+                tx_hash: "0xabc".to_string(),
+            }
+        );
+
+        // No transition, no event
+        assert!(state.update_claim_status(7, ClaimStatus::Confirmed, None, None));
+        assert!(events.try_recv().is_err());
+
+        assert!(state.update_claim_status(
+            7,
+            ClaimStatus::Failed,
+            None,
+            Some("reverted".to_string())
+        ));
+        assert_eq!(
+            events.try_recv().unwrap(),
+            LifecycleEvent::ClaimFailed {
+                source_index: 7,
+                error: "reverted".to_string(),
+            }
+        );
+        assert_eq!(
+            state.get_consolidation(7).unwrap().error.as_deref(),
+            Some("reverted")
+        );
+    }
+
+    #[test]
+    fn test_low_balance_pause_publishes_once() {
+        let state = AppState::new();
+        let mut events = state.subscribe_events();
+        assert!(!state.submitter_paused());
+
+        state.pause_submitter_for_low_balance("1".to_string(), "100".to_string());
+        state.pause_submitter_for_low_balance("1".to_string(), "100".to_string());
+
+        assert!(state.submitter_paused());
+        assert_eq!(events.try_recv().unwrap().name(), "submitter_paused");
+        assert!(events.try_recv().is_err());
+
+        state.set_submitter_paused(false);
+        assert!(!state.submitter_paused());
+    }
+
+    #[test]
+    fn test_operator_controls() {
+        let state = AppState::new();
+
+        assert!(state.ignore_source(9));
+        assert!(!state.ignore_source(9));
+        assert!(state.is_ignored(9));
+        assert!(state.unignore_source(9));
+        assert!(!state.unignore_source(9));
+        assert!(!state.is_ignored(9));
+
+        state.request_reprove(3, "head".to_string());
+        state.request_reprove(3, "12345".to_string());
+        assert_eq!(state.take_reprove_request(3).as_deref(), Some("12345"));
+        assert_eq!(state.take_reprove_request(3), None);
+
+        state.enqueue_backfill(10, 20);
+        state.enqueue_backfill(30, 40);
+        assert_eq!(state.next_backfill(), Some((10, 20)));
+        assert_eq!(state.next_backfill(), Some((30, 40)));
+        assert_eq!(state.next_backfill(), None);
+    }
+
+    #[test]
+    fn test_lifecycle_event_serialization() {
+        let event = LifecycleEvent::ConsolidationDetected {
+            source_index: 1,
+            target_index: 2,
+            epoch: 3,
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], event.name());
+        assert_eq!(json["source_index"], 1);
+        assert_eq!(json["epoch"], 3);
     }
 }
@@ -3,41 +3,28 @@
 //! Transaction Submitter
 //!
 //! Submits consolidation reward claims to the smart contract.
-
+//!
+//! The submit loop picks up `ProofBuilt` records from the scanner, skips the
+//! round while an operator or a low signer balance has paused it, and moves
+//! each record to `Confirmed` or `Failed`. While the gas price is above the
+//! cap, records stay `ProofBuilt` for the next round. Bundles go through
+//! [`Submitter::submit_bundle`], so a bundle proven for another chain or
+//! failing validation is never sent.
+
+use crate::state::{AppState, ClaimStatus};
 use alloy::{
     network::EthereumWallet,
-    primitives::{Address, FixedBytes, B256, U256},
+    primitives::{Address, B256, U256},
     providers::{Provider, ProviderBuilder},
     signers::local::PrivateKeySigner,
-    sol,
 };
 use anyhow::{Context, Result};
-use proof_gen::ConsolidationProofBundle;
-use tracing::{debug, info, instrument};
-
-// Generate contract bindings from ABI
-sol! {
-    #[sol(rpc)]
-    contract ConsolidationIncentives {
-        function claimReward(
-            uint64 beaconTimestamp,
-            uint64 consolidationIndex,
-            uint64 sourceIndex,
-            uint64 activationEpoch,
-            bytes32 sourceCredentials,
-            bytes32[] calldata proofConsolidation,
-            bytes32[] calldata proofCredentials,
-            bytes32[] calldata proofActivationEpoch
-        ) external;
-
-        function rewarded(uint64 sourceIndex) external view returns (bool);
-        function rewardAmount() external view returns (uint256);
-        function maxEpoch() external view returns (uint64);
-    }
-}
+use contract_bindings::{claimRewardCall, ConsolidationIncentives};
+use proof_gen::{ConsolidationProofBundle, ProofBundleV1};
+use std::time::Duration;
+use tracing::{debug, error, info, instrument, warn};
 
 /// Submitter configuration
-#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub struct SubmitterConfig {
     /// Gnosis RPC URL
@@ -48,12 +35,21 @@ pub struct SubmitterConfig {
     pub private_key: Option<String>,
     /// Max gas price in Gwei
     pub max_gas_price_gwei: u64,
-    /// Wait for confirmations (0 = don't wait)
+    /// Confirmations to wait for (0 = confirmed once included)
     pub confirmations: u64,
+    /// Pause submissions when the signer balance drops below this (wei)
+    pub min_balance_wei: U256,
+}
+
+/// The gas price is above `max_gas_price_gwei`; nothing was sent
+#[derive(Debug, thiserror::Error)]
+#[error("Gas price {gas_price_gwei} gwei exceeds maximum {max_gas_price_gwei} gwei")]
+pub struct GasPriceTooHigh {
+    pub gas_price_gwei: u128,
+    pub max_gas_price_gwei: u64,
 }
 
 /// Transaction submitter
-#[allow(dead_code)]
 pub struct Submitter {
     config: SubmitterConfig,
     contract_address: Address,
@@ -114,7 +110,7 @@ impl Submitter {
     /// # Errors
     /// Returns an error if:
     /// - Submitter not configured with signer
-    /// - Gas price exceeds configured maximum
+    /// - Gas price exceeds configured maximum ([`GasPriceTooHigh`])
     /// - Transaction fails or reverts
     #[instrument(skip(self, proof), fields(source_index = proof.source_index))]
     pub async fn submit_claim(&self, proof: ConsolidationProofBundle) -> Result<B256> {
@@ -133,37 +129,16 @@ impl Submitter {
         let max_gas_price_wei =
             U256::from(self.config.max_gas_price_gwei) * U256::from(1_000_000_000);
         if U256::from(gas_price) > max_gas_price_wei {
-            anyhow::bail!(
-                "Gas price {} gwei exceeds maximum {} gwei",
-                gas_price / 1_000_000_000,
-                self.config.max_gas_price_gwei
-            );
+            return Err(GasPriceTooHigh {
+                gas_price_gwei: gas_price / 1_000_000_000,
+                max_gas_price_gwei: self.config.max_gas_price_gwei,
+            }
+            .into());
         }
 
         // Create contract instance
         let contract = ConsolidationIncentives::new(self.contract_address, &provider);
 
-        // Convert proof data to alloy types
-        let proof_consolidation: Vec<FixedBytes<32>> = proof
-            .proof_consolidation
-            .iter()
-            .map(|p| FixedBytes::from_slice(p))
-            .collect();
-
-        let proof_credentials: Vec<FixedBytes<32>> = proof
-            .proof_credentials
-            .iter()
-            .map(|p| FixedBytes::from_slice(p))
-            .collect();
-
-        let proof_activation_epoch: Vec<FixedBytes<32>> = proof
-            .proof_activation_epoch
-            .iter()
-            .map(|p| FixedBytes::from_slice(p))
-            .collect();
-
-        let source_credentials = FixedBytes::from_slice(&proof.source_credentials);
-
         info!(
             source_index = proof.source_index,
             consolidation_index = proof.consolidation_index,
@@ -173,46 +148,166 @@ impl Submitter {
         );
 
         // Build and send transaction
-        let call = contract.claimReward(
-            proof.beacon_timestamp,
-            proof.consolidation_index,
-            proof.source_index,
-            proof.activation_epoch,
-            source_credentials,
-            proof_consolidation,
-            proof_credentials,
-            proof_activation_epoch,
-        );
+        let call = contract.call_builder(&claimRewardCall::from(&proof));
 
         let pending_tx = call.send().await.context("Failed to send transaction")?;
         let tx_hash = *pending_tx.tx_hash();
 
         info!(tx_hash = %tx_hash, "Transaction submitted");
 
-        // Wait for confirmations if configured
-        if self.config.confirmations > 0 {
-            debug!(
-                confirmations = self.config.confirmations,
-                "Waiting for confirmations"
+        // Inclusion counts as the first confirmation
+        let confirmations = self.config.confirmations.max(1);
+        debug!(confirmations, "Waiting for confirmations");
+        let receipt = pending_tx
+            .with_required_confirmations(confirmations)
+            .get_receipt()
+            .await
+            .context("Failed to get transaction receipt")?;
+
+        if !receipt.status() {
+            anyhow::bail!("Transaction reverted: {}", tx_hash);
+        }
+
+        info!(
+            tx_hash = %tx_hash,
+            gas_used = receipt.gas_used,
+            "Transaction confirmed"
+        );
+
+        Ok(tx_hash)
+    }
+
+    /// Submit the claim in a bundle file after checking it was proven for
+    /// this chain and contract
+    ///
+    /// # Errors
+    /// Returns an error if the bundle is for another chain or contract, fails
+    /// validation, or [`Self::submit_claim`] fails
+    #[instrument(skip(self, file), fields(source_index = file.bundle.source_index))]
+    pub async fn submit_bundle(&self, file: ProofBundleV1) -> Result<B256> {
+        let url: reqwest::Url = self.config.rpc_url.parse()?;
+        let provider = ProviderBuilder::new().connect_http(url);
+        let chain_id = provider.get_chain_id().await?;
+
+        file.check_deployment(chain_id, self.contract_address.into_array())?;
+        file.validate().context("Bundle failed validation")?;
+
+        self.submit_claim(file.bundle).await
+    }
+
+    /// Check the signer balance, pausing the submitter if it is below the minimum
+    ///
+    /// Returns `true` if the signer is funded. Pausing publishes a
+    /// `SubmitterPaused` event the first time it happens.
+    #[instrument(skip(self, state))]
+    pub async fn check_balance(&self, state: &AppState) -> Result<bool> {
+        let address = self
+            .signer_address()
+            .context("Submitter not configured with signer")?;
+
+        let url: reqwest::Url = self.config.rpc_url.parse()?;
+        let provider = ProviderBuilder::new().connect_http(url);
+        let balance = provider.get_balance(address).await?;
+
+        if balance < self.config.min_balance_wei {
+            warn!(
+                %address,
+                %balance,
+                min_balance = %self.config.min_balance_wei,
+                "Signer balance below minimum; pausing submitter"
             );
-            let receipt = pending_tx
-                .with_required_confirmations(self.config.confirmations)
-                .get_receipt()
-                .await
-                .context("Failed to get transaction receipt")?;
-
-            if !receipt.status() {
-                anyhow::bail!("Transaction reverted: {}", tx_hash);
+            state.pause_submitter_for_low_balance(
+                balance.to_string(),
+                self.config.min_balance_wei.to_string(),
+            );
+            return Ok(false);
+        }
+
+        debug!(%address, %balance, "Signer balance ok");
+        Ok(true)
+    }
+
+    /// Submit ready claims every `interval`, forever
+    pub async fn run(&self, state: AppState, interval: Duration) {
+        info!("Starting claim submitter");
+        loop {
+            if let Err(e) = self.submit_ready(&state).await {
+                error!(error = %e, "Submitter round failed");
+                state.set_error(Some(e.to_string()));
             }
+            tokio::time::sleep(interval).await;
+        }
+    }
 
-            info!(
-                tx_hash = %tx_hash,
-                gas_used = receipt.gas_used,
-                "Transaction confirmed"
-            );
+    /// Submit every `ProofBuilt` record, unless the submitter is paused
+    ///
+    /// The signer balance is checked first and may pause the submitter. The
+    /// pause flag is re-checked before each claim so an operator pause takes
+    /// effect mid-round. Each bundle is submitted with [`Self::submit_bundle`].
+    /// A gas price above the cap ends the round and leaves the remaining
+    /// claims `ProofBuilt`, so they are retried on the next tick.
+    ///
+    /// # Errors
+    /// Returns an error if the balance or reward status cannot be read;
+    /// failed claims are recorded on their record instead
+    pub async fn submit_ready(&self, state: &AppState) -> Result<()> {
+        if state.submitter_paused() {
+            debug!("Submitter paused; skipping round");
+            return Ok(());
+        }
+        let ready: Vec<u64> = state
+            .all_consolidations()
+            .into_iter()
+            .filter(|r| r.status == ClaimStatus::ProofBuilt && !state.is_ignored(r.source_index))
+            .map(|r| r.source_index)
+            .collect();
+        if ready.is_empty() || !self.check_balance(state).await? {
+            return Ok(());
         }
 
-        Ok(tx_hash)
+        for source_index in ready {
+            if state.submitter_paused() {
+                info!("Submitter paused; stopping round");
+                break;
+            }
+            let Some(bundle) = state.proof_bundle(source_index) else {
+                continue;
+            };
+            if self.is_rewarded(source_index).await? {
+                state.update_claim_status(
+                    source_index,
+                    ClaimStatus::Failed,
+                    None,
+                    Some("already rewarded".to_string()),
+                );
+                continue;
+            }
+
+            match self.submit_bundle(bundle).await {
+                Ok(tx_hash) => {
+                    state.update_claim_status(
+                        source_index,
+                        ClaimStatus::Confirmed,
+                        Some(tx_hash.to_string()),
+                        None,
+                    );
+                }
+                Err(e) if e.downcast_ref::<GasPriceTooHigh>().is_some() => {
+                    info!(error = %e, "Gas price above maximum; retrying next round");
+                    break;
+                }
+                Err(e) => {
+                    warn!(source = source_index, error = %e, "Claim failed");
+                    state.update_claim_status(
+                        source_index,
+                        ClaimStatus::Failed,
+                        None,
+                        Some(format!("{e:#}")),
+                    );
+                }
+            }
+        }
+        Ok(())
     }
 
     /// Check if a validator has already been rewarded
@@ -252,6 +347,7 @@ impl Submitter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proof_gen::BundleContext;
 
     #[test]
     fn test_submitter_creation_readonly() {
@@ -261,6 +357,7 @@ mod tests {
             private_key: None,
             max_gas_price_gwei: 100,
             confirmations: 1,
+            min_balance_wei: U256::ZERO,
         };
 
         let submitter = Submitter::new(config);
@@ -279,6 +376,7 @@ mod tests {
             ),
             max_gas_price_gwei: 100,
             confirmations: 1,
+            min_balance_wei: U256::ZERO,
         };
 
         let submitter = Submitter::with_signer(config);
@@ -304,6 +402,7 @@ mod tests {
             ),
             max_gas_price_gwei: 100,
             confirmations: 1,
+            min_balance_wei: U256::ZERO,
         };
 
         let submitter = Submitter::with_signer(config);
@@ -318,6 +417,7 @@ mod tests {
             private_key: None,
             max_gas_price_gwei: 100,
             confirmations: 1,
+            min_balance_wei: U256::ZERO,
         };
 
         let submitter = Submitter::with_signer(config);
@@ -332,6 +432,7 @@ mod tests {
             private_key: None,
             max_gas_price_gwei: 100,
             confirmations: 1,
+            min_balance_wei: U256::ZERO,
         };
 
         let submitter = Submitter::new(config);
@@ -346,9 +447,151 @@ mod tests {
             private_key: Some("not_a_key".to_string()),
             max_gas_price_gwei: 100,
             confirmations: 1,
+            min_balance_wei: U256::ZERO,
         };
 
         let submitter = Submitter::with_signer(config);
         assert!(submitter.is_err());
     }
+
+    fn unreachable_submitter() -> Submitter {
+        Submitter::with_signer(SubmitterConfig {
+            // Nothing listens here; any RPC call fails the round
+            rpc_url: "http://127.0.0.1:1".to_string(),
+            contract_address: "0x0000000000000000000000000000000000000001".to_string(),
+            private_key: Some(
+                "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80".to_string(),
+            ),
+            max_gas_price_gwei: 100,
+            confirmations: 1,
+            min_balance_wei: U256::from(1),
+        })
+        .unwrap()
+    }
+
+    fn proof_built_state() -> AppState {
+        let state = AppState::new();
+        state.upsert_consolidation(crate::state::ConsolidationRecord {
+            source_index: 5,
+            target_index: 6,
+            epoch_seen: 10,
+            status: ClaimStatus::Detected,
+            tx_hash: None,
+            error: None,
+            anchor_block_root: None,
+            state_seen: None,
+        });
+        let bundle = ConsolidationProofBundle {
+            beacon_timestamp: 0,
+            consolidation_index: 0,
+            source_index: 5,
+            activation_epoch: 0,
+            source_credentials: [0; 32],
+            proof_consolidation: vec![],
+            proof_credentials: vec![],
+            proof_activation_epoch: vec![],
+            target: None,
+        };
+        assert!(state.record_proof(ProofBundleV1::new(
+            BundleContext::gnosis([0; 32], 0),
+            bundle
+        )));
+        state
+    }
+
+    #[tokio::test]
+    async fn test_submit_ready_skips_round_while_paused() {
+        let state = proof_built_state();
+        state.set_submitter_paused(true);
+
+        unreachable_submitter().submit_ready(&state).await.unwrap();
+
+        assert_eq!(
+            state.get_consolidation(5).unwrap().status,
+            ClaimStatus::ProofBuilt
+        );
+    }
+
+    #[tokio::test]
+    async fn test_submit_ready_keeps_claims_ready_while_gas_price_is_too_high() {
+        use proof_gen::{StateProver, SyntheticStateConfig};
+        use serde_json::json;
+        use wiremock::matchers::{body_partial_json, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let synthetic = SyntheticStateConfig {
+            seed: 1,
+            validators: 64,
+            pending_consolidations: 2,
+            ..Default::default()
+        }
+        .generate()
+        .unwrap();
+        let bundle = StateProver::from_gnosis_state(&synthetic.state)
+            .unwrap()
+            .generate_full_proof_bundle(&synthetic.header, 0, synthetic.timestamp())
+            .unwrap();
+        let source_index = bundle.source_index;
+        let state = AppState::new();
+        state.upsert_consolidation(crate::state::ConsolidationRecord {
+            source_index,
+            target_index: 0,
+            epoch_seen: 10,
+            status: ClaimStatus::Detected,
+            tx_hash: None,
+            error: None,
+            anchor_block_root: None,
+            state_seen: None,
+        });
+        assert!(state.record_proof(ProofBundleV1::new(
+            BundleContext::gnosis(synthetic.block_root, synthetic.header.slot),
+            bundle
+        )));
+
+        let server = MockServer::start().await;
+        for (rpc_method, result) in [
+            ("eth_getBalance", json!("0xde0b6b3a7640000")),
+            ("eth_call", json!(format!("0x{}", "00".repeat(32)))),
+            ("eth_chainId", json!("0x64")),
+            // 200 gwei
+            ("eth_gasPrice", json!("0x2e90edd000")),
+        ] {
+            Mock::given(method("POST"))
+                .and(body_partial_json(json!({ "method": rpc_method })))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(json!({ "jsonrpc": "2.0", "id": 0, "result": result })),
+                )
+                .mount(&server)
+                .await;
+        }
+        let submitter = Submitter::with_signer(SubmitterConfig {
+            rpc_url: server.uri(),
+            ..unreachable_submitter().config
+        })
+        .unwrap();
+        let mut events = state.subscribe_events();
+
+        submitter.submit_ready(&state).await.unwrap();
+
+        let record = state.get_consolidation(source_index).unwrap();
+        assert_eq!(record.status, ClaimStatus::ProofBuilt);
+        assert!(events.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_submit_ready_checks_balance_before_submitting() {
+        let submitter = unreachable_submitter();
+
+        // Nothing to submit: no RPC call is made
+        submitter.submit_ready(&AppState::new()).await.unwrap();
+
+        // A ready claim needs the signer balance first
+        let state = proof_built_state();
+        assert!(submitter.submit_ready(&state).await.is_err());
+        assert_eq!(
+            state.get_consolidation(5).unwrap().status,
+            ClaimStatus::ProofBuilt
+        );
+    }
 }
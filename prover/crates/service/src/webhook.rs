@@ -0,0 +1,461 @@
+//! Webhook Notifications
+//!
+//! Delivers claim lifecycle events to configured HTTP endpoints.
+//!
+//! Each delivery is a JSON `POST` whose body is signed with HMAC-SHA256 over the
+//! raw bytes (`X-Webhook-Signature: sha256=<hex>`). Network errors, `429` and
+//! `5xx` responses are retried with exponential backoff; deliveries that exhaust
+//! their attempts or hit a permanent `4xx` are appended to a JSONL dead-letter log.
+
+use crate::state::LifecycleEvent;
+use hmac::{Hmac, Mac};
+use reqwest::{Client, StatusCode};
+use serde::Serialize;
+use sha2::Sha256;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{broadcast, Mutex};
+use tracing::{debug, error, info, warn};
+
+/// Header carrying the HMAC-SHA256 signature of the request body
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+/// Header carrying the event name
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+/// Header carrying the delivery id (stable across retries)
+pub const DELIVERY_HEADER: &str = "X-Webhook-Delivery";
+
+/// Webhook configuration
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    /// Endpoints receiving every event
+    pub urls: Vec<String>,
+    /// Shared secret for HMAC signatures (unsigned if `None`)
+    pub secret: Option<String>,
+    /// Total delivery attempts per endpoint, including the first
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled on each subsequent retry
+    pub initial_backoff: Duration,
+    /// Upper bound on the retry delay
+    pub max_backoff: Duration,
+    /// Per-request timeout
+    pub request_timeout: Duration,
+    /// JSONL file receiving undeliverable payloads
+    pub dead_letter_path: Option<PathBuf>,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            urls: Vec::new(),
+            secret: None,
+            max_attempts: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            request_timeout: Duration::from_secs(10),
+            dead_letter_path: None,
+        }
+    }
+}
+
+/// Body posted to webhook endpoints
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookPayload {
+    /// Unique delivery id, for receiver-side deduplication
+    pub id: String,
+    /// Unix timestamp (seconds) when the event was dispatched
+    pub timestamp: u64,
+    /// The lifecycle event (flattened; `type` names the event)
+    #[serde(flatten)]
+    pub event: LifecycleEvent,
+}
+
+/// Entry appended to the dead-letter log
+#[derive(Debug, Serialize)]
+struct DeadLetter<'a> {
+    url: &'a str,
+    attempts: u32,
+    error: &'a str,
+    failed_at: u64,
+    payload: &'a WebhookPayload,
+}
+
+/// Outcome of a single delivery attempt
+enum AttemptError {
+    /// Worth retrying (network error, 429, 5xx)
+    Retryable(String),
+    /// Retrying will not help (other 4xx)
+    Permanent(String),
+}
+
+/// Fans lifecycle events out to webhook endpoints
+pub struct WebhookDispatcher {
+    config: WebhookConfig,
+    client: Client,
+    next_id: AtomicU64,
+    dead_letter_lock: Mutex<()>,
+}
+
+impl WebhookDispatcher {
+    /// Create a new dispatcher
+    pub fn new(config: WebhookConfig) -> anyhow::Result<Self> {
+        let client = Client::builder().timeout(config.request_timeout).build()?;
+        Ok(Self {
+            config,
+            client,
+            next_id: AtomicU64::new(0),
+            dead_letter_lock: Mutex::new(()),
+        })
+    }
+
+    /// Consume events until the channel closes, delivering each to every endpoint
+    pub async fn run(self: Arc<Self>, mut events: broadcast::Receiver<LifecycleEvent>) {
+        info!(
+            endpoints = self.config.urls.len(),
+            "Starting webhook dispatcher"
+        );
+
+        loop {
+            match events.recv().await {
+                Ok(event) => {
+                    let payload = Arc::new(self.payload(event));
+                    for url in &self.config.urls {
+                        let dispatcher = Arc::clone(&self);
+                        let payload = Arc::clone(&payload);
+                        let url = url.clone();
+                        tokio::spawn(async move {
+                            dispatcher.deliver(&url, &payload).await;
+                        });
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!(skipped, "Webhook dispatcher lagged; events dropped");
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }
+
+    /// Wrap an event in a payload with a fresh delivery id
+    pub fn payload(&self, event: LifecycleEvent) -> WebhookPayload {
+        let timestamp = unix_now();
+        let seq = self.next_id.fetch_add(1, Ordering::Relaxed);
+        WebhookPayload {
+            id: format!("{timestamp}-{seq}"),
+            timestamp,
+            event,
+        }
+    }
+
+    /// Deliver a payload to one endpoint, retrying with backoff
+    ///
+    /// Returns `true` on a `2xx` response. Failed deliveries are dead-lettered.
+    pub async fn deliver(&self, url: &str, payload: &WebhookPayload) -> bool {
+        let body = match serde_json::to_vec(payload) {
+            Ok(body) => body,
+            Err(e) => {
+                error!(error = %e, "Failed to serialize webhook payload");
+                return false;
+            }
+        };
+
+        let mut backoff = self.config.initial_backoff;
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let reason = match self.attempt(url, payload, &body).await {
+                Ok(()) => {
+                    debug!(url, id = %payload.id, attempt, "Webhook delivered");
+                    return true;
+                }
+                Err(AttemptError::Retryable(reason)) if attempt < self.config.max_attempts => {
+                    warn!(url, id = %payload.id, attempt, error = %reason, "Webhook delivery failed; retrying");
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(self.config.max_backoff);
+                    continue;
+                }
+                Err(AttemptError::Retryable(reason) | AttemptError::Permanent(reason)) => reason,
+            };
+
+            error!(url, id = %payload.id, attempt, error = %reason, "Webhook delivery abandoned");
+            self.dead_letter(url, payload, attempt, &reason).await;
+            return false;
+        }
+    }
+
+    async fn attempt(
+        &self,
+        url: &str,
+        payload: &WebhookPayload,
+        body: &[u8],
+    ) -> Result<(), AttemptError> {
+        let mut request = self
+            .client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, payload.event.name())
+            .header(DELIVERY_HEADER, &payload.id)
+            .body(body.to_vec());
+        if let Some(secret) = &self.config.secret {
+            request = request.header(SIGNATURE_HEADER, sign_payload(secret.as_bytes(), body));
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| AttemptError::Retryable(e.to_string()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else if status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error() {
+            Err(AttemptError::Retryable(format!("HTTP {status}")))
+        } else {
+            Err(AttemptError::Permanent(format!("HTTP {status}")))
+        }
+    }
+
+    async fn dead_letter(&self, url: &str, payload: &WebhookPayload, attempts: u32, error: &str) {
+        let Some(path) = &self.config.dead_letter_path else {
+            return;
+        };
+
+        let entry = DeadLetter {
+            url,
+            attempts,
+            error,
+            failed_at: unix_now(),
+            payload,
+        };
+        let mut line = match serde_json::to_vec(&entry) {
+            Ok(line) => line,
+            Err(e) => {
+                error!(error = %e, "Failed to serialize dead-letter entry");
+                return;
+            }
+        };
+        line.push(b'\n');
+
+        // Serialize writers so concurrent failures don't interleave lines
+        let _guard = self.dead_letter_lock.lock().await;
+        let result = async {
+            let mut file = tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .await?;
+            file.write_all(&line).await?;
+            file.flush().await
+        }
+        .await;
+
+        if let Err(e) = result {
+            error!(path = %path.display(), error = %e, "Failed to write webhook dead-letter log");
+        }
+    }
+}
+
+/// Compute the `X-Webhook-Signature` value for a request body
+///
+/// Receivers verify by recomputing HMAC-SHA256 over the raw body with the
+/// shared secret and comparing in constant time.
+#[must_use]
+pub fn sign_payload(secret: &[u8], body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, header_exists, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn test_config(url: String) -> WebhookConfig {
+        WebhookConfig {
+            urls: vec![url],
+            secret: Some("key".to_string()),
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(5),
+            max_backoff: Duration::from_millis(20),
+            ..Default::default()
+        }
+    }
+
+    fn temp_dead_letter(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "webhook-dead-letter-{name}-{}.jsonl",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn detected() -> LifecycleEvent {
+        LifecycleEvent::ConsolidationDetected {
+            source_index: 42,
+            target_index: 100,
+            epoch: 500,
+        }
+    }
+
+    #[test]
+    fn test_sign_payload_known_vector() {
+        // RFC 4231-style vector commonly used for HMAC-SHA256
+        assert_eq!(
+            sign_payload(b"key", b"The quick brown fox jumps over the lazy dog"),
+            "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+
+    #[test]
+    fn test_payload_serialization_flattens_event() {
+        let dispatcher = WebhookDispatcher::new(WebhookConfig::default()).unwrap();
+        let first = dispatcher.payload(detected());
+        let second = dispatcher.payload(detected());
+        assert_ne!(first.id, second.id);
+
+        let json = serde_json::to_value(&first).unwrap();
+        assert_eq!(json["type"], "consolidation_detected");
+        assert_eq!(json["source_index"], 42);
+        assert_eq!(json["id"], first.id);
+    }
+
+    #[tokio::test]
+    async fn test_deliver_signed_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .and(header(EVENT_HEADER, "consolidation_detected"))
+            .and(header_exists(SIGNATURE_HEADER))
+            .and(header_exists(DELIVERY_HEADER))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dispatcher =
+            WebhookDispatcher::new(test_config(format!("{}/hook", server.uri()))).unwrap();
+        let payload = dispatcher.payload(detected());
+        assert!(
+            dispatcher
+                .deliver(&format!("{}/hook", server.uri()), &payload)
+                .await
+        );
+
+        let requests = server.received_requests().await.unwrap();
+        let signature = requests[0].headers.get(SIGNATURE_HEADER).unwrap();
+        assert_eq!(
+            signature.to_str().unwrap(),
+            sign_payload(b"key", &requests[0].body)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_deliver_retries_then_succeeds() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let dispatcher = WebhookDispatcher::new(test_config(server.uri())).unwrap();
+        let payload = dispatcher.payload(detected());
+        assert!(dispatcher.deliver(&server.uri(), &payload).await);
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_retries_are_dead_lettered() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&server)
+            .await;
+
+        let dead_letter = temp_dead_letter("exhausted");
+        let mut config = test_config(server.uri());
+        config.dead_letter_path = Some(dead_letter.clone());
+        let dispatcher = WebhookDispatcher::new(config).unwrap();
+
+        let payload = dispatcher.payload(detected());
+        assert!(!dispatcher.deliver(&server.uri(), &payload).await);
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+
+        let contents = std::fs::read_to_string(&dead_letter).unwrap();
+        let entry: serde_json::Value = serde_json::from_str(contents.trim()).unwrap();
+        assert_eq!(entry["attempts"], 3);
+        assert_eq!(entry["url"], server.uri());
+        assert_eq!(entry["payload"]["id"], payload.id);
+        std::fs::remove_file(dead_letter).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_client_error_is_not_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400))
+            .mount(&server)
+            .await;
+
+        let dead_letter = temp_dead_letter("permanent");
+        let mut config = test_config(server.uri());
+        config.dead_letter_path = Some(dead_letter.clone());
+        let dispatcher = WebhookDispatcher::new(config).unwrap();
+
+        let payload = dispatcher.payload(detected());
+        assert!(!dispatcher.deliver(&server.uri(), &payload).await);
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+        assert!(std::fs::read_to_string(&dead_letter)
+            .unwrap()
+            .contains("HTTP 400"));
+        std::fs::remove_file(dead_letter).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_run_fans_out_published_events() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header(EVENT_HEADER, "claim_failed"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let mut config = test_config(format!("{}/a", server.uri()));
+        config.urls.push(format!("{}/b", server.uri()));
+        let dispatcher = Arc::new(WebhookDispatcher::new(config).unwrap());
+
+        let state = crate::state::AppState::new();
+        let handle = tokio::spawn(dispatcher.run(state.subscribe_events()));
+
+        state.publish_event(LifecycleEvent::ClaimFailed {
+            source_index: 1,
+            error: "reverted".to_string(),
+        });
+
+        for _ in 0..100 {
+            if server.received_requests().await.unwrap().len() == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        handle.abort();
+        server.verify().await;
+    }
+}
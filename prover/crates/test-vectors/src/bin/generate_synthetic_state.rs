@@ -0,0 +1,122 @@
+//! Synthetic State Generator
+//!
+//! Writes a Gnosis-scale Electra state generated by
+//! [`SyntheticStateConfig`] as `state.ssz`, and the header of the block it is
+//! the post-state of as `header.json` in the shape of the Beacon API's
+//! `/eth/v1/beacon/headers/{block_id}` response, so both can be served by a
+//! mock beacon node or loaded with `StateSource::from_path`.
+//!
+//! The same arguments always produce the same files.
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use proof_gen::SyntheticStateConfig;
+use serde_json::json;
+use std::path::PathBuf;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+#[derive(Parser, Debug)]
+#[command(name = "generate-synthetic-state")]
+#[command(about = "Generate a deterministic Gnosis-scale Electra state and its block header")]
+struct Args {
+    /// Output directory for state.ssz and header.json
+    #[arg(short, long, default_value = "synthetic-state")]
+    output: PathBuf,
+
+    /// PRNG seed
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+
+    /// Number of validators
+    #[arg(long, default_value_t = SyntheticStateConfig::default().validators)]
+    validators: usize,
+
+    /// Number of pending consolidations
+    #[arg(long, default_value_t = SyntheticStateConfig::default().pending_consolidations)]
+    consolidations: usize,
+
+    /// Slot of the state (at or after Electra)
+    #[arg(long, default_value_t = SyntheticStateConfig::default().slot)]
+    slot: u64,
+
+    /// Eligibility cutoff that activation epochs are spread around
+    #[arg(long, default_value_t = SyntheticStateConfig::default().max_epoch)]
+    max_epoch: u64,
+
+    /// Activation epochs are drawn from max-epoch ± epoch-spread
+    #[arg(long, default_value_t = SyntheticStateConfig::default().epoch_spread)]
+    epoch_spread: u64,
+
+    /// Relative weights of 0x00, 0x01 and 0x02 withdrawal credentials
+    #[arg(long, value_delimiter = ',', num_args = 3, default_values_t = SyntheticStateConfig::default().credential_weights)]
+    credential_weights: Vec<u32>,
+}
+
+fn hex32(bytes: &[u8; 32]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn main() -> Result<()> {
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer())
+        .with(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
+
+    let args = Args::parse();
+    let config = SyntheticStateConfig {
+        seed: args.seed,
+        validators: args.validators,
+        pending_consolidations: args.consolidations,
+        slot: args.slot,
+        max_epoch: args.max_epoch,
+        epoch_spread: args.epoch_spread,
+        credential_weights: args
+            .credential_weights
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Expected three credential weights"))?,
+    };
+
+    tracing::info!(?config, "Generating synthetic state");
+    let synthetic = config.generate()?;
+    let header = &synthetic.header;
+
+    std::fs::create_dir_all(&args.output)?;
+
+    let state_path = args.output.join("state.ssz");
+    let bytes = ssz_rs::serialize(&synthetic.state)
+        .map_err(|e| anyhow::anyhow!("Failed to serialize state: {e:?}"))?;
+    std::fs::write(&state_path, &bytes)
+        .with_context(|| format!("Failed to write {}", state_path.display()))?;
+
+    let header_path = args.output.join("header.json");
+    let header_json = json!({
+        "execution_optimistic": false,
+        "finalized": true,
+        "data": {
+            "root": hex32(&synthetic.block_root),
+            "canonical": true,
+            "header": {
+                "message": {
+                    "slot": header.slot.to_string(),
+                    "proposer_index": header.proposer_index.to_string(),
+                    "parent_root": hex32(&header.parent_root),
+                    "state_root": hex32(&header.state_root),
+                    "body_root": hex32(&header.body_root),
+                },
+                "signature": format!("0x{}", "00".repeat(96)),
+            },
+        },
+    });
+    std::fs::write(&header_path, serde_json::to_string_pretty(&header_json)?)
+        .with_context(|| format!("Failed to write {}", header_path.display()))?;
+
+    tracing::info!(
+        state = %state_path.display(),
+        bytes = bytes.len(),
+        header = %header_path.display(),
+        block_root = %hex32(&synthetic.block_root),
+        beacon_timestamp = synthetic.timestamp(),
+        "Wrote synthetic state"
+    );
+    Ok(())
+}
@@ -8,14 +8,19 @@
 //! 1. Build validators and consolidations with known data
 //! 2. Compute all 37 BeaconState field roots (using gnosis depths for list fields)
 //! 3. Use StateProver with gnosis depths to generate proofs
-//! 4. Output JSON test vectors for Foundry tests
+//! 4. Output JSON test vectors for Foundry tests, plus a `ProofBundleV1` file
+//!    (JSON and SSZ) per valid claim under `bundles/`
 
 use anyhow::Result;
 use clap::Parser;
 use proof_gen::beacon_state::{BeaconBlockHeader, PendingConsolidation, Validator};
+use proof_gen::schema::StateLimits;
 use proof_gen::sparse_proof::mix_in_length;
 use proof_gen::state_prover::{compute_list_root, StateProver};
-use proof_gen::ConsolidationProofBundle;
+use proof_gen::{
+    BundleContext, ConsolidationProofBundle, ExitedValidatorProofBundle, ForkName, PathStep,
+    ProofBundleV1, ProofGenerator,
+};
 use serde::Serialize;
 use sha2::{Digest, Sha256};
 use ssz_rs::prelude::*;
@@ -35,6 +40,9 @@ struct Args {
 const VALIDATORS_TREE_DEPTH: u32 = 40;
 const CONSOLIDATIONS_TREE_DEPTH: u32 = 18;
 
+/// Chain ID of Foundry's test EVM, which the vectors are replayed on
+const FOUNDRY_CHAIN_ID: u64 = 31_337;
+
 /// Expected proof lengths (must match Solidity contract)
 const EXPECTED_CONSOLIDATION_PROOF_LEN: usize = 29; // 1 + 18 + 1 + 6 + 3
 const EXPECTED_VALIDATOR_PROOF_LEN: usize = 53; // 3 + 40 + 1 + 6 + 3
@@ -84,6 +92,46 @@ struct InvalidTestClaim {
     expected_error: String,
 }
 
+#[derive(Debug, Serialize)]
+struct ExitTestVectorFile {
+    /// Preset used
+    preset: String,
+    /// Block root (0x-prefixed hex)
+    block_root: String,
+    /// Beacon timestamp for EIP-4788 lookup
+    beacon_timestamp: u64,
+    /// Max epoch for eligibility checks
+    max_epoch: u64,
+    /// Valid exited-validator claims with proofs
+    claims: Vec<ExitTestClaim>,
+    /// Invalid claims for negative testing
+    invalid_claims: Vec<InvalidExitTestClaim>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ExitTestClaim {
+    source_index: u64,
+    activation_epoch: u64,
+    exit_epoch: u64,
+    withdrawable_epoch: u64,
+    effective_balance: u64,
+    source_credentials: String,
+    proof_credentials: Vec<String>,
+    proof_activation_epoch: Vec<String>,
+    proof_exit_epoch: Vec<String>,
+    proof_withdrawable_epoch: Vec<String>,
+    proof_effective_balance: Vec<String>,
+    expected_recipient: String,
+}
+
+#[derive(Debug, Serialize)]
+struct InvalidExitTestClaim {
+    description: String,
+    #[serde(flatten)]
+    claim: ExitTestClaim,
+    expected_error: String,
+}
+
 // ============================================================================
 // Helpers
 // ============================================================================
@@ -516,6 +564,17 @@ fn main() -> Result<()> {
     );
     claims.push(bundle_to_claim(&bundle0));
 
+    // Measure calldata of the equivalent compact multiproof
+    let multiproof = bundle0.to_multiproof(&StateLimits::GNOSIS)?;
+    bundle0.verify_multiproof(&multiproof, block_root, &StateLimits::GNOSIS)?;
+    let comparison = bundle0.calldata_comparison(&StateLimits::GNOSIS)?;
+    tracing::info!(
+        separate_hashes = comparison.separate_hashes,
+        multiproof_hashes = comparison.multiproof_hashes,
+        saved_bytes = comparison.saved_bytes(),
+        "Multiproof calldata comparison"
+    );
+
     // Claim 1: validator 2, consolidation 1 (0x01 credentials, eligible)
     let bundle1 = prover.generate_full_proof_bundle(&header, 1, beacon_timestamp)?;
     claims.push(bundle_to_claim(&bundle1));
@@ -530,6 +589,19 @@ fn main() -> Result<()> {
 
     tracing::info!(count = claims.len(), "Generated valid claims");
 
+    let context = BundleContext {
+        chain_id: FOUNDRY_CHAIN_ID,
+        contract_address: None,
+        fork: ForkName::Electra,
+        block_root,
+        slot: header.slot,
+    };
+    write_bundle_files(
+        &args.output.join("bundles"),
+        context,
+        [&bundle0, &bundle1, &bundle2, &bundle5],
+    )?;
+
     // ========================================================================
     // Generate invalid claims
     // ========================================================================
@@ -682,6 +754,8 @@ fn main() -> Result<()> {
         "Wrote test vectors"
     );
 
+    generate_exit_vectors(&args.output, beacon_timestamp, max_epoch)?;
+
     // Also verify the generated vectors by checking proof lengths
     tracing::info!("Verification:");
     tracing::info!(
@@ -698,6 +772,179 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+/// Write each bundle as a validated `ProofBundleV1` in both encodings:
+/// `consolidation_<index>.json` and `consolidation_<index>.ssz`
+fn write_bundle_files<'a>(
+    dir: &std::path::Path,
+    context: BundleContext,
+    bundles: impl IntoIterator<Item = &'a ConsolidationProofBundle>,
+) -> Result<()> {
+    std::fs::create_dir_all(dir)?;
+    for bundle in bundles {
+        let file = ProofBundleV1::new(context, bundle.clone());
+        file.validate()?;
+        for extension in ["json", "ssz"] {
+            let name = format!("consolidation_{}.{extension}", bundle.consolidation_index);
+            file.write_file(&dir.join(name))?;
+        }
+    }
+    tracing::info!(path = %dir.display(), "Wrote proof bundle files");
+    Ok(())
+}
+
+/// Generate `exit_test_vectors.json`: exited-validator bundles for sources of
+/// already processed consolidations, against a separate state with no
+/// pending consolidations.
+fn generate_exit_vectors(
+    output: &std::path::Path,
+    beacon_timestamp: u64,
+    max_epoch: u64,
+) -> Result<()> {
+    let exited = |index, activation_epoch, exit_epoch, cred_prefix| {
+        let mut v = make_validator(index, activation_epoch, cred_prefix);
+        v.exit_epoch = exit_epoch;
+        v.withdrawable_epoch = exit_epoch + 256;
+        v.effective_balance = 0;
+        v
+    };
+    let mut voluntary_exit = make_validator(3, 400, 0x01);
+    voluntary_exit.exit_epoch = 700;
+    voluntary_exit.withdrawable_epoch = 956;
+
+    let validators = vec![
+        exited(0, 100, 800, 0x01),    // consolidated source, 0x01 credentials
+        exited(1, 300, 900, 0x02),    // consolidated source, 0x02 credentials
+        make_validator(2, 200, 0x01), // active
+        voluntary_exit,               // exited, balance not yet withdrawn
+    ];
+
+    let prover = StateProver::new(
+        compute_gnosis_field_roots(&validators, &[]),
+        validators.clone(),
+        vec![],
+        VALIDATORS_TREE_DEPTH,
+        CONSOLIDATIONS_TREE_DEPTH,
+    )?;
+    let header = BeaconBlockHeader {
+        slot: 2000,
+        proposer_index: 0,
+        parent_root: [0u8; 32],
+        state_root: prover.compute_state_root(),
+        body_root: [1u8; 32],
+    };
+    let block_root: [u8; 32] = header.hash_tree_root()?.into();
+
+    let mut bundles = Vec::new();
+    for source_index in [0, 1] {
+        let bundle = prover.generate_exit_proof_bundle(&header, source_index, beacon_timestamp)?;
+        ProofGenerator::verify_exit_proof_bundle(&bundle, block_root)?;
+        assert_eq!(bundle.proof_exit_epoch.len(), EXPECTED_VALIDATOR_PROOF_LEN);
+        bundles.push(bundle);
+    }
+    let claims: Vec<ExitTestClaim> = bundles.iter().map(exit_bundle_to_claim).collect();
+
+    let active = prove_exit_fields(&prover, &header, &validators, 2)?;
+    let not_withdrawn = prove_exit_fields(&prover, &header, &validators, 3)?;
+    let mut tampered = claims[0].clone();
+    tampered.proof_exit_epoch = hex_encode_proof(&tamper_proof(&bundles[0].proof_exit_epoch));
+    let mut zero_balance_claimed = not_withdrawn.clone();
+    zero_balance_claimed.effective_balance = 0;
+
+    let invalid_claims = vec![
+        InvalidExitTestClaim {
+            description: "active validator (exit_epoch is FAR_FUTURE_EPOCH)".to_string(),
+            claim: active,
+            expected_error: "ValidatorNotDrained".to_string(),
+        },
+        InvalidExitTestClaim {
+            description: "exited validator with effective balance left".to_string(),
+            claim: not_withdrawn,
+            expected_error: "ValidatorNotDrained".to_string(),
+        },
+        InvalidExitTestClaim {
+            description: "tampered exit_epoch proof".to_string(),
+            claim: tampered,
+            expected_error: "InvalidProof".to_string(),
+        },
+        InvalidExitTestClaim {
+            description: "zero effective balance claimed against a funded validator".to_string(),
+            claim: zero_balance_claimed,
+            expected_error: "InvalidProof".to_string(),
+        },
+    ];
+
+    let vectors = ExitTestVectorFile {
+        preset: "gnosis".to_string(),
+        block_root: hex_encode_bytes32(&block_root),
+        beacon_timestamp,
+        max_epoch,
+        claims,
+        invalid_claims,
+    };
+    let output_path = output.join("exit_test_vectors.json");
+    let json = serde_json::to_string_pretty(&vectors)?;
+    std::fs::write(&output_path, &json)?;
+
+    tracing::info!(
+        path = %output_path.display(),
+        claims = vectors.claims.len(),
+        invalid_claims = vectors.invalid_claims.len(),
+        "Wrote exit test vectors"
+    );
+    Ok(())
+}
+
+/// Prove a validator's exit fields without requiring a completed exit, for
+/// negative vectors
+fn prove_exit_fields(
+    prover: &StateProver,
+    header: &BeaconBlockHeader,
+    validators: &[Validator],
+    source_index: u64,
+) -> Result<ExitTestClaim> {
+    let prove = |field| -> Result<Vec<String>> {
+        let path = [
+            "state_root".into(),
+            "validators".into(),
+            PathStep::Index(source_index),
+            PathStep::Field(field),
+        ];
+        Ok(hex_encode_proof(&prover.prove_path(header, &path)?.branch))
+    };
+    let validator = &validators[source_index as usize];
+    Ok(ExitTestClaim {
+        source_index,
+        activation_epoch: validator.activation_epoch,
+        exit_epoch: validator.exit_epoch,
+        withdrawable_epoch: validator.withdrawable_epoch,
+        effective_balance: validator.effective_balance,
+        source_credentials: hex_encode_bytes32(&validator.withdrawal_credentials),
+        proof_credentials: prove("withdrawal_credentials")?,
+        proof_activation_epoch: prove("activation_epoch")?,
+        proof_exit_epoch: prove("exit_epoch")?,
+        proof_withdrawable_epoch: prove("withdrawable_epoch")?,
+        proof_effective_balance: prove("effective_balance")?,
+        expected_recipient: address_from_credentials(&validator.withdrawal_credentials),
+    })
+}
+
+fn exit_bundle_to_claim(bundle: &ExitedValidatorProofBundle) -> ExitTestClaim {
+    ExitTestClaim {
+        source_index: bundle.source_index,
+        activation_epoch: bundle.activation_epoch,
+        exit_epoch: bundle.exit_epoch,
+        withdrawable_epoch: bundle.withdrawable_epoch,
+        effective_balance: bundle.effective_balance,
+        source_credentials: hex_encode_bytes32(&bundle.source_credentials),
+        proof_credentials: hex_encode_proof(&bundle.proof_credentials),
+        proof_activation_epoch: hex_encode_proof(&bundle.proof_activation_epoch),
+        proof_exit_epoch: hex_encode_proof(&bundle.proof_exit_epoch),
+        proof_withdrawable_epoch: hex_encode_proof(&bundle.proof_withdrawable_epoch),
+        proof_effective_balance: hex_encode_proof(&bundle.proof_effective_balance),
+        expected_recipient: address_from_credentials(&bundle.source_credentials),
+    }
+}
+
 fn bundle_to_claim(bundle: &ConsolidationProofBundle) -> TestClaim {
     TestClaim {
         consolidation_index: bundle.consolidation_index,